@@ -5,21 +5,28 @@ pub use glutin::*;
 
 use crate::window::{KeyboardInput, Window, WindowDescription, WindowEvent, WindowWidget};
 
-use crate::{Entity, State};
-use crate::{Length, Visibility};
+use crate::{Clipboard, Entity, State};
+use crate::Length;
 
 use crate::state::mouse::{MouseButton, MouseButtonState};
 
-use crate::events::{Event, EventManager, Propagation};
+use crate::events::{Event, EventManager};
+use crate::events::input::*;
 
-use crate::state::hierarchy::IntoHierarchyIterator;
+type GEvent<'a, T> = glutin::event::Event<'a, T>;
 
-use crate::state::Fonts;
 
-use crate::VirtualKeyCode;
+// Sizes the root entity to the window and builds the window widget
+pub(crate) fn init_root(state: &mut State, width: f32, height: f32) {
+    state.style.width.insert(state.root, Length::Pixels(width));
+    state.style.height.insert(state.root, Length::Pixels(height));
 
-type GEvent<'a, T> = glutin::event::Event<'a, T>;
+    state.transform.set_width(state.get_root(), width);
+    state.transform.set_height(state.get_root(), height);
+    state.transform.set_opacity(state.get_root(), 1.0);
 
+    WindowWidget::new().build_window(state);
+}
 
 pub struct Application {
     pub window: Window,
@@ -33,6 +40,8 @@ impl Application {
         let event_loop = EventLoop::new();
         let mut state = State::new();
 
+        state.clipboard = Clipboard::system();
+
         let event_manager = EventManager::new();
 
         let root = state.root;
@@ -41,44 +50,13 @@ impl Application {
         //let window_description = win(WindowDescription::new());
         let window_description = app(WindowDescription::new(), &mut state, root);
 
-        let mut window = Window::new(&event_loop, &window_description);
-
-        let regular_font = include_bytes!("../resources/Roboto-Regular.ttf");
-        let bold_font = include_bytes!("../resources/Roboto-Bold.ttf");
-        let icon_font = include_bytes!("../resources/entypo.ttf");
-
-        let fonts = Fonts {
-            regular: Some(window.canvas
-                .add_font_mem(regular_font)
-                .expect("Cannot add font")),
-            bold: Some(window.canvas
-                .add_font_mem(bold_font)
-                .expect("Cannot add font")),
-            icons: Some(window.canvas.add_font_mem(icon_font).expect("Cannot add font")),
-        };
-
-        state.fonts = fonts;
+        let window = Window::new(&event_loop, &window_description);
 
-        state.style.width.insert(
-            state.root,
-            Length::Pixels(window_description.inner_size.to_physical(1.0).width),
-        );
-        state.style.height.insert(
-            state.root,
-            Length::Pixels(window_description.inner_size.to_physical(1.0).height),
-        );
-
-        state.transform.set_width(
-            state.get_root(),
+        init_root(
+            &mut state,
             window_description.inner_size.to_physical(1.0).width,
-        );
-        state.transform.set_height(
-            state.get_root(),
             window_description.inner_size.to_physical(1.0).height,
         );
-        state.transform.set_opacity(state.get_root(), 1.0);
-
-        WindowWidget::new().build_window(&mut state);
 
         
 
@@ -105,8 +83,6 @@ impl Application {
     }
 
     pub fn run(self) {
-        let mut pos: (f32, f32) = (0.0, 0.0);
-
         let mut state = self.state;
         let mut event_manager = self.event_manager;
 
@@ -116,6 +92,9 @@ impl Application {
         let mut should_redraw = false;
         let hierarchy = state.hierarchy.clone();
 
+        // Stylesheet files are checked for changes a few times a second
+        let mut last_style_poll = std::time::Instant::now();
+
         state.insert_event(Event::new(WindowEvent::Restyle));
         state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
 
@@ -130,11 +109,17 @@ impl Application {
                 }
 
                 GEvent::MainEventsCleared => {
+                    if last_style_poll.elapsed() >= std::time::Duration::from_millis(250) {
+                        state.poll_styles();
+                        last_style_poll = std::time::Instant::now();
+                    }
+
                     if state.apply_animations() {
-                        state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()).origin(Entity::new(0, 0)));
                         state.insert_event(Event::new(WindowEvent::Redraw));
                     }
 
+                    state.fire_timers(std::time::Instant::now());
+
                     while !state.event_queue.is_empty() {
                         if event_manager.flush_events(&mut state, &mut window) {
                             window.handle.window().request_redraw();
@@ -183,114 +168,22 @@ impl Application {
                         // Focused Window //
                         ////////////////////
                         glutin::event::WindowEvent::ReceivedCharacter(input) => {
-                            state.insert_event(
-                                Event::new(WindowEvent::CharInput(input))
-                                    .target(state.focused)
-                                    .propagate(Propagation::Down),
-                            );
+                            char_input(&mut state, input);
                         }
 
                         glutin::event::WindowEvent::KeyboardInput { device_id: _, input, is_synthetic: _ } => {
-                    
                             let s = match input.state {
                                 glutin::event::ElementState::Pressed => MouseButtonState::Pressed,
                                 glutin::event::ElementState::Released => MouseButtonState::Released,
                             };
 
-                            if let Some(virtual_keycode) = input.virtual_keycode {
-
-
-                                if virtual_keycode == VirtualKeyCode::Tab && s == MouseButtonState::Pressed {
-
-                                    let next_focus = state.style.focus_order.get(state.focused).cloned().unwrap_or_default().next;
-                                    let prev_focus = state.style.focus_order.get(state.focused).cloned().unwrap_or_default().prev;
-
-                                    if state.modifiers.shift {
-                                        if prev_focus != Entity::null() {
-                                            state.focused = prev_focus;
-                                        } else {
-                                            // TODO impliment reverse iterator for hierarchy
-                                            // state.focused = match state.focused.into_iter(&state.hierarchy).next() {
-                                            //     Some(val) => val,
-                                            //     None => state.root,
-                                            // };
-                                        }
-                                    } else {
-                                        if next_focus != Entity::null() {
-                                            state.focused = next_focus;
-                                        } else {
-                                            state.focused = match state.focused.into_iter(&hierarchy).next() {
-                                                Some(val) => val,
-                                                None => state.root,
-                                            };
-                                        }
-                                    }
-
-                                    state.insert_event(
-                                        Event::new(WindowEvent::Restyle).target(state.root),
-                                    );
-
-                                    
-                                }
-                            }
-
-                            match s {
-                                MouseButtonState::Pressed => {
-                                    if state.focused != Entity::null() {
-                                        state.insert_event(
-                                            Event::new(WindowEvent::KeyDown(input.virtual_keycode))
-                                            .target(state.focused)
-                                            .propagate(Propagation::DownUp),
-                                        );
-                                    } else {
-                                        state.insert_event(
-                                            Event::new(WindowEvent::KeyDown(input.virtual_keycode))
-                                            .target(state.hovered)
-                                            .propagate(Propagation::DownUp),
-                                        );
-                                    }
-                                }
-
-                                MouseButtonState::Released => {
-                                    if state.focused != Entity::null() {
-                                        state.insert_event(
-                                            Event::new(WindowEvent::KeyUp(input.virtual_keycode))
-                                            .target(state.focused)
-                                            .propagate(Propagation::DownUp),
-                                        );
-                                    } else {
-                                        state.insert_event(
-                                            Event::new(WindowEvent::KeyUp(input.virtual_keycode))
-                                            .target(state.hovered)
-                                            .propagate(Propagation::DownUp),
-                                        );
-                                    }
-                                }
-                            }
-
-
-                            
-                            
+                            keyboard_input(&mut state, input.virtual_keycode, s);
                         }
     
                         glutin::event::WindowEvent::Resized(logical_size) => {
                             let physical_size = logical_size;
 
-                            state.style.width.insert(state.root, Length::Pixels(physical_size.width as f32));
-                            state.style.height.insert(state.root, Length::Pixels(physical_size.height as f32));
-    
-                            state
-                                .transform
-                                .set_width(state.root, physical_size.width as f32);
-                            state
-                                .transform
-                                .set_height(state.root, physical_size.height as f32);
-
-    
-                            state.insert_event(Event::new(WindowEvent::Restyle));
-                            state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
-                            state.insert_event(Event::new(WindowEvent::Redraw));
-    
+                            window_resized(&mut state, physical_size.width as f32, physical_size.height as f32);
                         }
     
                         glutin::event::WindowEvent::CursorMoved {
@@ -298,124 +191,7 @@ impl Application {
                             position,
                             modifiers: _,
                         } => {
-                            let cursorx = (position.x) as f32;
-                            let cursory = (position.y) as f32;
-    
-                            state.mouse.cursorx = cursorx as f32;
-                            state.mouse.cursory = cursory as f32;
-    
-                            let mut hovered_widget = Entity::new(0, 0);
-    
-                            // This only really needs to be computed when the hierarchy changes
-                            // Can be optimised
-                            let mut draw_hierarchy: Vec<Entity> = state.hierarchy.into_iter().collect();
-    
-                            draw_hierarchy.sort_by_cached_key(|entity| state.transform.get_z_order(*entity));
-    
-    
-                            for widget in draw_hierarchy.into_iter() {
-                                // Skip invisible widgets
-                                if state.transform.get_visibility(widget) == Visibility::Invisible
-                                {
-                                    continue;
-                                }
-                                
-                                // This shouldn't be here but there's a bug if it isn't 
-                                if state.transform.get_opacity(widget) == 0.0 {
-                                    continue;
-                                }
-                                
-                                // Skip non-hoverable widgets
-                                if state.transform.get_hoverability(widget) != true {
-                                    continue;
-                                }
-    
-                                let border_width = state
-                                    .style
-                                    .border_width
-                                    .get(widget)
-                                    .cloned()
-                                    .unwrap_or_default();
-    
-                                let posx = state.transform.get_posx(widget) - (border_width / 2.0);
-                                let posy = state.transform.get_posy(widget) - (border_width / 2.0);
-                                let width = state.transform.get_width(widget) + (border_width);
-                                let height = state.transform.get_height(widget) + (border_width);
-
-                                let clip_widget = state.transform.get_clip_widget(widget);
-
-
-                                let clip_posx = state.transform.get_posx(clip_widget);
-                                let clip_posy = state.transform.get_posy(clip_widget);
-                                let clip_width = state.transform.get_width(clip_widget);
-                                let clip_height = state.transform.get_height(clip_widget);
-    
-                                if cursorx >= posx && cursorx >= clip_posx
-                                    && cursorx < (posx + width) && cursorx < (clip_posx + clip_width)
-                                    && cursory >= posy && cursory >= clip_posy
-                                    && cursory < (posy + height) && cursory < (clip_posy + clip_height)
-                                {
-                                    hovered_widget = widget;
-                                    if let Some(pseudo_classes) = state.style.pseudo_classes.get_mut(hovered_widget) {
-                                        pseudo_classes.set_over(true);
-                                    }
-                                } else {
-                                    if let Some(pseudo_classes) = state.style.pseudo_classes.get_mut(hovered_widget) {
-                                        pseudo_classes.set_over(false);
-                                    }
-                                }
-                            }
-    
-                            if hovered_widget != state.hovered {
-
-                                // Useful for debugging
-                            
-                                // println!(
-                                //     "Hover changed to {:?} parent: {:?}, posx: {}, posy: {} width: {} height: {} z_order: {}",
-                                //     hovered_widget,
-                                //     state.hierarchy.get_parent(hovered_widget),
-                                //     state.transform.get_posx(hovered_widget),
-                                //     state.transform.get_posy(hovered_widget),
-                                //     state.transform.get_width(hovered_widget),
-                                //     state.transform.get_height(hovered_widget),
-                                //     state.transform.get_z_order(hovered_widget),
-                                // );
-
-                                if let Some(pseudo_classes) = state.style.pseudo_classes.get_mut(hovered_widget) {
-                                    pseudo_classes.set_hover(true);
-                                }
-
-                                if let Some(pseudo_classes) = state.style.pseudo_classes.get_mut(state.hovered) {
-                                    pseudo_classes.set_hover(false);
-                                }
-    
-                                state.insert_event(Event::new(WindowEvent::MouseOver).target(hovered_widget));
-                                state.insert_event(Event::new(WindowEvent::MouseOut).target(state.hovered));
-    
-                                state.hovered = hovered_widget;
-                                state.active = Entity::null();
-    
-                                state
-                                    .insert_event(Event::new(WindowEvent::Restyle));
-                                state
-                                    .insert_event(Event::new(WindowEvent::Redraw));
-                            }
-    
-                            if state.captured != Entity::null() {
-                                state.insert_event(
-                                    Event::new(WindowEvent::MouseMove(cursorx, cursory))
-                                        .target(state.captured)
-                                        .propagate(Propagation::Direct),
-                                );
-                            } else if state.hovered != Entity::new(0, 0) {
-                                state.insert_event(
-                                    Event::new(WindowEvent::MouseMove(cursorx, cursory))
-                                        .target(state.hovered),
-                                );
-                            }
-    
-                            pos = (cursorx, cursory);
-
+                            cursor_moved(&mut state, position.x as f32, position.y as f32);
                         }
 
                         glutin::event::WindowEvent::MouseInput {
@@ -435,105 +211,8 @@ impl Application {
                                 glutin::event::MouseButton::Middle => MouseButton::Middle,
                                 glutin::event::MouseButton::Other(id) => MouseButton::Other(id),
                             };
-    
-                            match b {
-                                MouseButton::Left => {
-                                    state.mouse.left.state = s;
-                                }
-    
-                                MouseButton::Right => {
-                                    state.mouse.right.state = s;
-                                }
-    
-                                MouseButton::Middle => {
-                                    state.mouse.middle.state = s;
-                                }
-    
-                                _ => {}
-                            }
-    
-                            match s {
-                                MouseButtonState::Pressed => {
-
-                                    if state.hovered != Entity::null()
-                                        && state.active != state.hovered
-                                    {
-                                        state.active = state.hovered;
-                                        state.insert_event(Event::new(WindowEvent::Restyle));
-                                    }
-    
-                                    if state.captured != Entity::null() {
-                                        state.insert_event(
-                                            Event::new(WindowEvent::MouseDown(b))
-                                                .target(state.captured)
-                                                .propagate(Propagation::Direct),
-                                        );
-                                    } else {
-                                        state.insert_event(
-                                            Event::new(WindowEvent::MouseDown(b))
-                                                .target(state.hovered),
-                                        );
-                                    }
-
-                                    match b {
-                                        MouseButton::Left => {
-                                            state.mouse.left.pos_down = (state.mouse.cursorx, state.mouse.cursory);
-                                            state.mouse.left.pressed = state.hovered;
-                                        }
-
-                                        MouseButton::Middle => {
-                                            state.mouse.middle.pos_down = (state.mouse.cursorx, state.mouse.cursory);
-                                            state.mouse.left.pressed = state.hovered;
-                                        }
-                                        
-                                        MouseButton::Right => {
-                                            state.mouse.right.pos_down = (state.mouse.cursorx, state.mouse.cursory);
-                                            state.mouse.left.pressed = state.hovered;
-                                        } 
-                                        
-                                        _ => {}
-                                    }
-                                }
-    
-                                MouseButtonState::Released => {
 
-                            
-                                    state.active = Entity::null();
-                                    state.insert_event(Event::new(WindowEvent::Restyle));
-    
-                                    if state.captured != Entity::null() {
-                                        state.insert_event(
-                                            Event::new(WindowEvent::MouseUp(b))
-                                                .target(state.captured)
-                                                .propagate(Propagation::Direct),
-                                        );
-                                    } else {
-                                        state.insert_event(
-                                            Event::new(WindowEvent::MouseUp(b))
-                                                .target(state.hovered),
-                                        );
-                                    }
-
-                                    match b {
-                                        MouseButton::Left => {
-                                            state.mouse.left.pos_up = (state.mouse.cursorx, state.mouse.cursory);
-                                            state.mouse.left.released = state.hovered;
-                                        }
-                                        
-                                        MouseButton::Middle => {
-                                            state.mouse.middle.pos_up = (state.mouse.cursorx, state.mouse.cursory);
-                                            state.mouse.left.released = state.hovered;
-                                        } 
-                                          
-                                        MouseButton::Right => {
-                                            state.mouse.right.pos_up = (state.mouse.cursorx, state.mouse.cursory);
-                                            state.mouse.left.released = state.hovered;
-                                        }
-
-                                        _ => {}
-                                    }
-                                }
-                            }
+                            mouse_input(&mut state, b, s);
                         }
     
                         glutin::event::WindowEvent::MouseWheel {
@@ -547,15 +226,7 @@ impl Application {
                                 _ => (0.0, 0.0),
                             };
 
-                            if state.captured != Entity::null() {
-                                state.insert_event(
-                                    Event::new(WindowEvent::MouseScroll(x, y)).target(state.captured).propagate(Propagation::Direct)
-                                );
-                            } else {
-                                state.insert_event(
-                                    Event::new(WindowEvent::MouseScroll(x, y)).target(state.hovered)
-                                );
-                            }
+                            mouse_scroll(&mut state, x, y);
                         }
     
                         _ => {}
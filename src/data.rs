@@ -0,0 +1,217 @@
+// Data binding between application data and widgets.
+//
+// Application data lives in a Store widget. Widgets built inside the store can be bound to
+// parts of the data with a lens. Edits made with a bound widget are written to the data and
+// changes to the data are sent to every bound widget which shows the changed value.
+
+use crate::{BuildHandler, Entity, Event, EventHandler, Propagation, State, WindowEvent};
+
+use std::marker::PhantomData;
+
+// A lens selects a part of some data
+pub trait Lens: 'static {
+    type Source;
+    type Target;
+
+    fn view<'a>(&self, data: &'a Self::Source) -> &'a Self::Target;
+    fn view_mut<'a>(&self, data: &'a mut Self::Source) -> &'a mut Self::Target;
+}
+
+// A lens made from a pair of functions, usually created with the lens! macro
+pub struct Field<S, T> {
+    get: fn(&S) -> &T,
+    get_mut: fn(&mut S) -> &mut T,
+}
+
+impl<S, T> Field<S, T> {
+    pub fn new(get: fn(&S) -> &T, get_mut: fn(&mut S) -> &mut T) -> Self {
+        Field { get, get_mut }
+    }
+}
+
+impl<S, T> Clone for Field<S, T> {
+    fn clone(&self) -> Self {
+        Field {
+            get: self.get,
+            get_mut: self.get_mut,
+        }
+    }
+}
+
+impl<S: 'static, T: 'static> Lens for Field<S, T> {
+    type Source = S;
+    type Target = T;
+
+    fn view<'a>(&self, data: &'a S) -> &'a T {
+        (self.get)(data)
+    }
+
+    fn view_mut<'a>(&self, data: &'a mut S) -> &'a mut T {
+        (self.get_mut)(data)
+    }
+}
+
+// Creates a lens to a field of a type, e.g. lens!(AppData, settings.volume)
+#[macro_export]
+macro_rules! lens {
+    ($source:ty, $($field:tt).+) => {
+        $crate::Field::<$source, _>::new(
+            |data| &data.$($field).+,
+            |data| &mut data.$($field).+,
+        )
+    };
+}
+
+// Data held by a store
+pub trait Model: 'static {
+    // Called for every event received by the store. Bound widgets are updated
+    // with any changes made to the data afterwards.
+    fn on_event(&mut self, _state: &mut State, _entity: Entity, _event: &mut Event) {}
+}
+
+// Widgets which can show and edit a value of type T
+pub trait Bind<T: Clone + PartialEq + 'static> {
+    // Sends a new value from the data to the widget
+    fn set_value(state: &mut State, entity: Entity, value: &T);
+
+    // Returns the new value if the event reports that the widget was edited
+    fn value_changed(entity: Entity, event: &Event) -> Option<T>;
+
+    // Binds a widget to the part of the data in a store selected by the lens.
+    // The widget must be a descendant of the store so that its edits reach the store.
+    fn bind<L: Lens<Target = T>>(state: &mut State, store: Entity, entity: Entity, lens: L)
+    where
+        Self: Sized + 'static,
+    {
+        let binding: Box<dyn AnyBinding<L::Source>> = Box::new(Binding::<L, Self> {
+            entity,
+            lens,
+            value: None,
+            widget: PhantomData,
+        });
+
+        state.bindings.push((store, Box::new(binding)));
+
+        state.insert_event(
+            Event::new(StoreEvent::Bind)
+                .target(store)
+                .propagate(Propagation::Direct),
+        );
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum StoreEvent {
+    // Sent to a store when there are new bindings waiting for it
+    Bind,
+}
+
+trait AnyBinding<D> {
+    fn entity(&self) -> Entity;
+
+    // Writes an edit from the widget to the data, returning true if the data changed
+    fn edit(&mut self, data: &mut D, event: &Event) -> bool;
+
+    // Sends the value to the widget if it has changed since it was last sent
+    fn update(&mut self, data: &D, state: &mut State);
+}
+
+struct Binding<L: Lens, W> {
+    entity: Entity,
+    lens: L,
+    // The value last shown by the widget
+    value: Option<L::Target>,
+    widget: PhantomData<W>,
+}
+
+impl<L, W> AnyBinding<L::Source> for Binding<L, W>
+where
+    L: Lens,
+    L::Target: Clone + PartialEq + 'static,
+    W: Bind<L::Target>,
+{
+    fn entity(&self) -> Entity {
+        self.entity
+    }
+
+    fn edit(&mut self, data: &mut L::Source, event: &Event) -> bool {
+        if let Some(value) = W::value_changed(self.entity, event) {
+            if self.lens.view(data) != &value {
+                *self.lens.view_mut(data) = value.clone();
+                self.value = Some(value);
+                return true;
+            }
+        }
+
+        false
+    }
+
+    fn update(&mut self, data: &L::Source, state: &mut State) {
+        let value = self.lens.view(data);
+
+        if self.value.as_ref() != Some(value) {
+            W::set_value(state, self.entity, value);
+            self.value = Some(value.clone());
+
+            state.insert_event(Event::new(WindowEvent::Redraw).target(self.entity));
+        }
+    }
+}
+
+// A widget which holds application data and keeps bound widgets in sync with it
+pub struct Store<M: Model> {
+    data: M,
+    bindings: Vec<Box<dyn AnyBinding<M>>>,
+}
+
+impl<M: Model> Store<M> {
+    pub fn new(data: M) -> Self {
+        Store {
+            data,
+            bindings: Vec::new(),
+        }
+    }
+}
+
+impl<M: Model> BuildHandler for Store<M> {
+    type Ret = Entity;
+    fn on_build(&mut self, state: &mut State, entity: Entity) -> Self::Ret {
+        state.style.insert_element(entity, "store");
+
+        entity
+    }
+}
+
+impl<M: Model> EventHandler for Store<M> {
+    fn on_event(&mut self, state: &mut State, entity: Entity, event: &mut Event) -> bool {
+        // Take any bindings waiting for this store
+        let mut index = 0;
+        while index < state.bindings.len() {
+            if state.bindings[index].0 == entity {
+                let (_, binding) = state.bindings.remove(index);
+                if let Ok(binding) = binding.downcast::<Box<dyn AnyBinding<M>>>() {
+                    self.bindings.push(*binding);
+                }
+            } else {
+                index += 1;
+            }
+        }
+
+        // Forget widgets which have been removed
+        self.bindings
+            .retain(|binding| state.is_alive(binding.entity()));
+
+        for binding in self.bindings.iter_mut() {
+            binding.edit(&mut self.data, event);
+        }
+
+        self.data.on_event(state, entity, event);
+
+        // Only widgets showing a changed value are updated
+        for binding in self.bindings.iter_mut() {
+            binding.update(&self.data, state);
+        }
+
+        false
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{Entity, EventHandler, EventManager, Selector, State};
+use crate::{Entity, EventHandler, EventManager, Selector, State, Tooltip};
 
 use crate::{Align, Display, FlexDirection, Hierarchy, Justify};
 
@@ -85,16 +85,40 @@ impl<'a> Builder<'a> {
         self
     }
 
+    pub fn set_transform(mut self, value: Vec<TransformFunction>) -> Self {
+        self.state.style.transform.insert(self.entity, value);
+
+        self
+    }
+
+    pub fn set_transform_origin(mut self, value: TransformOrigin) -> Self {
+        self.state.style.transform_origin.insert(self.entity, value);
+
+        self
+    }
+
     pub fn set_checked(mut self, val: bool) -> Self {
         if let Some(pseudo_classes) = self.state.style.pseudo_classes.get_mut(self.entity) {
             pseudo_classes.set_checked(val);
         }
 
+        self.state.style.set_dirty(self.entity);
+
+        self
+    }
+
+    pub fn set_pseudo_class(mut self, name: &str, val: bool) -> Self {
+        if let Some(pseudo_classes) = self.state.style.pseudo_classes.get_mut(self.entity) {
+            pseudo_classes.set(name, val);
+        }
+
+        self.state.style.set_dirty(self.entity);
+
         self
     }
 
     pub fn set_z_order(mut self, val: i32) -> Self {
-        self.state.style.z_order.insert(self.entity, val);
+        self.state.style.z_order.insert(self.entity, ZIndex::Index(val));
 
         self
     }
@@ -121,6 +145,22 @@ impl<'a> Builder<'a> {
         self
     }
 
+    pub fn set_tooltip(mut self, val: &str) -> Self {
+        self.state
+            .style
+            .tooltip
+            .insert(self.entity, Tooltip::Text(val.to_string()));
+
+        self
+    }
+
+    // Shows another entity, usually built hidden, as the tooltip
+    pub fn set_tooltip_widget(mut self, val: Entity) -> Self {
+        self.state.style.tooltip.insert(self.entity, Tooltip::Widget(val));
+
+        self
+    }
+
     // Display
 
     pub fn set_display(mut self, val: Display) -> Self {
@@ -334,6 +374,12 @@ impl<'a> Builder<'a> {
         self
     }
 
+    pub fn set_flex_wrap(mut self, val: FlexWrap) -> Self {
+        self.state.style.flex_wrap.insert(self.entity, val);
+
+        self
+    }
+
     pub fn set_justify_content(mut self, val: JustifyContent) -> Self {
         self.state.style.justify_content.insert(self.entity, val);
 
@@ -352,6 +398,46 @@ impl<'a> Builder<'a> {
         self
     }
 
+    // Grid Container
+
+    pub fn set_grid_columns(mut self, val: Vec<TrackSize>) -> Self {
+        self.state.style.grid_template_columns.insert(self.entity, val);
+
+        self
+    }
+
+    pub fn set_grid_rows(mut self, val: Vec<TrackSize>) -> Self {
+        self.state.style.grid_template_rows.insert(self.entity, val);
+
+        self
+    }
+
+    pub fn set_grid_column_gap(mut self, val: Length) -> Self {
+        self.state.style.grid_column_gap.insert(self.entity, val);
+
+        self
+    }
+
+    pub fn set_grid_row_gap(mut self, val: Length) -> Self {
+        self.state.style.grid_row_gap.insert(self.entity, val);
+
+        self
+    }
+
+    // Grid Item
+
+    pub fn set_grid_column(mut self, val: GridPlacement) -> Self {
+        self.state.style.grid_column.insert(self.entity, val);
+
+        self
+    }
+
+    pub fn set_grid_row(mut self, val: GridPlacement) -> Self {
+        self.state.style.grid_row.insert(self.entity, val);
+
+        self
+    }
+
     // Border
 
     pub fn set_border_color(mut self, val: Color) -> Self {
@@ -381,17 +467,62 @@ impl<'a> Builder<'a> {
     }
 
     pub fn set_font(mut self, value: String) -> Self {
-        if let Some(data) = self.state.style.text.get_mut(self.entity) {
-            data.font = value;
-        } else {
-            self.state.style.text.insert(
-                self.entity,
-                Text {
-                    font: value,
-                    ..Default::default()
-                },
-            );
-        }
+        self.state.style.font_family.insert(self.entity, vec![value]);
+
+        self
+    }
+
+    pub fn set_font_family(mut self, value: Vec<String>) -> Self {
+        self.state.style.font_family.insert(self.entity, value);
+
+        self
+    }
+
+    pub fn set_font_weight(mut self, value: FontWeight) -> Self {
+        self.state.style.font_weight.insert(self.entity, value);
+
+        self
+    }
+
+    pub fn set_font_style(mut self, value: FontStyle) -> Self {
+        self.state.style.font_style.insert(self.entity, value);
+
+        self
+    }
+
+    pub fn set_font_size(mut self, value: f32) -> Self {
+        self.state.style.font_size.insert(self.entity, value);
+
+        self
+    }
+
+    pub fn set_font_color(mut self, value: Color) -> Self {
+        self.state.style.font_color.insert(self.entity, value);
+
+        self
+    }
+
+    // Text Layout
+    pub fn set_white_space(mut self, value: WhiteSpace) -> Self {
+        self.state.style.white_space.insert(self.entity, value);
+
+        self
+    }
+
+    pub fn set_line_height(mut self, value: LineHeight) -> Self {
+        self.state.style.line_height.insert(self.entity, value);
+
+        self
+    }
+
+    pub fn set_letter_spacing(mut self, value: f32) -> Self {
+        self.state.style.letter_spacing.insert(self.entity, value);
+
+        self
+    }
+
+    pub fn set_text_overflow(mut self, value: TextOverflow) -> Self {
+        self.state.style.text_overflow.insert(self.entity, value);
 
         self
     }
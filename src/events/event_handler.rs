@@ -2,7 +2,7 @@ use crate::events::{Event, EventManager, Message};
 
 use crate::build_handler::Builder;
 
-use crate::{Entity, Hierarchy, State, Window};
+use crate::{text_layout, Entity, Hierarchy, State, Window};
 
 use std::collections::{HashMap, VecDeque};
 
@@ -77,36 +77,38 @@ pub trait EventHandler {
             .style
             .padding_left
             .get(entity)
-            .unwrap_or(&Length::Auto)
+            .cloned()
+            .unwrap_or_default()
         {
             Length::Pixels(val) => val,
-            _ => &0.0,
+            _ => 0.0,
         };
 
         let padding_right = match state
             .style
             .padding_right
             .get(entity)
-            .unwrap_or(&Length::Auto)
+            .cloned()
+            .unwrap_or_default()
         {
             Length::Pixels(val) => val,
-            _ => &0.0,
+            _ => 0.0,
         };
 
-        let padding_top = match state.style.padding_top.get(entity).unwrap_or(&Length::Auto)
-        {
+        let padding_top = match state.style.padding_top.get(entity).cloned().unwrap_or_default() {
             Length::Pixels(val) => val,
-            _ => &0.0,
+            _ => 0.0,
         };
 
         let padding_bottom = match state
             .style
             .padding_bottom
             .get(entity)
-            .unwrap_or(&Length::Auto)
+            .cloned()
+            .unwrap_or_default()
         {
             Length::Pixels(val) => val,
-            _ => &0.0,
+            _ => 0.0,
         };
 
         let background_color = state
@@ -144,24 +146,28 @@ pub trait EventHandler {
         let border_radius_top_left = match border_radius.top_left {
             Length::Pixels(val) => val,
             Length::Percentage(val) => parent_width * val,
+            Length::Calc(pixels, val) => pixels + parent_width * val,
             _ => 0.0,
         };
 
         let border_radius_top_right = match border_radius.top_right {
             Length::Pixels(val) => val,
             Length::Percentage(val) => parent_width * val,
+            Length::Calc(pixels, val) => pixels + parent_width * val,
             _ => 0.0,
         };
 
         let border_radius_bottom_left = match border_radius.bottom_left {
             Length::Pixels(val) => val,
             Length::Percentage(val) => parent_width * val,
+            Length::Calc(pixels, val) => pixels + parent_width * val,
             _ => 0.0,
         };
 
         let border_radius_bottom_right = match border_radius.bottom_right {
             Length::Pixels(val) => val,
             Length::Percentage(val) => parent_width * val,
+            Length::Calc(pixels, val) => pixels + parent_width * val,
             _ => 0.0,
         };
 
@@ -182,16 +188,6 @@ pub trait EventHandler {
 
         //println!("Border Width: {}", border_width);
 
-        let rotate = state.style.rotate.get(entity).unwrap_or(&0.0);
-
-        canvas.save();
-        canvas.translate(posx + width / 2.0, posy + height / 2.0);
-        canvas.rotate(rotate.to_radians());
-        canvas.translate(-(posx + width / 2.0), -(posy + height / 2.0));
-        //canvas.translate(posx + width / 2.0, posy + width / 2.0);
-        
-
-        
         let mut path = Path::new();
         path.rounded_rect_varying(posx, posy, width, height, border_radius_top_left, border_radius_top_right, border_radius_bottom_right, border_radius_bottom_left);
         let mut paint = Paint::color(background_color);
@@ -204,18 +200,24 @@ pub trait EventHandler {
         // canvas.scale(0.5,0.5);
         // canvas.translate(-posx-0.5*width, -posy-0.5*height);
 
-        if let Some(text) = state.style.text.get_mut(entity) {
-           
-            let font_id = match text.font.as_ref() {
-                "Sans" => state.fonts.regular.unwrap(),
-                "Icons" => state.fonts.icons.unwrap(),
-                _ => state.fonts.regular.unwrap(),
-            };
-
-            let mut x = posx;
-            let mut y = posy;
-
-            let text_string = text.text.to_owned();
+        // Text is broken into lines which fit the content box
+        let content_width = width - padding_left - padding_right;
+        let content_height = height - padding_top - padding_bottom;
+
+        if let Some(text_layout) = text_layout(state, entity, content_width, content_height) {
+            let font_family = state.style.font_family.get(entity).cloned().unwrap_or_default();
+            let font_weight = state.style.font_weight.get(entity).cloned().unwrap_or_default();
+            let font_style = state.style.font_style.get(entity).cloned().unwrap_or_default();
+            let font_size = state.style.font_size.get(entity).cloned().unwrap_or(14.0);
+            let letter_spacing = state.style.letter_spacing.get(entity).cloned().unwrap_or_default();
+
+            let font_ids = state.fonts.font_ids(
+                canvas,
+                &state.style.font_faces,
+                &font_family,
+                font_weight,
+                font_style,
+            );
 
             let text_align = state
                 .style
@@ -230,51 +232,38 @@ pub trait EventHandler {
                 .cloned()
                 .unwrap_or_default();
 
-            let align = match text_justify {
-                Justify::Start => {
-                    x += padding_left;
-                    Align::Left
-                }
-                Justify::Center => {
-                    x += 0.5 * width;
-                    Align::Center
-                }
-                Justify::End => {
-                    x += width - padding_right;
-                    Align::Right
-                }
+            let (x, align) = match text_justify {
+                Justify::Start => (posx + padding_left, Align::Left),
+                Justify::Center => (posx + padding_left + 0.5 * content_width, Align::Center),
+                Justify::End => (posx + width - padding_right, Align::Right),
             };
 
-            let baseline = match text_align {
-                crate::Align::Start => {
-                    y += padding_top;
-                    Baseline::Top
-                }
-                crate::Align::Center => {
-                    y += 0.5 * height;
-                    Baseline::Middle
-                }
-                crate::Align::End => {
-                    y += height - padding_bottom;
-                    Baseline::Bottom
-                }
+            // The lines are aligned as a block
+            let text_height = text_layout.height();
+            let mut y = match text_align {
+                crate::Align::Start => posy + padding_top,
+                crate::Align::Center => posy + padding_top + 0.5 * (content_height - text_height),
+                crate::Align::End => posy + height - padding_bottom - text_height,
             };
 
             let mut font_color: femtovg::Color = font_color.into();
             font_color.set_alphaf(font_color.a * opacity);
 
             let mut paint = Paint::color(font_color);
-            paint.set_font_size(text.font_size);
-            paint.set_font(&[font_id]);
+            paint.set_font_size(font_size);
+            paint.set_font(&font_ids);
+            paint.set_letter_spacing(letter_spacing);
             paint.set_text_align(align);
-            paint.set_text_baseline(baseline);
+            paint.set_text_baseline(Baseline::Middle);
             paint.set_anti_alias(false);
 
-            canvas.fill_text(x, y, &text_string, paint);
+            // Each line is centred vertically in its line height
+            for line in text_layout.lines.iter() {
+                canvas.fill_text(x, y + 0.5 * text_layout.line_height, &line.text, paint.clone());
+                y += text_layout.line_height;
+            }
         }
 
-        canvas.restore();
-
 
         
         /*
@@ -421,24 +410,28 @@ pub trait EventHandler {
                     let border_radius_top_left = match border_radius.top_left {
                         Length::Pixels(val) => val,
                         Length::Percentage(val) => parent_width * val,
+                        Length::Calc(pixels, val) => pixels + parent_width * val,
                         _ => 0.0,
                     };
     
                     let border_radius_top_right = match border_radius.top_right {
                         Length::Pixels(val) => val,
                         Length::Percentage(val) => parent_width * val,
+                        Length::Calc(pixels, val) => pixels + parent_width * val,
                         _ => 0.0,
                     };
     
                     let border_radius_bottom_left = match border_radius.bottom_left {
                         Length::Pixels(val) => val,
                         Length::Percentage(val) => parent_width * val,
+                        Length::Calc(pixels, val) => pixels + parent_width * val,
                         _ => 0.0,
                     };
     
                     let border_radius_bottom_right = match border_radius.bottom_right {
                         Length::Pixels(val) => val,
                         Length::Percentage(val) => parent_width * val,
+                        Length::Calc(pixels, val) => pixels + parent_width * val,
                         _ => 0.0,
                     };
     
@@ -2,6 +2,7 @@ use crate::{
     BuildHandler, Builder, Entity, Event, EventHandler, Hierarchy, HierarchyTree,
     IntoHierarchyIterator, IntoParentIterator, State, WidgetEvent, Window, WindowEvent, CursorIcon
 };
+use crate::{draw_scrollbars, scroll_input, ScrollDrag};
 use std::collections::{HashMap, VecDeque};
 
 use std::time::{Duration, Instant};
@@ -30,6 +31,10 @@ pub struct EventManager {
     pub event_queue: Vec<Event>,
     needs_redraw: bool,
     total_frames: usize,
+    // Cursor icon requested by a SetCursor event, applied to the window after flushing
+    cursor_icon: Option<CursorIcon>,
+    // The scrollbar thumb being dragged with the mouse
+    scroll_drag: Option<ScrollDrag>,
     pub start_time: std::time::Instant,
 }
 
@@ -40,6 +45,8 @@ impl EventManager {
             event_queue: Vec::new(),
             needs_redraw: false,
             total_frames: 0,
+            cursor_icon: None,
+            scroll_drag: None,
             start_time: std::time::Instant::now(),
         }
     }
@@ -49,6 +56,29 @@ impl EventManager {
     // }
 
     pub fn flush_events(&mut self, state: &mut State, window: &mut Window) -> bool {
+        let needs_redraw = self.process_events(state);
+
+        if let Some(cursor_icon) = self.cursor_icon.take() {
+            match cursor_icon {
+                CursorIcon::Arrow => {
+                    window.handle.window().set_cursor_icon(glutin::window::CursorIcon::Arrow);
+                }
+
+                CursorIcon::NResize => {
+                    window.handle.window().set_cursor_icon(glutin::window::CursorIcon::NResize);
+                }
+
+                CursorIcon::EResize => {
+                    window.handle.window().set_cursor_icon(glutin::window::CursorIcon::EResize);
+                }
+            }
+        }
+
+        needs_redraw
+    }
+
+    // Sends the queued events to their handlers without a window, returning true if a redraw is needed
+    pub fn process_events(&mut self, state: &mut State) -> bool {
         //println!("FLUSH");
 
         let mut needs_redraw = false;
@@ -66,6 +96,11 @@ impl EventManager {
         // Move event handlers from state to event manager
         self.event_handlers.extend(state.event_handlers.drain());
 
+        // Drop the event handlers of removed entities
+        for entity in state.removed.drain(..) {
+            self.event_handlers.remove(&entity);
+        }
+
         // Move events from state into event manager
         let event_queue = state.event_queue.clone();
         //self.event_queue.append(&mut state.event_queue);
@@ -78,6 +113,13 @@ impl EventManager {
 
         // Loop over the events in the event manager queue
         'events: for event in self.event_queue.iter_mut() {
+            // Entities can be removed by the previous event handler
+            if !state.removed.is_empty() {
+                for entity in state.removed.drain(..) {
+                    self.event_handlers.remove(&entity);
+                }
+            }
+
             //println!("Event: {:?}", event);
             // If a redraw is needed then set the flag to return true
             if let Some(window_event) = event.message.downcast::<WindowEvent>() {
@@ -87,20 +129,7 @@ impl EventManager {
                     }
 
                     WindowEvent::SetCursor(cursor_icon) => {
-                        match cursor_icon {
-                            CursorIcon::Arrow => {
-                                window.handle.window().set_cursor_icon(glutin::window::CursorIcon::Arrow);
-                            }
-
-                            CursorIcon::NResize => {
-                                window.handle.window().set_cursor_icon(glutin::window::CursorIcon::NResize);
-                            }
-
-                            CursorIcon::EResize => {
-                                window.handle.window().set_cursor_icon(glutin::window::CursorIcon::EResize);
-                            }
-                        }
-                    
+                        self.cursor_icon = Some(*cursor_icon);
                     }
 
                     _ => {}
@@ -182,6 +211,9 @@ impl EventManager {
                     }
                 }
             }
+
+            // No handler used the event
+            scroll_input(state, &mut self.scroll_drag, event);
         }
 
         return needs_redraw;
@@ -194,26 +226,92 @@ impl EventManager {
 
         //println!("Width: {}  Height: {}", size.width, size.height);
 
-        window.canvas.set_size(size.width as u32, size.height as u32, dpi_factor as f32);
-        window.canvas.clear_rect(0, 0, size.width as u32, size.height as u32, Color::rgb(60, 60, 60));
+        self.draw_to_canvas(state, &mut window.canvas, size.width, size.height, dpi_factor as f32);
+
+        window.canvas.flush();
+
+        window
+            .handle
+            .swap_buffers()
+            .expect("Failed to swap buffers");
+    }
+
+    // Draws the widgets in z order onto a canvas of the given size without flushing it
+    pub fn draw_to_canvas(
+        &mut self,
+        state: &mut State,
+        canvas: &mut Canvas<OpenGl>,
+        width: u32,
+        height: u32,
+        dpi_factor: f32,
+    ) {
+        canvas.set_size(width, height, dpi_factor);
+        canvas.clear_rect(0, 0, width, height, Color::rgb(60, 60, 60));
 
         let hierarchy = state.hierarchy.clone();
 
-        let mut draw_hierarchy: Vec<Entity> = hierarchy.into_iter().collect();
-        draw_hierarchy.sort_by_cached_key(|entity| state.transform.get_z_order(*entity));
+        let draw_order = state.transform.draw_order.clone();
 
+        // Scrollbars are drawn over the contents, once the last descendant of their entity is drawn
+        let mut scrolling: Vec<Entity> = Vec::new();
+
+        for widget in draw_order.into_iter() {
+            while let Some(entity) = scrolling.last().cloned() {
+                if widget.is_descendant_of(&hierarchy, entity) {
+                    break;
+                }
+
+                scrolling.pop();
+                draw_transformed(state, canvas, entity, |state, canvas| {
+                    draw_scrollbars(state, entity, canvas)
+                });
+            }
 
-        for widget in draw_hierarchy.into_iter() {
             if let Some(event_handler) = self.event_handlers.get_mut(&widget) {
-                event_handler.on_draw(state, widget, &mut window.canvas);
+                draw_transformed(state, canvas, widget, |state, canvas| {
+                    event_handler.on_draw(state, widget, canvas)
+                });
+            }
+
+            let overflow = state.style.overflow.get(widget).cloned().unwrap_or_default();
+            if overflow.scrolls() {
+                scrolling.push(widget);
             }
         }
 
-        window.canvas.flush();
+        while let Some(entity) = scrolling.pop() {
+            draw_transformed(state, canvas, entity, |state, canvas| {
+                draw_scrollbars(state, entity, canvas)
+            });
+        }
+    }
+}
 
-        window
-            .handle
-            .swap_buffers()
-            .expect("Failed to swap buffers");
+// Draws an entity clipped by its clip widgets, each in its own transform, and with its own transform applied
+// so that widgets draw in their laid out bounds
+fn draw_transformed<F>(state: &mut State, canvas: &mut Canvas<OpenGl>, entity: Entity, draw: F)
+where
+    F: FnOnce(&mut State, &mut Canvas<OpenGl>),
+{
+    canvas.save();
+
+    for clip_widget in state.transform.get_clip_widgets(entity) {
+        let [a, b, c, d, e, f] = state.transform.get_matrix(clip_widget).0;
+        canvas.reset_transform();
+        canvas.set_transform(a, b, c, d, e, f);
+        canvas.intersect_scissor(
+            state.transform.get_posx(clip_widget),
+            state.transform.get_posy(clip_widget),
+            state.transform.get_width(clip_widget),
+            state.transform.get_height(clip_widget),
+        );
     }
+
+    let [a, b, c, d, e, f] = state.transform.get_matrix(entity).0;
+    canvas.reset_transform();
+    canvas.set_transform(a, b, c, d, e, f);
+
+    draw(state, canvas);
+
+    canvas.restore();
 }
@@ -0,0 +1,392 @@
+// Translates raw input into events. Used by the application event loop and by the test driver.
+
+use crate::{Entity, Event, Propagation, State, Visibility, WindowEvent};
+use crate::{dismiss_overlays, hide_tooltip, hover_tooltip, outside_modal, scrollbar_at, IntoHierarchyIterator, IntoParentIterator, Length, VirtualKeyCode};
+
+use crate::state::mouse::{MouseButton, MouseButtonState};
+
+// Returns the topmost hoverable widget under the given point, or the root if there isn't one.
+// Widgets are tested front to back in the order they are drawn, so the first one containing the point is the topmost.
+pub fn hit_test(state: &mut State, x: f32, y: f32) -> Entity {
+    let mut hovered_widget = Entity::new(0, 0);
+
+    let draw_order = state.transform.draw_order.clone();
+
+    for widget in draw_order.iter().rev() {
+        if contains(state, *widget, x, y) {
+            hovered_widget = *widget;
+            break;
+        }
+    }
+
+    // Scrollbars are drawn over the contents of their entity
+    let ancestors: Vec<Entity> = hovered_widget.parent_iter(&state.hierarchy).collect();
+    for ancestor in ancestors.into_iter() {
+        if scrollbar_at(state, ancestor, x, y).is_some() && !clipped(state, ancestor, x, y) {
+            hovered_widget = ancestor;
+        }
+    }
+
+    // The point is over the hovered widget and its ancestors, but not the widgets hidden behind them
+    let ancestors: Vec<Entity> = hovered_widget.parent_iter(&state.hierarchy).collect();
+    for widget in draw_order.into_iter() {
+        let over = ancestors.contains(&widget);
+        if let Some(pseudo_classes) = state.style.pseudo_classes.get_mut(widget) {
+            if pseudo_classes.get("over") != over {
+                pseudo_classes.set_over(over);
+                state.style.set_dirty(widget);
+            }
+        }
+    }
+
+    hovered_widget
+}
+
+// Returns true if a point is inside the visible bounds of a hoverable widget
+fn contains(state: &State, widget: Entity, x: f32, y: f32) -> bool {
+    // Skip invisible widgets
+    if state.transform.get_visibility(widget) == Visibility::Invisible {
+        return false;
+    }
+
+    // This shouldn't be here but there's a bug if it isn't
+    if state.transform.get_opacity(widget) == 0.0 {
+        return false;
+    }
+
+    // Skip non-hoverable widgets
+    if state.transform.get_hoverability(widget) != true {
+        return false;
+    }
+
+    let border_width = state
+        .style
+        .border_width
+        .get(widget)
+        .cloned()
+        .unwrap_or_default();
+
+    let posx = state.transform.get_posx(widget) - (border_width / 2.0);
+    let posy = state.transform.get_posy(widget) - (border_width / 2.0);
+    let width = state.transform.get_width(widget) + (border_width);
+    let height = state.transform.get_height(widget) + (border_width);
+
+    // The point is compared with the bounds before they were transformed
+    match state.transform.to_local(widget, x, y) {
+        Some((localx, localy)) => {
+            localx >= posx
+                && localx < (posx + width)
+                && localy >= posy
+                && localy < (posy + height)
+                && !clipped(state, widget, x, y)
+        }
+
+        // A widget which is scaled to nothing can't be hovered
+        None => false,
+    }
+}
+
+// Returns true if a point is outside of any of the clip widgets of an entity
+fn clipped(state: &State, entity: Entity, x: f32, y: f32) -> bool {
+    state
+        .transform
+        .get_clip_widgets(entity)
+        .into_iter()
+        .any(|clip_widget| match state.transform.to_local(clip_widget, x, y) {
+            Some((clipx, clipy)) => {
+                let clip_posx = state.transform.get_posx(clip_widget);
+                let clip_posy = state.transform.get_posy(clip_widget);
+                let clip_width = state.transform.get_width(clip_widget);
+                let clip_height = state.transform.get_height(clip_widget);
+
+                clipx < clip_posx
+                    || clipx >= clip_posx + clip_width
+                    || clipy < clip_posy
+                    || clipy >= clip_posy + clip_height
+            }
+
+            None => true,
+        })
+}
+
+pub fn cursor_moved(state: &mut State, cursorx: f32, cursory: f32) {
+    state.mouse.cursorx = cursorx;
+    state.mouse.cursory = cursory;
+
+    let hovered_widget = hit_test(state, cursorx, cursory);
+
+    if hovered_widget != state.hovered {
+        // Useful for debugging
+
+        // println!(
+        //     "Hover changed to {:?} parent: {:?}, posx: {}, posy: {} width: {} height: {} z_order: {}",
+        //     hovered_widget,
+        //     state.hierarchy.get_parent(hovered_widget),
+        //     state.transform.get_posx(hovered_widget),
+        //     state.transform.get_posy(hovered_widget),
+        //     state.transform.get_width(hovered_widget),
+        //     state.transform.get_height(hovered_widget),
+        //     state.transform.get_z_order(hovered_widget),
+        // );
+
+        if let Some(pseudo_classes) = state.style.pseudo_classes.get_mut(hovered_widget) {
+            pseudo_classes.set_hover(true);
+        }
+
+        if let Some(pseudo_classes) = state.style.pseudo_classes.get_mut(state.hovered) {
+            pseudo_classes.set_hover(false);
+        }
+
+        state.style.set_dirty(hovered_widget);
+        state.style.set_dirty(state.hovered);
+
+        state.insert_event(Event::new(WindowEvent::MouseOver).target(hovered_widget));
+        state.insert_event(Event::new(WindowEvent::MouseOut).target(state.hovered));
+
+        state.hovered = hovered_widget;
+        state.active = Entity::null();
+
+        hover_tooltip(state);
+
+        state.insert_event(Event::new(WindowEvent::Restyle));
+        state.insert_event(Event::new(WindowEvent::Redraw));
+    }
+
+    if state.captured != Entity::null() {
+        state.insert_event(
+            Event::new(WindowEvent::MouseMove(cursorx, cursory))
+                .target(state.captured)
+                .propagate(Propagation::Direct),
+        );
+    } else if state.hovered != Entity::new(0, 0) {
+        state.insert_event(
+            Event::new(WindowEvent::MouseMove(cursorx, cursory)).target(state.hovered),
+        );
+    }
+}
+
+pub fn mouse_input(state: &mut State, button: MouseButton, button_state: MouseButtonState) {
+    match button {
+        MouseButton::Left => {
+            state.mouse.left.state = button_state;
+        }
+
+        MouseButton::Right => {
+            state.mouse.right.state = button_state;
+        }
+
+        MouseButton::Middle => {
+            state.mouse.middle.state = button_state;
+        }
+
+        _ => {}
+    }
+
+    match button_state {
+        MouseButtonState::Pressed => {
+            hide_tooltip(state);
+
+            // Pressing outside of a popup closes it without pressing what was under it
+            if dismiss_overlays(state) {
+                state.mouse.left.pressed = Entity::null();
+                return;
+            }
+
+            if state.hovered != Entity::null() && state.active != state.hovered {
+                state.active = state.hovered;
+                state.insert_event(Event::new(WindowEvent::Restyle));
+            }
+
+            if state.captured != Entity::null() {
+                state.insert_event(
+                    Event::new(WindowEvent::MouseDown(button))
+                        .target(state.captured)
+                        .propagate(Propagation::Direct),
+                );
+            } else {
+                state.insert_event(Event::new(WindowEvent::MouseDown(button)).target(state.hovered));
+            }
+
+            match button {
+                MouseButton::Left => {
+                    state.mouse.left.pos_down = (state.mouse.cursorx, state.mouse.cursory);
+                    state.mouse.left.pressed = state.hovered;
+                }
+
+                MouseButton::Middle => {
+                    state.mouse.middle.pos_down = (state.mouse.cursorx, state.mouse.cursory);
+                    state.mouse.left.pressed = state.hovered;
+                }
+
+                MouseButton::Right => {
+                    state.mouse.right.pos_down = (state.mouse.cursorx, state.mouse.cursory);
+                    state.mouse.left.pressed = state.hovered;
+                }
+
+                _ => {}
+            }
+        }
+
+        MouseButtonState::Released => {
+            state.active = Entity::null();
+            state.insert_event(Event::new(WindowEvent::Restyle));
+
+            if state.captured != Entity::null() {
+                state.insert_event(
+                    Event::new(WindowEvent::MouseUp(button))
+                        .target(state.captured)
+                        .propagate(Propagation::Direct),
+                );
+            } else {
+                state.insert_event(Event::new(WindowEvent::MouseUp(button)).target(state.hovered));
+            }
+
+            match button {
+                MouseButton::Left => {
+                    state.mouse.left.pos_up = (state.mouse.cursorx, state.mouse.cursory);
+                    state.mouse.left.released = state.hovered;
+                }
+
+                MouseButton::Middle => {
+                    state.mouse.middle.pos_up = (state.mouse.cursorx, state.mouse.cursory);
+                    state.mouse.left.released = state.hovered;
+                }
+
+                MouseButton::Right => {
+                    state.mouse.right.pos_up = (state.mouse.cursorx, state.mouse.cursory);
+                    state.mouse.left.released = state.hovered;
+                }
+
+                _ => {}
+            }
+        }
+    }
+}
+
+pub fn mouse_scroll(state: &mut State, x: f32, y: f32) {
+    if state.captured != Entity::null() {
+        state.insert_event(
+            Event::new(WindowEvent::MouseScroll(x, y))
+                .target(state.captured)
+                .propagate(Propagation::Direct),
+        );
+    } else {
+        state.insert_event(Event::new(WindowEvent::MouseScroll(x, y)).target(state.hovered));
+    }
+}
+
+pub fn keyboard_input(
+    state: &mut State,
+    virtual_keycode: Option<VirtualKeyCode>,
+    key_state: MouseButtonState,
+) {
+    if virtual_keycode == Some(VirtualKeyCode::Tab) && key_state == MouseButtonState::Pressed {
+        let next_focus = state
+            .style
+            .focus_order
+            .get(state.focused)
+            .cloned()
+            .unwrap_or_default()
+            .next;
+        let prev_focus = state
+            .style
+            .focus_order
+            .get(state.focused)
+            .cloned()
+            .unwrap_or_default()
+            .prev;
+
+        if state.modifiers.shift {
+            if prev_focus != Entity::null() {
+                state.focused = prev_focus;
+            } else {
+                // TODO impliment reverse iterator for hierarchy
+                // state.focused = match state.focused.into_iter(&state.hierarchy).next() {
+                //     Some(val) => val,
+                //     None => state.root,
+                // };
+            }
+        } else {
+            if next_focus != Entity::null() {
+                state.focused = next_focus;
+            } else {
+                // The iterator starts with the focused entity itself
+                let hierarchy = state.hierarchy.clone();
+                state.focused = match state.focused.into_iter(&hierarchy).nth(1) {
+                    Some(val) => val,
+                    None => state.root,
+                };
+            }
+        }
+
+        // Focus wraps around inside a modal overlay
+        if outside_modal(state, state.focused) {
+            if let Some(modal) = state.overlays.modal() {
+                state.focused = modal.entity;
+            }
+        }
+
+        state.insert_event(Event::new(WindowEvent::Restyle).target(state.root));
+    }
+
+    // Escape hides a tooltip, or closes the topmost popup, instead of going to the focused widget
+    if virtual_keycode == Some(VirtualKeyCode::Escape) && key_state == MouseButtonState::Pressed {
+        if hide_tooltip(state) {
+            return;
+        }
+
+        if let Some(overlay) = state.overlays.top().cloned() {
+            if overlay.light_dismiss {
+                state.close_overlay(overlay.entity);
+                return;
+            }
+        }
+    }
+
+    // Key events go to the focused widget, or the hovered widget if nothing has focus
+    let target = if state.focused != Entity::null() {
+        state.focused
+    } else {
+        state.hovered
+    };
+
+    match key_state {
+        MouseButtonState::Pressed => {
+            state.insert_event(
+                Event::new(WindowEvent::KeyDown(virtual_keycode))
+                    .target(target)
+                    .propagate(Propagation::DownUp),
+            );
+        }
+
+        MouseButtonState::Released => {
+            state.insert_event(
+                Event::new(WindowEvent::KeyUp(virtual_keycode))
+                    .target(target)
+                    .propagate(Propagation::DownUp),
+            );
+        }
+    }
+}
+
+pub fn char_input(state: &mut State, input: char) {
+    state.insert_event(
+        Event::new(WindowEvent::CharInput(input))
+            .target(state.focused)
+            .propagate(Propagation::Down),
+    );
+}
+
+pub fn window_resized(state: &mut State, width: f32, height: f32) {
+    state.style.width.insert(state.root, Length::Pixels(width));
+    state.style.height.insert(state.root, Length::Pixels(height));
+
+    state.transform.set_width(state.root, width);
+    state.transform.set_height(state.root, height);
+
+    state.transform.set_layout_dirty(state.root);
+
+    state.insert_event(Event::new(WindowEvent::Restyle));
+    state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
+    state.insert_event(Event::new(WindowEvent::Redraw));
+}
@@ -12,3 +12,5 @@ pub use event_handler::*;
 
 pub mod build_handler;
 pub use build_handler::*;
+
+pub mod input;
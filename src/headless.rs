@@ -0,0 +1,327 @@
+use glutin::dpi::PhysicalSize;
+use glutin::{Context, ContextBuilder, PossiblyCurrent};
+
+use femtovg::{renderer::OpenGl, Canvas};
+
+use image::{Rgba, RgbaImage};
+
+use std::path::{Path, PathBuf};
+
+use crate::application::init_root;
+use crate::events::{Event, EventManager};
+use crate::window::{WindowDescription, WindowEvent};
+use crate::{Entity, Length, State};
+
+// An offscreen OpenGL context which renders into a buffer in memory instead of a window
+pub struct HeadlessWindow {
+    pub context: Context<PossiblyCurrent>,
+    pub canvas: Canvas<OpenGl>,
+    pub width: u32,
+    pub height: u32,
+    // Headless contexts on some platforms need an event loop to stay alive
+    #[cfg(not(any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    )))]
+    _event_loop: glutin::event_loop::EventLoop<()>,
+}
+
+impl HeadlessWindow {
+    // On unix an OSMesa context is used so that no display server or GPU is needed
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    ))]
+    pub fn new(width: u32, height: u32) -> Self {
+        use glutin::platform::unix::HeadlessContextExt;
+
+        let context = ContextBuilder::new()
+            .build_osmesa(PhysicalSize::new(width, height))
+            .expect("Headless context creation failed!");
+
+        let (context, canvas) = Self::create_canvas(context, width, height);
+
+        HeadlessWindow {
+            context,
+            canvas,
+            width,
+            height,
+        }
+    }
+
+    #[cfg(not(any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    )))]
+    pub fn new(width: u32, height: u32) -> Self {
+        #[cfg(target_os = "windows")]
+        let event_loop = {
+            use glutin::platform::windows::EventLoopExtWindows;
+            glutin::event_loop::EventLoop::new_any_thread()
+        };
+
+        #[cfg(not(target_os = "windows"))]
+        let event_loop = glutin::event_loop::EventLoop::new();
+
+        let context = ContextBuilder::new()
+            .build_headless(&event_loop, PhysicalSize::new(width, height))
+            .expect("Headless context creation failed!");
+
+        let (context, canvas) = Self::create_canvas(context, width, height);
+
+        HeadlessWindow {
+            context,
+            canvas,
+            width,
+            height,
+            _event_loop: event_loop,
+        }
+    }
+
+    fn create_canvas(
+        context: Context<glutin::NotCurrent>,
+        width: u32,
+        height: u32,
+    ) -> (Context<PossiblyCurrent>, Canvas<OpenGl>) {
+        let context = unsafe { context.make_current().unwrap() };
+
+        gl::load_with(|ptr| context.get_proc_address(ptr) as *const _);
+
+        let renderer = OpenGl::new(|s| context.get_proc_address(s) as *const _)
+            .expect("Cannot create renderer");
+        let mut canvas = Canvas::new(renderer).expect("Cannot create canvas");
+
+        canvas.set_size(width, height, 1.0);
+
+        (context, canvas)
+    }
+
+    // Reads back the contents of the canvas as an RGBA image
+    pub fn read_pixels(&mut self) -> RgbaImage {
+        let screenshot = self
+            .canvas
+            .screenshot()
+            .expect("Failed to read pixels from canvas");
+
+        let mut image = RgbaImage::new(screenshot.width() as u32, screenshot.height() as u32);
+
+        for (y, row) in screenshot.rows().enumerate() {
+            for (x, pixel) in row.iter().enumerate() {
+                image.put_pixel(
+                    x as u32,
+                    y as u32,
+                    Rgba([pixel.r, pixel.g, pixel.b, pixel.a]),
+                );
+            }
+        }
+
+        image
+    }
+}
+
+// An application without a window, for rendering and testing widgets in CI.
+// Events are only processed when flushed and nothing is drawn until rendered.
+pub struct HeadlessApplication {
+    pub window: HeadlessWindow,
+    pub state: State,
+    pub event_manager: EventManager,
+}
+
+impl HeadlessApplication {
+    pub fn new<F: FnMut(WindowDescription, &mut State, Entity) -> WindowDescription>(
+        mut app: F,
+    ) -> Self {
+        let mut state = State::new();
+
+        let event_manager = EventManager::new();
+
+        let root = state.root;
+        state.hierarchy.add(state.root, None);
+
+        let window_description = app(WindowDescription::new(), &mut state, root);
+
+        let size = window_description.inner_size.to_physical::<u32>(1.0);
+
+        let window = HeadlessWindow::new(size.width, size.height);
+
+        init_root(&mut state, size.width as f32, size.height as f32);
+
+        state.insert_event(Event::new(WindowEvent::Restyle));
+        state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
+
+        HeadlessApplication {
+            window,
+            state,
+            event_manager,
+        }
+    }
+
+    pub fn get_window(&self) -> Entity {
+        self.state.root
+    }
+
+    pub fn get_state(&mut self) -> &mut State {
+        &mut self.state
+    }
+
+    pub fn get_event_manager(&mut self) -> &mut EventManager {
+        &mut self.event_manager
+    }
+
+    // Processes events until the queue is empty, returning true if a redraw was requested
+    pub fn flush(&mut self) -> bool {
+        let mut needs_redraw = false;
+
+        while !self.state.event_queue.is_empty() {
+            if self.event_manager.process_events(&mut self.state) {
+                needs_redraw = true;
+            }
+        }
+
+        needs_redraw
+    }
+
+    // Resizes the window, replacing the offscreen buffer with one of the new size
+    pub fn resize(&mut self, width: u32, height: u32) {
+        self.window = HeadlessWindow::new(width, height);
+        // Fonts are added to the new canvas again when they are next drawn with
+        self.state.fonts.unload();
+
+        let root = self.state.root;
+
+        self.state.style.width.insert(root, Length::Pixels(width as f32));
+        self.state.style.height.insert(root, Length::Pixels(height as f32));
+
+        self.state.transform.set_width(root, width as f32);
+        self.state.transform.set_height(root, height as f32);
+
+        self.state.transform.set_layout_dirty(root);
+
+        self.state.insert_event(Event::new(WindowEvent::Restyle));
+        self.state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
+        self.state.insert_event(Event::new(WindowEvent::Redraw));
+    }
+
+    // Flushes any pending events and draws the widgets into an RGBA image
+    pub fn render(&mut self) -> RgbaImage {
+        self.flush();
+
+        let width = self.window.width;
+        let height = self.window.height;
+
+        self.event_manager
+            .draw_to_canvas(&mut self.state, &mut self.window.canvas, width, height, 1.0);
+
+        self.window.canvas.flush();
+
+        self.window.read_pixels()
+    }
+}
+
+#[derive(Debug)]
+pub enum SnapshotError {
+    Image(image::ImageError),
+    // The golden image doesn't exist and TUIX_UPDATE_SNAPSHOTS isn't set
+    MissingGolden(PathBuf),
+    SizeMismatch {
+        expected: (u32, u32),
+        actual: (u32, u32),
+    },
+    PixelMismatch {
+        // Number of pixels which differ by more than the tolerance
+        count: usize,
+    },
+}
+
+impl std::fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            SnapshotError::Image(error) => write!(f, "failed to read or write snapshot: {}", error),
+            SnapshotError::MissingGolden(path) => write!(
+                f,
+                "snapshot {} doesn't exist, set TUIX_UPDATE_SNAPSHOTS to create it",
+                path.display()
+            ),
+            SnapshotError::SizeMismatch { expected, actual } => write!(
+                f,
+                "snapshot is {}x{} but the image is {}x{}",
+                expected.0, expected.1, actual.0, actual.1
+            ),
+            SnapshotError::PixelMismatch { count } => {
+                write!(f, "{} pixels differ from the snapshot", count)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {}
+
+impl From<image::ImageError> for SnapshotError {
+    fn from(error: image::ImageError) -> Self {
+        SnapshotError::Image(error)
+    }
+}
+
+// Compares an image against a golden PNG, allowing each channel to differ by up to `tolerance`.
+// The golden image is only written, whether or not it exists, if the TUIX_UPDATE_SNAPSHOTS
+// environment variable is set. When it's missing or doesn't match, the image is saved next to
+// the golden image with an `.actual.png` extension so the two can be compared.
+pub fn compare_snapshot<P: AsRef<Path>>(
+    image: &RgbaImage,
+    path: P,
+    tolerance: u8,
+) -> Result<(), SnapshotError> {
+    let path = path.as_ref();
+
+    if std::env::var_os("TUIX_UPDATE_SNAPSHOTS").is_some() {
+        image.save(path)?;
+        return Ok(());
+    }
+
+    if !path.exists() {
+        image.save(path.with_extension("actual.png"))?;
+        return Err(SnapshotError::MissingGolden(path.to_path_buf()));
+    }
+
+    let golden = image::open(path)?.to_rgba8();
+
+    let result = if golden.dimensions() != image.dimensions() {
+        Err(SnapshotError::SizeMismatch {
+            expected: golden.dimensions(),
+            actual: image.dimensions(),
+        })
+    } else {
+        let count = golden
+            .pixels()
+            .zip(image.pixels())
+            .filter(|(expected, actual)| {
+                expected
+                    .0
+                    .iter()
+                    .zip(actual.0.iter())
+                    .any(|(e, a)| (*e as i16 - *a as i16).abs() > tolerance as i16)
+            })
+            .count();
+
+        if count > 0 {
+            Err(SnapshotError::PixelMismatch { count })
+        } else {
+            Ok(())
+        }
+    };
+
+    if result.is_err() {
+        image.save(path.with_extension("actual.png"))?;
+    }
+
+    result
+}
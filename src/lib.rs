@@ -2,9 +2,15 @@ pub mod state;
 pub use state::*;
 
 pub mod application;
+pub mod data;
+pub mod headless;
+pub mod testing;
 pub mod window;
 
 pub use application::Application;
+pub use data::*;
+pub use headless::{compare_snapshot, HeadlessApplication};
+pub use testing::TestDriver;
 pub use window::{KeyboardInput, Window, WindowEvent, CursorIcon};
 
 pub mod events;
@@ -102,6 +102,30 @@ impl Transition {
     }
 }
 
+// A reference to a named @keyframes animation (e.g. animation: slide 2.0 0.5 infinite)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Animation {
+    // Name of the @keyframes rule
+    pub name: String,
+    // Duration of the animation
+    pub duration: f32,
+    // Delay of the animation
+    pub delay: f32,
+    // Number of times the animation is played (infinity for infinite)
+    pub iteration_count: f32,
+}
+
+impl Animation {
+    pub fn new(name: &str) -> Self {
+        Animation {
+            name: name.to_string(),
+            duration: 0.0,
+            delay: 0.0,
+            iteration_count: 1.0,
+        }
+    }
+}
+
 pub trait Interpolator {
     fn interpolate(start: &Self, end: &Self, t: f32) -> Self;
 }
@@ -125,6 +149,8 @@ pub struct AnimationState<Prop: Interpolator> {
     pub t0: f32,
     // How far through the animation between 0.0 and 1.0 (used for transitions)
     pub t: f32,
+    // Number of times the animation is repeated
+    pub iteration_count: f32,
 
     pub active: bool,
 
@@ -152,6 +178,7 @@ where
             persistent: false,
             t0: 0.0,
             t: 0.0,
+            iteration_count: 1.0,
             active: false,
             entities: Vec::new(),
             from_rule: std::usize::MAX,
@@ -179,6 +206,12 @@ where
         self
     }
 
+    pub fn with_iteration_count(mut self, iteration_count: f32) -> Self {
+        self.iteration_count = iteration_count;
+
+        self
+    }
+
     pub fn interpolate(&mut self, current_time: Instant) -> bool {
         if current_time > self.start_time + self.duration {
             return false;
@@ -201,6 +234,40 @@ where
     pub fn get_output(&self) -> Option<&Prop> {
         self.output.as_ref()
     }
+
+    // Interpolate between the two keyframes either side of t (between 0.0 and 1.0)
+    pub fn sample(&self, t: f32) -> Option<Prop>
+    where
+        Prop: Clone,
+    {
+        let first = self.keyframes.first()?;
+        let last = self.keyframes.last()?;
+
+        if t <= first.0 {
+            return Some(first.1.clone());
+        }
+
+        if t >= last.0 {
+            return Some(last.1.clone());
+        }
+
+        for pair in self.keyframes.windows(2) {
+            let (start, end) = (&pair[0], &pair[1]);
+            if t >= start.0 && t <= end.0 {
+                if end.0 == start.0 {
+                    return Some(end.1.clone());
+                }
+
+                return Some(Prop::interpolate(
+                    &start.1,
+                    &end.1,
+                    (t - start.0) / (end.0 - start.0),
+                ));
+            }
+        }
+
+        Some(last.1.clone())
+    }
 }
 
 impl<Prop> Default for AnimationState<Prop>
@@ -218,6 +285,7 @@ where
             persistent: true,
             t0: 0.0,
             t: 0.0,
+            iteration_count: 1.0,
             active: false,
             entities: Vec::new(),
             from_rule: std::usize::MAX,
@@ -0,0 +1,119 @@
+// Copies and pastes text through a backend.
+// State starts with a clipboard in memory, so that tests and headless rendering don't touch the clipboard
+// of the user, and the application replaces it with the system clipboard.
+
+use copypasta::{ClipboardContext, ClipboardProvider};
+
+pub trait ClipboardBackend {
+    // Returns the text on the clipboard, or None if it is empty or doesn't hold text
+    fn get_text(&mut self) -> Option<String>;
+
+    fn set_text(&mut self, text: String);
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct MemoryClipboard {
+    text: Option<String>,
+}
+
+impl ClipboardBackend for MemoryClipboard {
+    fn get_text(&mut self) -> Option<String> {
+        self.text.clone()
+    }
+
+    fn set_text(&mut self, text: String) {
+        self.text = Some(text);
+    }
+}
+
+// The clipboard of the operating system, which falls back to memory if it can't be opened or used,
+// so that copying and pasting still works within the application
+pub struct SystemClipboard {
+    context: Option<Box<dyn ClipboardProvider>>,
+    fallback: MemoryClipboard,
+}
+
+impl SystemClipboard {
+    pub fn new() -> Self {
+        SystemClipboard {
+            context: ClipboardContext::new()
+                .ok()
+                .map(|context| Box::new(context) as Box<dyn ClipboardProvider>),
+            fallback: MemoryClipboard::default(),
+        }
+    }
+
+    // Uses the given provider in place of the clipboard of the operating system
+    pub fn with_provider<P: ClipboardProvider + 'static>(provider: P) -> Self {
+        SystemClipboard {
+            context: Some(Box::new(provider)),
+            fallback: MemoryClipboard::default(),
+        }
+    }
+}
+
+impl Default for SystemClipboard {
+    fn default() -> Self {
+        SystemClipboard::new()
+    }
+}
+
+impl ClipboardBackend for SystemClipboard {
+    fn get_text(&mut self) -> Option<String> {
+        match &mut self.context {
+            Some(context) => match context.get_contents() {
+                Ok(text) => Some(text).filter(|text| !text.is_empty()),
+                Err(_) => self.fallback.get_text(),
+            },
+            None => self.fallback.get_text(),
+        }
+    }
+
+    fn set_text(&mut self, text: String) {
+        let copied = match &mut self.context {
+            Some(context) => context.set_contents(text.clone()).is_ok(),
+            None => false,
+        };
+
+        // Text left in the fallback would be pasted if the system clipboard later failed
+        self.fallback = MemoryClipboard::default();
+
+        if !copied {
+            self.fallback.set_text(text);
+        }
+    }
+}
+
+pub struct Clipboard {
+    backend: Box<dyn ClipboardBackend>,
+}
+
+impl Clipboard {
+    pub fn new<B: ClipboardBackend + 'static>(backend: B) -> Self {
+        Clipboard {
+            backend: Box::new(backend),
+        }
+    }
+
+    pub fn system() -> Self {
+        Clipboard::new(SystemClipboard::new())
+    }
+
+    pub fn memory() -> Self {
+        Clipboard::new(MemoryClipboard::default())
+    }
+
+    pub fn get_text(&mut self) -> Option<String> {
+        self.backend.get_text()
+    }
+
+    pub fn set_text(&mut self, text: &str) {
+        self.backend.set_text(text.to_string());
+    }
+}
+
+impl Default for Clipboard {
+    fn default() -> Self {
+        Clipboard::memory()
+    }
+}
@@ -0,0 +1,521 @@
+// Font faces registered under family names.
+// Faces are added to the canvas the first time they are drawn with, so they can be registered
+// before the window is created. Text is measured from the font data so that it can be laid out
+// without a canvas.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use femtovg::{renderer::OpenGl, Canvas, FontId};
+
+use crate::style::{FontStyle, FontWeight};
+
+// The family used when none of the families of an entity have a face
+pub const DEFAULT_FONT_FAMILY: &str = "Sans";
+
+// femtovg can fall back through at most this many fonts
+const MAX_FONTS: usize = 8;
+
+// Each face is given an id when it's created. Faces created from memory are keyed by it,
+// as the address of the data could be reused once the face is dropped.
+static NEXT_FACE_ID: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Clone)]
+pub enum FontSource {
+    Memory(Rc<[u8]>),
+    File(PathBuf),
+}
+
+// Identifies the font data of a face, so that faces with the same data are only added to the canvas once
+#[derive(Clone, PartialEq, Eq, Hash)]
+enum SourceKey {
+    Memory(usize),
+    File(PathBuf),
+}
+
+// A font face which couldn't be added to the canvas
+#[derive(Clone, Debug, PartialEq)]
+pub struct FontError {
+    pub family: String,
+    // The file of the face, None if it was created from memory
+    pub path: Option<PathBuf>,
+    pub message: String,
+}
+
+impl fmt::Display for FontError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.path {
+            Some(path) => write!(
+                f,
+                "failed to load font `{}` from {}: {}",
+                self.family,
+                path.display(),
+                self.message
+            ),
+            None => write!(f, "failed to load font `{}`: {}", self.family, self.message),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct FontFace {
+    pub family: String,
+    pub weight: FontWeight,
+    pub style: FontStyle,
+    pub source: FontSource,
+    id: usize,
+}
+
+impl FontFace {
+    pub fn from_memory(family: &str, data: &[u8]) -> Self {
+        FontFace::new(family, FontSource::Memory(Rc::from(data)))
+    }
+
+    pub fn from_file<P: AsRef<Path>>(family: &str, path: P) -> Self {
+        FontFace::new(family, FontSource::File(path.as_ref().to_path_buf()))
+    }
+
+    fn new(family: &str, source: FontSource) -> Self {
+        FontFace {
+            family: family.to_string(),
+            weight: FontWeight::default(),
+            style: FontStyle::default(),
+            source,
+            id: NEXT_FACE_ID.fetch_add(1, Ordering::Relaxed),
+        }
+    }
+
+    pub fn with_weight(mut self, weight: FontWeight) -> Self {
+        self.weight = weight;
+
+        self
+    }
+
+    pub fn with_style(mut self, style: FontStyle) -> Self {
+        self.style = style;
+
+        self
+    }
+
+    fn key(&self) -> SourceKey {
+        match &self.source {
+            FontSource::Memory(_) => SourceKey::Memory(self.id),
+            FontSource::File(path) => SourceKey::File(path.clone()),
+        }
+    }
+
+    fn has_family(&self, family: &str) -> bool {
+        let family = match family.to_ascii_lowercase().as_str() {
+            "sans-serif" => DEFAULT_FONT_FAMILY.to_string(),
+            _ => family.to_string(),
+        };
+
+        self.family.eq_ignore_ascii_case(&family)
+    }
+
+    // How far the face is from the requested weight and style, lower is better.
+    // A matching style is preferred over a matching weight.
+    fn distance(&self, weight: FontWeight, style: FontStyle) -> u32 {
+        let style_distance = match (self.style, style) {
+            (a, b) if a == b => 0,
+            (FontStyle::Italic, FontStyle::Oblique) | (FontStyle::Oblique, FontStyle::Italic) => 1,
+            _ => 2,
+        };
+
+        let weight_distance = (self.weight.0 as i32 - weight.0 as i32).unsigned_abs();
+
+        style_distance * 1000 + weight_distance
+    }
+}
+
+// The metrics of a font used to measure text, in font units
+struct FontData {
+    data: Rc<[u8]>,
+    units_per_em: f32,
+    ascender: f32,
+    descender: f32,
+    line_gap: f32,
+    // The advance of each character measured so far. None if the font has no glyph for it.
+    advances: HashMap<char, Option<f32>>,
+}
+
+impl FontData {
+    fn parse(data: Rc<[u8]>) -> Result<Self, ttf_parser::FaceParsingError> {
+        let face = ttf_parser::Face::from_slice(&data, 0)?;
+
+        Ok(FontData {
+            units_per_em: face.units_per_em() as f32,
+            ascender: face.ascender() as f32,
+            descender: face.descender() as f32,
+            line_gap: face.line_gap() as f32,
+            advances: HashMap::new(),
+            data,
+        })
+    }
+
+    fn advance(&mut self, c: char) -> Option<f32> {
+        let data = &self.data;
+
+        *self.advances.entry(c).or_insert_with(|| {
+            let face = ttf_parser::Face::from_slice(data, 0).ok()?;
+            let glyph = face.glyph_index(c)?;
+            face.glyph_hor_advance(glyph).map(|advance| advance as f32)
+        })
+    }
+}
+
+// The fonts picked for each list of families, with the weight and style they were picked for
+type Resolved<T> = HashMap<Vec<String>, Vec<(FontWeight, FontStyle, Vec<T>)>>;
+
+pub struct Fonts {
+    faces: Vec<FontFace>,
+    // Fonts which have been added to the canvas, or the error if the font failed to load
+    loaded: HashMap<SourceKey, Result<FontId, FontError>>,
+    // Fonts which have been parsed for measuring text. None if the font failed to parse.
+    parsed: HashMap<SourceKey, Option<FontData>>,
+    // The results of font_ids and measure, cleared when a face is registered or the stylesheet faces change
+    resolved_ids: Resolved<FontId>,
+    resolved_keys: Resolved<SourceKey>,
+    // The ids of the stylesheet faces the fonts were resolved with
+    stylesheet_faces: Vec<usize>,
+    // Faces which failed to load since the errors were last taken
+    errors: Vec<FontError>,
+}
+
+impl Fonts {
+    // Creates a registry with the bundled fonts, Sans (Roboto) and Icons (Entypo)
+    pub fn new() -> Self {
+        let mut fonts = Fonts {
+            faces: Vec::new(),
+            loaded: HashMap::new(),
+            parsed: HashMap::new(),
+            resolved_ids: HashMap::new(),
+            resolved_keys: HashMap::new(),
+            stylesheet_faces: Vec::new(),
+            errors: Vec::new(),
+        };
+
+        fonts.add(FontFace::from_memory(
+            DEFAULT_FONT_FAMILY,
+            include_bytes!("../../resources/Roboto-Regular.ttf"),
+        ));
+        fonts.add(
+            FontFace::from_memory(
+                DEFAULT_FONT_FAMILY,
+                include_bytes!("../../resources/Roboto-Bold.ttf"),
+            )
+            .with_weight(FontWeight::BOLD),
+        );
+        fonts.add(FontFace::from_memory(
+            "Icons",
+            include_bytes!("../../resources/entypo.ttf"),
+        ));
+
+        fonts
+    }
+
+    // Registers a font face. Faces of the same family with different weights and styles
+    // are picked from with the font-weight and font-style properties.
+    pub fn add(&mut self, face: FontFace) {
+        self.faces.push(face);
+
+        self.resolved_ids.clear();
+        self.resolved_keys.clear();
+    }
+
+    pub fn add_memory(&mut self, family: &str, data: &[u8]) {
+        self.add(FontFace::from_memory(family, data));
+    }
+
+    pub fn add_file<P: AsRef<Path>>(&mut self, family: &str, path: P) {
+        self.add(FontFace::from_file(family, path));
+    }
+
+    // Returns true if a face has been registered for the family
+    pub fn has_family(&self, family: &str) -> bool {
+        self.faces.iter().any(|face| face.has_family(family))
+    }
+
+    // Forgets the fonts added to the canvas, for when the canvas is replaced
+    pub fn unload(&mut self) {
+        self.loaded.clear();
+        self.resolved_ids.clear();
+    }
+
+    // Returns the faces which have failed to load since this was last called.
+    // Text drawn with a face which fails to load falls back to the next face.
+    pub fn take_errors(&mut self) -> Vec<FontError> {
+        std::mem::take(&mut self.errors)
+    }
+
+    // Returns the fonts to draw with for a list of families in order of preference, followed by the default family.
+    // Faces declared in stylesheets are picked from too, before the registered faces.
+    // Each family contributes the face closest to the weight and style which loads.
+    pub fn font_ids(
+        &mut self,
+        canvas: &mut Canvas<OpenGl>,
+        stylesheet_faces: &[FontFace],
+        families: &[String],
+        weight: FontWeight,
+        style: FontStyle,
+    ) -> Vec<FontId> {
+        self.check_stylesheet_faces(stylesheet_faces);
+
+        if let Some(font_ids) = resolved(&self.resolved_ids, families, weight, style) {
+            return font_ids.clone();
+        }
+
+        let mut font_ids = Vec::new();
+
+        for candidates in self.candidates(stylesheet_faces, families, weight, style) {
+            let font_id = candidates.iter().find_map(|face| match self.load(canvas, face) {
+                Ok(font_id) => Some(font_id),
+                Err(error) => {
+                    if !self.errors.contains(&error) {
+                        self.errors.push(error);
+                    }
+                    None
+                }
+            });
+
+            if let Some(font_id) = font_id {
+                if !font_ids.contains(&font_id) {
+                    font_ids.push(font_id);
+                }
+            }
+
+            if font_ids.len() == MAX_FONTS {
+                break;
+            }
+        }
+
+        self.resolved_ids
+            .entry(families.to_vec())
+            .or_default()
+            .push((weight, style, font_ids.clone()));
+
+        font_ids
+    }
+
+    // Returns a measure for text drawn with the same fonts as font_ids would return
+    pub fn measure(
+        &mut self,
+        stylesheet_faces: &[FontFace],
+        families: &[String],
+        weight: FontWeight,
+        style: FontStyle,
+        font_size: f32,
+        letter_spacing: f32,
+    ) -> TextMeasure<'_> {
+        self.check_stylesheet_faces(stylesheet_faces);
+
+        let keys = match resolved(&self.resolved_keys, families, weight, style) {
+            Some(keys) => keys.clone(),
+            None => {
+                let mut keys = Vec::new();
+
+                for candidates in self.candidates(stylesheet_faces, families, weight, style) {
+                    let key = candidates.iter().find_map(|face| self.parse(face));
+
+                    if let Some(key) = key {
+                        if !keys.contains(&key) {
+                            keys.push(key);
+                        }
+                    }
+
+                    if keys.len() == MAX_FONTS {
+                        break;
+                    }
+                }
+
+                self.resolved_keys
+                    .entry(families.to_vec())
+                    .or_default()
+                    .push((weight, style, keys.clone()));
+
+                keys
+            }
+        };
+
+        TextMeasure {
+            fonts: self,
+            keys,
+            font_size,
+            letter_spacing,
+        }
+    }
+
+    // Clears the resolved fonts if the stylesheet faces aren't the ones they were resolved with
+    fn check_stylesheet_faces(&mut self, stylesheet_faces: &[FontFace]) {
+        if stylesheet_faces
+            .iter()
+            .map(|face| face.id)
+            .eq(self.stylesheet_faces.iter().cloned())
+        {
+            return;
+        }
+
+        self.stylesheet_faces = stylesheet_faces.iter().map(|face| face.id).collect();
+        self.resolved_ids.clear();
+        self.resolved_keys.clear();
+    }
+
+    // Returns the faces of each family, followed by the default family, closest to the weight and style first
+    fn candidates(
+        &self,
+        stylesheet_faces: &[FontFace],
+        families: &[String],
+        weight: FontWeight,
+        style: FontStyle,
+    ) -> Vec<Vec<FontFace>> {
+        let default_family = [DEFAULT_FONT_FAMILY.to_string()];
+
+        families
+            .iter()
+            .chain(default_family.iter())
+            .map(|family| {
+                let mut candidates: Vec<FontFace> = stylesheet_faces
+                    .iter()
+                    .chain(self.faces.iter())
+                    .filter(|face| face.has_family(family))
+                    .cloned()
+                    .collect();
+
+                // The sort is stable so that earlier faces win ties
+                candidates.sort_by_key(|face| face.distance(weight, style));
+
+                candidates
+            })
+            .collect()
+    }
+
+    // Adds the font data of a face to the canvas if it hasn't been already.
+    // A font which failed to load isn't tried again until the fonts are unloaded.
+    pub fn load(
+        &mut self,
+        canvas: &mut Canvas<OpenGl>,
+        face: &FontFace,
+    ) -> Result<FontId, FontError> {
+        let key = face.key();
+
+        if let Some(result) = self.loaded.get(&key) {
+            return result.clone();
+        }
+
+        let result = match &face.source {
+            FontSource::Memory(data) => canvas.add_font_mem(data),
+            FontSource::File(path) => canvas.add_font(path),
+        }
+        .map_err(|error| FontError {
+            family: face.family.clone(),
+            path: match &face.source {
+                FontSource::Memory(_) => None,
+                FontSource::File(path) => Some(path.clone()),
+            },
+            message: format!("{:?}", error),
+        });
+
+        self.loaded.insert(key, result.clone());
+
+        result
+    }
+
+    // Parses the font data of a face for measuring if it hasn't been already, returning its key if it parsed
+    fn parse(&mut self, face: &FontFace) -> Option<SourceKey> {
+        let key = face.key();
+
+        if let Some(font_data) = self.parsed.get(&key) {
+            return font_data.as_ref().map(|_| key);
+        }
+
+        let data = match &face.source {
+            FontSource::Memory(data) => Ok(data.clone()),
+            FontSource::File(path) => std::fs::read(path).map(Rc::from),
+        };
+
+        // Errors are reported when the font is added to the canvas
+        let font_data = data
+            .ok()
+            .and_then(|data| FontData::parse(data).ok());
+        let parsed = font_data.is_some();
+
+        self.parsed.insert(key.clone(), font_data);
+
+        if parsed {
+            Some(key)
+        } else {
+            None
+        }
+    }
+}
+
+// Returns the fonts resolved for a list of families with a weight and style
+fn resolved<'a, T>(
+    resolved: &'a Resolved<T>,
+    families: &[String],
+    weight: FontWeight,
+    style: FontStyle,
+) -> Option<&'a Vec<T>> {
+    resolved
+        .get(families)?
+        .iter()
+        .find(|(resolved_weight, resolved_style, _)| {
+            *resolved_weight == weight && *resolved_style == style
+        })
+        .map(|(_, _, fonts)| fonts)
+}
+
+// Measures text with a list of fonts, where each character is measured with the first font which has a glyph for it
+pub struct TextMeasure<'a> {
+    fonts: &'a mut Fonts,
+    keys: Vec<SourceKey>,
+    pub font_size: f32,
+    pub letter_spacing: f32,
+}
+
+impl TextMeasure<'_> {
+    // Returns the width of a single line of text
+    pub fn width(&mut self, text: &str) -> f32 {
+        text.chars()
+            .map(|c| self.advance(c) + self.letter_spacing)
+            .sum()
+    }
+
+    // Returns the normal line height of the first font
+    pub fn normal_line_height(&self) -> f32 {
+        match self.font_data(0) {
+            Some(font_data) => {
+                (font_data.ascender - font_data.descender + font_data.line_gap) * self.font_size
+                    / font_data.units_per_em
+            }
+            None => self.font_size * 1.2,
+        }
+    }
+
+    fn advance(&mut self, c: char) -> f32 {
+        let font_size = self.font_size;
+
+        for key in self.keys.iter() {
+            if let Some(Some(font_data)) = self.fonts.parsed.get_mut(key) {
+                if let Some(advance) = font_data.advance(c) {
+                    return advance * font_size / font_data.units_per_em;
+                }
+            }
+        }
+
+        0.0
+    }
+
+    fn font_data(&self, index: usize) -> Option<&FontData> {
+        self.fonts.parsed.get(self.keys.get(index)?)?.as_ref()
+    }
+}
+
+impl Default for Fonts {
+    fn default() -> Self {
+        Fonts::new()
+    }
+}
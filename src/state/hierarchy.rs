@@ -1,5 +1,7 @@
 use crate::entity::Entity;
 
+use std::collections::HashSet;
+
 #[derive(Clone)]
 pub struct Hierarchy {
     pub entities: Vec<Entity>,
@@ -111,15 +113,37 @@ impl Hierarchy {
         false
     }
 
-    pub fn remove_children(&mut self, _entity: Entity) {}
+    pub fn remove_children(&mut self, entity: Entity) {
+        let mut removed = HashSet::new();
+
+        while let Some(child) = self.get_first_child(entity) {
+            self.unlink(child, &mut removed);
+        }
+
+        self.entities.retain(|e| !removed.contains(e));
+    }
 
     pub fn has_children(&self, entity: Entity) -> bool {
         self.first_child[entity.index()].is_some()
     }
 
+    // Removes an entity and all of its descendants from the hierarchy
     pub fn remove(&mut self, entity: Entity) {
-        if let Some(child) = self.get_first_child(entity) {
-            self.remove(child);
+        if entity.index() >= self.parent.len() {
+            return;
+        }
+
+        let mut removed = HashSet::new();
+        self.unlink(entity, &mut removed);
+
+        // The list of entities is filtered once for the whole branch
+        self.entities.retain(|e| !removed.contains(e));
+    }
+
+    // Detaches an entity and its descendants from the tree, collecting them to be removed from the list of entities
+    fn unlink(&mut self, entity: Entity, removed: &mut HashSet<Entity>) {
+        while let Some(child) = self.get_first_child(entity) {
+            self.unlink(child, removed);
         }
 
         if let Some(parent) = self.get_parent(entity) {
@@ -137,6 +161,10 @@ impl Hierarchy {
         }
 
         self.parent[entity.index()] = None;
+        self.next_sibling[entity.index()] = None;
+        self.prev_sibling[entity.index()] = None;
+
+        removed.insert(entity);
     }
 
     pub fn set_parent(&mut self, entity: Entity, parent: Entity) {
@@ -390,7 +418,7 @@ pub trait HierarchyTree<'a> {
     fn parent(&self, hierarchy: &'a Hierarchy) -> Option<Entity>;
     fn is_sibling(&self, hierarchy: &'a Hierarchy, entity: Entity) -> bool;
     fn is_child_of(&self, hierarchy: &'a Hierarchy, entity: Entity) -> bool;
-    //fn is_descendant_of(&self, hierarchy: &'a Hierarchy, entity: Entity) -> bool; TODO
+    fn is_descendant_of(&self, hierarchy: &'a Hierarchy, entity: Entity) -> bool;
 }
 
 impl<'a> HierarchyTree<'a> for Entity {
@@ -414,18 +442,8 @@ impl<'a> HierarchyTree<'a> for Entity {
         }
     }
 
-    // TODO
-    //fn is_descendant_of(&self, hierarchy: &'a Hierarchy, entity: Entity) -> bool {
-    //    return false;
-        
-        // if let Some(parent) = hierarchy.get_parent(*self) {
-        //     if parent == entity {
-        //         return true;
-        //     } else {
-        //         return false;
-        //     }
-        // } else {
-        //     return false;
-        // }
-    //}
+    fn is_descendant_of(&self, hierarchy: &'a Hierarchy, entity: Entity) -> bool {
+        // The parent iterator starts with the entity itself
+        self.parent_iter(hierarchy).skip(1).any(|ancestor| ancestor == entity)
+    }
 }
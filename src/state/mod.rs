@@ -22,19 +22,32 @@ pub use mouse::*;
 pub mod resource;
 pub use resource::*;
 
-pub use crate::events::{Builder, Event, EventHandler, Propagation};
-pub use crate::window::WindowEvent;
+pub mod fonts;
+pub use fonts::*;
 
-use femtovg::FontId;
+pub mod text_layout;
+pub use text_layout::*;
 
+pub mod clipboard;
+pub use clipboard::*;
 
-use std::collections::{HashMap, VecDeque};
+pub mod overlay;
+pub use overlay::*;
 
-pub struct Fonts {
-    pub regular: Option<FontId>,
-    pub bold: Option<FontId>,
-    pub icons: Option<FontId>,
-}
+pub mod timer;
+pub use timer::*;
+
+pub mod tooltip;
+pub use tooltip::*;
+
+pub use crate::events::{Builder, Event, EventHandler, Propagation};
+pub use crate::window::WindowEvent;
+
+use crate::systems::hide_tooltip;
+
+use std::any::Any;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::time::{Duration, Instant};
 
 pub struct State {
     entity_manager: EntityManager,  // Creates and destroys entities
@@ -52,7 +65,26 @@ pub struct State {
     pub event_handlers: HashMap<Entity, Box<dyn EventHandler>>,
     pub event_queue: VecDeque<Event>,
 
-    pub fonts: Fonts, //TODO - Replace with resource manager
+    // Entities which have been removed but may still have event handlers in the event manager
+    pub(crate) removed: Vec<Entity>,
+
+    // Data bindings waiting to be received by their store
+    pub(crate) bindings: Vec<(Entity, Box<dyn Any>)>,
+
+    // Font faces which text can be drawn with
+    pub fonts: Fonts,
+
+    // Text copied and pasted by text widgets
+    pub clipboard: Clipboard,
+
+    // Popups and dialogs drawn above the rest of the window
+    pub overlays: Overlays,
+
+    // Events waiting to be sent after a delay
+    pub timers: Timers,
+
+    // The tooltip delay and the tooltip which is waiting to be shown or is shown
+    pub tooltips: Tooltips,
 
     //pub resource_manager: ResourceManager, //TODO
 }
@@ -88,7 +120,13 @@ impl State {
             focused: Entity::new(0,0),
             event_handlers: HashMap::new(),
             event_queue: VecDeque::new(),
-            fonts: Fonts{regular: None, bold: None, icons: None},
+            removed: Vec::new(),
+            bindings: Vec::new(),
+            fonts: Fonts::new(),
+            clipboard: Clipboard::memory(),
+            overlays: Overlays::new(),
+            timers: Timers::new(),
+            tooltips: Tooltips::new(),
             //resource_manager: ResourceManager::new(),
         }
     }
@@ -102,10 +140,57 @@ impl State {
         Builder::new(self, entity)
     }
 
-    // This should return an error type
-    pub fn insert_style(&mut self, stylesheet: &str) {
+    // Parses a stylesheet, returning a report of any problems found in it.
+    // Rules and declarations which fail to parse are left out.
+    pub fn insert_style(&mut self, stylesheet: &str) -> StyleReport {
         // Parse the theme stylesheet
-        self.style.parse_theme(stylesheet);
+        self.style.parse_theme(stylesheet)
+    }
+
+    // Parses a stylesheet, failing without adding any rules if it has errors
+    pub fn insert_style_strict(&mut self, stylesheet: &str) -> Result<StyleReport, StyleReport> {
+        self.style.parse_theme_strict(stylesheet)
+    }
+
+    // Parses a stylesheet from a file, which is reloaded when it changes if poll_styles is called
+    pub fn insert_style_file<P: AsRef<std::path::Path>>(
+        &mut self,
+        path: P,
+    ) -> std::io::Result<StyleReport> {
+        self.style.load_theme(path)
+    }
+
+    // Rebuilds the style rules from every stylesheet and restyles all entities
+    pub fn reload_styles(&mut self) -> StyleReport {
+        let report = self.style.reload_themes();
+
+        self.restyle_all();
+
+        report
+    }
+
+    // Reloads the stylesheets if any of their files have changed, returning true if they have
+    pub fn poll_styles(&mut self) -> bool {
+        if self.style.poll_themes() {
+            self.restyle_all();
+            return true;
+        }
+
+        false
+    }
+
+    // Takes the diagnostics for stylesheet files reloaded by poll_styles and for declarations
+    // using var() which fail to parse once their variables are substituted
+    pub fn take_style_report(&mut self) -> StyleReport {
+        self.style.take_report()
+    }
+
+    fn restyle_all(&mut self) {
+        self.transform.set_all_layout_dirty();
+
+        self.insert_event(Event::new(WindowEvent::Restyle));
+        self.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
+        self.insert_event(Event::new(WindowEvent::Redraw));
     }
 
     pub fn insert_event(&mut self, mut event: Event) {
@@ -141,6 +226,112 @@ impl State {
         
     }
 
+    // Sends an event once the delay has passed, unless the timer is cancelled first
+    pub fn set_timer(&mut self, delay: Duration, event: Event) -> TimerId {
+        self.timers.start(delay, event)
+    }
+
+    pub fn cancel_timer(&mut self, id: TimerId) {
+        self.timers.cancel(id);
+    }
+
+    // Queues the events of the timers which are due by the given time. Returns true if any were.
+    pub fn fire_timers(&mut self, now: Instant) -> bool {
+        let due = self.timers.take_due(now);
+        let fired = !due.is_empty();
+
+        for event in due {
+            self.insert_event(event);
+        }
+
+        fired
+    }
+
+    // Opens an entity as an overlay above the rest of the window and any overlays which are already open.
+    // The entity is positioned absolutely and shown, and should be hidden by default so that it only
+    // appears while it is open. If it is already open it is placed again with the new anchor and placement.
+    pub fn open_overlay(&mut self, entity: Entity, mut overlay: Overlay) {
+        if let Some(open) = self.overlays.stack.iter_mut().find(|open| open.entity == entity) {
+            open.anchor = overlay.anchor;
+            open.placement = overlay.placement;
+            open.light_dismiss = overlay.light_dismiss;
+
+            self.transform.set_layout_dirty(entity);
+            self.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
+            self.insert_event(Event::new(WindowEvent::Redraw));
+            return;
+        }
+
+        overlay.entity = entity;
+        overlay.focus = self.focused;
+
+        if overlay.modal {
+            let backdrop = self.add(self.root);
+            self.build(backdrop, Backdrop);
+            self.style.insert_element(backdrop, "backdrop");
+            self.style.position.insert(backdrop, Position::Absolute);
+            self.style.left.insert(backdrop, Length::Pixels(0.0));
+            self.style.top.insert(backdrop, Length::Pixels(0.0));
+            self.style.width.insert(backdrop, Length::Percentage(1.0));
+            self.style.height.insert(backdrop, Length::Percentage(1.0));
+
+            overlay.backdrop = backdrop;
+
+            // Keys go to the dialog rather than whatever was behind it
+            self.focused = entity;
+        }
+
+        self.style.position.insert(entity, Position::Absolute);
+        self.style.visibility.insert(entity, Visibility::Visible);
+        self.transform.set_layout_dirty(entity);
+        self.transform.draw_order_dirty = true;
+
+        self.overlays.stack.push(overlay);
+
+        self.insert_event(Event::new(WindowEvent::Restyle));
+        self.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
+        self.insert_event(Event::new(WindowEvent::Redraw));
+    }
+
+    // Closes an overlay along with any opened above it, hiding them and sending each an OverlayEvent::Closed
+    pub fn close_overlay(&mut self, entity: Entity) {
+        let index = match self.overlays.stack.iter().position(|open| open.entity == entity) {
+            Some(index) => index,
+            None => return,
+        };
+
+        let closed: Vec<Overlay> = self.overlays.stack.drain(index..).collect();
+
+        for overlay in closed.into_iter().rev() {
+            self.style.visibility.insert(overlay.entity, Visibility::Invisible);
+            self.transform.set_layout_dirty(overlay.entity);
+            self.transform.draw_order_dirty = true;
+
+            if overlay.backdrop != Entity::null() {
+                self.remove(overlay.backdrop);
+            }
+
+            // Focus left inside of the overlay goes back to where it was when the overlay was opened
+            if self.focused == overlay.entity || self.focused.is_descendant_of(&self.hierarchy, overlay.entity) {
+                self.focused = if self.is_alive(overlay.focus) {
+                    overlay.focus
+                } else {
+                    self.root
+                };
+            }
+
+            self.insert_event(
+                Event::new(OverlayEvent::Closed(overlay.entity))
+                    .target(overlay.entity)
+                    .propagate(Propagation::Up),
+            );
+        }
+
+        self.insert_event(Event::new(WindowEvent::Restyle));
+        self.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
+        self.insert_event(Event::new(WindowEvent::Redraw));
+    }
+
     pub fn add(&mut self, parent: Entity) -> Entity {
         let entity = self
             .entity_manager
@@ -151,6 +342,12 @@ impl State {
         self.transform.add(entity);
         self.style.add(entity);
 
+        // The parent is no longer :empty and the previous last child is no longer :last-child
+        self.style.set_dirty(parent);
+        if let Some(prev_sibling) = self.hierarchy.get_prev_sibling(entity) {
+            self.style.set_dirty(prev_sibling);
+        }
+
         entity
     }
 
@@ -167,16 +364,170 @@ impl State {
     //     entity
     // }
 
-    //  TODO
-    // pub fn remove(&mut self, entity: Entity) {
-    //     //self.hierarchy.remove(entity);
-    //     //self.transform.remove(entity);
-    //     //self.style.remove(entity);
-    //     //self.entity_manager.destroy_entity(entity);
-    // }
+    // Removes an entity and all of its descendants, freeing their data and event handlers.
+    // The parent is sent a WindowEvent::Removed event with the removed entity.
+    pub fn remove(&mut self, entity: Entity) {
+        // The root can't be removed
+        if entity == self.root || entity.is_null() || !self.entity_manager.is_alive(entity) {
+            return;
+        }
+
+        // Collect the entity and its descendants
+        let mut branch = vec![entity];
+        let mut index = 0;
+        while index < branch.len() {
+            let current = branch[index];
+            branch.extend(current.child_iter(&self.hierarchy));
+            index += 1;
+        }
+
+        // Looked up by the retains below, which would otherwise search the branch for every item
+        let in_branch: HashSet<Entity> = branch.iter().cloned().collect();
+
+        // The slots of removed entities may be reused, so their hoverability isn't restored
+        self.tooltips.hoverability.retain(|(entity, _)| !in_branch.contains(entity));
+
+        // Hides the tooltip of a removed entity, or a removed tooltip
+        if in_branch.contains(&self.tooltips.owner) || in_branch.contains(&self.tooltips.shown) {
+            hide_tooltip(self);
+        }
+
+        if in_branch.contains(&self.tooltips.owner) {
+            self.tooltips.owner = Entity::null();
+        }
+
+        if in_branch.contains(&self.tooltips.label) {
+            self.tooltips.label = Entity::null();
+        }
+
+        // Overlays which are removed, or whose anchors are removed, are closed
+        let closing: Vec<Entity> = self
+            .overlays
+            .iter()
+            .filter(|overlay| in_branch.contains(&overlay.entity) || in_branch.contains(&overlay.anchor))
+            .map(|overlay| overlay.entity)
+            .collect();
+
+        for overlay in closing {
+            self.close_overlay(overlay);
+        }
+
+        let parent = self.hierarchy.get_parent(entity);
+
+        // The positions of the siblings change, which affects structural pseudo-classes
+        if let Some(parent) = parent {
+            self.style.set_dirty(parent);
+            for sibling in parent.child_iter(&self.hierarchy) {
+                self.style.set_dirty(sibling);
+            }
+        }
+
+        self.hierarchy.remove(entity);
+
+        for removed in branch.iter().rev() {
+            let removed = *removed;
+
+            self.transform.remove(removed);
+            self.style.remove(removed);
+            self.event_handlers.remove(&removed);
+            self.entity_manager.destroy_entity(removed);
 
+            // The event manager may still own the event handler
+            self.removed.push(removed);
+
+            if self.hovered == removed {
+                self.hovered = self.root;
+            }
+
+            if self.focused == removed {
+                self.focused = self.root;
+            }
+
+            if self.active == removed {
+                self.active = Entity::null();
+            }
+
+            if self.captured == removed {
+                self.captured = Entity::null();
+            }
+        }
+
+        // Drop any events still waiting to be sent to removed entities
+        self.event_queue
+            .retain(|event| !in_branch.contains(&event.target));
+        self.timers
+            .pending
+            .retain(|(_, _, event)| !in_branch.contains(&event.target));
+
+        self.bindings.retain(|(store, _)| !in_branch.contains(store));
+
+        if let Some(parent) = parent {
+            self.transform.set_layout_dirty(parent);
+
+            self.insert_event(
+                Event::new(WindowEvent::Removed(entity))
+                    .target(parent)
+                    .origin(entity)
+                    .propagate(Propagation::Up),
+            );
+        }
+
+        self.insert_event(Event::new(WindowEvent::Restyle));
+        self.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
+        self.insert_event(Event::new(WindowEvent::Redraw));
+    }
+
+    // Returns true if the entity has not been removed
+    pub fn is_alive(&self, entity: Entity) -> bool {
+        !entity.is_null() && self.entity_manager.is_alive(entity)
+    }
+
+    // Steps the active animations, flagging the entities whose layout, opacity or rotation is animated so that only
+    // they are laid out again. Returns true while any animations are running.
     pub fn apply_animations(&mut self) -> bool {
 
+        // Entities with animations which affect layout need to be laid out again
+        let mut layout_animations: Vec<Entity> = Vec::new();
+        for storage in [
+            &self.style.left,
+            &self.style.right,
+            &self.style.top,
+            &self.style.bottom,
+            &self.style.width,
+            &self.style.height,
+            &self.style.margin_left,
+            &self.style.margin_right,
+            &self.style.margin_top,
+            &self.style.margin_bottom,
+        ]
+        .iter()
+        {
+            for animation in storage.active_animations.iter() {
+                layout_animations.extend(animation.entities.iter());
+            }
+        }
+
+        for animation in self.style.flex_grow.active_animations.iter() {
+            layout_animations.extend(animation.entities.iter());
+        }
+
+        // Opacity is composed with the ancestors and rotation with the transforms, which are updated on relayout
+        for animation in self.style.opacity.active_animations.iter() {
+            layout_animations.extend(animation.entities.iter());
+        }
+
+        for animation in self.style.rotate.active_animations.iter() {
+            layout_animations.extend(animation.entities.iter());
+        }
+
+        if !layout_animations.is_empty() {
+            for entity in layout_animations {
+                self.transform.set_layout_dirty(entity);
+            }
+
+            self.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
+        }
+
         self.style
             .background_color
             .animate(std::time::Instant::now());
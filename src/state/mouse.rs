@@ -22,6 +22,17 @@ pub struct ModifiersState {
     pub logo: bool,
 }
 
+impl ModifiersState {
+    // Returns true if the modifier for shortcuts such as copy and paste is held, which is command on macOS
+    pub fn shortcut(&self) -> bool {
+        if cfg!(target_os = "macos") {
+            self.logo
+        } else {
+            self.ctrl
+        }
+    }
+}
+
 impl Default for ModifiersState {
     fn default() -> Self {
         ModifiersState {
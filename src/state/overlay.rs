@@ -0,0 +1,138 @@
+// Popups and modal dialogs which are drawn above the rest of the window.
+// An overlay stays where it is in the hierarchy, so it is styled and receives events as before, but while it is
+// open it isn't clipped by its ancestors and is placed next to its anchor, inside the window.
+
+use crate::{Entity, EventHandler};
+
+// Where an overlay is placed
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Placement {
+    // Below the anchor, or above it if there isn't room below
+    Below,
+    // To the right of the anchor, or to the left if there isn't room on the right
+    Right,
+    // Below the anchor if there's room, otherwise to the right, above or to the left
+    Auto,
+    // In the middle of the window
+    Center,
+    // With its top left corner at a point in the window, or flipped to fit
+    At(f32, f32),
+}
+
+// Sent up from an overlay when it is closed, including when it is dismissed or closed along with an overlay below it
+#[derive(Debug, Clone, PartialEq)]
+pub enum OverlayEvent {
+    Closed(Entity),
+}
+
+#[derive(Clone, Debug)]
+pub struct Overlay {
+    pub entity: Entity,
+    // The entity the overlay is placed next to
+    pub anchor: Entity,
+    pub placement: Placement,
+    // A modal overlay has a backdrop covering the rest of the window and keeps focus inside of it
+    pub modal: bool,
+    // Closes when the mouse is pressed outside of it or escape is pressed
+    pub light_dismiss: bool,
+
+    pub(crate) backdrop: Entity,
+    // The focused entity when the overlay was opened, which is focused again when it closes
+    pub(crate) focus: Entity,
+    // The distance the overlay is moved from where it was laid out
+    pub(crate) offset: (f32, f32),
+}
+
+impl Overlay {
+    // A popup placed next to an anchor which is dismissed by pressing outside of it
+    pub fn popup(anchor: Entity, placement: Placement) -> Self {
+        Overlay {
+            entity: Entity::null(),
+            anchor,
+            placement,
+            modal: false,
+            light_dismiss: true,
+            backdrop: Entity::null(),
+            focus: Entity::null(),
+            offset: (0.0, 0.0),
+        }
+    }
+
+    // A dialog in the middle of the window which must be closed by the application
+    pub fn modal() -> Self {
+        Overlay {
+            entity: Entity::null(),
+            anchor: Entity::null(),
+            placement: Placement::Center,
+            modal: true,
+            light_dismiss: false,
+            backdrop: Entity::null(),
+            focus: Entity::null(),
+            offset: (0.0, 0.0),
+        }
+    }
+
+    pub fn with_placement(mut self, placement: Placement) -> Self {
+        self.placement = placement;
+        self
+    }
+
+    pub fn with_light_dismiss(mut self, light_dismiss: bool) -> Self {
+        self.light_dismiss = light_dismiss;
+        self
+    }
+
+    pub fn get_backdrop(&self) -> Entity {
+        self.backdrop
+    }
+}
+
+// The open overlays, from the bottom to the top
+#[derive(Clone, Debug, Default)]
+pub struct Overlays {
+    pub(crate) stack: Vec<Overlay>,
+}
+
+impl Overlays {
+    pub fn new() -> Self {
+        Overlays { stack: Vec::new() }
+    }
+
+    pub fn is_open(&self, entity: Entity) -> bool {
+        self.stack.iter().any(|overlay| overlay.entity == entity)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.stack.is_empty()
+    }
+
+    pub fn get(&self, entity: Entity) -> Option<&Overlay> {
+        self.stack.iter().find(|overlay| overlay.entity == entity)
+    }
+
+    // Returns the overlay which is drawn above the others
+    pub fn top(&self) -> Option<&Overlay> {
+        self.stack.last()
+    }
+
+    // Returns the topmost modal overlay
+    pub fn modal(&self) -> Option<&Overlay> {
+        self.stack.iter().rev().find(|overlay| overlay.modal)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Overlay> {
+        self.stack.iter()
+    }
+
+    // Returns true if the entity is an open overlay or the backdrop of one
+    pub fn is_layer(&self, entity: Entity) -> bool {
+        self.stack
+            .iter()
+            .any(|overlay| overlay.entity == entity || overlay.backdrop == entity)
+    }
+}
+
+// Covers the window behind a modal overlay. Styled with the backdrop element selector.
+pub(crate) struct Backdrop;
+
+impl EventHandler for Backdrop {}
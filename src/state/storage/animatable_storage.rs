@@ -34,6 +34,8 @@ impl Index {
         // Set first bit to 1 to indicate that the value is inhertied
         if val {
             self.0 = self.0 | mask;
+        } else {
+            self.0 &= !mask;
         }
 
         self
@@ -44,6 +46,8 @@ impl Index {
         
         if val {
             self.0 = self.0 | mask;
+        } else {
+            self.0 &= !mask;
         }
 
         self
@@ -75,7 +79,7 @@ impl Index {
 
 impl Default for Index {
     fn default() -> Self {
-        Index(std::usize::MAX & (std::usize::MAX / 2).rotate_right(1))
+        Index(usize::MAX / 4)
     }
 }
 
@@ -144,6 +148,8 @@ pub enum LinkType {
     AlreadyLinked,
     NoRule,
     NoData,
+    // The rule sets the property to inherit from the parent
+    Inherit,
 }
 
 #[derive(Copy, Clone)]
@@ -185,9 +191,10 @@ impl Default for DataIndex {
 }
 
 pub struct AnimatableStorage<T: Interpolator> {
-    // Mapping from entity index to data and animations
+    // Mapping from entity index to data and animations.
+    // An inherited index points to the parent entity instead of data.
     pub entity_indices: Vec<DataIndex>,
-    // Mapping from rule index to data
+    // Mapping from rule index to data. An inherited index marks a rule which sets the property to inherit.
     pub rule_indices: Vec<DataIndex>,
     // An index to the animation either in definitions or active
     //pub animation_indices: Vec<usize>,
@@ -197,6 +204,12 @@ pub struct AnimatableStorage<T: Interpolator> {
     pub animations: Vec<AnimationState<T>>,
     // Active Animations
     pub active_animations: Vec<AnimationState<T>>,
+    // Animation descriptions added by rules (transitions and keyframes), which are removed with the rules
+    rule_animations: Vec<usize>,
+    // Removed animation descriptions which can be reused, so that the ids of the others don't change
+    free_animations: Vec<usize>,
+    // Entities given an inline value since they were last taken, for properties which are tracked
+    inserted: Option<Vec<Entity>>,
 }
 
 impl<T> AnimatableStorage<T>
@@ -211,11 +224,33 @@ where
             data: Vec::new(),
             animations: Vec::new(),
             active_animations: Vec::new(),
+            rule_animations: Vec::new(),
+            free_animations: Vec::new(),
+            inserted: None,
+        }
+    }
+
+    // Records the entities which are given inline values, so that a change can be noticed
+    // without going through a setter
+    pub fn track_inserts(mut self) -> Self {
+        self.inserted = Some(Vec::new());
+        self
+    }
+
+    // Returns the entities given inline values since this was last called
+    pub fn take_inserted(&mut self) -> Vec<Entity> {
+        match &mut self.inserted {
+            Some(inserted) => std::mem::take(inserted),
+            None => Vec::new(),
         }
     }
 
     // Insert inline data
     pub fn insert(&mut self, entity: Entity, value: T) {
+        if let Some(inserted) = &mut self.inserted {
+            inserted.push(entity);
+        }
+
         if entity.index() >= self.entity_indices.len() {
             // Resize entity indices to include new entity
             self.entity_indices
@@ -228,7 +263,8 @@ where
         } else {
             let data_index = self.entity_indices[entity.index()].data_index;
 
-            if data_index.index() >= self.data.len() {
+            // Data linked to a rule is shared so the inline value is added separately
+            if !data_index.is_inline() || data_index.index() >= self.data.len() {
                 self.entity_indices[entity.index()].data_index =
                     Index::new(self.data.len()).inherited(false).inline(true);
                 //self.entity_indices[entity.index()].animation_index = AnimationIndex::default();
@@ -248,6 +284,11 @@ where
 
     // Insert an animation definition
     pub fn insert_animation(&mut self, animation_state: AnimationState<T>) -> usize {
+        if let Some(animation_id) = self.free_animations.pop() {
+            self.animations[animation_id] = animation_state;
+            return animation_id;
+        }
+
         let animation_id = self.animations.len();
 
         self.animations.push(animation_state);
@@ -255,6 +296,14 @@ where
         return animation_id;
     }
 
+    // Insert an animation definition which belongs to the style rules, such as one made from @keyframes
+    pub fn insert_rule_animation(&mut self, animation_state: AnimationState<T>) -> usize {
+        let animation_id = self.insert_animation(animation_state);
+        self.rule_animations.push(animation_id);
+
+        animation_id
+    }
+
     pub fn play_animation(&mut self, entity: Entity, description_id: usize) {
 
         // Check if animation exists
@@ -278,6 +327,8 @@ where
             self.active_animations[animation_index].start_time = std::time::Instant::now();
             self.active_animations[animation_index].duration = animation.duration;
             self.active_animations[animation_index].delay = animation.delay;
+            self.active_animations[animation_index].iteration_count = animation.iteration_count;
+            self.active_animations[animation_index].persistent = animation.persistent;
             self.active_animations[animation_index].keyframes = animation.keyframes.clone();
             // FIX ME (Needed because sometimes drawing happens before animation for some reason. Stops output being null if accessed before animated)
             self.active_animations[animation_index].output = Some(animation.keyframes.first().unwrap().1.clone());
@@ -305,7 +356,7 @@ where
             let start = state.keyframes.first().unwrap();
             let end = state.keyframes.last().unwrap();
 
-            if start.1 == end.1 {
+            if state.keyframes.iter().all(|keyframe| keyframe.1 == start.1) {
                 state.t0 = 1.0;
                 state.output = Some(end.1.clone());
                 continue;
@@ -316,12 +367,12 @@ where
             // Store previous time state
             state.t0 = state.t;
 
-            // Update time state
-            state.t = (elapsed_time.as_secs_f32() / state.duration.as_secs_f32()) - state.delay;
+            // Progress through the animation measured in iterations
+            let progress = (elapsed_time.as_secs_f32() / state.duration.as_secs_f32()) - state.delay;
 
-            if state.t >= 1.0 {
+            if progress >= state.iteration_count {
                 //Animation is finished
-                state.output = Some(T::interpolate(&start.1, &end.1, 1.0));
+                state.output = state.sample(1.0);
 
                 if !state.persistent {
                     state.t = 1.0;
@@ -329,10 +380,13 @@ where
                 } else {
                     state.t = 1.0;
                 }
-            } else if state.t <= 0.0 {
+            } else if progress <= 0.0 {
+                state.t = progress;
                 state.output = Some(start.1.clone());
             } else {
-                state.output = Some(T::interpolate(&start.1, &end.1, state.t));
+                // Update time state (wraps back to 0.0 for each iteration)
+                state.t = progress - progress.floor();
+                state.output = state.sample(state.t);
             }
         }
 
@@ -401,6 +455,10 @@ where
             return LinkType::NoRule;
         }
 
+        if self.rule_indices[rule].data_index.is_inherited() {
+            return LinkType::Inherit;
+        }
+
         let rule_data_index = self.rule_indices[rule].data_index.index();
 
         // Check if the rule has any associated data
@@ -414,8 +472,10 @@ where
                 .resize(entity.index() + 1, Default::default());
         }
 
+        let current_data_index = self.entity_indices[entity.index()].data_index;
+
         // Check if the entity is already linked to the rule
-        if self.entity_indices[entity.index()].data_index.index() == rule_data_index {
+        if !current_data_index.is_inherited() && current_data_index.index() == rule_data_index {
             return LinkType::AlreadyLinked;
         }
 
@@ -450,19 +510,25 @@ where
         } else {
             if rule_animation_id < self.animations.len() {
                 // Get the transition animation definition
-                let transition = self.animations.get_mut(rule_animation_id).unwrap();
-                let current_data_index = self.entity_indices[entity.index()].data_index.index();
-                let start = self
-                    .data
-                    .get(current_data_index)
-                    .cloned()
-                    .unwrap_or_default();
+                // An inherited value transitions from the value of the parent
+                let (start, from_rule) = if current_data_index.is_inherited() {
+                    (self.get(entity).cloned().unwrap_or_default(), usize::MAX)
+                } else {
+                    let start = self
+                        .data
+                        .get(current_data_index.index())
+                        .cloned()
+                        .unwrap_or_default();
+                    (start, current_data_index.index())
+                };
                 let end = self.data.get(rule_data_index).cloned().unwrap_or_default();
 
+                let transition = self.animations.get_mut(rule_animation_id).unwrap();
+
                 *transition.keyframes.first_mut().unwrap() = (0.0, start);
                 *transition.keyframes.last_mut().unwrap() = (1.0, end);
 
-                transition.from_rule = self.entity_indices[entity.index()].data_index.index();
+                transition.from_rule = from_rule;
                 transition.to_rule = rule_data_index;
 
                 // Play any transition animation
@@ -494,6 +560,119 @@ where
         self.entity_indices[entity.index()].data_index = Index::default();
     }
 
+    // Removes an entity from the storage, stopping its animations and freeing any inline data which belongs only to it
+    pub fn remove(&mut self, entity: Entity) {
+        if entity.index() >= self.entity_indices.len() {
+            return;
+        }
+
+        let data_index = self.entity_indices[entity.index()];
+
+        self.entity_indices[entity.index()] = DataIndex::default();
+
+        if data_index.anim_id() < self.active_animations.len() {
+            self.active_animations[data_index.anim_id()]
+                .entities
+                .retain(|e| *e != entity);
+
+            // Drop animations which are no longer playing on any entity
+            self.active_animations
+                .retain(|animation| !animation.entities.is_empty());
+
+            for (index, state) in self.active_animations.iter().enumerate() {
+                for entity in state.entities.iter() {
+                    self.entity_indices[entity.index()].animation_id = index;
+                }
+            }
+        }
+
+        // Rule data is shared so is only removed with the rule
+        let data_index = data_index.index();
+
+        if !data_index.is_inline() || data_index.index() >= self.data.len() {
+            return;
+        }
+
+        // Inherited entities point to their parent rather than to data
+        let shared = self
+            .rule_indices
+            .iter()
+            .chain(self.entity_indices.iter())
+            .any(|index| {
+                !index.data_index.is_inherited() && index.data_index.index() == data_index.index()
+            });
+
+        if !shared {
+            self.remove_data(data_index.index());
+        }
+    }
+
+    // Removes the data for every rule and unlinks entities from them, keeping inline data.
+    // The transitions and keyframe animations defined by rules are removed too, freeing their ids to be reused,
+    // while animations inserted by widgets are kept. Playing animations carry on.
+    pub fn clear_rules(&mut self) {
+        let mut data = Vec::new();
+
+        for index in self.entity_indices.iter_mut() {
+            if index.data_index.is_inline() && index.data_index.index() < self.data.len() {
+                data.push(self.data[index.data_index.index()].clone());
+                index.data_index.set_value(data.len() - 1);
+            } else {
+                index.data_index = Index::default();
+            }
+        }
+
+        self.data = data;
+        self.rule_indices.clear();
+
+        for animation_id in self.rule_animations.drain(..) {
+            self.animations[animation_id] = AnimationState::default();
+            self.free_animations.push(animation_id);
+        }
+
+        // Playing transitions can no longer be reversed as the rule data has gone
+        for animation in self.active_animations.iter_mut() {
+            animation.from_rule = usize::MAX;
+            animation.to_rule = usize::MAX;
+        }
+    }
+
+    // Removes data by swapping it with the last item and updating anything which pointed to the last item
+    fn remove_data(&mut self, data_index: usize) {
+        let last = self.data.len() - 1;
+
+        self.data.swap_remove(data_index);
+
+        if data_index == last {
+            return;
+        }
+
+        for index in self
+            .entity_indices
+            .iter_mut()
+            .chain(self.rule_indices.iter_mut())
+        {
+            if !index.data_index.is_inherited() && index.data_index.index() == last {
+                index.data_index.set_value(data_index);
+            }
+        }
+
+        // Transitions refer to the data they animate between
+        for animation in self
+            .animations
+            .iter_mut()
+            .chain(self.active_animations.iter_mut())
+        {
+            if animation.from_rule == last {
+                animation.from_rule = data_index;
+            }
+
+            if animation.to_rule == last {
+                animation.to_rule = data_index;
+            }
+        }
+    }
+
     pub fn link_rule(&mut self, entity: Entity, rule_list: &Vec<usize>) -> bool {
         // Check if the entity already has an inline style. If so then rules don't affect it.
         if entity.index() < self.entity_indices.len() {
@@ -512,16 +691,29 @@ where
                     return false;
                 }
 
+                LinkType::Inherit => break,
+
                 _ => {}
             }
         }
 
-        // If none of the matching rules have a specified property then unlink the entity from any rules
-        // Cascading could happen here but would need to pass in the hierarchy
+        // If none of the matching rules have a specified property then unlink the entity from any rules.
+        // Inherited properties are then cascaded from the parent with inherit().
+        // Returns true if the entity was linked to a rule which no longer matches
+        if entity.index() >= self.entity_indices.len() {
+            return false;
+        }
+
+        // An entity which already inherits keeps its link so that restyling it doesn't report a change
+        if self.entity_indices[entity.index()].data_index.is_inherited() {
+            return false;
+        }
+
+        let linked = self.entity_indices[entity.index()].data_index.index() < self.data.len();
 
         self.unlink(entity);
 
-        false
+        linked
     }
 
     // Insert rule data
@@ -541,10 +733,53 @@ where
         }
     }
 
+    // Marks a rule as setting the property to inherit from the parent
+    pub fn insert_rule_inherit(&mut self, rule: usize) {
+        if rule >= self.rule_indices.len() {
+            self.rule_indices.resize(rule + 1, Default::default());
+        }
+
+        self.rule_indices[rule].data_index = Index::default().inherited(true);
+    }
+
+    pub fn is_inherit_rule(&self, rule: usize) -> bool {
+        self.rule_indices
+            .get(rule)
+            .map(|index| index.data_index.is_inherited())
+            .unwrap_or(false)
+    }
+
+    // Inherits the value of the parent if the entity has no inline value and isn't linked to a rule.
+    // Returns true if the entity wasn't already inheriting from the parent.
+    pub fn inherit(&mut self, entity: Entity, parent: Entity) -> bool {
+        if entity.index() >= self.entity_indices.len() {
+            self.entity_indices
+                .resize(entity.index() + 1, Default::default());
+        }
+
+        let data_index = self.entity_indices[entity.index()].data_index;
+
+        if data_index.is_inherited() {
+            if data_index.index() == parent.index() {
+                return false;
+            }
+        } else if data_index.index() < self.data.len() {
+            return false;
+        }
+
+        self.entity_indices[entity.index()].data_index = Index::new(parent.index()).inherited(true);
+
+        true
+    }
+
     // Links a rule to a transition animation
     pub fn insert_transition(&mut self, rule: usize, animation_state: AnimationState<T>) {
-        self.rule_indices[rule].animation_id = self.animations.len();
-        self.animations.push(animation_state);
+        // The rule may not have a value for the property yet
+        if rule >= self.rule_indices.len() {
+            self.rule_indices.resize(rule + 1, Default::default());
+        }
+
+        self.rule_indices[rule].animation_id = self.insert_rule_animation(animation_state);
     }
 
     // Get the current value (either animation or data rule)
@@ -561,6 +796,11 @@ where
 
         let data_index = self.entity_indices[entity.index()].data_index;
 
+        // Inherited values are looked up on the parent so that they follow its animations
+        if data_index.is_inherited() {
+            return self.get(Entity::new(data_index.index() as u16, 0));
+        }
+
         if data_index.index() >= self.data.len() {
             return None;
         }
@@ -583,6 +823,20 @@ where
         true
     }
 
+    pub fn get_rule(&self, rule: usize) -> Option<&T> {
+        let data_index = self.rule_indices.get(rule)?.data_index.index();
+
+        self.data.get(data_index)
+    }
+
+    // Returns true if the entity has an inline value, which takes priority over rules
+    pub fn is_inline(&self, entity: Entity) -> bool {
+        self.entity_indices
+            .get(entity.index())
+            .map(|index| index.data_index.is_inline() && index.data_index.index() < self.data.len())
+            .unwrap_or(false)
+    }
+
     pub fn get_rule_mut(&mut self, rule: usize) -> Option<&mut T> {
         if rule >= self.rule_indices.len() {
             return None;
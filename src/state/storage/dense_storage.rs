@@ -32,7 +32,31 @@ where
         }
     }
 
-    pub fn remove(&mut self, _entity: Entity) {}
+    // Removes the data for an entity by swapping it with the last item in the storage
+    pub fn remove(&mut self, entity: Entity) {
+        if entity.index() >= self.indices.len() {
+            return;
+        }
+
+        let data_index = self.indices[entity.index()] as usize;
+
+        if data_index >= self.data.len() {
+            return;
+        }
+
+        self.indices[entity.index()] = u32::MAX;
+
+        let last = self.data.len() - 1;
+
+        self.data.swap_remove(data_index);
+
+        // Point the entity which owned the last item to its new position
+        if data_index != last {
+            if let Some(index) = self.indices.iter_mut().find(|index| **index as usize == last) {
+                *index = data_index as u32;
+            }
+        }
+    }
 
     pub fn get(&self, entity: Entity) -> Option<&T> {
         if entity.index() >= self.indices.len() {
@@ -6,8 +6,15 @@ pub enum LinkType {
     AlreadyLinked,
     NoRule,
     NoData,
+    // The rule sets the property to inherit from the parent
+    Inherit,
 }
 
+// An animation index which marks the entity as inheriting, with the data index pointing to the parent entity
+const INHERITED: usize = usize::MAX - 2;
+// A rule index which marks the rule as setting the property to inherit
+const INHERIT_RULE: usize = usize::MAX - 1;
+
 #[derive(Copy, Clone)]
 pub struct DataIndex {
     pub data_index: usize,
@@ -52,6 +59,8 @@ pub struct StyleStorage<T> {
     // Mapping from rule to data
     pub rule_indices: Vec<usize>,
     pub data: Vec<T>,
+    // Entities given an inline value since they were last taken, for properties which are tracked
+    inserted: Option<Vec<Entity>>,
 }
 
 impl<T> StyleStorage<T>
@@ -63,11 +72,31 @@ where
             entity_indices: Vec::new(),
             rule_indices: Vec::new(),
             data: Vec::new(),
+            inserted: None,
+        }
+    }
+
+    // Records the entities which are given inline values, so that a change can be noticed
+    // without going through a setter
+    pub fn track_inserts(mut self) -> Self {
+        self.inserted = Some(Vec::new());
+        self
+    }
+
+    // Returns the entities given inline values since this was last called
+    pub fn take_inserted(&mut self) -> Vec<Entity> {
+        match &mut self.inserted {
+            Some(inserted) => std::mem::take(inserted),
+            None => Vec::new(),
         }
     }
 
     //Use std::usize::MAX to represent inline style
     pub fn insert(&mut self, entity: Entity, value: T) {
+        if let Some(inserted) = &mut self.inserted {
+            inserted.push(entity);
+        }
+
         if entity.index() >= self.entity_indices.len() {
             //println!("Insert New: {:?} - Data {:?}", entity, value);
             self.entity_indices
@@ -76,13 +105,15 @@ where
             self.entity_indices[entity.index()].animation_index = std::usize::MAX - 1;
             self.data.push(value);
         } else {
-            let data_index = self.entity_indices[entity.index()].data_index;
+            let data_index = self.entity_indices[entity.index()];
 
-            if data_index >= self.data.len() {
+            // Data linked to a rule is shared so the inline value is added separately
+            if data_index.anim_index() != usize::MAX - 1 || data_index.index() >= self.data.len() {
                 self.entity_indices[entity.index()].data_index = self.data.len();
 
                 self.data.push(value);
             } else {
+                let data_index = data_index.index();
                 self.data[data_index] = value;
             }
 
@@ -104,6 +135,10 @@ where
 
         let rule_data_index = self.rule_indices[rule];
 
+        if rule_data_index == INHERIT_RULE {
+            return LinkType::Inherit;
+        }
+
         // Check if the rule has any associated data
         // BUG - If there is no rule then reverse transitions wont work
         if rule_data_index >= self.data.len() {
@@ -118,11 +153,13 @@ where
         // Link the entity to the same data as the rule
 
         // Check if the entity is already linked to the rule
-        if self.entity_indices[entity.index()].data_index == rule_data_index {
+        if self.entity_indices[entity.index()].anim_index() != INHERITED
+            && self.entity_indices[entity.index()].data_index == rule_data_index
+        {
             return LinkType::AlreadyLinked;
         }
 
-        self.entity_indices[entity.index()].data_index = rule_data_index;
+        self.entity_indices[entity.index()] = DataIndex::from_index(rule_data_index);
 
         LinkType::NewLink
     }
@@ -135,6 +172,72 @@ where
         self.entity_indices[entity.index()] = DataIndex::default();
     }
 
+    // Removes an entity from the storage, freeing any inline data which belongs only to it
+    pub fn remove(&mut self, entity: Entity) {
+        if entity.index() >= self.entity_indices.len() {
+            return;
+        }
+
+        let data_index = self.entity_indices[entity.index()];
+
+        self.entity_indices[entity.index()] = DataIndex::default();
+
+        // Rule data is shared so is only removed with the rule
+        if data_index.anim_index() != usize::MAX - 1 || data_index.index() >= self.data.len() {
+            return;
+        }
+
+        // Inherited entities point to their parent rather than to data
+        let shared = self.rule_indices.contains(&data_index.index())
+            || self.entity_indices.iter().any(|index| {
+                index.anim_index() != INHERITED && index.data_index == data_index.index()
+            });
+
+        if !shared {
+            self.remove_data(data_index.index());
+        }
+    }
+
+    // Removes the data for every rule and unlinks entities from them, keeping inline data
+    pub fn clear_rules(&mut self) {
+        let mut data = Vec::new();
+
+        for index in self.entity_indices.iter_mut() {
+            if index.anim_index() == usize::MAX - 1 && index.index() < self.data.len() {
+                data.push(self.data[index.index()].clone());
+                index.data_index = data.len() - 1;
+            } else {
+                *index = DataIndex::default();
+            }
+        }
+
+        self.data = data;
+        self.rule_indices.clear();
+    }
+
+    // Removes data by swapping it with the last item and updating anything which pointed to the last item
+    fn remove_data(&mut self, data_index: usize) {
+        let last = self.data.len() - 1;
+
+        self.data.swap_remove(data_index);
+
+        if data_index == last {
+            return;
+        }
+
+        for index in self.entity_indices.iter_mut() {
+            if index.anim_index() != INHERITED && index.data_index == last {
+                index.data_index = data_index;
+            }
+        }
+
+        for index in self.rule_indices.iter_mut() {
+            if *index == last {
+                *index = data_index;
+            }
+        }
+    }
+
     // Returns true if
     pub fn link_rule(&mut self, entity: Entity, rule_list: &Vec<usize>) -> bool {
         // Check if the entity already has an inline style. If so then rules don't affect it.
@@ -154,15 +257,29 @@ where
                     return false;
                 }
 
+                LinkType::Inherit => break,
+
                 _ => {}
             }
         }
 
-        // If none of the matching rules have a specified property then unlink the entity from any rules
+        // If none of the matching rules have a specified property then unlink the entity from any rules.
+        // Inherited properties are then cascaded from the parent with inherit().
+        // Returns true if the entity was linked to a rule which no longer matches
+        if entity.index() >= self.entity_indices.len() {
+            return false;
+        }
+
+        // An entity which already inherits keeps its link so that restyling it doesn't report a change
+        if self.entity_indices[entity.index()].anim_index() == INHERITED {
+            return false;
+        }
+
+        let linked = self.entity_indices[entity.index()].data_index < self.data.len();
 
         self.unlink(entity);
 
-        false
+        linked
     }
 
     // Insert data
@@ -183,12 +300,50 @@ where
         }
     }
 
+    // Marks a rule as setting the property to inherit from the parent
+    pub fn insert_rule_inherit(&mut self, rule: usize) {
+        if rule >= self.rule_indices.len() {
+            self.rule_indices.resize(rule + 1, usize::MAX);
+        }
+
+        self.rule_indices[rule] = INHERIT_RULE;
+    }
+
+    // Inherits the value of the parent if the entity has no inline value and isn't linked to a rule.
+    // Returns true if the entity wasn't already inheriting from the parent.
+    pub fn inherit(&mut self, entity: Entity, parent: Entity) -> bool {
+        if entity.index() >= self.entity_indices.len() {
+            self.entity_indices
+                .resize(entity.index() + 1, Default::default());
+        }
+
+        let data_index = self.entity_indices[entity.index()];
+
+        if data_index.anim_index() == INHERITED {
+            if data_index.index() == parent.index() {
+                return false;
+            }
+        } else if data_index.index() < self.data.len() {
+            return false;
+        }
+
+        self.entity_indices[entity.index()] = DataIndex::new(parent.index(), INHERITED);
+
+        true
+    }
+
     // Get data linked to entity
     pub fn get(&self, entity: Entity) -> Option<&T> {
         if entity.index() >= self.entity_indices.len() {
             return None;
         }
 
+        // Inherited values are looked up on the parent
+        if self.entity_indices[entity.index()].anim_index() == INHERITED {
+            let parent = self.entity_indices[entity.index()].index();
+            return self.get(Entity::new(parent as u16, 0));
+        }
+
         let data_index = self.entity_indices[entity.index()].index();
 
         if data_index >= self.data.len() {
@@ -203,6 +358,11 @@ where
             return None;
         }
 
+        // The data of an inherited value belongs to an ancestor
+        if self.entity_indices[entity.index()].anim_index() == INHERITED {
+            return None;
+        }
+
         let data_index = self.entity_indices[entity.index()].index();
 
         if data_index >= self.data.len() {
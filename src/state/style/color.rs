@@ -42,6 +42,153 @@ impl Color {
         ((self.data & 0xFF00_0000) >> 24) as u8
     }
 
+    // Create a new color from HSL, with the hue in degrees and the saturation and lightness from 0 to 1
+    pub fn hsl(h: f32, s: f32, l: f32) -> Self {
+        Color::hsla(h, s, l, 1.0)
+    }
+
+    // Create a new color from HSLA, with the alpha from 0 to 1
+    pub fn hsla(h: f32, s: f32, l: f32, a: f32) -> Self {
+        let h = h.rem_euclid(360.0) / 360.0;
+        let s = s.clamp(0.0, 1.0);
+        let l = l.clamp(0.0, 1.0);
+
+        let q = if l < 0.5 { l * (1.0 + s) } else { l + s - l * s };
+        let p = 2.0 * l - q;
+
+        let r = hue_to_rgb(p, q, h + 1.0 / 3.0);
+        let g = hue_to_rgb(p, q, h);
+        let b = hue_to_rgb(p, q, h - 1.0 / 3.0);
+
+        Color::rgba(to_u8(r), to_u8(g), to_u8(b), to_u8(a))
+    }
+
+    // Parse a CSS hex color (#rgb, #rgba, #rrggbb or #rrggbbaa), where the alpha comes last
+    pub fn from_hex(hex: &str) -> Option<Color> {
+        let hex = hex.trim_start_matches('#');
+
+        if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+
+        let x = u32::from_str_radix(hex, 16).ok()?;
+
+        // Short forms repeat each digit
+        let expand = |x: u32, digits: u32| {
+            (0..digits).rev().fold(0, |data, digit| {
+                let value = (x >> (digit * 4)) & 0xF;
+                (data << 8) | (value << 4) | value
+            })
+        };
+
+        let (rgb, a) = match hex.len() {
+            3 => (expand(x, 3), 0xFF),
+            4 => (expand(x >> 4, 3), expand(x & 0xF, 1)),
+            6 => (x, 0xFF),
+            8 => (x >> 8, x & 0xFF),
+            _ => return None,
+        };
+
+        Some(Color {
+            data: (a << 24) | rgb,
+        })
+    }
+
+    // Look up a CSS named color
+    pub fn from_name(name: &str) -> Option<Color> {
+        let name = name.to_ascii_lowercase();
+
+        if name == "transparent" {
+            return Some(Color::rgba(0, 0, 0, 0));
+        }
+
+        NAMED_COLORS
+            .binary_search_by(|(color_name, _)| color_name.cmp(&name.as_str()))
+            .ok()
+            .map(|index| {
+                let rgb = NAMED_COLORS[index].1;
+                Color {
+                    data: 0xFF00_0000 | rgb,
+                }
+            })
+    }
+
+    // Get the hue (in degrees), saturation and lightness (from 0 to 1)
+    pub fn to_hsl(self) -> (f32, f32, f32) {
+        let r = self.r() as f32 / 255.0;
+        let g = self.g() as f32 / 255.0;
+        let b = self.b() as f32 / 255.0;
+
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let l = (max + min) / 2.0;
+
+        if max == min {
+            return (0.0, 0.0, l);
+        }
+
+        let d = max - min;
+        let s = if l > 0.5 {
+            d / (2.0 - max - min)
+        } else {
+            d / (max + min)
+        };
+
+        let h = if max == r {
+            (g - b) / d + if g < b { 6.0 } else { 0.0 }
+        } else if max == g {
+            (b - r) / d + 2.0
+        } else {
+            (r - g) / d + 4.0
+        };
+
+        (h * 60.0, s, l)
+    }
+
+    // Returns the color with a different alpha value
+    pub fn with_alpha(self, a: u8) -> Color {
+        Color::rgba(self.r(), self.g(), self.b(), a)
+    }
+
+    // Increase the lightness by an amount from 0 to 1
+    pub fn lighten(self, amount: f32) -> Color {
+        let (h, s, l) = self.to_hsl();
+        Color::hsl(h, s, l + amount).with_alpha(self.a())
+    }
+
+    // Decrease the lightness by an amount from 0 to 1
+    pub fn darken(self, amount: f32) -> Color {
+        self.lighten(-amount)
+    }
+
+    // Mix with another color, where weight is the proportion of this color from 0 to 1
+    pub fn mix(self, other: Color, weight: f32) -> Color {
+        Color::interpolate(self, other, 1.0 - weight.clamp(0.0, 1.0) as f64)
+    }
+
+    // Composite this color over a background color using its alpha
+    pub fn blend(self, background: Color) -> Color {
+        let alpha = self.a() as f32 / 255.0;
+        let background_alpha = background.a() as f32 / 255.0;
+
+        let a = alpha + background_alpha * (1.0 - alpha);
+        if a == 0.0 {
+            return Color::default();
+        }
+
+        let channel = |top: u8, bottom: u8| {
+            let value = (top as f32 * alpha + bottom as f32 * background_alpha * (1.0 - alpha)) / a;
+            value.round() as u8
+        };
+
+        Color::rgba(
+            channel(self.r(), background.r()),
+            channel(self.g(), background.g()),
+            channel(self.b(), background.b()),
+            to_u8(a),
+        )
+    }
+
     // Interpolate between two colors
     pub fn interpolate(start_color: Color, end_color: Color, scale: f64) -> Color {
         let r = Color::interp(start_color.r(), end_color.r(), scale);
@@ -56,6 +203,176 @@ impl Color {
     }
 }
 
+// CSS named colors, sorted by name
+const NAMED_COLORS: [(&str, u32); 148] = [
+    ("aliceblue", 0xF0F8FF),
+    ("antiquewhite", 0xFAEBD7),
+    ("aqua", 0x00FFFF),
+    ("aquamarine", 0x7FFFD4),
+    ("azure", 0xF0FFFF),
+    ("beige", 0xF5F5DC),
+    ("bisque", 0xFFE4C4),
+    ("black", 0x000000),
+    ("blanchedalmond", 0xFFEBCD),
+    ("blue", 0x0000FF),
+    ("blueviolet", 0x8A2BE2),
+    ("brown", 0xA52A2A),
+    ("burlywood", 0xDEB887),
+    ("cadetblue", 0x5F9EA0),
+    ("chartreuse", 0x7FFF00),
+    ("chocolate", 0xD2691E),
+    ("coral", 0xFF7F50),
+    ("cornflowerblue", 0x6495ED),
+    ("cornsilk", 0xFFF8DC),
+    ("crimson", 0xDC143C),
+    ("cyan", 0x00FFFF),
+    ("darkblue", 0x00008B),
+    ("darkcyan", 0x008B8B),
+    ("darkgoldenrod", 0xB8860B),
+    ("darkgray", 0xA9A9A9),
+    ("darkgreen", 0x006400),
+    ("darkgrey", 0xA9A9A9),
+    ("darkkhaki", 0xBDB76B),
+    ("darkmagenta", 0x8B008B),
+    ("darkolivegreen", 0x556B2F),
+    ("darkorange", 0xFF8C00),
+    ("darkorchid", 0x9932CC),
+    ("darkred", 0x8B0000),
+    ("darksalmon", 0xE9967A),
+    ("darkseagreen", 0x8FBC8F),
+    ("darkslateblue", 0x483D8B),
+    ("darkslategray", 0x2F4F4F),
+    ("darkslategrey", 0x2F4F4F),
+    ("darkturquoise", 0x00CED1),
+    ("darkviolet", 0x9400D3),
+    ("deeppink", 0xFF1493),
+    ("deepskyblue", 0x00BFFF),
+    ("dimgray", 0x696969),
+    ("dimgrey", 0x696969),
+    ("dodgerblue", 0x1E90FF),
+    ("firebrick", 0xB22222),
+    ("floralwhite", 0xFFFAF0),
+    ("forestgreen", 0x228B22),
+    ("fuchsia", 0xFF00FF),
+    ("gainsboro", 0xDCDCDC),
+    ("ghostwhite", 0xF8F8FF),
+    ("gold", 0xFFD700),
+    ("goldenrod", 0xDAA520),
+    ("gray", 0x808080),
+    ("green", 0x008000),
+    ("greenyellow", 0xADFF2F),
+    ("grey", 0x808080),
+    ("honeydew", 0xF0FFF0),
+    ("hotpink", 0xFF69B4),
+    ("indianred", 0xCD5C5C),
+    ("indigo", 0x4B0082),
+    ("ivory", 0xFFFFF0),
+    ("khaki", 0xF0E68C),
+    ("lavender", 0xE6E6FA),
+    ("lavenderblush", 0xFFF0F5),
+    ("lawngreen", 0x7CFC00),
+    ("lemonchiffon", 0xFFFACD),
+    ("lightblue", 0xADD8E6),
+    ("lightcoral", 0xF08080),
+    ("lightcyan", 0xE0FFFF),
+    ("lightgoldenrodyellow", 0xFAFAD2),
+    ("lightgray", 0xD3D3D3),
+    ("lightgreen", 0x90EE90),
+    ("lightgrey", 0xD3D3D3),
+    ("lightpink", 0xFFB6C1),
+    ("lightsalmon", 0xFFA07A),
+    ("lightseagreen", 0x20B2AA),
+    ("lightskyblue", 0x87CEFA),
+    ("lightslategray", 0x778899),
+    ("lightslategrey", 0x778899),
+    ("lightsteelblue", 0xB0C4DE),
+    ("lightyellow", 0xFFFFE0),
+    ("lime", 0x00FF00),
+    ("limegreen", 0x32CD32),
+    ("linen", 0xFAF0E6),
+    ("magenta", 0xFF00FF),
+    ("maroon", 0x800000),
+    ("mediumaquamarine", 0x66CDAA),
+    ("mediumblue", 0x0000CD),
+    ("mediumorchid", 0xBA55D3),
+    ("mediumpurple", 0x9370DB),
+    ("mediumseagreen", 0x3CB371),
+    ("mediumslateblue", 0x7B68EE),
+    ("mediumspringgreen", 0x00FA9A),
+    ("mediumturquoise", 0x48D1CC),
+    ("mediumvioletred", 0xC71585),
+    ("midnightblue", 0x191970),
+    ("mintcream", 0xF5FFFA),
+    ("mistyrose", 0xFFE4E1),
+    ("moccasin", 0xFFE4B5),
+    ("navajowhite", 0xFFDEAD),
+    ("navy", 0x000080),
+    ("oldlace", 0xFDF5E6),
+    ("olive", 0x808000),
+    ("olivedrab", 0x6B8E23),
+    ("orange", 0xFFA500),
+    ("orangered", 0xFF4500),
+    ("orchid", 0xDA70D6),
+    ("palegoldenrod", 0xEEE8AA),
+    ("palegreen", 0x98FB98),
+    ("paleturquoise", 0xAFEEEE),
+    ("palevioletred", 0xDB7093),
+    ("papayawhip", 0xFFEFD5),
+    ("peachpuff", 0xFFDAB9),
+    ("peru", 0xCD853F),
+    ("pink", 0xFFC0CB),
+    ("plum", 0xDDA0DD),
+    ("powderblue", 0xB0E0E6),
+    ("purple", 0x800080),
+    ("rebeccapurple", 0x663399),
+    ("red", 0xFF0000),
+    ("rosybrown", 0xBC8F8F),
+    ("royalblue", 0x4169E1),
+    ("saddlebrown", 0x8B4513),
+    ("salmon", 0xFA8072),
+    ("sandybrown", 0xF4A460),
+    ("seagreen", 0x2E8B57),
+    ("seashell", 0xFFF5EE),
+    ("sienna", 0xA0522D),
+    ("silver", 0xC0C0C0),
+    ("skyblue", 0x87CEEB),
+    ("slateblue", 0x6A5ACD),
+    ("slategray", 0x708090),
+    ("slategrey", 0x708090),
+    ("snow", 0xFFFAFA),
+    ("springgreen", 0x00FF7F),
+    ("steelblue", 0x4682B4),
+    ("tan", 0xD2B48C),
+    ("teal", 0x008080),
+    ("thistle", 0xD8BFD8),
+    ("tomato", 0xFF6347),
+    ("turquoise", 0x40E0D0),
+    ("violet", 0xEE82EE),
+    ("wheat", 0xF5DEB3),
+    ("white", 0xFFFFFF),
+    ("whitesmoke", 0xF5F5F5),
+    ("yellow", 0xFFFF00),
+    ("yellowgreen", 0x9ACD32),
+];
+
+fn hue_to_rgb(p: f32, q: f32, t: f32) -> f32 {
+    let t = t.rem_euclid(1.0);
+
+    if t < 1.0 / 6.0 {
+        p + (q - p) * 6.0 * t
+    } else if t < 1.0 / 2.0 {
+        q
+    } else if t < 2.0 / 3.0 {
+        p + (q - p) * (2.0 / 3.0 - t) * 6.0
+    } else {
+        p
+    }
+}
+
+fn to_u8(value: f32) -> u8 {
+    (value.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
 impl ToString for Color {
     fn to_string(&self) -> String {
         if self.a() == 0 {
@@ -71,22 +388,17 @@ impl ToString for Color {
     }
 }
 
+// Parses a hex color (#rgb, #rrggbb or #aarrggbb), returning transparent if it isn't valid.
+// The alpha of an 8 digit color comes first, unlike in CSS where it comes last (see Color::from_hex).
 impl From<&str> for Color {
     fn from(s: &str) -> Color {
         let clean_hex = s.trim_start_matches('#');
-        match clean_hex.len() {
-            6 | 8 => {
-                let mut x = match u32::from_str_radix(&clean_hex, 16) {
-                    Ok(x) => x,
-                    Err(_) => 0,
-                };
-
-                if clean_hex.len() == 6 {
-                    x |= 0xFF_000_000;
-                }
 
-                Color { data: x }
-            }
+        match clean_hex.len() {
+            3 | 6 => Color::from_hex(clean_hex).unwrap_or(Color { data: 0 }),
+            8 => Color {
+                data: u32::from_str_radix(clean_hex, 16).unwrap_or(0),
+            },
             _ => Color { data: 0 },
         }
     }
@@ -17,8 +17,8 @@ impl Default for FlexDirection {
 #[derive(Copy, Clone, PartialEq, Debug)]
 pub enum FlexWrap {
     NoWrap,
-    //Wrap,        //TODO
-    //WrapReverse, //TODO
+    Wrap,
+    WrapReverse,
 }
 
 impl Default for FlexWrap {
@@ -1,116 +1,57 @@
-
-// TODO - Grid is not yet implemented
-
-use super::layout::*;
-
-use super::flexbox::JustifyContent;
-
 // Container Properties
-#[derive(Copy, Clone, PartialEq, Debug)]
-pub enum JustifyItems {
-    Start,
-    End,
-    Center,
-    Stretch,
-}
-
-impl Default for JustifyItems {
-    fn default() -> Self {
-        JustifyItems::Start
-    }
-}
 
+// The size of a row or column track in the grid
 #[derive(Copy, Clone, PartialEq, Debug)]
-pub enum AlignItems {
-    Start,
-    End,
-    Center,
-    Stetch,
+pub enum TrackSize {
+    Pixels(f32),     // Value in pixels
+    Percentage(f32), // Percentage of the grid container
+    Fraction(f32),   // Share of the free space (fr)
+    Auto,            // Size of the largest item in the track
 }
 
-impl Default for AlignItems {
+impl Default for TrackSize {
     fn default() -> Self {
-        AlignItems::Start
+        TrackSize::Auto
     }
 }
 
-// Determines the alignment of the grid items in the row dimension when the grid is smaller than the container.
-// #[derive(Copy, Clone, PartialEq, Debug)]
-// pub enum JustifyContent {
-//     Start,
-//     End,
-//     Center,
-//     Stretch,
-//     SpaceBetween,
-//     SpaceAround,
-//     SpaceEvenly,
-// }
-
-// impl Default for JustifyContent {
-//     fn default() -> Self {
-//         JustifyContent::Start
-//     }
-// }
-
 #[derive(Copy, Clone, PartialEq, Debug)]
-pub enum AlignContent {
+pub enum JustifyItems {
     Start,
     End,
     Center,
     Stretch,
-    SpaceBetween,
-    SpaceAround,
-    SpaceEvenly,
 }
 
-impl Default for AlignContent {
+impl Default for JustifyItems {
     fn default() -> Self {
-        AlignContent::Start
+        JustifyItems::Start
     }
 }
 
 // Item Properties
 
-#[derive(Clone, PartialEq, Debug)]
-pub struct GridContainer {
-    pub grid_template_rows: Vec<f32>,
-    pub grid_template_columns: Vec<f32>,
-    pub grid_template_areas: Vec<f32>,
-    pub grid_column_gap: f32,
-    pub grid_row_gap: f32,
+// The placement of an item between grid lines (e.g. grid-column: 1 / span 2)
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct GridPlacement {
+    // Grid line to start at, counting from 1 (0 means the item is placed automatically)
+    pub start: u32,
+    // Number of tracks spanned by the item
+    pub span: u32,
 }
 
-impl Default for GridContainer {
-    fn default() -> Self {
-        GridContainer {
-            grid_template_rows: Vec::new(),
-            grid_template_columns: Vec::new(),
-            grid_template_areas: Vec::new(),
-            grid_column_gap: 0.0,
-            grid_row_gap: 0.0,
-        }
+impl GridPlacement {
+    pub fn new(start: u32, span: u32) -> Self {
+        GridPlacement { start, span }
     }
-}
 
-#[derive(Copy, Clone, PartialEq, Debug)]
-pub struct GridItem {
-    pub grid_row_start: u32,
-    pub grid_row_span: u32,
-    pub grid_column_start: u32,
-    pub grid_column_span: u32,
-    pub justify_self: JustifySelf,
-    pub align_self: AlignSelf,
+    pub fn is_auto(&self) -> bool {
+        self.start == 0
+    }
 }
 
-impl Default for GridItem {
+impl Default for GridPlacement {
     fn default() -> Self {
-        GridItem {
-            grid_row_start: 0,
-            grid_row_span: 0,
-            grid_column_start: 0,
-            grid_column_span: 0,
-            justify_self: Default::default(),
-            align_self: Default::default(),
-        }
+        GridPlacement { start: 0, span: 1 }
     }
 }
@@ -7,6 +7,7 @@ pub enum Length {
     Auto,            // Let Layout System Decide
     Pixels(f32),     // Value in pixels
     Percentage(f32), // Percentage of parent
+    Calc(f32, f32),  // Pixels plus a percentage of parent, from calc()
 }
 
 impl Length {
@@ -23,6 +24,7 @@ impl Length {
             Length::Initial(value) => 0.0,
             Length::Pixels(value) => *value,
             Length::Percentage(value) => *value * 100.0,
+            Length::Calc(value, _) => *value,
         }
     }
 
@@ -49,11 +51,35 @@ impl Default for Length {
 
 impl Interpolator for Length {
     fn interpolate(start: &Self, end: &Self, t: f32) -> Self {
+        // Lengths involving calc() are interpolated by their pixel and percentage parts
+        match (start, end) {
+            (Length::Calc(..), _) | (_, Length::Calc(..)) => {
+                let parts = |length: &Length| match length {
+                    Length::Pixels(val) => Some((*val, 0.0)),
+                    Length::Percentage(val) => Some((0.0, *val)),
+                    Length::Calc(pixels, percentage) => Some((*pixels, *percentage)),
+                    _ => None,
+                };
+
+                if let (Some(s), Some(e)) = (parts(start), parts(end)) {
+                    return Length::Calc(
+                        f32::interpolate(&s.0, &e.0, t),
+                        f32::interpolate(&s.1, &e.1, t),
+                    );
+                }
+
+                return end.clone();
+            }
+
+            _ => {}
+        }
+
         let s = match start {
             Length::Pixels(val) => val,
             Length::Percentage(val) => val,
             Length::Auto => return end.clone(),
             Length::Initial(val) => val,
+            Length::Calc(..) => return end.clone(),
         };
 
         match end {
@@ -61,6 +87,7 @@ impl Interpolator for Length {
             Length::Percentage(e) => Length::Percentage(f32::interpolate(s, e, t)),
             Length::Auto => return end.clone(),
             Length::Initial(e) => Length::Pixels(f32::interpolate(s, e, t)),
+            Length::Calc(..) => return end.clone(),
         }
     }
 }
@@ -152,7 +179,24 @@ impl Default for JustifySelf {
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub enum Overflow {
     Visible,
+    // Clipped without scrollbars, although the contents can still be scrolled by the application
     Hidden,
+    // Clipped with scrollbars which are always shown
+    Scroll,
+    // Clipped with scrollbars which are shown when the contents don't fit
+    Auto,
+}
+
+impl Overflow {
+    // Returns true if the contents are clipped to the bounds of the entity
+    pub fn clips(&self) -> bool {
+        *self != Overflow::Visible
+    }
+
+    // Returns true if the contents can be scrolled with the mouse
+    pub fn scrolls(&self) -> bool {
+        *self == Overflow::Scroll || *self == Overflow::Auto
+    }
 }
 
 impl Default for Overflow {
@@ -161,6 +205,32 @@ impl Default for Overflow {
     }
 }
 
+// The stacking order of an entity among the entities drawn with it. An entity with an index forms a
+// stacking context, so its descendants are drawn and hit-tested together with it.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ZIndex {
+    Auto,
+    Index(i32),
+}
+
+impl ZIndex {
+    // Returns the index of an entity which forms a stacking context
+    pub fn index(&self) -> Option<i32> {
+        match self {
+            ZIndex::Auto => None,
+            ZIndex::Index(index) => Some(*index),
+        }
+    }
+}
+
+impl Default for ZIndex {
+    fn default() -> Self {
+        ZIndex::Auto
+    }
+}
+
+// The scroll position of an entity as a proportion of the distance its contents can move,
+// and the proportion of its contents which is visible
 #[derive(Copy, Clone, Debug)]
 pub struct Scroll {
     pub x: f32,
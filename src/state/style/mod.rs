@@ -2,7 +2,7 @@
 use cssparser::{Parser, ParserInput, RuleListParser};
 
 use std::collections::hash_map::DefaultHasher;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::hash::{Hash, Hasher};
 
 use crate::entity::Entity;
@@ -11,7 +11,9 @@ use crate::state::storage::animatable_storage::AnimatableStorage;
 use crate::state::storage::dense_storage::DenseStorage;
 use crate::state::storage::style_storage::StyleStorage;
 
-use crate::state::animator::AnimationState;
+use crate::state::animator::{Animation, AnimationState, Interpolator};
+use crate::state::fonts::FontFace;
+use crate::state::tooltip::Tooltip;
 
 pub mod theme;
 
@@ -21,8 +23,8 @@ pub use prop::PropSet;
 pub mod flexbox;
 pub use flexbox::*;
 
-// pub mod grid;
-// pub use grid::*;
+pub mod grid;
+pub use grid::*;
 
 pub mod layout;
 pub use layout::*;
@@ -39,8 +41,8 @@ pub use display::*;
 pub mod property;
 pub use property::*;
 
-// pub mod stylesheet;
-// pub use stylesheet::StyleSheet;
+pub mod stylesheet;
+pub use stylesheet::StyleSheet;
 
 pub mod selector;
 pub use selector::*;
@@ -55,7 +57,43 @@ pub mod color;
 pub use color::Color;
 
 pub mod trans;
-pub use trans::Scale;
+pub use trans::{Scale, Transform2D, TransformFunction, TransformOrigin};
+
+pub mod variables;
+pub use variables::{Declaration, Variables};
+
+pub mod report;
+pub use report::*;
+
+// Parses the rules of a stylesheet, collecting the problems found in it
+fn parse_stylesheet(stylesheet: &str) -> (Vec<Rule>, StyleReport) {
+    let mut input = ParserInput::new(stylesheet);
+    let mut parser = Parser::new(&mut input);
+
+    let mut rule_list_parser =
+        cssparser::RuleListParser::new_for_stylesheet(&mut parser, theme::RuleParser::new());
+
+    let mut rules = Vec::new();
+    let mut report = StyleReport::new();
+
+    for rule in &mut rule_list_parser {
+        match rule {
+            Ok(rule) => rules.push(rule),
+            Err((error, rule)) => report.diagnostics.push(theme::rule_diagnostic(&error, rule)),
+        }
+    }
+
+    report
+        .diagnostics
+        .append(&mut rule_list_parser.parser.diagnostics);
+
+    // Declaration diagnostics are collected separately so put them back in order
+    report
+        .diagnostics
+        .sort_by_key(|diagnostic| (diagnostic.line, diagnostic.column));
+
+    (rules, report)
+}
 
 pub struct Style {
     pub style_rules: Vec<StyleRule>,
@@ -63,6 +101,32 @@ pub struct Style {
     //pub rules: Vec<usize>,
     pub rule_selectors: Vec<Vec<Selector>>,
 
+    // Parsed stylesheets in the order they were added, used to rebuild the rules
+    pub stylesheets: Vec<StyleSheet>,
+
+    // Keyframe animations defined with @keyframes
+    pub keyframes: HashMap<String, KeyframesRule>,
+
+    // Font faces defined with @font-face, which are picked from before the faces registered in State::fonts
+    pub font_faces: Vec<FontFace>,
+
+    // Custom properties and the declarations which use them
+    pub variables: Variables,
+
+    // Diagnostics found after a stylesheet was added, from stylesheet files reloaded by poll_themes
+    // and from declarations which fail to parse once their variables are substituted
+    report: StyleReport,
+
+    // List of (property, animation id) played when an entity is linked to a rule with an animation property
+    pub animation: StyleStorage<Vec<(KeyframeProperty, usize)>>,
+
+    // Flags for entities which need their style rules rematched (indexed by entity)
+    pub dirty: Vec<bool>,
+    // The flagged entities, so that a restyle only visits them and the entities which depend on them
+    dirty_entities: Vec<Entity>,
+    // Set when every entity needs to be restyled, e.g. when the rules change
+    all_dirty: bool,
+
     pub ids: DenseStorage<u64>,
 
     pub elements: DenseStorage<u64>,
@@ -79,11 +143,13 @@ pub struct Style {
     //pub over: DenseStorage<bool>,
     pub pseudo_classes: DenseStorage<PseudoClasses>,
 
-    pub z_order: DenseStorage<i32>,
+    pub z_order: StyleStorage<ZIndex>,
 
     // Transform
     pub rotate: AnimatableStorage<f32>, // in degrees
     pub scaley: AnimatableStorage<Scale>,
+    pub transform: StyleStorage<Vec<TransformFunction>>,
+    pub transform_origin: StyleStorage<TransformOrigin>,
 
     // General
     pub display: StyleStorage<Display>,
@@ -137,19 +203,27 @@ pub struct Style {
     pub flex_shrink: StyleStorage<f32>,
     pub flex_basis: StyleStorage<f32>,
 
-    //pub grid_item: DenseStorage<GridItem>,
-
     //pub justification: DenseStorage<Justification>,
     //pub alignment: DenseStorage<Alignment>,
 
     //pub flex_container: DenseStorage<FlexContainer>,
     pub flex_direction: StyleStorage<FlexDirection>,
+    pub flex_wrap: StyleStorage<FlexWrap>,
     pub justify_content: StyleStorage<JustifyContent>,
     pub align_items: StyleStorage<AlignItems>,
     pub align_content: StyleStorage<AlignContent>,
 
     // pub area_container: DenseStorage<AreaContainer>,
-    //pub grid_container: DenseStorage<GridContainer>,
+
+    // Grid Container
+    pub grid_template_columns: StyleStorage<Vec<TrackSize>>,
+    pub grid_template_rows: StyleStorage<Vec<TrackSize>>,
+    pub grid_column_gap: StyleStorage<Length>,
+    pub grid_row_gap: StyleStorage<Length>,
+
+    // Grid Item
+    pub grid_column: StyleStorage<GridPlacement>,
+    pub grid_row: StyleStorage<GridPlacement>,
 
     // Shape
     //pub background: DenseStorage<Background>,
@@ -165,10 +239,22 @@ pub struct Style {
     //Text Properties
     pub text: DenseStorage<Text>,
 
+    // Shown when the mouse rests over the widget
+    pub tooltip: DenseStorage<Tooltip>,
+
+    // Inherited from the parent when not set
+    pub font_family: StyleStorage<Vec<String>>,
+    pub font_weight: StyleStorage<FontWeight>,
+    pub font_style: StyleStorage<FontStyle>,
+    pub font_size: AnimatableStorage<f32>,
     pub font_color: AnimatableStorage<Color>,
+    pub white_space: StyleStorage<WhiteSpace>,
+    pub line_height: StyleStorage<LineHeight>,
+    pub letter_spacing: StyleStorage<f32>,
 
     pub text_align: StyleStorage<Align>,
     pub text_justify: StyleStorage<Justify>,
+    pub text_overflow: StyleStorage<TextOverflow>,
 }
 
 impl Style {
@@ -178,6 +264,17 @@ impl Style {
 
             //rules: Vec::new(),
             rule_selectors: Vec::new(),
+            stylesheets: Vec::new(),
+
+            keyframes: HashMap::new(),
+            font_faces: Vec::new(),
+            variables: Variables::new(),
+            report: StyleReport::new(),
+            animation: StyleStorage::new(),
+
+            dirty: Vec::new(),
+            dirty_entities: Vec::new(),
+            all_dirty: false,
 
             ids: DenseStorage::new(),
             elements: DenseStorage::new(),
@@ -189,65 +286,76 @@ impl Style {
             //over: DenseStorage::new(),
             opacity: AnimatableStorage::new(),
 
-            z_order: DenseStorage::new(),
+            z_order: StyleStorage::new(),
 
             // Transform
             rotate: AnimatableStorage::new(),
             scaley: AnimatableStorage::new(),
+            transform: StyleStorage::new(),
+            transform_origin: StyleStorage::new(),
 
             // Positioning
-            position: StyleStorage::new(),
-            left: AnimatableStorage::new(),
-            right: AnimatableStorage::new(),
-            top: AnimatableStorage::new(),
-            bottom: AnimatableStorage::new(),
+            position: StyleStorage::new().track_inserts(),
+            left: AnimatableStorage::new().track_inserts(),
+            right: AnimatableStorage::new().track_inserts(),
+            top: AnimatableStorage::new().track_inserts(),
+            bottom: AnimatableStorage::new().track_inserts(),
 
             // Size
-            width: AnimatableStorage::new(),
-            height: AnimatableStorage::new(),
+            width: AnimatableStorage::new().track_inserts(),
+            height: AnimatableStorage::new().track_inserts(),
 
             // Size Constraints
-            max_width: StyleStorage::new(),
-            max_height: StyleStorage::new(),
-            min_width: StyleStorage::new(),
-            min_height: StyleStorage::new(),
+            max_width: StyleStorage::new().track_inserts(),
+            max_height: StyleStorage::new().track_inserts(),
+            min_width: StyleStorage::new().track_inserts(),
+            min_height: StyleStorage::new().track_inserts(),
 
             // Margin
-            margin_left: AnimatableStorage::new(),
-            margin_right: AnimatableStorage::new(),
-            margin_top: AnimatableStorage::new(),
-            margin_bottom: AnimatableStorage::new(),
+            margin_left: AnimatableStorage::new().track_inserts(),
+            margin_right: AnimatableStorage::new().track_inserts(),
+            margin_top: AnimatableStorage::new().track_inserts(),
+            margin_bottom: AnimatableStorage::new().track_inserts(),
 
             // Padding
-            padding_left: StyleStorage::new(),
-            padding_right: StyleStorage::new(),
-            padding_top: StyleStorage::new(),
-            padding_bottom: StyleStorage::new(),
+            padding_left: StyleStorage::new().track_inserts(),
+            padding_right: StyleStorage::new().track_inserts(),
+            padding_top: StyleStorage::new().track_inserts(),
+            padding_bottom: StyleStorage::new().track_inserts(),
 
             // Border
-            border_width: StyleStorage::new(),
+            border_width: StyleStorage::new().track_inserts(),
             border_color: StyleStorage::new(),
             border_radius: StyleStorage::new(),
 
             // Flex Container
-            flex_direction: StyleStorage::new(),
-            justify_content: StyleStorage::new(),
-            align_items: StyleStorage::new(),
-            align_content: StyleStorage::new(),
+            flex_direction: StyleStorage::new().track_inserts(),
+            flex_wrap: StyleStorage::new().track_inserts(),
+            justify_content: StyleStorage::new().track_inserts(),
+            align_items: StyleStorage::new().track_inserts(),
+            align_content: StyleStorage::new().track_inserts(),
 
             // Text
             text_align: StyleStorage::new(),
             text_justify: StyleStorage::new(),
+            text_overflow: StyleStorage::new(),
 
+            font_family: StyleStorage::new().track_inserts(),
+            font_weight: StyleStorage::new().track_inserts(),
+            font_style: StyleStorage::new().track_inserts(),
+            font_size: AnimatableStorage::new().track_inserts(),
             font_color: AnimatableStorage::new(),
+            white_space: StyleStorage::new().track_inserts(),
+            line_height: StyleStorage::new().track_inserts(),
+            letter_spacing: StyleStorage::new().track_inserts(),
 
             overflow: StyleStorage::new(),
             scroll: DenseStorage::new(),
 
             // area_container: DenseStorage::new(),
             // area_item: DenseStorage::new(),
-            display: StyleStorage::new(),
-            visibility: StyleStorage::new(),
+            display: StyleStorage::new().track_inserts(),
+            visibility: StyleStorage::new().track_inserts(),
             clip_widget: DenseStorage::new(),
             focus_order: DenseStorage::new(),
             box_shadow: DenseStorage::new(),
@@ -260,31 +368,146 @@ impl Style {
 
             //flex_container: DenseStorage::new(),
             //flex_item: DenseStorage::new(),
-            align_self: StyleStorage::new(),
-            flex_grow: AnimatableStorage::new(),
-            flex_shrink: StyleStorage::new(),
-            flex_basis: StyleStorage::new(),
+            align_self: StyleStorage::new().track_inserts(),
+            flex_grow: AnimatableStorage::new().track_inserts(),
+            flex_shrink: StyleStorage::new().track_inserts(),
+            flex_basis: StyleStorage::new().track_inserts(),
+
+            grid_template_columns: StyleStorage::new().track_inserts(),
+            grid_template_rows: StyleStorage::new().track_inserts(),
+            grid_column_gap: StyleStorage::new().track_inserts(),
+            grid_row_gap: StyleStorage::new().track_inserts(),
+            grid_column: StyleStorage::new().track_inserts(),
+            grid_row: StyleStorage::new().track_inserts(),
 
-            //grid_container: DenseStorage::new(),
-            //grid_item: DenseStorage::new(),
             //size_constraints: DenseStorage::new(),
             text: DenseStorage::new(),
+            tooltip: DenseStorage::new(),
+        }
+    }
+
+    // Parses a stylesheet and adds its rules, returning any problems found in it.
+    // Rules and declarations which fail to parse are left out.
+    pub fn parse_theme(&mut self, stylesheet: &str) -> StyleReport {
+        let stylesheet = StyleSheet::new(stylesheet);
+
+        let report = self.parse_rules(&stylesheet);
+
+        self.stylesheets.push(stylesheet);
+
+        report
+    }
+
+    // Parses a stylesheet and adds its rules only if there are no errors in it
+    pub fn parse_theme_strict(&mut self, stylesheet: &str) -> Result<StyleReport, StyleReport> {
+        let (rules, report) = parse_stylesheet(stylesheet);
+
+        if report.has_errors() {
+            return Err(report);
         }
+
+        self.stylesheets.push(StyleSheet::new(stylesheet));
+
+        self.insert_rules(rules, None);
+
+        Ok(report)
     }
 
-    pub fn parse_theme(&mut self, stylesheet: &str) {
-        let mut input = ParserInput::new(stylesheet);
-        let mut parser = Parser::new(&mut input);
-        let rule_parser = theme::RuleParser::new();
+    // Parses a stylesheet from a file. The file can be watched for changes with poll_themes.
+    pub fn load_theme<P: AsRef<std::path::Path>>(&mut self, path: P) -> std::io::Result<StyleReport> {
+        let stylesheet = StyleSheet::from_file(path)?;
+
+        let report = self.parse_rules(&stylesheet);
+
+        self.stylesheets.push(stylesheet);
+
+        Ok(report)
+    }
+
+    // Rebuilds the style rules from the stylesheets, returning true if any stylesheet file has changed
+    pub fn poll_themes(&mut self) -> bool {
+        let mut changed = false;
+
+        for stylesheet in self.stylesheets.iter_mut() {
+            if stylesheet.poll() {
+                changed = true;
+            }
+        }
+
+        if changed {
+            // Kept until taken with take_report as there's no caller to return it to
+            let mut report = self.reload_themes();
+            self.report.append(&mut report);
+        }
+
+        changed
+    }
+
+    // Takes the diagnostics found since the stylesheets were added or since the last call
+    pub fn take_report(&mut self) -> StyleReport {
+        std::mem::take(&mut self.report)
+    }
+
+    // Clears the style rules and parses every stylesheet again
+    pub fn reload_themes(&mut self) -> StyleReport {
+        self.clear_rules();
+
+        let stylesheets = self.stylesheets.clone();
+
+        let mut report = StyleReport::new();
+        for stylesheet in stylesheets.iter() {
+            report.append(&mut self.parse_rules(stylesheet));
+        }
+
+        report
+    }
+
+    fn parse_rules(&mut self, stylesheet: &StyleSheet) -> StyleReport {
+        let (rules, report) = parse_stylesheet(&stylesheet.source);
+
+        let directory = stylesheet.path.as_ref().and_then(|path| path.parent());
+
+        self.insert_rules(rules, directory);
+
+        report
+    }
+
+    // Adds parsed rules, where directory is the location of the stylesheet file that files are relative to
+    fn insert_rules(&mut self, rules: Vec<Rule>, directory: Option<&std::path::Path>) {
+        let mut rule_list: Vec<StyleRule> = Vec::new();
+
+        for rule in rules {
+            match rule {
+                Rule::Style(style_rule) => {
+                    rule_list.push(style_rule);
+                }
+
+                Rule::Keyframes(keyframes_rule) => {
+                    self.keyframes
+                        .insert(keyframes_rule.name.clone(), keyframes_rule);
+                }
+
+                // Each source is a face of its own, the first which loads is used
+                Rule::FontFace(font_face_rule) => {
+                    for source in font_face_rule.sources.iter() {
+                        let path = match directory {
+                            Some(directory) => directory.join(source),
+                            None => std::path::PathBuf::from(source),
+                        };
+
+                        self.font_faces.push(
+                            FontFace::from_file(&font_face_rule.family, path)
+                                .with_weight(font_face_rule.weight)
+                                .with_style(font_face_rule.style),
+                        );
+                    }
+                }
+            }
+        }
 
-        let rules = {
-            let rule_list_parser =
-                cssparser::RuleListParser::new_for_stylesheet(&mut parser, rule_parser);
-            rule_list_parser.collect::<Vec<_>>()
-        };
+        // New rules can apply to any entity
+        self.set_all_dirty();
 
-        let mut rule_list: Vec<StyleRule> =
-            rules.into_iter().filter_map(|rule| rule.ok()).collect();
         rule_list.sort_by_key(|rule| rule.specificity());
         rule_list.reverse();
 
@@ -295,258 +518,673 @@ impl Style {
             //self.rules.push(rule_id);
             for property in rule.properties.clone() {
                 match property {
-                    Property::Display(value) => {
-                        self.display.insert_rule(rule_id, value);
+                    // Custom properties and declarations using them are linked through the variables
+                    Property::Custom(name, value) => {
+                        self.variables.insert_declaration(rule_id, name, value);
                     }
 
-                    Property::Visibility(value) => {
-                        self.visibility.insert_rule(rule_id, value);
+                    Property::Unresolved(declaration) => {
+                        self.variables.insert_unresolved(rule_id, declaration);
                     }
 
-                    Property::Opacity(value) => {
-                        self.opacity.insert_rule(rule_id, Opacity(value));
+                    Property::Transition(transitions) => {
+                        self.variables
+                            .insert_transition(rule_id, Property::Transition(transitions.clone()));
+                        self.insert_rule_property(rule_id, Property::Transition(transitions));
                     }
 
-                    Property::Overflow(value) => {
-                        self.overflow.insert_rule(rule_id, value);
-                    }
+                    property => self.insert_rule_property(rule_id, property),
+                }
+            }
+        }
+    }
 
-                    Property::TextAlign(value) => {
-                        self.text_align.insert_rule(rule_id, value);
-                    }
+    // Inserts the value of a property for a rule into the storage for the property
+    fn insert_rule_property(&mut self, rule_id: usize, property: Property) {
+        match property {
+            Property::Display(value) => {
+                self.display.insert_rule(rule_id, value);
+            }
 
-                    Property::TextJustify(value) => {
-                        self.text_justify.insert_rule(rule_id, value);
-                    }
+            Property::Visibility(value) => {
+                self.visibility.insert_rule(rule_id, value);
+            }
 
-                    Property::Position(value) => {
-                        self.position.insert_rule(rule_id, value);
-                    }
+            Property::Opacity(value) => {
+                self.opacity.insert_rule(rule_id, Opacity(value));
+            }
 
-                    Property::Left(value) => {
-                        self.left.insert_rule(rule_id, value);
-                    }
+            Property::Overflow(value) => {
+                self.overflow.insert_rule(rule_id, value);
+            }
 
-                    Property::Right(value) => {
-                        self.right.insert_rule(rule_id, value);
-                    }
+            Property::ZIndex(value) => {
+                self.z_order.insert_rule(rule_id, value);
+            }
 
-                    Property::Top(value) => {
-                        self.top.insert_rule(rule_id, value);
-                    }
+            Property::Transform(value) => {
+                self.transform.insert_rule(rule_id, value);
+            }
 
-                    Property::Bottom(value) => {
-                        self.bottom.insert_rule(rule_id, value);
-                    }
+            Property::TransformOrigin(value) => {
+                self.transform_origin.insert_rule(rule_id, value);
+            }
 
-                    Property::Width(value) => {
-                        self.width.insert_rule(rule_id, value);
-                    }
+            Property::TextAlign(value) => {
+                self.text_align.insert_rule(rule_id, value);
+            }
 
-                    Property::Height(value) => {
-                        self.height.insert_rule(rule_id, value);
-                    }
+            Property::TextJustify(value) => {
+                self.text_justify.insert_rule(rule_id, value);
+            }
 
-                    Property::MaxWidth(value) => {
-                        self.max_width.insert_rule(rule_id, value);
-                    }
+            Property::TextOverflow(value) => {
+                self.text_overflow.insert_rule(rule_id, value);
+            }
 
-                    Property::MinWidth(value) => {
-                        self.min_width.insert_rule(rule_id, value);
-                    }
+            Property::Position(value) => {
+                self.position.insert_rule(rule_id, value);
+            }
 
-                    Property::MaxHeight(value) => {
-                        self.max_height.insert_rule(rule_id, value);
-                    }
+            Property::Left(value) => {
+                self.left.insert_rule(rule_id, value);
+            }
 
-                    Property::MinHeight(value) => {
-                        self.min_height.insert_rule(rule_id, value);
-                    }
+            Property::Right(value) => {
+                self.right.insert_rule(rule_id, value);
+            }
 
-                    Property::Margin(value) => {
-                        self.margin_left.insert_rule(rule_id, value);
-                        self.margin_right.insert_rule(rule_id, value);
-                        self.margin_top.insert_rule(rule_id, value);
-                        self.margin_bottom.insert_rule(rule_id, value);
-                    }
+            Property::Top(value) => {
+                self.top.insert_rule(rule_id, value);
+            }
 
-                    Property::MarginLeft(value) => {
-                        self.margin_left.insert_rule(rule_id, value);
-                    }
+            Property::Bottom(value) => {
+                self.bottom.insert_rule(rule_id, value);
+            }
 
-                    Property::MarginRight(value) => {
-                        self.margin_right.insert_rule(rule_id, value);
-                    }
+            Property::Width(value) => {
+                self.width.insert_rule(rule_id, value);
+            }
 
-                    Property::MarginTop(value) => {
-                        self.margin_top.insert_rule(rule_id, value);
-                    }
+            Property::Height(value) => {
+                self.height.insert_rule(rule_id, value);
+            }
 
-                    Property::MarginBottom(value) => {
-                        self.margin_bottom.insert_rule(rule_id, value);
-                    }
+            Property::MaxWidth(value) => {
+                self.max_width.insert_rule(rule_id, value);
+            }
 
-                    Property::Padding(value) => {
-                        self.padding_left.insert_rule(rule_id, value);
-                        self.padding_right.insert_rule(rule_id, value);
-                        self.padding_top.insert_rule(rule_id, value);
-                        self.padding_bottom.insert_rule(rule_id, value);
-                    }
+            Property::MinWidth(value) => {
+                self.min_width.insert_rule(rule_id, value);
+            }
 
-                    Property::PaddingLeft(value) => {
-                        self.padding_left.insert_rule(rule_id, value);
-                    }
+            Property::MaxHeight(value) => {
+                self.max_height.insert_rule(rule_id, value);
+            }
 
-                    Property::PaddingRight(value) => {
-                        self.padding_right.insert_rule(rule_id, value);
-                    }
+            Property::MinHeight(value) => {
+                self.min_height.insert_rule(rule_id, value);
+            }
 
-                    Property::PaddingTop(value) => {
-                        self.padding_top.insert_rule(rule_id, value);
-                    }
+            Property::Margin(value) => {
+                self.margin_left.insert_rule(rule_id, value);
+                self.margin_right.insert_rule(rule_id, value);
+                self.margin_top.insert_rule(rule_id, value);
+                self.margin_bottom.insert_rule(rule_id, value);
+            }
 
-                    Property::PaddingBottom(value) => {
-                        self.padding_bottom.insert_rule(rule_id, value);
-                    }
+            Property::MarginLeft(value) => {
+                self.margin_left.insert_rule(rule_id, value);
+            }
 
-                    // Border
-                    Property::BorderWidth(value) => {
-                        self.border_width.insert_rule(rule_id, value);
-                    }
+            Property::MarginRight(value) => {
+                self.margin_right.insert_rule(rule_id, value);
+            }
 
-                    Property::BorderColor(value) => {
-                        self.border_color.insert_rule(rule_id, value);
-                    }
+            Property::MarginTop(value) => {
+                self.margin_top.insert_rule(rule_id, value);
+            }
 
-                    Property::BorderRadius(value) => {
-                        self.border_radius.insert_rule(
-                            rule_id,
-                            BorderRadius {
-                                top_left: value,
-                                top_right: value,
-                                bottom_left: value,
-                                bottom_right: value,
-                            },
-                        );
-                    }
+            Property::MarginBottom(value) => {
+                self.margin_bottom.insert_rule(rule_id, value);
+            }
 
-                    Property::FontColor(value) => {
-                        println!("Val: {:?}", value);
-                        self.font_color.insert_rule(rule_id, value);
-                    }
+            Property::Padding(value) => {
+                self.padding_left.insert_rule(rule_id, value);
+                self.padding_right.insert_rule(rule_id, value);
+                self.padding_top.insert_rule(rule_id, value);
+                self.padding_bottom.insert_rule(rule_id, value);
+            }
 
-                    Property::BackgroundColor(value) => {
-                        self.background_color.insert_rule(rule_id, value);
-                    }
+            Property::PaddingLeft(value) => {
+                self.padding_left.insert_rule(rule_id, value);
+            }
 
-                    Property::BackgroundImage(value) => {
-                        self.background_image.insert_rule(rule_id, value);
-                    }
+            Property::PaddingRight(value) => {
+                self.padding_right.insert_rule(rule_id, value);
+            }
 
-                    // Flex Container
-                    Property::FlexDirection(value) => {
-                        self.flex_direction.insert_rule(rule_id, value);
-                    }
-                    Property::JustifyContent(value) => {
-                        self.justify_content.insert_rule(rule_id, value);
-                    }
-                    Property::AlignContent(value) => {
-                        self.align_content.insert_rule(rule_id, value);
-                    }
-                    Property::AlignItems(value) => {
-                        self.align_items.insert_rule(rule_id, value);
-                    }
+            Property::PaddingTop(value) => {
+                self.padding_top.insert_rule(rule_id, value);
+            }
 
-                    Property::AlignSelf(value) => {
-                        self.align_self.insert_rule(rule_id, value);
-                    }
+            Property::PaddingBottom(value) => {
+                self.padding_bottom.insert_rule(rule_id, value);
+            }
 
-                    // Flex Item
-                    Property::FlexGrow(value) => {
-                        self.flex_grow.insert_rule(rule_id, value);
-                    }
+            // Border
+            Property::BorderWidth(value) => {
+                self.border_width.insert_rule(rule_id, value);
+            }
+
+            Property::BorderColor(value) => {
+                self.border_color.insert_rule(rule_id, value);
+            }
+
+            Property::BorderRadius(value) => {
+                self.border_radius.insert_rule(
+                    rule_id,
+                    BorderRadius {
+                        top_left: value,
+                        top_right: value,
+                        bottom_left: value,
+                        bottom_right: value,
+                    },
+                );
+            }
+
+            Property::FontColor(value) => {
+                self.font_color.insert_rule(rule_id, value);
+            }
+
+            Property::FontSize(value) => {
+                self.font_size.insert_rule(rule_id, value);
+            }
+
+            Property::FontFamily(value) => {
+                self.font_family.insert_rule(rule_id, value);
+            }
+
+            Property::FontWeight(value) => {
+                self.font_weight.insert_rule(rule_id, value);
+            }
+
+            Property::FontStyle(value) => {
+                self.font_style.insert_rule(rule_id, value);
+            }
+
+            Property::WhiteSpace(value) => {
+                self.white_space.insert_rule(rule_id, value);
+            }
 
-                    Property::FlexShrink(value) => {
-                        self.flex_shrink.insert_rule(rule_id, value);
+            Property::LineHeight(value) => {
+                self.line_height.insert_rule(rule_id, value);
+            }
+
+            Property::LetterSpacing(value) => {
+                self.letter_spacing.insert_rule(rule_id, value);
+            }
+
+            Property::Inherit(name) => match name.as_ref() {
+                "color" => self.font_color.insert_rule_inherit(rule_id),
+                "font-size" => self.font_size.insert_rule_inherit(rule_id),
+                "font-family" => self.font_family.insert_rule_inherit(rule_id),
+                "font-weight" => self.font_weight.insert_rule_inherit(rule_id),
+                "font-style" => self.font_style.insert_rule_inherit(rule_id),
+                "white-space" => self.white_space.insert_rule_inherit(rule_id),
+                "line-height" => self.line_height.insert_rule_inherit(rule_id),
+                "letter-spacing" => self.letter_spacing.insert_rule_inherit(rule_id),
+                "text-align" => self.text_align.insert_rule_inherit(rule_id),
+                "text-justify" => self.text_justify.insert_rule_inherit(rule_id),
+                _ => {}
+            },
+
+            Property::BackgroundColor(value) => {
+                self.background_color.insert_rule(rule_id, value);
+            }
+
+            Property::BackgroundImage(value) => {
+                self.background_image.insert_rule(rule_id, value);
+            }
+
+            // Flex Container
+            Property::FlexDirection(value) => {
+                self.flex_direction.insert_rule(rule_id, value);
+            }
+            Property::FlexWrap(value) => {
+                self.flex_wrap.insert_rule(rule_id, value);
+            }
+            Property::JustifyContent(value) => {
+                self.justify_content.insert_rule(rule_id, value);
+            }
+            Property::AlignContent(value) => {
+                self.align_content.insert_rule(rule_id, value);
+            }
+            Property::AlignItems(value) => {
+                self.align_items.insert_rule(rule_id, value);
+            }
+
+            Property::AlignSelf(value) => {
+                self.align_self.insert_rule(rule_id, value);
+            }
+
+            // Flex Item
+            Property::FlexGrow(value) => {
+                self.flex_grow.insert_rule(rule_id, value);
+            }
+
+            Property::FlexShrink(value) => {
+                self.flex_shrink.insert_rule(rule_id, value);
+            }
+
+            Property::FlexBasis(value) => {
+                self.flex_basis.insert_rule(rule_id, value);
+            }
+
+            // Grid Container
+            Property::GridTemplateColumns(value) => {
+                self.grid_template_columns.insert_rule(rule_id, value);
+            }
+
+            Property::GridTemplateRows(value) => {
+                self.grid_template_rows.insert_rule(rule_id, value);
+            }
+
+            Property::GridColumnGap(value) => {
+                self.grid_column_gap.insert_rule(rule_id, value);
+            }
+
+            Property::GridRowGap(value) => {
+                self.grid_row_gap.insert_rule(rule_id, value);
+            }
+
+            Property::GridGap(row_gap, column_gap) => {
+                self.grid_row_gap.insert_rule(rule_id, row_gap);
+                self.grid_column_gap.insert_rule(rule_id, column_gap);
+            }
+
+            // Grid Item
+            Property::GridColumn(value) => {
+                self.grid_column.insert_rule(rule_id, value);
+            }
+
+            Property::GridRow(value) => {
+                self.grid_row.insert_rule(rule_id, value);
+            }
+
+            Property::Animation(animations) => {
+                let mut animation_ids = Vec::new();
+                for animation in animations.iter() {
+                    if let Some(keyframes) = self.keyframes.get(&animation.name).cloned() {
+                        animation_ids.append(&mut self.insert_keyframes(&keyframes, animation));
                     }
+                }
+
+                self.animation.insert_rule(rule_id, animation_ids);
+            }
+
+            Property::Transition(transitions) => {
+                for transition in transitions {
+                    match transition.property.as_ref() {
+                        "background-color" => {
+                            self.background_color.insert_transition(
+                                rule_id,
+                                AnimationState::new()
+                                    .with_duration(std::time::Duration::from_secs_f32(
+                                        transition.duration,
+                                    ))
+                                    .with_delay(std::time::Duration::from_secs_f32(transition.delay))
+                                    .with_keyframe((0.0, Default::default()))
+                                    .with_keyframe((1.0, Default::default())),
+                            );
+                        }
+
+                        "left" => {
+                            self.left.insert_transition(
+                                rule_id,
+                                AnimationState::new()
+                                    .with_duration(std::time::Duration::from_secs_f32(
+                                        transition.duration,
+                                    ))
+                                    .with_delay(std::time::Duration::from_secs_f32(transition.delay))
+                                    .with_keyframe((0.0, Default::default()))
+                                    .with_keyframe((1.0, Default::default())),
+                            );
+                        }
+
+                        "width" => {
+                            self.width.insert_transition(
+                                rule_id,
+                                AnimationState::new()
+                                    .with_duration(std::time::Duration::from_secs_f32(
+                                        transition.duration,
+                                    ))
+                                    .with_delay(std::time::Duration::from_secs_f32(transition.delay))
+                                    .with_keyframe((0.0, Default::default()))
+                                    .with_keyframe((1.0, Default::default())),
+                            );
+                        }
+
+                        "opacity" => {
+                            self.opacity.insert_transition(
+                                rule_id,
+                                AnimationState::new()
+                                    .with_duration(std::time::Duration::from_secs_f32(
+                                        transition.duration,
+                                    ))
+                                    .with_delay(std::time::Duration::from_secs_f32(transition.delay))
+                                    .with_keyframe((0.0, Default::default()))
+                                    .with_keyframe((1.0, Default::default())),
+                            );
+                        }
 
-                    Property::FlexBasis(value) => {
-                        self.flex_basis.insert_rule(rule_id, value);
+                        
+                        _ => {}
                     }
+                }
+            }
+            _ => {}
+        }
+    }
 
-                    Property::Transition(transitions) => {
-                        for transition in transitions {
-                            match transition.property.as_ref() {
-                                "background-color" => {
-                                    self.background_color.insert_transition(
-                                        rule_id,
-                                        AnimationState::new()
-                                            .with_duration(std::time::Duration::from_secs_f32(
-                                                transition.duration,
-                                            ))
-                                            .with_delay(std::time::Duration::from_secs_f32(transition.delay))
-                                            .with_keyframe((0.0, Default::default()))
-                                            .with_keyframe((1.0, Default::default())),
-                                    );
-                                }
-
-                                "left" => {
-                                    self.left.insert_transition(
-                                        rule_id,
-                                        AnimationState::new()
-                                            .with_duration(std::time::Duration::from_secs_f32(
-                                                transition.duration,
-                                            ))
-                                            .with_delay(std::time::Duration::from_secs_f32(transition.delay))
-                                            .with_keyframe((0.0, Default::default()))
-                                            .with_keyframe((1.0, Default::default())),
-                                    );
-                                }
-
-                                "width" => {
-                                    self.width.insert_transition(
-                                        rule_id,
-                                        AnimationState::new()
-                                            .with_duration(std::time::Duration::from_secs_f32(
-                                                transition.duration,
-                                            ))
-                                            .with_delay(std::time::Duration::from_secs_f32(transition.delay))
-                                            .with_keyframe((0.0, Default::default()))
-                                            .with_keyframe((1.0, Default::default())),
-                                    );
-                                }
-
-                                "opacity" => {
-                                    self.opacity.insert_transition(
-                                        rule_id,
-                                        AnimationState::new()
-                                            .with_duration(std::time::Duration::from_secs_f32(
-                                                transition.duration,
-                                            ))
-                                            .with_delay(std::time::Duration::from_secs_f32(transition.delay))
-                                            .with_keyframe((0.0, Default::default()))
-                                            .with_keyframe((1.0, Default::default())),
-                                    );
-                                }
-
-                                
-                                _ => {}
+    // Computes the custom properties of an entity and returns the rules to link it to.
+    // Rules with declarations using var() are preceded by a rule with the substituted values.
+    pub fn link_variables(
+        &mut self,
+        entity: Entity,
+        parent: Option<Entity>,
+        matched_rules: Vec<usize>,
+    ) -> Vec<usize> {
+        self.variables.compute(entity, parent, &matched_rules);
+
+        let current_color = self.current_color(entity, parent, &matched_rules);
+
+        let mut rules = Vec::with_capacity(matched_rules.len());
+
+        for rule in matched_rules {
+            if let Some(declarations) = self.variables.resolve(entity, rule, current_color) {
+                let resolved_rule = match self.variables.get_resolved_rule(rule, &declarations) {
+                    Some(resolved_rule) => resolved_rule,
+
+                    None => {
+                        // Resolved rules have no selectors so they are only linked through this rule
+                        let resolved_rule = self.rule_selectors.len();
+                        self.rule_selectors.push(Vec::new());
+
+                        for declaration in declarations.iter() {
+                            match theme::parse_declaration(&declaration.name, &declaration.value) {
+                                Some(property) => self.insert_rule_property(resolved_rule, property),
+
+                                // The declaration is dropped, as it would be if it failed to parse
+                                None => self.report.diagnostics.push(Diagnostic {
+                                    severity: Severity::Error,
+                                    kind: DiagnosticKind::InvalidValue {
+                                        property: declaration.name.clone(),
+                                        value: declaration.value.clone(),
+                                    },
+                                    line: declaration.line,
+                                    column: declaration.column,
+                                }),
                             }
                         }
+
+                        // Values from the resolved rule transition like those of the rule it came from
+                        for transition in self.variables.get_transitions(rule) {
+                            self.insert_rule_property(resolved_rule, transition);
+                        }
+
+                        self.variables
+                            .insert_resolved_rule(rule, declarations, resolved_rule);
+
+                        resolved_rule
                     }
-                    _ => {}
-                }
+                };
+
+                rules.push(resolved_rule);
+            }
+
+            rules.push(rule);
+        }
+
+        rules
+    }
+
+    // Returns the color which currentColor refers to for an entity, which is its font color.
+    // A color declaration using currentColor refers to the color of the parent.
+    fn current_color(&self, entity: Entity, parent: Option<Entity>, matched_rules: &[usize]) -> Color {
+        let parent_color = parent
+            .and_then(|parent| self.font_color.get(parent))
+            .cloned()
+            .unwrap_or(Color::rgb(255, 255, 255));
+
+        if self.font_color.is_inline(entity) {
+            return self.font_color.get(entity).cloned().unwrap_or(parent_color);
+        }
+
+        // The first rule to set the color has the highest specificity
+        for rule in matched_rules.iter() {
+            if self.font_color.is_inherit_rule(*rule) {
+                return parent_color;
+            }
+
+            let declarations = self
+                .variables
+                .resolve(entity, *rule, parent_color)
+                .unwrap_or_default();
+
+            let color = declarations
+                .iter()
+                .rev()
+                .filter(|declaration| declaration.name == "color")
+                .find_map(|declaration| {
+                    match theme::parse_declaration(&declaration.name, &declaration.value) {
+                        Some(Property::FontColor(color)) => Some(color),
+                        _ => None,
+                    }
+                });
+
+            if let Some(color) = color.or_else(|| self.font_color.get_rule(*rule).cloned()) {
+                return color;
             }
         }
+
+        // The color is inherited
+        parent_color
+    }
+
+    // Flag an entity (and its descendants) to be restyled on the next restyle.
+    // Selectors changed directly in the storages need to be flagged with this to be restyled.
+    pub fn set_dirty(&mut self, entity: Entity) {
+        if entity.index() >= self.dirty.len() {
+            self.dirty.resize(entity.index() + 1, false);
+        }
+
+        if !self.dirty[entity.index()] {
+            self.dirty[entity.index()] = true;
+            self.dirty_entities.push(entity);
+        }
+    }
+
+    // Removes every style rule, keeping any inline properties
+    pub fn clear_rules(&mut self) {
+        self.rule_selectors.clear();
+        self.keyframes.clear();
+        self.font_faces.clear();
+        self.variables.clear_rules();
+
+        self.animation.clear_rules();
+
+        // Transform
+        self.rotate.clear_rules();
+        self.scaley.clear_rules();
+        self.transform.clear_rules();
+        self.transform_origin.clear_rules();
+
+        // General
+        self.display.clear_rules();
+        self.visibility.clear_rules();
+        self.opacity.clear_rules();
+        self.overflow.clear_rules();
+        self.z_order.clear_rules();
+
+        // Positioning
+        self.position.clear_rules();
+        self.left.clear_rules();
+        self.right.clear_rules();
+        self.top.clear_rules();
+        self.bottom.clear_rules();
+
+        // Size
+        self.width.clear_rules();
+        self.height.clear_rules();
+
+        // Size Constraints
+        self.max_width.clear_rules();
+        self.max_height.clear_rules();
+        self.min_width.clear_rules();
+        self.min_height.clear_rules();
+
+        // Margin
+        self.margin_left.clear_rules();
+        self.margin_right.clear_rules();
+        self.margin_top.clear_rules();
+        self.margin_bottom.clear_rules();
+
+        // Padding
+        self.padding_left.clear_rules();
+        self.padding_right.clear_rules();
+        self.padding_top.clear_rules();
+        self.padding_bottom.clear_rules();
+
+        // Border
+        self.border_width.clear_rules();
+        self.border_color.clear_rules();
+        self.border_radius.clear_rules();
+
+        // Flex Item
+        self.align_self.clear_rules();
+        self.flex_grow.clear_rules();
+        self.flex_shrink.clear_rules();
+        self.flex_basis.clear_rules();
+
+        // Flex Container
+        self.flex_direction.clear_rules();
+        self.flex_wrap.clear_rules();
+        self.justify_content.clear_rules();
+        self.align_items.clear_rules();
+        self.align_content.clear_rules();
+
+        // Grid Container
+        self.grid_template_columns.clear_rules();
+        self.grid_template_rows.clear_rules();
+        self.grid_column_gap.clear_rules();
+        self.grid_row_gap.clear_rules();
+
+        // Grid Item
+        self.grid_column.clear_rules();
+        self.grid_row.clear_rules();
+
+        // Shape
+        self.background_color.clear_rules();
+        self.background_image.clear_rules();
+
+        // Text
+        self.font_family.clear_rules();
+        self.font_weight.clear_rules();
+        self.font_style.clear_rules();
+        self.font_size.clear_rules();
+        self.font_color.clear_rules();
+        self.white_space.clear_rules();
+        self.line_height.clear_rules();
+        self.letter_spacing.clear_rules();
+        self.text_align.clear_rules();
+        self.text_justify.clear_rules();
+        self.text_overflow.clear_rules();
+
+        // Every entity needs to be linked to the new rules
+        self.set_all_dirty();
+    }
+
+    // Flag every entity to be restyled on the next restyle
+    pub fn set_all_dirty(&mut self) {
+        self.all_dirty = true;
+    }
+
+    // Takes the flagged entities and clears their flags. Returns None if every entity needs to be restyled.
+    pub fn take_dirty(&mut self) -> Option<Vec<Entity>> {
+        let dirty = std::mem::take(&mut self.dirty_entities);
+        for entity in dirty.iter() {
+            self.dirty[entity.index()] = false;
+        }
+
+        if std::mem::replace(&mut self.all_dirty, false) {
+            return None;
+        }
+
+        Some(dirty)
     }
 
     // Add style data to an entity
+    // Returns the entities given inline values for properties which affect layout since this was last called,
+    // including values inserted directly into the storages rather than through a setter
+    pub fn take_layout_inserts(&mut self) -> Vec<Entity> {
+        let mut inserted = Vec::new();
+
+        inserted.extend(self.position.take_inserted());
+        inserted.extend(self.left.take_inserted());
+        inserted.extend(self.right.take_inserted());
+        inserted.extend(self.top.take_inserted());
+        inserted.extend(self.bottom.take_inserted());
+        inserted.extend(self.width.take_inserted());
+        inserted.extend(self.height.take_inserted());
+        inserted.extend(self.max_width.take_inserted());
+        inserted.extend(self.max_height.take_inserted());
+        inserted.extend(self.min_width.take_inserted());
+        inserted.extend(self.min_height.take_inserted());
+        inserted.extend(self.margin_left.take_inserted());
+        inserted.extend(self.margin_right.take_inserted());
+        inserted.extend(self.margin_top.take_inserted());
+        inserted.extend(self.margin_bottom.take_inserted());
+        inserted.extend(self.padding_left.take_inserted());
+        inserted.extend(self.padding_right.take_inserted());
+        inserted.extend(self.padding_top.take_inserted());
+        inserted.extend(self.padding_bottom.take_inserted());
+        inserted.extend(self.border_width.take_inserted());
+        inserted.extend(self.flex_direction.take_inserted());
+        inserted.extend(self.flex_wrap.take_inserted());
+        inserted.extend(self.justify_content.take_inserted());
+        inserted.extend(self.align_items.take_inserted());
+        inserted.extend(self.align_content.take_inserted());
+        inserted.extend(self.align_self.take_inserted());
+        inserted.extend(self.flex_grow.take_inserted());
+        inserted.extend(self.flex_shrink.take_inserted());
+        inserted.extend(self.flex_basis.take_inserted());
+        inserted.extend(self.grid_template_columns.take_inserted());
+        inserted.extend(self.grid_template_rows.take_inserted());
+        inserted.extend(self.grid_column_gap.take_inserted());
+        inserted.extend(self.grid_row_gap.take_inserted());
+        inserted.extend(self.grid_column.take_inserted());
+        inserted.extend(self.grid_row.take_inserted());
+        inserted.extend(self.display.take_inserted());
+        inserted.extend(self.visibility.take_inserted());
+        inserted.extend(self.font_family.take_inserted());
+        inserted.extend(self.font_weight.take_inserted());
+        inserted.extend(self.font_style.take_inserted());
+        inserted.extend(self.font_size.take_inserted());
+        inserted.extend(self.white_space.take_inserted());
+        inserted.extend(self.line_height.take_inserted());
+        inserted.extend(self.letter_spacing.take_inserted());
+
+        inserted
+    }
+
     pub fn add(&mut self, entity: Entity) {
+        self.set_dirty(entity);
+
         self.pseudo_classes.insert(entity, PseudoClasses::default());
 
         //self.z_order.insert(entity, 0);
 
-        self.overflow.insert(entity, Default::default());
         self.scroll.insert(entity, Default::default());
 
         self.visibility.insert(entity, Default::default());
@@ -559,7 +1197,130 @@ impl Style {
         // Check if there is already a rule with the same entity id
     }
 
-    pub fn remove(&mut self, entity: Entity) {}
+    // Removes all style data for an entity so that it can be reused
+    pub fn remove(&mut self, entity: Entity) {
+        // An entity which reuses the index is flagged again when it's added
+        if let Some(dirty) = self.dirty.get_mut(entity.index()) {
+            *dirty = false;
+        }
+
+        self.animation.remove(entity);
+        self.variables.remove(entity);
+
+        // Selectors
+        self.ids.remove(entity);
+        self.elements.remove(entity);
+        self.classes.remove(entity);
+        self.pseudo_classes.remove(entity);
+
+        self.z_order.remove(entity);
+
+        // Transform
+        self.rotate.remove(entity);
+        self.scaley.remove(entity);
+        self.transform.remove(entity);
+        self.transform_origin.remove(entity);
+
+        // General
+        self.display.remove(entity);
+        self.visibility.remove(entity);
+        self.opacity.remove(entity);
+        self.overflow.remove(entity);
+        self.scroll.remove(entity);
+
+        // Positioning
+        self.position.remove(entity);
+        self.left.remove(entity);
+        self.right.remove(entity);
+        self.top.remove(entity);
+        self.bottom.remove(entity);
+
+        // Size
+        self.width.remove(entity);
+        self.height.remove(entity);
+
+        // Size Constraints
+        self.max_width.remove(entity);
+        self.max_height.remove(entity);
+        self.min_width.remove(entity);
+        self.min_height.remove(entity);
+
+        // Margin
+        self.margin_left.remove(entity);
+        self.margin_right.remove(entity);
+        self.margin_top.remove(entity);
+        self.margin_bottom.remove(entity);
+
+        // Padding
+        self.padding_left.remove(entity);
+        self.padding_right.remove(entity);
+        self.padding_top.remove(entity);
+        self.padding_bottom.remove(entity);
+
+        // Border
+        self.border_width.remove(entity);
+        self.border_color.remove(entity);
+        self.border_radius.remove(entity);
+
+        self.clip_widget.remove(entity);
+
+        self.focus_order.remove(entity);
+
+        // Other widgets shouldn't pass focus to a removed widget
+        for focus_order in self.focus_order.data.iter_mut() {
+            if focus_order.next == entity {
+                focus_order.next = Entity::null();
+            }
+
+            if focus_order.prev == entity {
+                focus_order.prev = Entity::null();
+            }
+        }
+
+        // Flex Item
+        self.align_self.remove(entity);
+        self.flex_grow.remove(entity);
+        self.flex_shrink.remove(entity);
+        self.flex_basis.remove(entity);
+
+        // Flex Container
+        self.flex_direction.remove(entity);
+        self.flex_wrap.remove(entity);
+        self.justify_content.remove(entity);
+        self.align_items.remove(entity);
+        self.align_content.remove(entity);
+
+        // Grid Container
+        self.grid_template_columns.remove(entity);
+        self.grid_template_rows.remove(entity);
+        self.grid_column_gap.remove(entity);
+        self.grid_row_gap.remove(entity);
+
+        // Grid Item
+        self.grid_column.remove(entity);
+        self.grid_row.remove(entity);
+
+        // Shape
+        self.background_color.remove(entity);
+        self.background_image.remove(entity);
+
+        self.box_shadow.remove(entity);
+
+        // Text
+        self.text.remove(entity);
+        self.tooltip.remove(entity);
+        self.font_family.remove(entity);
+        self.font_weight.remove(entity);
+        self.font_style.remove(entity);
+        self.font_size.remove(entity);
+        self.font_color.remove(entity);
+        self.white_space.remove(entity);
+        self.line_height.remove(entity);
+        self.letter_spacing.remove(entity);
+        self.text_align.remove(entity);
+        self.text_justify.remove(entity);
+        self.text_overflow.remove(entity);
+    }
 
     pub fn insert_style_rule(&mut self, style_rule: StyleRule) -> &mut Self {
         self.style_rules.push(style_rule);
@@ -568,6 +1329,8 @@ impl Style {
     }
 
     pub fn insert_id(&mut self, entity: Entity, id: &str) -> &mut Self {
+        self.set_dirty(entity);
+
         let mut s = DefaultHasher::new();
         id.hash(&mut s);
         self.ids.insert(entity, s.finish());
@@ -576,6 +1339,8 @@ impl Style {
     }
 
     pub fn insert_element(&mut self, entity: Entity, element: &str) -> &mut Self {
+        self.set_dirty(entity);
+
         let mut s = DefaultHasher::new();
         element.hash(&mut s);
         self.elements.insert(entity, s.finish());
@@ -584,6 +1349,8 @@ impl Style {
     }
 
     pub fn insert_class(&mut self, entity: Entity, class: &str) -> &mut Self {
+        self.set_dirty(entity);
+
         if let Some(class_list) = self.classes.get_mut(entity) {
             class_list.insert(class.to_string());
         } else {
@@ -595,3 +1362,100 @@ impl Style {
         self
     }
 }
+
+// Declares the properties which can be animated with @keyframes, along with the storage each is animated in
+// and the declarations which give it a value, and generates the code which creates and plays their animations
+macro_rules! keyframe_properties {
+    ($($name:ident: $storage:ident { $($pattern:pat => $value:expr),+ })*) => {
+        // A property animated by a keyframe animation
+        #[derive(Copy, Clone, Debug, PartialEq)]
+        pub enum KeyframeProperty {
+            $($name,)*
+        }
+
+        impl Style {
+            // Creates an animation definition for each property in the keyframes and
+            // returns a list of (property, animation id)
+            fn insert_keyframes(
+                &mut self,
+                keyframes: &KeyframesRule,
+                animation: &Animation,
+            ) -> Vec<(KeyframeProperty, usize)> {
+                let mut animation_ids = Vec::new();
+
+                // An animation with no duration has no effect
+                if animation.duration <= 0.0 {
+                    return animation_ids;
+                }
+
+                $(
+                    if let Some(state) = keyframe_animation(keyframes, animation, |property| match property {
+                        $($pattern => Some($value),)+
+                        _ => None,
+                    }) {
+                        let id = self.$storage.insert_rule_animation(state);
+                        animation_ids.push((KeyframeProperty::$name, id));
+                    }
+                )*
+
+                animation_ids
+            }
+
+            // Play the keyframe animations linked to an entity by the animation property
+            pub fn play_animations(&mut self, entity: Entity) {
+                if let Some(animation_ids) = self.animation.get(entity).cloned() {
+                    for (property, id) in animation_ids {
+                        match property {
+                            $(KeyframeProperty::$name => self.$storage.play_animation(entity, id),)*
+                        }
+                    }
+                }
+            }
+        }
+    };
+}
+
+keyframe_properties! {
+    BackgroundColor: background_color { Property::BackgroundColor(value) => *value }
+    Color: font_color { Property::FontColor(value) => *value }
+    Opacity: opacity { Property::Opacity(value) => Opacity(*value) }
+    Left: left { Property::Left(value) => *value }
+    Right: right { Property::Right(value) => *value }
+    Top: top { Property::Top(value) => *value }
+    Bottom: bottom { Property::Bottom(value) => *value }
+    Width: width { Property::Width(value) => *value }
+    Height: height { Property::Height(value) => *value }
+    MarginLeft: margin_left { Property::MarginLeft(value) => *value, Property::Margin(value) => *value }
+    MarginRight: margin_right { Property::MarginRight(value) => *value, Property::Margin(value) => *value }
+    MarginTop: margin_top { Property::MarginTop(value) => *value, Property::Margin(value) => *value }
+    MarginBottom: margin_bottom { Property::MarginBottom(value) => *value, Property::Margin(value) => *value }
+    FlexGrow: flex_grow { Property::FlexGrow(value) => *value }
+}
+
+// Builds an animation definition from the keyframes which specify a value for a property
+fn keyframe_animation<T, F>(
+    keyframes: &KeyframesRule,
+    animation: &Animation,
+    value: F,
+) -> Option<AnimationState<T>>
+where
+    T: Interpolator,
+    F: Fn(&Property) -> Option<T>,
+{
+    let mut state = AnimationState::new()
+        .with_duration(std::time::Duration::from_secs_f32(animation.duration))
+        .with_delay(std::time::Duration::from_secs_f32(animation.delay))
+        .with_iteration_count(animation.iteration_count);
+
+    for (time, properties) in keyframes.keyframes.iter() {
+        if let Some(value) = properties.iter().filter_map(|property| value(property)).last() {
+            state = state.with_keyframe((*time, value));
+        }
+    }
+
+    if state.keyframes.is_empty() {
+        return None;
+    }
+
+    Some(state)
+}
@@ -1,6 +1,6 @@
 use crate::entity::Entity;
 use crate::state::style::*;
-use crate::State;
+use crate::{State, Tooltip};
 
 use crate::{Event, WindowEvent};
 
@@ -17,6 +17,7 @@ pub trait PropSet {
     fn set_checked(self, state: &mut State, value: bool) -> Self;
     fn set_over(self, state: &mut State, value: bool) -> Self;
     fn set_active(self, state: &mut State, value: bool) -> Self;
+    fn set_pseudo_class(self, state: &mut State, name: &str, value: bool) -> Self;
 
     // Style
     fn set_element(self, state: &mut State, value: &str) -> Self;
@@ -38,16 +39,25 @@ pub trait PropSet {
     // Rotate
     fn set_rotate(self, state: &mut State, value: f32) -> Self;
 
+    // Transform
+    fn set_transform(self, state: &mut State, value: Vec<TransformFunction>) -> Self;
+    fn set_transform_origin(self, state: &mut State, value: TransformOrigin) -> Self;
+
     // Grid Container
-    //fn set_grid_columns(self, state: &mut State, value: Vec<f32>) -> Self;
-    //fn set_grid_rows(self, state: &mut State, value: Vec<f32>) -> Self;
+    fn set_grid_columns(self, state: &mut State, value: Vec<TrackSize>) -> Self;
+    fn set_grid_rows(self, state: &mut State, value: Vec<TrackSize>) -> Self;
+    fn set_grid_column_gap(self, state: &mut State, value: Length) -> Self;
+    fn set_grid_row_gap(self, state: &mut State, value: Length) -> Self;
 
     // Grid Item
-    //fn set_grid_column_start(self, state: &mut State, value: u32) -> Self;
-    //fn set_grid_column_span(self, state: &mut State, value: u32) -> Self;
+    fn set_grid_column_start(self, state: &mut State, value: u32) -> Self;
+    fn set_grid_column_span(self, state: &mut State, value: u32) -> Self;
+    fn set_grid_row_start(self, state: &mut State, value: u32) -> Self;
+    fn set_grid_row_span(self, state: &mut State, value: u32) -> Self;
 
     // Flex Container
     fn set_flex_direction(self, state: &mut State, value: FlexDirection) -> Self;
+    fn set_flex_wrap(self, state: &mut State, value: FlexWrap) -> Self;
     fn set_justify_content(self, state: &mut State, value: JustifyContent) -> Self;
     fn set_align_content(self, state: &mut State, value: AlignContent) -> Self;
     fn set_align_items(self, state: &mut State, value: AlignItems) -> Self;
@@ -78,11 +88,24 @@ pub trait PropSet {
     // Text
     fn set_text(self, state: &mut State, text: &str) -> Self;
 
+    // Tooltip
+    fn set_tooltip(self, state: &mut State, text: &str) -> Self;
+    fn set_tooltip_widget(self, state: &mut State, widget: Entity) -> Self;
+
     // Text Font
     fn set_font(self, state: &mut State, font: String) -> Self;
+    fn set_font_family(self, state: &mut State, families: Vec<String>) -> Self;
+    fn set_font_weight(self, state: &mut State, weight: FontWeight) -> Self;
+    fn set_font_style(self, state: &mut State, style: FontStyle) -> Self;
     fn set_font_size(self, state: &mut State, size: f32) -> Self;
     fn set_font_color(self, state: &mut State, color: Color) -> Self;
 
+    // Text Layout
+    fn set_white_space(self, state: &mut State, value: WhiteSpace) -> Self;
+    fn set_line_height(self, state: &mut State, value: LineHeight) -> Self;
+    fn set_letter_spacing(self, state: &mut State, value: f32) -> Self;
+    fn set_text_overflow(self, state: &mut State, value: TextOverflow) -> Self;
+
     // Text Alignment
     fn set_text_align(self, state: &mut State, align: Align) -> Self;
     fn set_text_justify(self, state: &mut State, justify: Justify) -> Self;
@@ -118,7 +141,7 @@ pub trait PropSet {
     // Clipping
     fn set_clip_widget(self, state: &mut State, value: Entity) -> Self;
 
-    fn set_z_order(self, state: &mut State, vaale: i32) -> Self;
+    fn set_z_order(self, state: &mut State, value: i32) -> Self;
 
     fn set_next_focus(self, state: &mut State, value: Entity) -> Self;
     fn set_prev_focus(self, state: &mut State, value: Entity) -> Self;
@@ -137,6 +160,7 @@ impl PropSet for Entity {
             pseudo_classes.set_disabled(!value);
         }
 
+        state.style.set_dirty(self);
         state.insert_event(Event::new(WindowEvent::Restyle));
 
         self
@@ -148,6 +172,7 @@ impl PropSet for Entity {
             pseudo_classes.set_enabled(!value);
         }
 
+        state.style.set_dirty(self);
         state.insert_event(Event::new(WindowEvent::Restyle));
 
         self
@@ -158,6 +183,7 @@ impl PropSet for Entity {
             pseudo_classes.set_checked(value);
         }
 
+        state.style.set_dirty(self);
         state.insert_event(Event::new(WindowEvent::Restyle));
 
         self
@@ -168,6 +194,7 @@ impl PropSet for Entity {
             pseudo_classes.set_over(value);
         }
         
+        state.style.set_dirty(self);
         state.insert_event(Event::new(WindowEvent::Restyle));
 
         self
@@ -178,6 +205,19 @@ impl PropSet for Entity {
             pseudo_classes.set_active(value);
         }
         
+        state.style.set_dirty(self);
+        state.insert_event(Event::new(WindowEvent::Restyle));
+
+        self
+    }
+
+    // Sets a built-in or custom pseudo-class, e.g. entity.set_pseudo_class(state, "selected", true)
+    fn set_pseudo_class(self, state: &mut State, name: &str, value: bool) -> Self {
+        if let Some(pseudo_classes) = state.style.pseudo_classes.get_mut(self) {
+            pseudo_classes.set(name, value);
+        }
+
+        state.style.set_dirty(self);
         state.insert_event(Event::new(WindowEvent::Restyle));
 
         self
@@ -206,6 +246,7 @@ impl PropSet for Entity {
     fn set_visibility(self, state: &mut State, value: Visibility) -> Self {
         state.style.visibility.insert(self, value);
 
+        state.transform.set_layout_dirty(self);
         state.insert_event(
             Event::new(WindowEvent::Relayout)
                 .target(Entity::null())
@@ -220,6 +261,7 @@ impl PropSet for Entity {
     fn set_overflow(self, state: &mut State, value: Overflow) -> Self {
         state.style.overflow.insert(self, value);
 
+        state.transform.set_layout_dirty(self);
         state.insert_event(
             Event::new(WindowEvent::Relayout)
                 .target(Entity::null())
@@ -234,6 +276,7 @@ impl PropSet for Entity {
     fn set_display(self, state: &mut State, value: Display) -> Self {
         state.style.display.insert(self, value);
 
+        state.transform.set_layout_dirty(self);
         state.insert_event(
             Event::new(WindowEvent::Relayout)
                 .target(Entity::null())
@@ -248,6 +291,7 @@ impl PropSet for Entity {
     fn set_opacity(self, state: &mut State, value: f32) -> Self {
         state.style.opacity.insert(self, Opacity(value));
 
+        state.transform.set_layout_dirty(self);
         state.insert_event(
             Event::new(WindowEvent::Relayout)
                 .target(Entity::null())
@@ -262,6 +306,7 @@ impl PropSet for Entity {
     fn set_rotate(self, state: &mut State, value: f32) -> Self {
         state.style.rotate.insert(self, value);
 
+        state.transform.set_layout_dirty(self);
         state.insert_event(
             Event::new(WindowEvent::Relayout)
                 .target(Entity::null())
@@ -272,10 +317,90 @@ impl PropSet for Entity {
         self
     }
 
+    // Transform
+    fn set_transform(self, state: &mut State, value: Vec<TransformFunction>) -> Self {
+        state.style.transform.insert(self, value);
+
+        state.transform.set_layout_dirty(self);
+        state.insert_event(
+            Event::new(WindowEvent::Relayout)
+                .target(Entity::null())
+                .origin(self),
+        );
+        state.insert_event(Event::new(WindowEvent::Redraw));
+
+        self
+    }
+
+    fn set_transform_origin(self, state: &mut State, value: TransformOrigin) -> Self {
+        state.style.transform_origin.insert(self, value);
+
+        state.transform.set_layout_dirty(self);
+        state.insert_event(
+            Event::new(WindowEvent::Relayout)
+                .target(Entity::null())
+                .origin(self),
+        );
+        state.insert_event(Event::new(WindowEvent::Redraw));
+
+        self
+    }
+
+    // Grid Container
+    fn set_grid_columns(self, state: &mut State, value: Vec<TrackSize>) -> Self {
+        set_layout_property(self, state, |style| style.grid_template_columns.insert(self, value))
+    }
+
+    fn set_grid_rows(self, state: &mut State, value: Vec<TrackSize>) -> Self {
+        set_layout_property(self, state, |style| style.grid_template_rows.insert(self, value))
+    }
+
+    fn set_grid_column_gap(self, state: &mut State, value: Length) -> Self {
+        set_layout_property(self, state, |style| style.grid_column_gap.insert(self, value))
+    }
+
+    fn set_grid_row_gap(self, state: &mut State, value: Length) -> Self {
+        set_layout_property(self, state, |style| style.grid_row_gap.insert(self, value))
+    }
+
+    // Grid Item
+    fn set_grid_column_start(self, state: &mut State, value: u32) -> Self {
+        set_layout_property(self, state, |style| {
+            let mut placement = style.grid_column.get(self).cloned().unwrap_or_default();
+            placement.start = value;
+            style.grid_column.insert(self, placement);
+        })
+    }
+
+    fn set_grid_column_span(self, state: &mut State, value: u32) -> Self {
+        set_layout_property(self, state, |style| {
+            let mut placement = style.grid_column.get(self).cloned().unwrap_or_default();
+            placement.span = value.max(1);
+            style.grid_column.insert(self, placement);
+        })
+    }
+
+    fn set_grid_row_start(self, state: &mut State, value: u32) -> Self {
+        set_layout_property(self, state, |style| {
+            let mut placement = style.grid_row.get(self).cloned().unwrap_or_default();
+            placement.start = value;
+            style.grid_row.insert(self, placement);
+        })
+    }
+
+    fn set_grid_row_span(self, state: &mut State, value: u32) -> Self {
+        set_layout_property(self, state, |style| {
+            let mut placement = style.grid_row.get(self).cloned().unwrap_or_default();
+            placement.span = value.max(1);
+            style.grid_row.insert(self, placement);
+        })
+    }
+
     // Flex Container
     fn set_flex_direction(self, state: &mut State, value: FlexDirection) -> Self {
         state.style.flex_direction.insert(self, value);
 
+        state.transform.set_layout_dirty(self);
         state.insert_event(
             Event::new(WindowEvent::Relayout)
                 .target(Entity::null())
@@ -286,18 +411,24 @@ impl PropSet for Entity {
         self
     }
 
-    //TODO
-    // fn set_flex_wrap(self, state: &mut State, value: FlexDirection) -> Self {
-    //     if let Some(data) = state.style.grid_container.get_mut(self) {
-    //         data.flex_direction = value;
-    //     }
+    fn set_flex_wrap(self, state: &mut State, value: FlexWrap) -> Self {
+        state.style.flex_wrap.insert(self, value);
+
+        state.transform.set_layout_dirty(self);
+        state.insert_event(
+            Event::new(WindowEvent::Relayout)
+                .target(Entity::null())
+                .origin(self),
+        );
+        state.insert_event(Event::new(WindowEvent::Redraw));
 
-    //     self
-    // }
+        self
+    }
 
     fn set_justify_content(self, state: &mut State, value: JustifyContent) -> Self {
         state.style.justify_content.insert(self, value);
 
+        state.transform.set_layout_dirty(self);
         state.insert_event(
             Event::new(WindowEvent::Relayout)
                 .target(Entity::null())
@@ -311,6 +442,7 @@ impl PropSet for Entity {
     fn set_align_content(self, state: &mut State, value: AlignContent) -> Self {
         state.style.align_content.insert(self, value);
 
+        state.transform.set_layout_dirty(self);
         state.insert_event(
             Event::new(WindowEvent::Relayout)
                 .target(Entity::null())
@@ -324,6 +456,7 @@ impl PropSet for Entity {
     fn set_align_items(self, state: &mut State, value: AlignItems) -> Self {
         state.style.align_items.insert(self, value);
 
+        state.transform.set_layout_dirty(self);
         state.insert_event(
             Event::new(WindowEvent::Relayout)
                 .target(Entity::null())
@@ -338,6 +471,7 @@ impl PropSet for Entity {
     fn set_flex_grow(self, state: &mut State, value: f32) -> Self {
         state.style.flex_grow.insert(self, value);
 
+        state.transform.set_layout_dirty(self);
         state.insert_event(
             Event::new(WindowEvent::Relayout)
                 .target(Entity::null())
@@ -351,6 +485,7 @@ impl PropSet for Entity {
     fn set_flex_shrink(self, state: &mut State, value: f32) -> Self {
         state.style.flex_shrink.insert(self, value);
 
+        state.transform.set_layout_dirty(self);
         state.insert_event(
             Event::new(WindowEvent::Relayout)
                 .target(Entity::null())
@@ -364,6 +499,7 @@ impl PropSet for Entity {
     fn set_flex_basis(self, state: &mut State, value: f32) -> Self {
         state.style.flex_basis.insert(self, value);
 
+        state.transform.set_layout_dirty(self);
         state.insert_event(
             Event::new(WindowEvent::Relayout)
                 .target(Entity::null())
@@ -377,6 +513,7 @@ impl PropSet for Entity {
     fn set_align_self(self, state: &mut State, value: AlignSelf) -> Self {
         state.style.align_self.insert(self, value);
 
+        state.transform.set_layout_dirty(self);
         state.insert_event(
             Event::new(WindowEvent::Relayout)
                 .target(Entity::null())
@@ -391,6 +528,7 @@ impl PropSet for Entity {
     fn set_position(self, state: &mut State, value: Position) -> Self {
         state.style.position.insert(self, value);
 
+        state.transform.set_layout_dirty(self);
         state.insert_event(
             Event::new(WindowEvent::Relayout)
                 .target(Entity::null())
@@ -404,6 +542,7 @@ impl PropSet for Entity {
     fn set_left(self, state: &mut State, value: Length) -> Self {
         state.style.left.insert(self, value);
 
+        state.transform.set_layout_dirty(self);
         state.insert_event(
             Event::new(WindowEvent::Relayout)
                 .target(Entity::null())
@@ -417,6 +556,7 @@ impl PropSet for Entity {
     fn set_right(self, state: &mut State, value: Length) -> Self {
         state.style.right.insert(self, value);
 
+        state.transform.set_layout_dirty(self);
         state.insert_event(
             Event::new(WindowEvent::Relayout)
                 .target(Entity::null())
@@ -430,6 +570,7 @@ impl PropSet for Entity {
     fn set_top(self, state: &mut State, value: Length) -> Self {
         state.style.top.insert(self, value);
 
+        state.transform.set_layout_dirty(self);
         state.insert_event(
             Event::new(WindowEvent::Relayout)
                 .target(Entity::null())
@@ -443,6 +584,7 @@ impl PropSet for Entity {
     fn set_bottom(self, state: &mut State, value: Length) -> Self {
         state.style.bottom.insert(self, value);
 
+        state.transform.set_layout_dirty(self);
         state.insert_event(
             Event::new(WindowEvent::Relayout)
                 .target(Entity::null())
@@ -457,6 +599,7 @@ impl PropSet for Entity {
     fn set_width(self, state: &mut State, value: Length) -> Self {
         state.style.width.insert(self, value);
 
+        state.transform.set_layout_dirty(self);
         state.insert_event(
             Event::new(WindowEvent::Relayout)
                 .target(Entity::null())
@@ -470,6 +613,7 @@ impl PropSet for Entity {
     fn set_height(self, state: &mut State, value: Length) -> Self {
         state.style.height.insert(self, value);
 
+        state.transform.set_layout_dirty(self);
         state.insert_event(
             Event::new(WindowEvent::Relayout)
                 .target(Entity::null())
@@ -484,6 +628,7 @@ impl PropSet for Entity {
     fn set_min_width(self, state: &mut State, value: Length) -> Self {
         state.style.min_width.insert(self, value);
 
+        state.transform.set_layout_dirty(self);
         state.insert_event(
             Event::new(WindowEvent::Relayout)
                 .target(Entity::null())
@@ -497,6 +642,7 @@ impl PropSet for Entity {
     fn set_max_width(self, state: &mut State, value: Length) -> Self {
         state.style.max_width.insert(self, value);
 
+        state.transform.set_layout_dirty(self);
         state.insert_event(
             Event::new(WindowEvent::Relayout)
                 .target(Entity::null())
@@ -510,6 +656,7 @@ impl PropSet for Entity {
     fn set_min_height(self, state: &mut State, value: Length) -> Self {
         state.style.min_height.insert(self, value);
 
+        state.transform.set_layout_dirty(self);
         state.insert_event(
             Event::new(WindowEvent::Relayout)
                 .target(Entity::null())
@@ -523,6 +670,7 @@ impl PropSet for Entity {
     fn set_max_height(self, state: &mut State, value: Length) -> Self {
         state.style.max_height.insert(self, value);
 
+        state.transform.set_layout_dirty(self);
         state.insert_event(
             Event::new(WindowEvent::Relayout)
                 .target(Entity::null())
@@ -547,6 +695,7 @@ impl PropSet for Entity {
             );
         }
 
+        state.transform.set_layout_dirty(self);
         state.insert_event(
             Event::new(WindowEvent::Relayout)
                 .target(Entity::null())
@@ -557,20 +706,65 @@ impl PropSet for Entity {
         self
     }
 
+    // Tooltip
+    fn set_tooltip(self, state: &mut State, value: &str) -> Self {
+        state
+            .style
+            .tooltip
+            .insert(self, Tooltip::Text(value.to_string()));
+
+        // A tooltip which is already shown follows the text, such as the value of a knob being turned
+        let label = state.tooltips.label;
+        if state.tooltips.owner == self && state.tooltips.shown == label && state.is_alive(label) {
+            label.set_text(state, value);
+        }
+
+        self
+    }
+
+    fn set_tooltip_widget(self, state: &mut State, value: Entity) -> Self {
+        state.style.tooltip.insert(self, Tooltip::Widget(value));
+
+        self
+    }
+
     // Text Font
     fn set_font(self, state: &mut State, value: String) -> Self {
-        if let Some(data) = state.style.text.get_mut(self) {
-            data.font = value;
-        } else {
-            state.style.text.insert(
-                self,
-                Text {
-                    font: value,
-                    ..Default::default()
-                },
-            );
-        }
+        self.set_font_family(state, vec![value])
+    }
+
+    fn set_font_family(self, state: &mut State, value: Vec<String>) -> Self {
+        state.style.font_family.insert(self, value);
+
+        state.transform.set_layout_dirty(self);
+        state.insert_event(
+            Event::new(WindowEvent::Relayout)
+                .target(Entity::null())
+                .origin(self),
+        );
+        state.insert_event(Event::new(WindowEvent::Redraw));
 
+        self
+    }
+
+    fn set_font_weight(self, state: &mut State, value: FontWeight) -> Self {
+        state.style.font_weight.insert(self, value);
+
+        state.transform.set_layout_dirty(self);
+        state.insert_event(
+            Event::new(WindowEvent::Relayout)
+                .target(Entity::null())
+                .origin(self),
+        );
+        state.insert_event(Event::new(WindowEvent::Redraw));
+
+        self
+    }
+
+    fn set_font_style(self, state: &mut State, value: FontStyle) -> Self {
+        state.style.font_style.insert(self, value);
+
+        state.transform.set_layout_dirty(self);
         state.insert_event(
             Event::new(WindowEvent::Relayout)
                 .target(Entity::null())
@@ -582,18 +776,9 @@ impl PropSet for Entity {
     }
 
     fn set_font_size(self, state: &mut State, value: f32) -> Self {
-        if let Some(data) = state.style.text.get_mut(self) {
-            data.font_size = value;
-        } else {
-            state.style.text.insert(
-                self,
-                Text {
-                    font_size: value,
-                    ..Default::default()
-                },
-            );
-        }
+        state.style.font_size.insert(self, value);
 
+        state.transform.set_layout_dirty(self);
         state.insert_event(
             Event::new(WindowEvent::Relayout)
                 .target(Entity::null())
@@ -612,6 +797,57 @@ impl PropSet for Entity {
         self
     }
 
+    // Text Layout
+    fn set_white_space(self, state: &mut State, value: WhiteSpace) -> Self {
+        state.style.white_space.insert(self, value);
+
+        state.transform.set_layout_dirty(self);
+        state.insert_event(
+            Event::new(WindowEvent::Relayout)
+                .target(Entity::null())
+                .origin(self),
+        );
+        state.insert_event(Event::new(WindowEvent::Redraw));
+
+        self
+    }
+
+    fn set_line_height(self, state: &mut State, value: LineHeight) -> Self {
+        state.style.line_height.insert(self, value);
+
+        state.transform.set_layout_dirty(self);
+        state.insert_event(
+            Event::new(WindowEvent::Relayout)
+                .target(Entity::null())
+                .origin(self),
+        );
+        state.insert_event(Event::new(WindowEvent::Redraw));
+
+        self
+    }
+
+    fn set_letter_spacing(self, state: &mut State, value: f32) -> Self {
+        state.style.letter_spacing.insert(self, value);
+
+        state.transform.set_layout_dirty(self);
+        state.insert_event(
+            Event::new(WindowEvent::Relayout)
+                .target(Entity::null())
+                .origin(self),
+        );
+        state.insert_event(Event::new(WindowEvent::Redraw));
+
+        self
+    }
+
+    fn set_text_overflow(self, state: &mut State, value: TextOverflow) -> Self {
+        state.style.text_overflow.insert(self, value);
+
+        state.insert_event(Event::new(WindowEvent::Redraw));
+
+        self
+    }
+
     // Text Alignment
     fn set_text_justify(self, state: &mut State, value: Justify) -> Self {
         state.style.text_justify.insert(self, value);
@@ -642,6 +878,7 @@ impl PropSet for Entity {
     fn set_border_width(self, state: &mut State, value: f32) -> Self {
         state.style.border_width.insert(self, value);
 
+        state.transform.set_layout_dirty(self);
         state.insert_event(
             Event::new(WindowEvent::Relayout)
                 .target(Entity::null())
@@ -724,6 +961,7 @@ impl PropSet for Entity {
         state.style.margin_top.insert(self, value);
         state.style.margin_bottom.insert(self, value);
 
+        state.transform.set_layout_dirty(self);
         state.insert_event(
             Event::new(WindowEvent::Relayout)
                 .target(Entity::null())
@@ -737,6 +975,7 @@ impl PropSet for Entity {
     fn set_margin_left(self, state: &mut State, value: Length) -> Self {
         state.style.margin_left.insert(self, value);
 
+        state.transform.set_layout_dirty(self);
         state.insert_event(
             Event::new(WindowEvent::Relayout)
                 .target(Entity::null())
@@ -749,6 +988,7 @@ impl PropSet for Entity {
     fn set_margin_right(self, state: &mut State, value: Length) -> Self {
         state.style.margin_right.insert(self, value);
 
+        state.transform.set_layout_dirty(self);
         state.insert_event(
             Event::new(WindowEvent::Relayout)
                 .target(Entity::null())
@@ -761,6 +1001,7 @@ impl PropSet for Entity {
     fn set_margin_top(self, state: &mut State, value: Length) -> Self {
         state.style.margin_top.insert(self, value);
 
+        state.transform.set_layout_dirty(self);
         state.insert_event(
             Event::new(WindowEvent::Relayout)
                 .target(Entity::null())
@@ -773,6 +1014,7 @@ impl PropSet for Entity {
     fn set_margin_bottom(self, state: &mut State, value: Length) -> Self {
         state.style.margin_bottom.insert(self, value);
 
+        state.transform.set_layout_dirty(self);
         state.insert_event(
             Event::new(WindowEvent::Relayout)
                 .target(Entity::null())
@@ -790,6 +1032,7 @@ impl PropSet for Entity {
         state.style.padding_top.insert(self, value);
         state.style.padding_bottom.insert(self, value);
 
+        state.transform.set_layout_dirty(self);
         state.insert_event(
             Event::new(WindowEvent::Relayout)
                 .target(Entity::null())
@@ -803,6 +1046,7 @@ impl PropSet for Entity {
     fn set_padding_left(self, state: &mut State, value: Length) -> Self {
         state.style.padding_left.insert(self, value);
 
+        state.transform.set_layout_dirty(self);
         state.insert_event(
             Event::new(WindowEvent::Relayout)
                 .target(Entity::null())
@@ -815,6 +1059,7 @@ impl PropSet for Entity {
     fn set_padding_right(self, state: &mut State, value: Length) -> Self {
         state.style.padding_right.insert(self, value);
 
+        state.transform.set_layout_dirty(self);
         state.insert_event(
             Event::new(WindowEvent::Relayout)
                 .target(Entity::null())
@@ -827,6 +1072,7 @@ impl PropSet for Entity {
     fn set_padding_top(self, state: &mut State, value: Length) -> Self {
         state.style.padding_top.insert(self, value);
 
+        state.transform.set_layout_dirty(self);
         state.insert_event(
             Event::new(WindowEvent::Relayout)
                 .target(Entity::null())
@@ -839,6 +1085,7 @@ impl PropSet for Entity {
     fn set_padding_bottom(self, state: &mut State, value: Length) -> Self {
         state.style.padding_bottom.insert(self, value);
 
+        state.transform.set_layout_dirty(self);
         state.insert_event(
             Event::new(WindowEvent::Relayout)
                 .target(Entity::null())
@@ -853,6 +1100,7 @@ impl PropSet for Entity {
     fn set_clip_widget(self, state: &mut State, value: Entity) -> Self {
         state.style.clip_widget.insert(self, value);
 
+        state.transform.set_layout_dirty(self);
         state.insert_event(
             Event::new(WindowEvent::Relayout)
                 .target(Entity::null())
@@ -864,8 +1112,10 @@ impl PropSet for Entity {
     }
 
     fn set_z_order(self, state: &mut State, value: i32) -> Self {
-        state.style.z_order.set(self, value);
+        state.style.z_order.insert(self, ZIndex::Index(value));
 
+        state.transform.set_layout_dirty(self);
+        state.transform.draw_order_dirty = true;
         state.insert_event(
             Event::new(WindowEvent::Relayout)
                 .target(Entity::null())
@@ -889,6 +1139,7 @@ impl PropSet for Entity {
             );
         }
 
+        state.transform.set_layout_dirty(self);
         state.insert_event(
             Event::new(WindowEvent::Relayout)
                 .target(Entity::null())
@@ -912,6 +1163,7 @@ impl PropSet for Entity {
             );
         }
 
+        state.transform.set_layout_dirty(self);
         state.insert_event(
             Event::new(WindowEvent::Relayout)
                 .target(Entity::null())
@@ -933,6 +1185,7 @@ impl PropSet for Entity {
                 .insert(self, FocusOrder { next, prev });
         }
 
+        state.transform.set_layout_dirty(self);
         state.insert_event(
             Event::new(WindowEvent::Relayout)
                 .target(Entity::null())
@@ -943,3 +1196,18 @@ impl PropSet for Entity {
         self
     }
 }
+
+// Stores a layout property with the given function and flags the entity to be laid out again
+fn set_layout_property(entity: Entity, state: &mut State, set: impl FnOnce(&mut Style)) -> Entity {
+    set(&mut state.style);
+
+    state.transform.set_layout_dirty(entity);
+    state.insert_event(
+        Event::new(WindowEvent::Relayout)
+            .target(Entity::null())
+            .origin(entity),
+    );
+    state.insert_event(Event::new(WindowEvent::Redraw));
+
+    entity
+}
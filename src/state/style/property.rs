@@ -5,20 +5,25 @@ use crate::style::color::Color;
 
 use crate::state::style::*;
 
-use crate::state::animator::Transition;
+use crate::state::animator::{Animation, Transition};
 
 #[derive(Clone, Debug)]
 pub enum Property {
     None,
 
-    Animation(String),
+    Animation(Vec<Animation>),
     AnimationDuration(std::time::Duration),
 
     Display(Display),
     Visibility(Visibility),
     Overflow(Overflow),
+    ZIndex(ZIndex),
     Opacity(f32),
 
+    // Transform
+    Transform(Vec<TransformFunction>),
+    TransformOrigin(TransformOrigin),
+
     // Positioning
     Position(Position),
     Left(Length),
@@ -52,6 +57,7 @@ pub enum Property {
 
     // Flex Container
     FlexDirection(FlexDirection),
+    FlexWrap(FlexWrap),
     JustifyContent(JustifyContent),
     AlignItems(AlignItems),
     AlignContent(AlignContent),
@@ -62,6 +68,17 @@ pub enum Property {
     FlexShrink(f32),
     AlignSelf(AlignSelf),
 
+    // Grid Container
+    GridTemplateColumns(Vec<TrackSize>),
+    GridTemplateRows(Vec<TrackSize>),
+    GridColumnGap(Length),
+    GridRowGap(Length),
+    GridGap(Length, Length),
+
+    // Grid Item
+    GridColumn(GridPlacement),
+    GridRow(GridPlacement),
+
     // Border
     BorderRadius(Length),
     BorderWidth(f32),
@@ -73,9 +90,23 @@ pub enum Property {
 
     TextJustify(Justify),
     TextAlign(Align),
+    TextOverflow(TextOverflow),
 
+    FontFamily(Vec<String>),
+    FontWeight(FontWeight),
+    FontStyle(FontStyle),
     FontSize(f32),
     FontColor(Color),
+    WhiteSpace(WhiteSpace),
+    LineHeight(LineHeight),
+    LetterSpacing(f32),
 
     Transition(Vec<Transition>),
+
+    // A custom property declaration (--name: value)
+    Custom(String, String),
+    // The inherit keyword (or unset) for an inherited property, e.g. color: inherit
+    Inherit(String),
+    // A declaration containing var() or currentColor, parsed when the entity it applies to is known
+    Unresolved(Declaration),
 }
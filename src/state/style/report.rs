@@ -0,0 +1,99 @@
+use std::fmt;
+
+// Problems found while parsing a stylesheet
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Severity {
+    // The declaration or rule was dropped
+    Error,
+    // The declaration or rule is valid CSS but isn't supported and was ignored
+    Warning,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum DiagnosticKind {
+    UnknownProperty(String),
+    InvalidValue { property: String, value: String },
+    UnsupportedAtRule(String),
+    InvalidSelector(String),
+    MissingDescriptor { at_rule: String, descriptor: String },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub kind: DiagnosticKind,
+    // Line and column in the stylesheet, both starting at 1
+    pub line: u32,
+    pub column: u32,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let severity = match self.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+
+        write!(f, "{} at {}:{}: ", severity, self.line, self.column)?;
+
+        match &self.kind {
+            DiagnosticKind::UnknownProperty(name) => write!(f, "unknown property `{}`", name),
+            DiagnosticKind::InvalidValue { property, value } => {
+                write!(f, "invalid value `{}` for property `{}`", value, property)
+            }
+            DiagnosticKind::UnsupportedAtRule(name) => write!(f, "unsupported at-rule `@{}`", name),
+            DiagnosticKind::InvalidSelector(selector) => write!(f, "invalid selector `{}`", selector),
+            DiagnosticKind::MissingDescriptor {
+                at_rule,
+                descriptor,
+            } => write!(f, "`{}` is missing `{}`", at_rule, descriptor),
+        }
+    }
+}
+
+// The diagnostics from parsing one or more stylesheets
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct StyleReport {
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl StyleReport {
+    pub fn new() -> Self {
+        StyleReport::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.diagnostics.is_empty()
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.errors().next().is_some()
+    }
+
+    pub fn errors(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.diagnostics
+            .iter()
+            .filter(|diagnostic| diagnostic.severity == Severity::Error)
+    }
+
+    pub fn warnings(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.diagnostics
+            .iter()
+            .filter(|diagnostic| diagnostic.severity == Severity::Warning)
+    }
+
+    pub fn append(&mut self, other: &mut StyleReport) {
+        self.diagnostics.append(&mut other.diagnostics);
+    }
+}
+
+impl fmt::Display for StyleReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for diagnostic in self.diagnostics.iter() {
+            writeln!(f, "{}", diagnostic)?;
+        }
+
+        Ok(())
+    }
+}
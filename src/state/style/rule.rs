@@ -4,7 +4,7 @@ use super::Property;
 
 use crate::state::storage::dense_storage::DenseStorage;
 
-use crate::style::{Relation, Selector, Specificity};
+use crate::style::{FontStyle, FontWeight, Relation, Selector, Specificity};
 
 #[derive(Clone, Debug)]
 pub struct StyleRule {
@@ -47,4 +47,45 @@ impl StyleRule {
 
         return specificity;
     }
-}
\ No newline at end of file
+}
+
+// A named set of keyframes parsed from a @keyframes rule
+#[derive(Clone, Debug)]
+pub struct KeyframesRule {
+    pub name: String,
+    // List of (time, properties) where time is between 0.0 and 1.0
+    pub keyframes: Vec<(f32, Vec<Property>)>,
+}
+
+impl KeyframesRule {
+    pub fn new(name: &str) -> Self {
+        KeyframesRule {
+            name: name.to_string(),
+            keyframes: Vec::new(),
+        }
+    }
+
+    pub fn keyframe(mut self, time: f32, properties: Vec<Property>) -> Self {
+        self.keyframes.push((time, properties));
+
+        self
+    }
+}
+
+// A font face declared with @font-face
+#[derive(Clone, Debug)]
+pub struct FontFaceRule {
+    pub family: String,
+    // Font files from url() in order of preference, relative to the stylesheet
+    pub sources: Vec<String>,
+    pub weight: FontWeight,
+    pub style: FontStyle,
+}
+
+// A top level rule in a stylesheet
+#[derive(Clone, Debug)]
+pub enum Rule {
+    Style(StyleRule),
+    Keyframes(KeyframesRule),
+    FontFace(FontFaceRule),
+}
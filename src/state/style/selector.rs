@@ -28,74 +28,110 @@ use crate::Specificity;
 // 6 - Checked
 // 7 - Unassigned
 
-#[derive(Debug, Clone)]
-pub struct PseudoClasses(u8);
+#[derive(Debug, Clone, PartialEq)]
+pub struct PseudoClasses {
+    // Bits for the built-in pseudo-classes
+    flags: u8,
+    // Pseudo-classes set by widgets, e.g. :selected or :expanded
+    custom: HashSet<String>,
+}
 
 impl Default for PseudoClasses {
     fn default() -> Self {
-        PseudoClasses(0)
+        PseudoClasses {
+            flags: 0,
+            custom: HashSet::new(),
+        }
+    }
+}
+
+// Returns the bit of a built-in pseudo-class
+fn pseudo_class_flag(name: &str) -> Option<u8> {
+    match name {
+        "hover" => Some(1),
+        "over" => Some(1 << 1),
+        "active" => Some(1 << 2),
+        "focus" => Some(1 << 3),
+        "enabled" => Some(1 << 4),
+        "disabled" => Some(1 << 5),
+        "checked" => Some(1 << 6),
+        _ => None,
     }
 }
 
 impl PseudoClasses {
     pub fn new() -> Self {
-        PseudoClasses(0)
+        PseudoClasses::default()
     }
 
-    pub fn set_hover(&mut self, flag: bool) {
-        if flag {
-            self.0 |= 1;
+    // Sets a pseudo-class by name. Names which aren't built-in are custom pseudo-classes.
+    pub fn set(&mut self, name: &str, flag: bool) {
+        if let Some(bit) = pseudo_class_flag(name) {
+            self.set_flag(bit, flag);
+        } else if flag {
+            self.custom.insert(name.to_string());
         } else {
-            self.0 &= !1;
+            self.custom.remove(name);
         }
     }
 
-    pub fn set_over(&mut self, flag: bool) {
-        if flag {
-            self.0 |= (1 << 1);
+    // Returns true if the pseudo-class is set
+    pub fn get(&self, name: &str) -> bool {
+        if let Some(bit) = pseudo_class_flag(name) {
+            self.flags & bit != 0
         } else {
-            self.0 &= !(1 << 1);
+            self.custom.contains(name)
         }
     }
 
-    pub fn set_active(&mut self, flag: bool) {
+    // Returns true if every pseudo-class in other is also set
+    pub fn contains(&self, other: &PseudoClasses) -> bool {
+        (self.flags & other.flags) == other.flags && other.custom.is_subset(&self.custom)
+    }
+
+    // Returns the number of pseudo-classes which are set
+    pub fn len(&self) -> usize {
+        self.flags.count_ones() as usize + self.custom.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn set_flag(&mut self, bit: u8, flag: bool) {
         if flag {
-            self.0 |= (1 << 2);
+            self.flags |= bit;
         } else {
-            self.0 &= !(1 << 2);
+            self.flags &= !bit;
         }
     }
 
+    pub fn set_hover(&mut self, flag: bool) {
+        self.set_flag(1, flag);
+    }
+
+    pub fn set_over(&mut self, flag: bool) {
+        self.set_flag(1 << 1, flag);
+    }
+
+    pub fn set_active(&mut self, flag: bool) {
+        self.set_flag(1 << 2, flag);
+    }
+
     pub fn set_focus(&mut self, flag: bool) {
-        if flag {
-            self.0 |= (1 << 3);
-        } else {
-            self.0 &= !(1 << 3);
-        }
+        self.set_flag(1 << 3, flag);
     }
 
     pub fn set_enabled(&mut self, flag: bool) {
-        if flag {
-            self.0 |= (1 << 4);
-        } else {
-            self.0 &= !(1 << 4);
-        }
+        self.set_flag(1 << 4, flag);
     }
 
     pub fn set_disabled(&mut self, flag: bool) {
-        if flag {
-            self.0 |= (1 << 5);
-        } else {
-            self.0 &= !(1 << 5);
-        }
+        self.set_flag(1 << 5, flag);
     }
 
     pub fn set_checked(&mut self, flag: bool) {
-        if flag {
-            self.0 |= (1 << 6);
-        } else {
-            self.0 &= !(1 << 6);
-        }
+        self.set_flag(1 << 6, flag);
     }
 }
 
@@ -104,6 +140,10 @@ pub enum Relation {
     None,
     Ancestor,
     Parent,
+    // The selector matches the previous sibling (+)
+    Sibling,
+    // The selector matches any previous sibling (~)
+    GeneralSibling,
 }
 
 
@@ -117,6 +157,15 @@ pub struct Selector {
     pub pseudo_classes: PseudoClasses,
     pub relation: Relation,
     pub asterisk: bool,
+
+    // Structural pseudo-classes, matched against the position of the entity in the hierarchy
+    pub first_child: bool,
+    pub last_child: bool,
+    // The (a, b) of :nth-child(an+b)
+    pub nth_child: Option<(i32, i32)>,
+    pub empty: bool,
+    // Selectors the entity must not match, from :not(...)
+    pub not: Vec<Selector>,
 }
 
 impl Default for Selector {
@@ -129,6 +178,11 @@ impl Default for Selector {
             pseudo_classes: PseudoClasses::default(),
             relation: Relation::None,
             asterisk: false,
+            first_child: false,
+            last_child: false,
+            nth_child: None,
+            empty: false,
+            not: Vec::new(),
         }
     }
 }
@@ -143,6 +197,11 @@ impl Selector {
             pseudo_classes: PseudoClasses::default(),
             relation: Relation::None,
             asterisk: false,
+            first_child: false,
+            last_child: false,
+            nth_child: None,
+            empty: false,
+            not: Vec::new(),
         }
     }
 
@@ -158,6 +217,11 @@ impl Selector {
             pseudo_classes: PseudoClasses::default(),
             relation: Relation::None,
             asterisk: false,
+            first_child: false,
+            last_child: false,
+            nth_child: None,
+            empty: false,
+            not: Vec::new(),
         }
     }
 
@@ -188,9 +252,8 @@ impl Selector {
         //     return false;
         // }
 
-        //println!("Selector: {:?}  Widget: {:?}  Combined: 0b{:08b}", self.pseudo_classes, other.pseudo_classes, (self.pseudo_classes.0 & other.pseudo_classes.0));
-
-        if self.pseudo_classes.0 != 0 && (self.pseudo_classes.0 & entity_selector.pseudo_classes.0) == 0 {
+        // All of the pseudo-classes in the selector must be set on the entity
+        if !entity_selector.pseudo_classes.contains(&self.pseudo_classes) {
             return false;
         }
 
@@ -219,12 +282,74 @@ impl Selector {
     // }
 
     pub fn specificity(&self) -> Specificity {
-        Specificity([
+        let structural = [
+            self.first_child,
+            self.last_child,
+            self.nth_child.is_some(),
+            self.empty,
+        ]
+        .iter()
+        .filter(|flag| **flag)
+        .count();
+
+        let mut specificity = Specificity([
             if self.id.is_some() { 1 } else { 0 },
             //(self.classes.len() + self.pseudo_classes.len()) as u8,
-            (self.classes.len() + self.pseudo_classes.0.count_ones() as usize) as u8,
+            (self.classes.len() + self.pseudo_classes.len() + structural) as u8,
             if self.element.is_some() { 1 } else { 0 },
-        ])
+        ]);
+
+        // :not(...) takes the specificity of its argument
+        for selector in self.not.iter() {
+            specificity += selector.specificity();
+        }
+
+        specificity
+    }
+
+    // Returns true if a 1-based position among siblings is matched by :nth-child(an+b)
+    pub fn matches_nth_child(&self, position: i32) -> bool {
+        match self.nth_child {
+            Some((a, b)) => {
+                if a == 0 {
+                    position == b
+                } else {
+                    let offset = position - b;
+                    offset % a == 0 && offset / a >= 0
+                }
+            }
+
+            None => true,
+        }
+    }
+
+    // Returns true if the selector has nothing to match
+    pub fn is_empty(&self) -> bool {
+        self.id.is_none()
+            && self.element.is_none()
+            && self.classes.is_empty()
+            && self.pseudo_classes.is_empty()
+            && !self.asterisk
+            && !self.first_child
+            && !self.last_child
+            && self.nth_child.is_none()
+            && !self.empty
+            && self.not.is_empty()
+    }
+
+    // Returns true if the selector is a single simple selector, e.g. `.a` or `:hover` but not `.a:hover` or `.a > .b`
+    pub fn is_simple(&self) -> bool {
+        let parts = self.id.is_some() as usize
+            + self.element.is_some() as usize
+            + self.classes.len()
+            + self.pseudo_classes.len()
+            + self.asterisk as usize
+            + self.first_child as usize
+            + self.last_child as usize
+            + self.nth_child.is_some() as usize
+            + self.empty as usize;
+
+        parts == 1 && self.not.is_empty() && matches!(self.relation, Relation::None)
     }
 
     pub fn id(mut self, id: &str) -> Self {
@@ -0,0 +1,69 @@
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+// The source of a parsed stylesheet, kept so that the style rules can be rebuilt when it changes
+#[derive(Clone, Debug)]
+pub struct StyleSheet {
+    pub source: String,
+    // The file the stylesheet was loaded from
+    pub path: Option<PathBuf>,
+    // Modification time of the file when it was last read
+    pub modified: Option<SystemTime>,
+}
+
+impl StyleSheet {
+    pub fn new(source: &str) -> Self {
+        StyleSheet {
+            source: source.to_string(),
+            path: None,
+            modified: None,
+        }
+    }
+
+    pub fn from_file<P: AsRef<Path>>(path: P) -> std::io::Result<Self> {
+        let path = path.as_ref();
+
+        let modified = std::fs::metadata(path)?.modified().ok();
+        let source = std::fs::read_to_string(path)?;
+
+        Ok(StyleSheet {
+            source,
+            path: Some(path.to_path_buf()),
+            modified,
+        })
+    }
+
+    // Reads the file again if it has been modified, returning true if the source has changed
+    pub fn poll(&mut self) -> bool {
+        let path = match &self.path {
+            Some(path) => path,
+            None => return false,
+        };
+
+        let modified = match std::fs::metadata(path).and_then(|metadata| metadata.modified()) {
+            Ok(modified) => modified,
+            Err(_) => return false,
+        };
+
+        if self.modified == Some(modified) {
+            return false;
+        }
+
+        // The file may be part way through being saved, in which case the old source is kept
+        // and the file is read again on the next poll
+        let source = match std::fs::read_to_string(path) {
+            Ok(source) => source,
+            Err(_) => return false,
+        };
+
+        self.modified = Some(modified);
+
+        if source == self.source {
+            return false;
+        }
+
+        self.source = source;
+
+        true
+    }
+}
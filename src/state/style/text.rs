@@ -2,24 +2,114 @@ use crate::entity::Entity;
 
 use crate::layout::{Align, Justify};
 
-use crate::style::Color;
-
 #[derive(Debug, Clone)]
 pub struct Text {
     pub text: String,
-    pub font: String,
-    pub font_size: f32,
-    pub font_color: Color,
     pub indent: f32,
 }
 
+// The weight of a font from 1 to 1000, where normal is 400 and bold is 700
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct FontWeight(pub u16);
+
+impl FontWeight {
+    pub const NORMAL: FontWeight = FontWeight(400);
+    pub const BOLD: FontWeight = FontWeight(700);
+}
+
+impl Default for FontWeight {
+    fn default() -> Self {
+        FontWeight::NORMAL
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum FontStyle {
+    Normal,
+    Italic,
+    Oblique,
+}
+
+impl Default for FontStyle {
+    fn default() -> Self {
+        FontStyle::Normal
+    }
+}
+
+// How white space in text is collapsed and whether lines wrap
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum WhiteSpace {
+    // Sequences of white space, including line breaks, are collapsed and lines wrap
+    Normal,
+    // Like normal but lines don't wrap
+    NoWrap,
+    // White space and line breaks are kept and lines don't wrap
+    Pre,
+    // Like pre but lines wrap
+    PreWrap,
+    // Sequences of white space are collapsed but line breaks are kept and lines wrap
+    PreLine,
+}
+
+impl WhiteSpace {
+    pub fn wraps(&self) -> bool {
+        match self {
+            WhiteSpace::Normal | WhiteSpace::PreWrap | WhiteSpace::PreLine => true,
+            WhiteSpace::NoWrap | WhiteSpace::Pre => false,
+        }
+    }
+}
+
+impl Default for WhiteSpace {
+    fn default() -> Self {
+        WhiteSpace::Normal
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum LineHeight {
+    // The line height of the font
+    Normal,
+    // A multiple of the font size
+    Number(f32),
+    Pixels(f32),
+}
+
+impl LineHeight {
+    // Returns the height of a line in pixels, given the font size and the normal line height of the font
+    pub fn resolve(&self, font_size: f32, normal: f32) -> f32 {
+        match self {
+            LineHeight::Normal => normal,
+            LineHeight::Number(val) => val * font_size,
+            LineHeight::Pixels(val) => *val,
+        }
+    }
+}
+
+impl Default for LineHeight {
+    fn default() -> Self {
+        LineHeight::Normal
+    }
+}
+
+// How text which doesn't fit in the content box is shown
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum TextOverflow {
+    Clip,
+    // The text is cut short with an ellipsis where it overflows
+    Ellipsis,
+}
+
+impl Default for TextOverflow {
+    fn default() -> Self {
+        TextOverflow::Clip
+    }
+}
+
 impl Default for Text {
     fn default() -> Self {
         Text {
             text: "".to_string(),
-            font: "Sans".to_string(),
-            font_size: 14.0,
-            font_color: Color::rgba(255, 255, 255, 255),
             indent: 0.0,
         }
     }
@@ -2,7 +2,8 @@ use std::{fs::File, io::BufReader, io::Read, mem, path::Path, sync::Arc};
 
 use cssparser::{
     self, AtRuleType, BasicParseError, BasicParseErrorKind, CowRcStr, DeclarationListParser,
-    ParseError, ParseErrorKind, Parser, ParserInput, RuleListParser, SourceLocation, Token,
+    ParseError, ParseErrorKind, Parser, ParserInput, RuleListParser, SourceLocation,
+    Token,
 };
 
 use crate::layout::{Align, Justify};
@@ -10,13 +11,15 @@ use crate::layout::{Align, Justify};
 use crate::state::style::property::Property;
 use crate::state::style::selector::{Relation, Selector};
 
-use crate::state::animator::Transition;
-use crate::state::style::StyleRule;
+use crate::state::animator::{Animation, Transition};
+use crate::state::style::{KeyframesRule, Rule, StyleRule};
 
 use crate::state::style::*;
 
 use crate::state::style::color::Color;
 
+use crate::state::fonts::DEFAULT_FONT_FAMILY;
+
 #[derive(Clone, Debug)]
 pub enum CustomParseError {
     InvalidLengthUnits(String),
@@ -24,6 +27,10 @@ pub enum CustomParseError {
     InvalidColorName(String),
     InvalidColorHex(String),
     InvalidStringName(String),
+    UnknownProperty(String),
+    UnsupportedAtRule(String),
+    // A descriptor which an at-rule can't do without, e.g. the src of a @font-face
+    MissingDescriptor(String),
 }
 
 impl<'t> From<CustomParseError> for ParseError<'t, CustomParseError> {
@@ -35,25 +42,134 @@ impl<'t> From<CustomParseError> for ParseError<'t, CustomParseError> {
     }
 }
 
-pub struct RuleParser;
+// Sets the location of an error which was created without one
+fn locate<'i>(
+    mut error: ParseError<'i, CustomParseError>,
+    location: SourceLocation,
+) -> ParseError<'i, CustomParseError> {
+    // Columns start at 1 so a column of 0 is a missing location
+    if error.location.column == 0 {
+        error.location = location;
+    }
+
+    error
+}
+
+fn diagnostic(severity: Severity, kind: DiagnosticKind, location: SourceLocation) -> Diagnostic {
+    Diagnostic {
+        severity,
+        kind,
+        line: location.line + 1,
+        column: location.column.max(1),
+    }
+}
+
+// Creates a diagnostic for a declaration which failed to parse
+fn declaration_diagnostic(error: &ParseError<CustomParseError>, declaration: &str) -> Diagnostic {
+    let (property, value) = match declaration.find(':') {
+        Some(index) => (&declaration[..index], &declaration[index + 1..]),
+        None => (declaration, ""),
+    };
+
+    let kind = match &error.kind {
+        ParseErrorKind::Custom(CustomParseError::UnknownProperty(name)) => {
+            DiagnosticKind::UnknownProperty(name.clone())
+        }
+
+        _ => DiagnosticKind::InvalidValue {
+            property: property.trim().to_string(),
+            value: value.trim().trim_end_matches(';').trim().to_string(),
+        },
+    };
+
+    diagnostic(Severity::Error, kind, error.location)
+}
+
+// Creates a diagnostic for a rule which failed to parse
+pub(crate) fn rule_diagnostic(error: &ParseError<CustomParseError>, rule: &str) -> Diagnostic {
+    // The block of the rule isn't useful in the diagnostic
+    let prelude = rule.split('{').next().unwrap_or_default().trim();
+
+    match &error.kind {
+        ParseErrorKind::Custom(CustomParseError::UnsupportedAtRule(name)) => diagnostic(
+            Severity::Warning,
+            DiagnosticKind::UnsupportedAtRule(name.clone()),
+            error.location,
+        ),
+
+        ParseErrorKind::Custom(CustomParseError::MissingDescriptor(descriptor)) => diagnostic(
+            Severity::Error,
+            DiagnosticKind::MissingDescriptor {
+                at_rule: prelude.to_string(),
+                descriptor: descriptor.clone(),
+            },
+            error.location,
+        ),
+
+        _ if prelude.starts_with('@') => {
+            let (name, value) = prelude.split_at(prelude.find(char::is_whitespace).unwrap_or(prelude.len()));
+            diagnostic(
+                Severity::Error,
+                DiagnosticKind::InvalidValue {
+                    property: name.to_string(),
+                    value: value.trim().to_string(),
+                },
+                error.location,
+            )
+        }
+
+        _ => diagnostic(
+            Severity::Error,
+            DiagnosticKind::InvalidSelector(prelude.to_string()),
+            error.location,
+        ),
+    }
+}
+
+// Parses the declarations of a block, adding a diagnostic for each one which fails to parse
+fn parse_declarations<'i, 't>(
+    input: &mut Parser<'i, 't>,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Vec<Property> {
+    let mut properties = Vec::new();
+
+    for declaration in DeclarationListParser::new(input, DeclarationParser {}) {
+        match declaration {
+            Ok(property) => properties.push(property),
+            Err((error, declaration)) => {
+                diagnostics.push(declaration_diagnostic(&error, declaration));
+            }
+        }
+    }
+
+    properties
+}
+
+pub struct RuleParser {
+    // Problems found while parsing, collected by the rule list parser
+    pub diagnostics: Vec<Diagnostic>,
+}
 
 impl RuleParser {
     pub fn new() -> Self {
-        RuleParser {}
+        RuleParser {
+            diagnostics: Vec::new(),
+        }
     }
 }
 
 impl<'i> cssparser::QualifiedRuleParser<'i> for RuleParser {
     type Prelude = Vec<Selector>;
-    type QualifiedRule = StyleRule;
+    type QualifiedRule = Rule;
     type Error = CustomParseError;
 
     fn parse_prelude<'t>(
         &mut self,
         input: &mut Parser<'i, 't>,
     ) -> Result<Self::Prelude, ParseError<'i, Self::Error>> {
-        let res = parse_selectors(input)?;
-        Ok(res)
+        let location = input.current_source_location();
+
+        parse_selectors(input).map_err(|error| locate(error, location))
     }
 
     fn parse_block<'t>(
@@ -62,23 +178,25 @@ impl<'i> cssparser::QualifiedRuleParser<'i> for RuleParser {
         location: SourceLocation,
         input: &mut Parser<'i, 't>,
     ) -> Result<Self::QualifiedRule, ParseError<'i, Self::Error>> {
-        let decl_parser = DeclarationParser {};
+        let properties = parse_declarations(input, &mut self.diagnostics);
 
-        let properties = DeclarationListParser::new(input, decl_parser)
-            .filter_map(|property| property.ok())
-            .collect::<Vec<_>>();
-
-        Ok(StyleRule {
+        Ok(Rule::Style(StyleRule {
             selectors,
             properties,
-        })
+        }))
     }
 }
 
+// The prelude of an at-rule with a block
+pub enum AtRulePrelude {
+    Keyframes(String),
+    FontFace,
+}
+
 impl<'i> cssparser::AtRuleParser<'i> for RuleParser {
-    type PreludeBlock = ();
+    type PreludeBlock = AtRulePrelude;
     type PreludeNoBlock = ();
-    type AtRule = StyleRule;
+    type AtRule = Rule;
     type Error = CustomParseError;
 
     fn parse_prelude<'t>(
@@ -87,51 +205,300 @@ impl<'i> cssparser::AtRuleParser<'i> for RuleParser {
         input: &mut Parser<'i, 't>,
     ) -> Result<AtRuleType<Self::PreludeNoBlock, Self::PreludeBlock>, ParseError<'i, Self::Error>>
     {
-        println!("Parse At Rule: {}", name);
+        let location = input.current_source_location();
 
         match &*name {
             "keyframes" => {
-                while let Ok(t) = input.next() {
-                    match t {
-                        Token::Ident(animation_name) => {}
+                match input.next()? {
+                    Token::Ident(animation_name) => {
+                        return Ok(AtRuleType::WithBlock(AtRulePrelude::Keyframes(
+                            animation_name.to_string(),
+                        )));
+                    }
 
-                        t => {
-                            let basic_error = BasicParseError {
-                                kind: BasicParseErrorKind::UnexpectedToken(t.clone()),
-                                location: input.current_source_location(),
-                            };
-                            return Err(basic_error.into());
-                        }
+                    t => {
+                        let basic_error = BasicParseError {
+                            kind: BasicParseErrorKind::UnexpectedToken(t.clone()),
+                            location,
+                        };
+                        return Err(basic_error.into());
                     }
                 }
             }
 
+            "font-face" => {
+                input.expect_exhausted()?;
+
+                return Ok(AtRuleType::WithBlock(AtRulePrelude::FontFace));
+            }
+
             _ => {
-                let token = input.next()?.to_owned();
-                return Err(input.new_basic_unexpected_token_error(token).into());
+                return Err(ParseError {
+                    kind: ParseErrorKind::Custom(CustomParseError::UnsupportedAtRule(
+                        name.to_string(),
+                    )),
+                    location,
+                });
             }
         }
-
-        Ok(AtRuleType::WithBlock(()))
     }
 
-    // TODO
-    /*
     fn parse_block<'t>(
         &mut self,
         prelude: Self::PreludeBlock,
         location: SourceLocation,
-        input: &mut Parser<'i, 't>
+        input: &mut Parser<'i, 't>,
     ) -> Result<Self::AtRule, ParseError<'i, Self::Error>> {
-        let rule_parser = RuleParser::new();
-        let rule_list_parser = RuleListParser::new_for_nested_rule(input, rule_parser).collect::<Vec<_>>();
+        let animation_name = match prelude {
+            AtRulePrelude::Keyframes(animation_name) => animation_name,
+            AtRulePrelude::FontFace => {
+                return parse_font_face(input, &mut self.diagnostics)
+                    .map(Rule::FontFace)
+                    .map_err(|error| locate(error, location));
+            }
+        };
+
+        let mut keyframes_parser =
+            RuleListParser::new_for_nested_rule(input, KeyframesParser::new());
+
+        // A keyframe block can apply to more than one time, e.g. 0%, 100% { ... }
+        let mut keyframes = Vec::new();
+        for keyframe in &mut keyframes_parser {
+            match keyframe {
+                Ok(mut keyframe) => keyframes.append(&mut keyframe),
+                Err((error, keyframe)) => self.diagnostics.push(rule_diagnostic(&error, keyframe)),
+            }
+        }
+
+        self.diagnostics
+            .append(&mut keyframes_parser.parser.diagnostics);
+
+        keyframes.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+
+        Ok(Rule::Keyframes(KeyframesRule {
+            name: animation_name,
+            keyframes,
+        }))
+    }
+}
+
+// Parses the descriptors of a @font-face block, which must have a font-family and a src
+fn parse_font_face<'i, 't>(
+    input: &mut Parser<'i, 't>,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Result<FontFaceRule, ParseError<'i, CustomParseError>> {
+    let mut declarations = DeclarationListParser::new(input, FontFaceParser::new());
+
+    for declaration in &mut declarations {
+        if let Err((error, declaration)) = declaration {
+            diagnostics.push(declaration_diagnostic(&error, declaration));
+        }
+    }
+
+    let rule = declarations.parser.rule;
 
-        // Keyframe rules
-        for rule in &rule_list_parser {
+    if rule.family.is_empty() {
+        return Err(CustomParseError::MissingDescriptor(String::from("font-family")).into());
+    }
+
+    if rule.sources.is_empty() {
+        return Err(CustomParseError::MissingDescriptor(String::from("src")).into());
+    }
+
+    Ok(rule)
+}
+
+// Parser for the descriptors within a @font-face block
+struct FontFaceParser {
+    rule: FontFaceRule,
+}
+
+impl FontFaceParser {
+    fn new() -> Self {
+        FontFaceParser {
+            rule: FontFaceRule {
+                family: String::new(),
+                sources: Vec::new(),
+                weight: FontWeight::default(),
+                style: FontStyle::default(),
+            },
+        }
+    }
+}
+
+impl<'i> cssparser::DeclarationParser<'i> for FontFaceParser {
+    type Declaration = ();
+    type Error = CustomParseError;
+
+    fn parse_value<'t>(
+        &mut self,
+        name: CowRcStr<'i>,
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Self::Declaration, ParseError<'i, Self::Error>> {
+        let location = input.current_source_location();
+
+        match name.as_ref() {
+            "font-family" => {
+                // A font face has exactly one family
+                let mut families = parse_font_family(input).map_err(|error| locate(error, location))?;
+                if families.len() != 1 {
+                    return Err(locate(
+                        CustomParseError::InvalidValue(String::from("font-family")).into(),
+                        location,
+                    ));
+                }
+                self.rule.family = families.remove(0);
+            }
+            "src" => self.rule.sources = parse_font_sources(input).map_err(|error| locate(error, location))?,
+            "font-weight" => self.rule.weight = parse_font_weight(input).map_err(|error| locate(error, location))?,
+            "font-style" => self.rule.style = parse_font_style(input).map_err(|error| locate(error, location))?,
+            _ => {
+                return Err(locate(
+                    CustomParseError::UnknownProperty(name.to_string()).into(),
+                    location,
+                ))
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<'i> cssparser::AtRuleParser<'i> for FontFaceParser {
+    type PreludeNoBlock = ();
+    type PreludeBlock = ();
+    type AtRule = ();
+    type Error = CustomParseError;
+}
 
+// Parses a list of url() sources with optional format() hints, e.g. url("a.woff2") format("woff2"), url(a.ttf).
+// local() sources are skipped as fonts aren't looked up on the system.
+fn parse_font_sources<'i, 't>(
+    input: &mut Parser<'i, 't>,
+) -> Result<Vec<String>, ParseError<'i, CustomParseError>> {
+    let sources = input.parse_comma_separated(|input| {
+        let location = input.current_source_location();
+
+        let source = match input.next()?.clone() {
+            Token::UnquotedUrl(url) => Some(url.to_string()),
+
+            Token::Function(name) if name.eq_ignore_ascii_case("url") => Some(
+                input
+                    .parse_nested_block(|input| Ok(input.expect_string()?.to_string()))?,
+            ),
+
+            Token::Function(name) if name.eq_ignore_ascii_case("local") => {
+                input.parse_nested_block(|input| {
+                    while input.next().is_ok() {}
+                    Ok(())
+                })?;
+                None
+            }
+
+            t => {
+                let basic_error = BasicParseError {
+                    kind: BasicParseErrorKind::UnexpectedToken(t),
+                    location,
+                };
+                return Err(basic_error.into());
+            }
+        };
+
+        // The format hint is ignored, the font is checked when it is loaded
+        if input
+            .try_parse(|input| input.expect_function_matching("format"))
+            .is_ok()
+        {
+            input.parse_nested_block(|input| {
+                while input.next().is_ok() {}
+                Ok(())
+            })?;
         }
+
+        Ok(source)
+    })?;
+
+    Ok(sources.into_iter().flatten().collect())
+}
+
+// Parser for the keyframes within a @keyframes block
+struct KeyframesParser {
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl KeyframesParser {
+    fn new() -> Self {
+        KeyframesParser {
+            diagnostics: Vec::new(),
+        }
+    }
+}
+
+impl<'i> cssparser::QualifiedRuleParser<'i> for KeyframesParser {
+    type Prelude = Vec<f32>;
+    type QualifiedRule = Vec<(f32, Vec<Property>)>;
+    type Error = CustomParseError;
+
+    fn parse_prelude<'t>(
+        &mut self,
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Self::Prelude, ParseError<'i, Self::Error>> {
+        let location = input.current_source_location();
+
+        input
+            .parse_comma_separated(|input| parse_keyframe_selector(input))
+            .map_err(|error| locate(error, location))
+    }
+
+    fn parse_block<'t>(
+        &mut self,
+        times: Self::Prelude,
+        location: SourceLocation,
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Self::QualifiedRule, ParseError<'i, Self::Error>> {
+        let properties = parse_declarations(input, &mut self.diagnostics);
+
+        Ok(times
+            .into_iter()
+            .map(|time| (time, properties.clone()))
+            .collect())
     }
-    */
+}
+
+impl<'i> cssparser::AtRuleParser<'i> for KeyframesParser {
+    type PreludeNoBlock = ();
+    type PreludeBlock = ();
+    type AtRule = Vec<(f32, Vec<Property>)>;
+    type Error = CustomParseError;
+}
+
+fn parse_keyframe_selector<'i, 't>(
+    input: &mut Parser<'i, 't>,
+) -> Result<f32, ParseError<'i, CustomParseError>> {
+    let location = input.current_source_location();
+
+    Ok(match input.next()? {
+        Token::Ident(name) => match name.as_ref() {
+            "from" => 0.0,
+            "to" => 1.0,
+
+            _ => {
+                return Err(
+                    CustomParseError::InvalidStringName(name.to_owned().to_string()).into(),
+                );
+            }
+        },
+
+        Token::Percentage { unit_value: x, .. } => *x,
+
+        t => {
+            let basic_error = BasicParseError {
+                kind: BasicParseErrorKind::UnexpectedToken(t.to_owned()),
+                location,
+            };
+            return Err(basic_error.into());
+        }
+    })
 }
 
 fn parse_selectors<'i, 't>(
@@ -170,6 +537,33 @@ fn parse_selectors<'i, 't>(
                 //     selec.relation = Relation::Parent;
                 // }
                 //selector.relation = Some(Box::new(SelectorRelation::Parent(old_selector)));
+
+                // Whitespace around a combinator isn't a descendant combinator
+                whitespace = false;
+                first_token_in_selector = true;
+                continue;
+            }
+
+            // Adjacent sibling
+            Token::Delim('+') => {
+                selector.relation = Relation::Sibling;
+                selectors.push(selector);
+                selector = Selector::default();
+
+                whitespace = false;
+                first_token_in_selector = true;
+                continue;
+            }
+
+            // General sibling
+            Token::Delim('~') => {
+                selector.relation = Relation::GeneralSibling;
+                selectors.push(selector);
+                selector = Selector::default();
+
+                whitespace = false;
+                first_token_in_selector = true;
+                continue;
             }
 
             // Id
@@ -211,22 +605,59 @@ fn parse_selectors<'i, 't>(
             }
 
             Token::WhiteSpace(ref ws) => {
-                whitespace = true;
+                if !first_token_in_selector {
+                    whitespace = true;
+                }
             }
 
             // Pseudo-class
             Token::Colon => {
-                let pseudo_class_str = input.expect_ident()?.to_owned();
+                let location = input.current_source_location();
+
+                match input.next_including_whitespace()?.clone() {
+                    Token::Ident(pseudo_class_str) => match pseudo_class_str.as_ref() {
+                        "first-child" => selector.first_child = true,
+                        "last-child" => selector.last_child = true,
+                        "empty" => selector.empty = true,
+
+                        // Built-in or custom pseudo-class
+                        name => selector.pseudo_classes.set(name, true),
+                    },
+
+                    Token::Function(name) => match name.as_ref() {
+                        "nth-child" => {
+                            selector.nth_child =
+                                Some(input.parse_nested_block(|input| {
+                                    cssparser::parse_nth(input).map_err(|err| err.into())
+                                })?);
+                        }
+
+                        // Only a simple selector can be negated, so :not(.a .b) and :not(.a, .b) are errors
+                        "not" => {
+                            let mut not = input.parse_nested_block(|input| parse_selectors(input))?;
 
-                match pseudo_class_str.as_ref() {
-                    "hover" => selector.pseudo_classes.set_hover(true),
-                    "active" => selector.pseudo_classes.set_active(true),
-                    "focus" => selector.pseudo_classes.set_focus(true),
-                    "enabled" => selector.pseudo_classes.set_enabled(true),
-                    "disabled" => selector.pseudo_classes.set_disabled(true),
-                    "checked" => selector.pseudo_classes.set_checked(true),
+                            if not.len() != 1 || !not[0].is_simple() {
+                                return Err(locate(
+                                    CustomParseError::InvalidValue(String::from(":not")).into(),
+                                    location,
+                                ));
+                            }
 
-                    _ => {}
+                            selector.not.append(&mut not);
+                        }
+
+                        _ => {
+                            return Err(CustomParseError::InvalidStringName(name.to_string()).into());
+                        }
+                    },
+
+                    t => {
+                        let basic_error = BasicParseError {
+                            kind: BasicParseErrorKind::UnexpectedToken(t),
+                            location,
+                        };
+                        return Err(basic_error.into());
+                    }
                 }
 
                 // let pseudo_class = match pseudo_class_str.as_ref() {
@@ -266,6 +697,11 @@ fn parse_selectors<'i, 't>(
 
     selectors.push(selector);
 
+    // A combinator must have a selector on both sides, e.g. `button >` is invalid
+    if selectors.iter().any(|selector| selector.is_empty()) {
+        return Err(CustomParseError::InvalidValue(String::from("selector")).into());
+    }
+
     // for selec in selectors.iter() {
     //     println!("{:?}", selec);
     // }
@@ -310,92 +746,159 @@ impl<'i> cssparser::DeclarationParser<'i> for DeclarationParser {
         name: CowRcStr<'i>,
         input: &mut Parser<'i, 't>,
     ) -> Result<Self::Declaration, ParseError<'i, Self::Error>> {
-        Ok(match &*name {
-            // Colors
-            "background-color" => Property::BackgroundColor(parse_color(input)?),
-            "color" => Property::FontColor(parse_color(input)?),
-            "background-image" => Property::BackgroundImage(parse_string(input)?),
-
-            // Positioning
-            "position" => Property::Position(parse_position(input)?),
-
-            "left" => Property::Left(parse_length(input)?),
-            "right" => Property::Right(parse_length(input)?),
-            "top" => Property::Top(parse_length(input)?),
-            "bottom" => Property::Bottom(parse_length(input)?),
-
-            // Size
-            "width" => Property::Width(parse_length(input)?),
-            "height" => Property::Height(parse_length(input)?),
-
-            // Size Constraints
-            //TODO - Are percentages supported?
-            "min-width" => Property::MinWidth(parse_length(input)?),
-            "min-height" => Property::MinHeight(parse_length(input)?),
-            "max-width" => Property::MaxWidth(parse_length(input)?),
-            "max-height" => Property::MaxHeight(parse_length(input)?),
-
-            // Margin
-            "margin" => Property::Margin(parse_length(input)?),
-            "margin-left" => Property::MarginLeft(parse_length(input)?),
-            "margin-right" => Property::MarginRight(parse_length(input)?),
-            "margin-top" => Property::MarginTop(parse_length(input)?),
-            "margin-bottom" => Property::MarginBottom(parse_length(input)?),
-
-            // Padding
-            "padding" => Property::Padding(parse_length(input)?),
-            "padding-left" => Property::PaddingLeft(parse_length(input)?),
-            "padding-right" => Property::PaddingRight(parse_length(input)?),
-            "padding-top" => Property::PaddingTop(parse_length(input)?),
-            "padding-bottom" => Property::PaddingBottom(parse_length(input)?),
-
-            "text-align" => Property::TextAlign(parse_alignment(input)?),
-            "text-justify" => Property::TextJustify(parse_justification(input)?),
-
-            "font-size" => Property::FontSize(parse_font_size(input)?),
-
-            // Border
-            "border-width" => Property::BorderWidth(parse_length_or_percentage(input)?),
-            "border-color" => Property::BorderColor(parse_color(input)?),
-            // TODO - Support array for specifying each corner
-            "border-radius" => Property::BorderRadius(parse_length(input)?),
-
-            "opacity" => Property::Opacity(parse_length_or_percentage(input)?),
-
-            // Flex Container
-            "flex-direction" => Property::FlexDirection(parse_flex_direction(input)?),
-            "justify-content" => Property::JustifyContent(parse_justify_content(input)?),
-            "align-content" => Property::AlignContent(parse_align_content(input)?),
-            "align-items" => Property::AlignItems(parse_align_items(input)?),
-            "align-self" => Property::AlignSelf(parse_align_self(input)?),
-
-            // Flex Item
-            "flex-basis" => Property::FlexBasis(parse_length_or_percentage(input)?),
-            "flex-grow" => Property::FlexGrow(parse_length_or_percentage(input)?),
-            "flex-shrink" => Property::FlexShrink(parse_length_or_percentage(input)?),
-
-            "display" => Property::Display(parse_display(input)?),
-            "visibility" => Property::Visibility(parse_visibility(input)?),
-
-            "overflow" => Property::Overflow(parse_overflow(input)?),
-
-            "transition" => {
-                //let mut transition = Transition::new();
-                //Property::Transition(parse_transition(input, transition)?)
-                //let test = ;
-                //println!("Transitions: {:?}", test);
-                Property::Transition(input.parse_comma_separated(|F| parse_transition2(F))?)
-            }
+        // Custom properties keep their value unparsed until it is substituted into var()
+        if name.starts_with("--") {
+            return Ok(Property::Custom(name.to_string(), parse_raw_value(input)));
+        }
 
-            _ => {
-                let basic_error = BasicParseError {
-                    kind: BasicParseErrorKind::UnexpectedToken(input.next()?.to_owned()),
-                    location: SourceLocation { line: 0, column: 0 },
-                };
-                return Err(basic_error.into());
+        let location = input.current_source_location();
+
+        // Values using var() or currentColor can only be parsed once the entity they apply to is known
+        let start = input.state();
+        let value = parse_raw_value(input);
+        if value.contains("var(") || value.to_ascii_lowercase().contains("currentcolor") {
+            if !is_known_property(&name) {
+                return Err(locate(
+                    CustomParseError::UnknownProperty(name.to_string()).into(),
+                    location,
+                ));
             }
-        })
+
+            return Ok(Property::Unresolved(Declaration {
+                name: name.to_string(),
+                value,
+                line: location.line + 1,
+                column: location.column.max(1),
+            }));
+        }
+        input.reset(&start);
+
+        parse_property(&name, input).map_err(|error| locate(error, location))
+    }
+}
+
+// Parses the value of a property
+fn parse_property<'i, 't>(
+    name: &str,
+    input: &mut Parser<'i, 't>,
+) -> Result<Property, ParseError<'i, CustomParseError>> {
+    if let Ok(keyword) = input.try_parse(parse_css_wide_keyword) {
+        return css_wide_keyword(name, &keyword)
+            .ok_or_else(|| CustomParseError::InvalidValue(keyword).into());
     }
+
+    Ok(match name {
+        // Colors
+        "background-color" => Property::BackgroundColor(parse_color(input)?),
+        "color" => Property::FontColor(parse_color(input)?),
+        "background-image" => Property::BackgroundImage(parse_string(input)?),
+
+        // Positioning
+        "position" => Property::Position(parse_position(input)?),
+
+        "left" => Property::Left(parse_length(input)?),
+        "right" => Property::Right(parse_length(input)?),
+        "top" => Property::Top(parse_length(input)?),
+        "bottom" => Property::Bottom(parse_length(input)?),
+
+        // Size
+        "width" => Property::Width(parse_length(input)?),
+        "height" => Property::Height(parse_length(input)?),
+
+        // Size Constraints
+        //TODO - Are percentages supported?
+        "min-width" => Property::MinWidth(parse_length(input)?),
+        "min-height" => Property::MinHeight(parse_length(input)?),
+        "max-width" => Property::MaxWidth(parse_length(input)?),
+        "max-height" => Property::MaxHeight(parse_length(input)?),
+
+        // Margin
+        "margin" => Property::Margin(parse_length(input)?),
+        "margin-left" => Property::MarginLeft(parse_length(input)?),
+        "margin-right" => Property::MarginRight(parse_length(input)?),
+        "margin-top" => Property::MarginTop(parse_length(input)?),
+        "margin-bottom" => Property::MarginBottom(parse_length(input)?),
+
+        // Padding
+        "padding" => Property::Padding(parse_length(input)?),
+        "padding-left" => Property::PaddingLeft(parse_length(input)?),
+        "padding-right" => Property::PaddingRight(parse_length(input)?),
+        "padding-top" => Property::PaddingTop(parse_length(input)?),
+        "padding-bottom" => Property::PaddingBottom(parse_length(input)?),
+
+        "text-align" => Property::TextAlign(parse_alignment(input)?),
+        "text-justify" => Property::TextJustify(parse_justification(input)?),
+        "text-overflow" => Property::TextOverflow(parse_text_overflow(input)?),
+        "white-space" => Property::WhiteSpace(parse_white_space(input)?),
+        "line-height" => Property::LineHeight(parse_line_height(input)?),
+        "letter-spacing" => Property::LetterSpacing(parse_letter_spacing(input)?),
+
+        "font-size" => Property::FontSize(parse_font_size(input)?),
+        "font-family" => Property::FontFamily(parse_font_family(input)?),
+        "font-weight" => Property::FontWeight(parse_font_weight(input)?),
+        "font-style" => Property::FontStyle(parse_font_style(input)?),
+
+        // Border
+        "border-width" => Property::BorderWidth(parse_length_or_percentage(input)?),
+        "border-color" => Property::BorderColor(parse_color(input)?),
+        // TODO - Support array for specifying each corner
+        "border-radius" => Property::BorderRadius(parse_length(input)?),
+
+        "opacity" => Property::Opacity(parse_length_or_percentage(input)?),
+
+        // Flex Container
+        "flex-direction" => Property::FlexDirection(parse_flex_direction(input)?),
+        "flex-wrap" => Property::FlexWrap(parse_flex_wrap(input)?),
+        "justify-content" => Property::JustifyContent(parse_justify_content(input)?),
+        "align-content" => Property::AlignContent(parse_align_content(input)?),
+        "align-items" => Property::AlignItems(parse_align_items(input)?),
+        "align-self" => Property::AlignSelf(parse_align_self(input)?),
+
+        // Flex Item
+        "flex-basis" => Property::FlexBasis(parse_length_or_percentage(input)?),
+        "flex-grow" => Property::FlexGrow(parse_length_or_percentage(input)?),
+        "flex-shrink" => Property::FlexShrink(parse_length_or_percentage(input)?),
+
+        // Grid Container
+        "grid-template-columns" => Property::GridTemplateColumns(parse_track_list(input)?),
+        "grid-template-rows" => Property::GridTemplateRows(parse_track_list(input)?),
+        "column-gap" | "grid-column-gap" => Property::GridColumnGap(parse_length(input)?),
+        "row-gap" | "grid-row-gap" => Property::GridRowGap(parse_length(input)?),
+        "gap" | "grid-gap" => {
+            let row_gap = parse_length(input)?;
+            let column_gap = input.try_parse(parse_length).unwrap_or(row_gap);
+            Property::GridGap(row_gap, column_gap)
+        }
+
+        // Grid Item
+        "grid-column" => Property::GridColumn(parse_grid_placement(input)?),
+        "grid-row" => Property::GridRow(parse_grid_placement(input)?),
+
+        "display" => Property::Display(parse_display(input)?),
+        "visibility" => Property::Visibility(parse_visibility(input)?),
+
+        "overflow" => Property::Overflow(parse_overflow(input)?),
+        "z-index" => Property::ZIndex(parse_z_index(input)?),
+
+        "transform" => Property::Transform(parse_transform(input)?),
+        "transform-origin" => Property::TransformOrigin(parse_transform_origin(input)?),
+
+        "transition" => {
+            //let mut transition = Transition::new();
+            //Property::Transition(parse_transition(input, transition)?)
+            //let test = ;
+            //println!("Transitions: {:?}", test);
+            Property::Transition(input.parse_comma_separated(|F| parse_transition2(F))?)
+        }
+
+        "animation" => {
+            Property::Animation(input.parse_comma_separated(|input| parse_animation(input))?)
+        }
+
+        _ => {
+            return Err(CustomParseError::UnknownProperty(name.to_string()).into());
+        }
+    })
 }
 
 impl<'i> cssparser::AtRuleParser<'i> for DeclarationParser {
@@ -405,28 +908,47 @@ impl<'i> cssparser::AtRuleParser<'i> for DeclarationParser {
     type Error = CustomParseError;
 }
 
-fn css_color(name: &str) -> Option<Color> {
-    Some(match name {
-        "transparent" => Color::from(name),
-
-        "black" => Color::from("#000000"),
-        "silver" => Color::from("#C0C0C0"),
-        "gray" | "grey" => Color::from("#808080"),
-        "white" => Color::from("#FFFFFF"),
-        "maroon" => Color::from("#800000"),
-        "red" => Color::from("#FF0000"),
-        "purple" => Color::from("#800080"),
-        "fuchsia" => Color::from("#FF00FF"),
-        "green" => Color::from("#008000"),
-        "lime" => Color::from("#00FF00"),
-        "olive" => Color::from("#808000"),
-        "yellow" => Color::from("#FFFF00"),
-        "navy" => Color::from("#000080"),
-        "blue" => Color::from("#0000FF"),
-        "teal" => Color::from("#008080"),
-        "aqua" => Color::from("#00FFFF"),
-        _ => return None,
-    })
+// Returns true if the property is one which parse_property knows about.
+// An empty value fails to parse for every property, but only unknown properties fail with UnknownProperty.
+fn is_known_property(name: &str) -> bool {
+    let mut input = ParserInput::new("");
+    let mut parser = Parser::new(&mut input);
+
+    !matches!(
+        parse_property(name, &mut parser),
+        Err(ParseError {
+            kind: ParseErrorKind::Custom(CustomParseError::UnknownProperty(_)),
+            ..
+        })
+    )
+}
+
+// Parses a single declaration, such as one with its variables substituted
+pub(crate) fn parse_declaration(name: &str, value: &str) -> Option<Property> {
+    let mut input = ParserInput::new(value);
+    let mut parser = Parser::new(&mut input);
+
+    let property = cssparser::DeclarationParser::parse_value(
+        &mut DeclarationParser {},
+        CowRcStr::from(name),
+        &mut parser,
+    )
+    .ok()?;
+
+    // The whole value must be used
+    if parser.is_exhausted() {
+        Some(property)
+    } else {
+        None
+    }
+}
+
+// Returns the rest of the input as text
+fn parse_raw_value<'i, 't>(input: &mut Parser<'i, 't>) -> String {
+    let start = input.position();
+    while input.next().is_ok() {}
+
+    input.slice_from(start).trim().to_string()
 }
 
 fn css_string(name: &str) -> Option<String> {
@@ -459,14 +981,19 @@ fn parse_basic_color<'i, 't>(
     input: &mut Parser<'i, 't>,
 ) -> Result<Color, ParseError<'i, CustomParseError>> {
     Ok(match input.next()? {
-        Token::Ident(s) => match css_color(&s) {
+        Token::Ident(s) => match Color::from_name(&s) {
             Some(color) => color,
             None => {
                 return Err(CustomParseError::InvalidColorName(s.to_owned().to_string()).into());
             }
         },
 
-        Token::IDHash(hash) | Token::Hash(hash) => Color::from(hash.to_owned().to_string()),
+        Token::IDHash(hash) | Token::Hash(hash) => match Color::from_hex(&hash) {
+            Some(color) => color,
+            None => {
+                return Err(CustomParseError::InvalidColorHex(hash.to_owned().to_string()).into());
+            }
+        },
 
         t => {
             let basic_error = BasicParseErrorKind::UnexpectedToken(t.to_owned());
@@ -591,33 +1118,378 @@ fn parse_transition2<'i, 't>(
     })
 }
 
-fn parse_length<'i, 't>(
+// Parses an animation in the form: name duration delay iteration-count
+// where duration, delay and iteration-count are optional
+fn parse_animation<'i, 't>(
     input: &mut Parser<'i, 't>,
-) -> Result<Length, ParseError<'i, CustomParseError>> {
-    Ok(match input.next()? {
-        Token::Number { value: x, .. } => Length::Pixels(*x as f32),
-        Token::Percentage { unit_value: x, .. } => Length::Percentage(*x as f32),
+) -> Result<Animation, ParseError<'i, CustomParseError>> {
+    let location = input.current_source_location();
+
+    let mut animation = match input.next()? {
+        Token::Ident(name) => Animation::new(name),
 
-        Token::Dimension { value: x, .. } => Length::Pixels(*x as f32),
         t => {
             let basic_error = BasicParseError {
                 kind: BasicParseErrorKind::UnexpectedToken(t.to_owned()),
-                location: SourceLocation { line: 0, column: 0 },
+                location,
             };
             return Err(basic_error.into());
         }
-    })
+    };
+
+    if let Ok(duration) = input.try_parse(|input| parse_time(input)) {
+        animation.duration = duration;
+
+        if let Ok(delay) = input.try_parse(|input| parse_time(input)) {
+            animation.delay = delay;
+        }
+    }
+
+    if let Ok(iteration_count) = input.try_parse(|input| parse_iteration_count(input)) {
+        animation.iteration_count = iteration_count;
+    }
+
+    Ok(animation)
 }
 
-fn parse_position<'i, 't>(
+// Parses a time in seconds. Plain numbers are treated as seconds.
+fn parse_time<'i, 't>(
     input: &mut Parser<'i, 't>,
-) -> Result<Position, ParseError<'i, CustomParseError>> {
+) -> Result<f32, ParseError<'i, CustomParseError>> {
     let location = input.current_source_location();
 
     Ok(match input.next()? {
-        Token::Ident(name) => match name.as_ref() {
-            "absolute" => Position::Absolute,
-            "relative" => Position::Relative,
+        Token::Number { value: x, .. } => *x,
+
+        Token::Dimension {
+            value: x, ref unit, ..
+        } => match unit.as_ref() {
+            "s" => *x,
+            "ms" => *x / 1000.0,
+
+            _ => {
+                return Err(
+                    CustomParseError::InvalidLengthUnits(unit.to_owned().to_string()).into(),
+                );
+            }
+        },
+
+        t => {
+            let basic_error = BasicParseError {
+                kind: BasicParseErrorKind::UnexpectedToken(t.to_owned()),
+                location,
+            };
+            return Err(basic_error.into());
+        }
+    })
+}
+
+fn parse_iteration_count<'i, 't>(
+    input: &mut Parser<'i, 't>,
+) -> Result<f32, ParseError<'i, CustomParseError>> {
+    let location = input.current_source_location();
+
+    Ok(match input.next()? {
+        Token::Ident(name) => match name.as_ref() {
+            "infinite" => std::f32::INFINITY,
+
+            _ => {
+                return Err(
+                    CustomParseError::InvalidStringName(name.to_owned().to_string()).into(),
+                );
+            }
+        },
+
+        Token::Number { value: x, .. } => *x,
+
+        t => {
+            let basic_error = BasicParseError {
+                kind: BasicParseErrorKind::UnexpectedToken(t.to_owned()),
+                location,
+            };
+            return Err(basic_error.into());
+        }
+    })
+}
+
+fn parse_length<'i, 't>(
+    input: &mut Parser<'i, 't>,
+) -> Result<Length, ParseError<'i, CustomParseError>> {
+    Ok(match input.next()? {
+        Token::Number { value: x, .. } => Length::Pixels(*x as f32),
+        Token::Percentage { unit_value: x, .. } => Length::Percentage(*x as f32),
+
+        Token::Dimension { value: x, .. } => Length::Pixels(*x as f32),
+
+        Token::Function(ref name) if name.eq_ignore_ascii_case("calc") => {
+            let (pixels, percentage) = input.parse_nested_block(|input| parse_calc_sum(input))?;
+
+            if percentage == 0.0 {
+                Length::Pixels(pixels)
+            } else if pixels == 0.0 {
+                Length::Percentage(percentage)
+            } else {
+                Length::Calc(pixels, percentage)
+            }
+        }
+
+        t => {
+            let basic_error = BasicParseError {
+                kind: BasicParseErrorKind::UnexpectedToken(t.to_owned()),
+                location: SourceLocation { line: 0, column: 0 },
+            };
+            return Err(basic_error.into());
+        }
+    })
+}
+
+// Parses the sum inside calc() into (pixels, percentage)
+fn parse_calc_sum<'i, 't>(
+    input: &mut Parser<'i, 't>,
+) -> Result<(f32, f32), ParseError<'i, CustomParseError>> {
+    let (mut pixels, mut percentage, _) = parse_calc_product(input)?;
+
+    loop {
+        let sign = input.try_parse(|input| match input.next()?.clone() {
+            Token::Delim('+') => Ok(1.0),
+            Token::Delim('-') => Ok(-1.0),
+            t => Err(input.new_basic_unexpected_token_error(t)),
+        });
+
+        let sign = match sign {
+            Ok(sign) => sign,
+            Err(_) => break,
+        };
+
+        let (term_pixels, term_percentage, _) = parse_calc_product(input)?;
+        pixels += sign * term_pixels;
+        percentage += sign * term_percentage;
+    }
+
+    Ok((pixels, percentage))
+}
+
+// Parses a product of calc() values, one side of each multiplication or division must be a number.
+// Returns (pixels, percentage, is_number) where a number is stored in the pixels.
+fn parse_calc_product<'i, 't>(
+    input: &mut Parser<'i, 't>,
+) -> Result<(f32, f32, bool), ParseError<'i, CustomParseError>> {
+    let mut value = parse_calc_value(input)?;
+
+    loop {
+        let operator = input.try_parse(|input| match input.next()?.clone() {
+            Token::Delim('*') => Ok('*'),
+            Token::Delim('/') => Ok('/'),
+            t => Err(input.new_basic_unexpected_token_error(t)),
+        });
+
+        let operator = match operator {
+            Ok(operator) => operator,
+            Err(_) => break,
+        };
+
+        let rhs = parse_calc_value(input)?;
+
+        value = match (operator, value.2, rhs.2) {
+            ('*', true, _) => (value.0 * rhs.0, value.0 * rhs.1, rhs.2),
+            ('*', false, true) => (value.0 * rhs.0, value.1 * rhs.0, false),
+            ('/', _, true) if rhs.0 != 0.0 => (value.0 / rhs.0, value.1 / rhs.0, value.2),
+            _ => {
+                return Err(CustomParseError::InvalidValue(String::from("calc")).into());
+            }
+        };
+    }
+
+    Ok(value)
+}
+
+fn parse_calc_value<'i, 't>(
+    input: &mut Parser<'i, 't>,
+) -> Result<(f32, f32, bool), ParseError<'i, CustomParseError>> {
+    let location = input.current_source_location();
+
+    Ok(match input.next()?.clone() {
+        Token::Number { value, .. } => (value, 0.0, true),
+        Token::Percentage { unit_value, .. } => (0.0, unit_value, false),
+        Token::Dimension { value, .. } => (value, 0.0, false),
+
+        Token::ParenthesisBlock => {
+            let (pixels, percentage) = input.parse_nested_block(|input| parse_calc_sum(input))?;
+            (pixels, percentage, false)
+        }
+
+        Token::Function(ref name) if name.eq_ignore_ascii_case("calc") => {
+            let (pixels, percentage) = input.parse_nested_block(|input| parse_calc_sum(input))?;
+            (pixels, percentage, false)
+        }
+
+        t => {
+            let basic_error = BasicParseError {
+                kind: BasicParseErrorKind::UnexpectedToken(t),
+                location,
+            };
+            return Err(basic_error.into());
+        }
+    })
+}
+
+// Parses a list of track sizes, expanding any repeat(n, ...) functions
+fn parse_track_list<'i, 't>(
+    input: &mut Parser<'i, 't>,
+) -> Result<Vec<TrackSize>, ParseError<'i, CustomParseError>> {
+    let mut tracks = Vec::new();
+
+    while !input.is_exhausted() {
+        if input
+            .try_parse(|input| input.expect_function_matching("repeat"))
+            .is_ok()
+        {
+            let (count, repeated) = input.parse_nested_block(|input| {
+                let count = input.expect_integer()?;
+                if count < 1 {
+                    return Err(CustomParseError::InvalidValue(count.to_string()).into());
+                }
+
+                input.expect_comma()?;
+
+                let mut repeated = Vec::new();
+                while !input.is_exhausted() {
+                    repeated.push(parse_track_size(input)?);
+                }
+
+                Ok((count as usize, repeated))
+            })?;
+
+            for _ in 0..count {
+                tracks.extend_from_slice(&repeated);
+            }
+        } else {
+            tracks.push(parse_track_size(input)?);
+        }
+    }
+
+    Ok(tracks)
+}
+
+fn parse_track_size<'i, 't>(
+    input: &mut Parser<'i, 't>,
+) -> Result<TrackSize, ParseError<'i, CustomParseError>> {
+    let location = input.current_source_location();
+
+    Ok(match input.next()? {
+        Token::Number { value: x, .. } => TrackSize::Pixels(*x),
+        Token::Percentage { unit_value: x, .. } => TrackSize::Percentage(*x),
+
+        Token::Dimension {
+            value: x, ref unit, ..
+        } => match unit.as_ref() {
+            "px" => TrackSize::Pixels(*x),
+            "fr" => TrackSize::Fraction(*x),
+
+            _ => {
+                return Err(
+                    CustomParseError::InvalidLengthUnits(unit.to_owned().to_string()).into(),
+                );
+            }
+        },
+
+        Token::Ident(name) => match name.as_ref() {
+            "auto" => TrackSize::Auto,
+
+            _ => {
+                return Err(
+                    CustomParseError::InvalidStringName(name.to_owned().to_string()).into(),
+                );
+            }
+        },
+
+        t => {
+            let basic_error = BasicParseError {
+                kind: BasicParseErrorKind::UnexpectedToken(t.to_owned()),
+                location,
+            };
+            return Err(basic_error.into());
+        }
+    })
+}
+
+// Parses a grid line placement such as `2`, `1 / 3`, `1 / span 2` or `span 2`
+fn parse_grid_placement<'i, 't>(
+    input: &mut Parser<'i, 't>,
+) -> Result<GridPlacement, ParseError<'i, CustomParseError>> {
+    let mut placement = GridPlacement::default();
+
+    let (start_line, start_span) = parse_grid_line(input)?;
+    let (end_line, end_span) = if input.try_parse(|input| input.expect_delim('/')).is_ok() {
+        parse_grid_line(input)?
+    } else {
+        (0, 0)
+    };
+
+    if start_line > 0 {
+        placement.start = start_line;
+        if end_line > start_line {
+            placement.span = end_line - start_line;
+        } else if end_span > 0 {
+            placement.span = end_span;
+        }
+    } else {
+        if start_span > 0 {
+            placement.span = start_span;
+        }
+
+        if end_line > placement.span {
+            placement.start = end_line - placement.span;
+        }
+    }
+
+    Ok(placement)
+}
+
+// Returns a (line, span) pair where 0 means unset
+fn parse_grid_line<'i, 't>(
+    input: &mut Parser<'i, 't>,
+) -> Result<(u32, u32), ParseError<'i, CustomParseError>> {
+    let location = input.current_source_location();
+
+    Ok(match input.next()? {
+        Token::Number {
+            int_value: Some(x), ..
+        } if *x > 0 => (*x as u32, 0),
+
+        Token::Ident(name) => match name.as_ref() {
+            "auto" => (0, 0),
+            "span" => {
+                let span = input.expect_integer()?;
+                (0, span.max(1) as u32)
+            }
+
+            _ => {
+                return Err(
+                    CustomParseError::InvalidStringName(name.to_owned().to_string()).into(),
+                );
+            }
+        },
+
+        t => {
+            let basic_error = BasicParseError {
+                kind: BasicParseErrorKind::UnexpectedToken(t.to_owned()),
+                location,
+            };
+            return Err(basic_error.into());
+        }
+    })
+}
+
+fn parse_position<'i, 't>(
+    input: &mut Parser<'i, 't>,
+) -> Result<Position, ParseError<'i, CustomParseError>> {
+    let location = input.current_source_location();
+
+    Ok(match input.next()? {
+        Token::Ident(name) => match name.as_ref() {
+            "absolute" => Position::Absolute,
+            "relative" => Position::Relative,
 
             t => {
                 return Err(
@@ -701,6 +1573,7 @@ fn parse_display<'i, 't>(
         Token::Ident(name) => match name.as_ref() {
             "none" => Display::None,
             "flex" => Display::Flexbox,
+            "grid" => Display::Grid,
 
             _ => {
                 return Err(
@@ -755,6 +1628,34 @@ fn parse_overflow<'i, 't>(
         Token::Ident(name) => match name.as_ref() {
             "visible" => Overflow::Visible,
             "hidden" => Overflow::Hidden,
+            "scroll" => Overflow::Scroll,
+            "auto" => Overflow::Auto,
+
+            _ => {
+                return Err(
+                    CustomParseError::InvalidStringName(name.to_owned().to_string()).into(),
+                );
+            }
+        },
+
+        t => {
+            let basic_error = BasicParseError {
+                kind: BasicParseErrorKind::UnexpectedToken(t.to_owned()),
+                location,
+            };
+            return Err(basic_error.into());
+        }
+    })
+}
+
+fn parse_z_index<'i, 't>(
+    input: &mut Parser<'i, 't>,
+) -> Result<ZIndex, ParseError<'i, CustomParseError>> {
+    let location = input.current_source_location();
+
+    Ok(match input.next()? {
+        Token::Ident(name) => match name.as_ref() {
+            "auto" => ZIndex::Auto,
 
             _ => {
                 return Err(
@@ -763,6 +1664,10 @@ fn parse_overflow<'i, 't>(
             }
         },
 
+        Token::Number {
+            int_value: Some(x), ..
+        } => ZIndex::Index(*x),
+
         t => {
             let basic_error = BasicParseError {
                 kind: BasicParseErrorKind::UnexpectedToken(t.to_owned()),
@@ -800,6 +1705,34 @@ fn parse_flex_direction<'i, 't>(
     })
 }
 
+fn parse_flex_wrap<'i, 't>(
+    input: &mut Parser<'i, 't>,
+) -> Result<FlexWrap, ParseError<'i, CustomParseError>> {
+    let location = input.current_source_location();
+
+    Ok(match input.next()? {
+        Token::Ident(name) => match name.as_ref() {
+            "nowrap" => FlexWrap::NoWrap,
+            "wrap" => FlexWrap::Wrap,
+            "wrap-reverse" => FlexWrap::WrapReverse,
+
+            _ => {
+                return Err(
+                    CustomParseError::InvalidStringName(name.to_owned().to_string()).into(),
+                );
+            }
+        },
+
+        t => {
+            let basic_error = BasicParseError {
+                kind: BasicParseErrorKind::UnexpectedToken(t.to_owned()),
+                location,
+            };
+            return Err(basic_error.into());
+        }
+    })
+}
+
 fn parse_justify_content<'i, 't>(
     input: &mut Parser<'i, 't>,
 ) -> Result<JustifyContent, ParseError<'i, CustomParseError>> {
@@ -921,47 +1854,583 @@ fn parse_align_self<'i, 't>(
     })
 }
 
-// TODO
-// fn parse_transform<'i,'t>(
-//     input: &mut Parser<'i,'t>
-// ) -> Result<Scale, ParseError<'i, CustomParseError>> {
-
-// }
-
-fn parse_color<'i, 't>(
+// Parses none or a list of transform functions, e.g. translate(10px, 50%) rotate(45deg) scale(2)
+fn parse_transform<'i, 't>(
     input: &mut Parser<'i, 't>,
-) -> Result<Color, ParseError<'i, CustomParseError>> {
-    let location = input.current_source_location();
+) -> Result<Vec<TransformFunction>, ParseError<'i, CustomParseError>> {
+    let mut functions = Vec::new();
 
-    Ok(match input.next()? {
-        Token::Ident(name) => {
-            // if input.try_parse(|input| input.expect_ident_matching("rgb")).is_ok() {
-            //     if input.expect_parenthesis_block().is_ok() {
-            //         input.parse_nested_block(parse: F)
-            //     }
-            // }
+    if input.try_parse(|input| input.expect_ident_matching("none")).is_ok() {
+        return Ok(functions);
+    }
 
-            match css_color(&name) {
-                Some(color) => color,
-                None => {
-                    return Err(
-                        CustomParseError::InvalidColorName(name.to_owned().to_string()).into(),
-                    );
-                }
-            }
+    loop {
+        let name = input.expect_function()?.to_ascii_lowercase();
+        let function = input.parse_nested_block(|input| parse_transform_function(&name, input))?;
+        functions.push(function);
+
+        if input.is_exhausted() {
+            break;
         }
+    }
 
-        Token::IDHash(hash) | Token::Hash(hash) => Color::from(hash.to_owned().to_string()),
+    Ok(functions)
+}
 
-        t => {
-            let basic_error = BasicParseError {
-                kind: BasicParseErrorKind::UnexpectedToken(t.to_owned()),
-                location,
+fn parse_transform_function<'i, 't>(
+    name: &str,
+    input: &mut Parser<'i, 't>,
+) -> Result<TransformFunction, ParseError<'i, CustomParseError>> {
+    let function = match name {
+        "translate" => {
+            let x = parse_length(input)?;
+            let y = match input.try_parse(|input| input.expect_comma()) {
+                Ok(_) => parse_length(input)?,
+                Err(_) => Length::Pixels(0.0),
             };
-            return Err(basic_error.into());
+
+            TransformFunction::Translate(x, y)
         }
-    })
-}
+        "translatex" => TransformFunction::Translate(parse_length(input)?, Length::Pixels(0.0)),
+        "translatey" => TransformFunction::Translate(Length::Pixels(0.0), parse_length(input)?),
+
+        // A single scale applies to both axes
+        "scale" => {
+            let x = parse_length_or_percentage(input)?;
+            let y = match input.try_parse(|input| input.expect_comma()) {
+                Ok(_) => parse_length_or_percentage(input)?,
+                Err(_) => x,
+            };
+
+            TransformFunction::Scale(x, y)
+        }
+        "scalex" => TransformFunction::Scale(parse_length_or_percentage(input)?, 1.0),
+        "scaley" => TransformFunction::Scale(1.0, parse_length_or_percentage(input)?),
+
+        "rotate" => TransformFunction::Rotate(parse_angle(input)?),
+
+        "skew" => {
+            let x = parse_angle(input)?;
+            let y = match input.try_parse(|input| input.expect_comma()) {
+                Ok(_) => parse_angle(input)?,
+                Err(_) => 0.0,
+            };
+
+            TransformFunction::Skew(x, y)
+        }
+        "skewx" => TransformFunction::Skew(parse_angle(input)?, 0.0),
+        "skewy" => TransformFunction::Skew(0.0, parse_angle(input)?),
+
+        // matrix(a, b, c, d, e, f)
+        "matrix" => {
+            let mut matrix = [0.0; 6];
+            for (index, value) in matrix.iter_mut().enumerate() {
+                if index > 0 {
+                    input.expect_comma()?;
+                }
+                *value = input.expect_number()?;
+            }
+
+            TransformFunction::Matrix(matrix)
+        }
+
+        _ => {
+            return Err(CustomParseError::InvalidValue(name.to_string()).into());
+        }
+    };
+
+    input.expect_exhausted()?;
+
+    Ok(function)
+}
+
+// Parses one or two of a length, a percentage or a keyword, e.g. left top or 25% 10px.
+// A single value sets the horizontal position, unless it is top or bottom, and the other is centred.
+fn parse_transform_origin<'i, 't>(
+    input: &mut Parser<'i, 't>,
+) -> Result<TransformOrigin, ParseError<'i, CustomParseError>> {
+    let first = parse_origin_component(input)?;
+    let second = input.try_parse(parse_origin_component).ok();
+
+    let center = Length::Percentage(0.5);
+
+    let (x, y) = match (first, second) {
+        ((x, false), None) => (x, center),
+        ((x, false), Some((y, _))) => (x, y),
+        // top or bottom on its own or before the horizontal position
+        ((y, true), None) => (center, y),
+        ((y, true), Some((x, false))) => (x, y),
+        ((_, true), Some((_, true))) => {
+            return Err(CustomParseError::InvalidValue(String::from("transform-origin")).into());
+        }
+    };
+
+    Ok(TransformOrigin { x, y })
+}
+
+// Returns a position and whether it is a vertical keyword (top or bottom)
+fn parse_origin_component<'i, 't>(
+    input: &mut Parser<'i, 't>,
+) -> Result<(Length, bool), ParseError<'i, CustomParseError>> {
+    if let Ok(keyword) = input.try_parse(|input| input.expect_ident_cloned()) {
+        return Ok(match keyword.to_ascii_lowercase().as_ref() {
+            "left" => (Length::Percentage(0.0), false),
+            "center" => (Length::Percentage(0.5), false),
+            "right" => (Length::Percentage(1.0), false),
+            "top" => (Length::Percentage(0.0), true),
+            "bottom" => (Length::Percentage(1.0), true),
+            _ => {
+                return Err(CustomParseError::InvalidValue(keyword.to_string()).into());
+            }
+        });
+    }
+
+    Ok((parse_length(input)?, false))
+}
+
+fn parse_color<'i, 't>(
+    input: &mut Parser<'i, 't>,
+) -> Result<Color, ParseError<'i, CustomParseError>> {
+    let location = input.current_source_location();
+
+    Ok(match input.next()? {
+        Token::Ident(name) => match Color::from_name(&name) {
+            Some(color) => color,
+            None => {
+                return Err(CustomParseError::InvalidColorName(name.to_owned().to_string()).into());
+            }
+        },
+
+        Token::IDHash(hash) | Token::Hash(hash) => match Color::from_hex(&hash) {
+            Some(color) => color,
+            None => {
+                return Err(CustomParseError::InvalidColorHex(hash.to_owned().to_string()).into());
+            }
+        },
+
+        Token::Function(name) => {
+            let name = name.to_ascii_lowercase();
+            input.parse_nested_block(|input| parse_color_function(&name, input))?
+        }
+
+        t => {
+            let basic_error = BasicParseError {
+                kind: BasicParseErrorKind::UnexpectedToken(t.to_owned()),
+                location,
+            };
+            return Err(basic_error.into());
+        }
+    })
+}
+
+// Parses the arguments of rgb(), rgba(), hsl(), hsla(), lighten(), darken(), mix() and blend()
+fn parse_color_function<'i, 't>(
+    name: &str,
+    input: &mut Parser<'i, 't>,
+) -> Result<Color, ParseError<'i, CustomParseError>> {
+    let color = match name {
+        // rgb(255, 0, 0) or rgb(100% 0% 0% / 50%)
+        "rgb" | "rgba" => {
+            let r = parse_color_channel(input)?;
+            let comma = input.try_parse(|input| input.expect_comma()).is_ok();
+            let g = parse_color_channel(input)?;
+            parse_color_separator(input, comma)?;
+            let b = parse_color_channel(input)?;
+            let a = parse_color_alpha(input, comma)?;
+
+            Color::rgba(r, g, b, a)
+        }
+
+        // hsl(120, 100%, 50%) or hsl(120deg 100% 50% / 0.5)
+        "hsl" | "hsla" => {
+            let h = parse_angle(input)?;
+            let comma = input.try_parse(|input| input.expect_comma()).is_ok();
+            let s = parse_unit_interval(input)?;
+            parse_color_separator(input, comma)?;
+            let l = parse_unit_interval(input)?;
+            let a = parse_color_alpha(input, comma)?;
+
+            Color::hsl(h, s, l).with_alpha(a)
+        }
+
+        // lighten(red, 10%) and darken(red, 10%)
+        "lighten" | "darken" => {
+            let color = parse_color(input)?;
+            input.expect_comma()?;
+            let amount = parse_unit_interval(input)?;
+
+            if name == "lighten" {
+                color.lighten(amount)
+            } else {
+                color.darken(amount)
+            }
+        }
+
+        // mix(red, blue, 25%), where the weight is the proportion of the first color
+        "mix" => {
+            let first = parse_color(input)?;
+            input.expect_comma()?;
+            let second = parse_color(input)?;
+            let weight = if input.try_parse(|input| input.expect_comma()).is_ok() {
+                parse_unit_interval(input)?
+            } else {
+                0.5
+            };
+
+            first.mix(second, weight)
+        }
+
+        // blend(rgba(255, 0, 0, 0.5), white) composites the first color over the second
+        "blend" => {
+            let color = parse_color(input)?;
+            input.expect_comma()?;
+            let background = parse_color(input)?;
+
+            color.blend(background)
+        }
+
+        _ => {
+            return Err(CustomParseError::InvalidColorName(format!("{}()", name)).into());
+        }
+    };
+
+    input.expect_exhausted()?;
+
+    Ok(color)
+}
+
+// A color channel is a number from 0 to 255 or a percentage
+fn parse_color_channel<'i, 't>(
+    input: &mut Parser<'i, 't>,
+) -> Result<u8, ParseError<'i, CustomParseError>> {
+    let location = input.current_source_location();
+
+    Ok(match input.next()? {
+        Token::Number { value, .. } => value.clamp(0.0, 255.0).round() as u8,
+        Token::Percentage { unit_value, .. } => (unit_value.clamp(0.0, 1.0) * 255.0).round() as u8,
+
+        t => {
+            let basic_error = BasicParseError {
+                kind: BasicParseErrorKind::UnexpectedToken(t.to_owned()),
+                location,
+            };
+            return Err(basic_error.into());
+        }
+    })
+}
+
+// A number from 0 to 1 or a percentage
+fn parse_unit_interval<'i, 't>(
+    input: &mut Parser<'i, 't>,
+) -> Result<f32, ParseError<'i, CustomParseError>> {
+    let location = input.current_source_location();
+
+    Ok(match input.next()? {
+        Token::Number { value, .. } => *value,
+        Token::Percentage { unit_value, .. } => *unit_value,
+
+        t => {
+            let basic_error = BasicParseError {
+                kind: BasicParseErrorKind::UnexpectedToken(t.to_owned()),
+                location,
+            };
+            return Err(basic_error.into());
+        }
+    })
+}
+
+// Parses an angle in degrees, which can also be given in rad, grad or turn
+fn parse_angle<'i, 't>(
+    input: &mut Parser<'i, 't>,
+) -> Result<f32, ParseError<'i, CustomParseError>> {
+    let location = input.current_source_location();
+
+    Ok(match input.next()? {
+        Token::Number { value, .. } => *value,
+        Token::Dimension { value, unit, .. } => match unit.to_ascii_lowercase().as_str() {
+            "deg" => *value,
+            "rad" => value.to_degrees(),
+            "grad" => value * 0.9,
+            "turn" => value * 360.0,
+            _ => {
+                return Err(CustomParseError::InvalidValue(unit.to_string()).into());
+            }
+        },
+
+        t => {
+            let basic_error = BasicParseError {
+                kind: BasicParseErrorKind::UnexpectedToken(t.to_owned()),
+                location,
+            };
+            return Err(basic_error.into());
+        }
+    })
+}
+
+// Color components are separated by commas or whitespace, but not a mix of both
+fn parse_color_separator<'i, 't>(
+    input: &mut Parser<'i, 't>,
+    comma: bool,
+) -> Result<(), ParseError<'i, CustomParseError>> {
+    if comma {
+        input.expect_comma()?;
+    }
+
+    Ok(())
+}
+
+// Parses the optional alpha after ", " or " / ", returning 255 if there isn't one
+fn parse_color_alpha<'i, 't>(
+    input: &mut Parser<'i, 't>,
+    comma: bool,
+) -> Result<u8, ParseError<'i, CustomParseError>> {
+    if input.is_exhausted() {
+        return Ok(255);
+    }
+
+    if comma {
+        input.expect_comma()?;
+    } else {
+        input.expect_delim('/')?;
+    }
+
+    let alpha = parse_unit_interval(input)?;
+
+    Ok((alpha.clamp(0.0, 1.0) * 255.0).round() as u8)
+}
+
+// Parses a font family list in order of preference, e.g. "Noto Sans", Roboto Mono, sans-serif
+fn parse_font_family<'i, 't>(
+    input: &mut Parser<'i, 't>,
+) -> Result<Vec<String>, ParseError<'i, CustomParseError>> {
+    input.parse_comma_separated(|input| {
+        if let Ok(name) = input.try_parse(|input| input.expect_string_cloned()) {
+            return Ok(name.to_string());
+        }
+
+        // Unquoted names can be several identifiers
+        let mut words = vec![input.expect_ident_cloned()?.to_string()];
+        while let Ok(word) = input.try_parse(|input| input.expect_ident_cloned()) {
+            words.push(word.to_string());
+        }
+
+        Ok(words.join(" "))
+    })
+}
+
+fn parse_font_weight<'i, 't>(
+    input: &mut Parser<'i, 't>,
+) -> Result<FontWeight, ParseError<'i, CustomParseError>> {
+    let location = input.current_source_location();
+
+    Ok(match input.next()? {
+        Token::Ident(name) => match name.as_ref() {
+            "normal" => FontWeight::NORMAL,
+            "bold" => FontWeight::BOLD,
+
+            _ => {
+                return Err(
+                    CustomParseError::InvalidStringName(name.to_owned().to_string()).into(),
+                );
+            }
+        },
+
+        Token::Number {
+            int_value: Some(weight),
+            ..
+        } if (1..=1000).contains(weight) => FontWeight(*weight as u16),
+
+        t => {
+            let basic_error = BasicParseError {
+                kind: BasicParseErrorKind::UnexpectedToken(t.to_owned()),
+                location,
+            };
+            return Err(basic_error.into());
+        }
+    })
+}
+
+fn parse_font_style<'i, 't>(
+    input: &mut Parser<'i, 't>,
+) -> Result<FontStyle, ParseError<'i, CustomParseError>> {
+    let location = input.current_source_location();
+
+    Ok(match input.next()? {
+        Token::Ident(name) => match name.as_ref() {
+            "normal" => FontStyle::Normal,
+            "italic" => FontStyle::Italic,
+            "oblique" => FontStyle::Oblique,
+
+            _ => {
+                return Err(
+                    CustomParseError::InvalidStringName(name.to_owned().to_string()).into(),
+                );
+            }
+        },
+
+        t => {
+            let basic_error = BasicParseError {
+                kind: BasicParseErrorKind::UnexpectedToken(t.to_owned()),
+                location,
+            };
+            return Err(basic_error.into());
+        }
+    })
+}
+
+fn parse_white_space<'i, 't>(
+    input: &mut Parser<'i, 't>,
+) -> Result<WhiteSpace, ParseError<'i, CustomParseError>> {
+    let location = input.current_source_location();
+
+    Ok(match input.next()? {
+        Token::Ident(name) => match name.as_ref() {
+            "normal" => WhiteSpace::Normal,
+            "nowrap" => WhiteSpace::NoWrap,
+            "pre" => WhiteSpace::Pre,
+            "pre-wrap" => WhiteSpace::PreWrap,
+            "pre-line" => WhiteSpace::PreLine,
+
+            _ => {
+                return Err(
+                    CustomParseError::InvalidStringName(name.to_owned().to_string()).into(),
+                );
+            }
+        },
+
+        t => {
+            let basic_error = BasicParseError {
+                kind: BasicParseErrorKind::UnexpectedToken(t.to_owned()),
+                location,
+            };
+            return Err(basic_error.into());
+        }
+    })
+}
+
+// Parses normal, a multiple of the font size (as a number or percentage) or a length
+fn parse_line_height<'i, 't>(
+    input: &mut Parser<'i, 't>,
+) -> Result<LineHeight, ParseError<'i, CustomParseError>> {
+    let location = input.current_source_location();
+
+    Ok(match input.next()? {
+        Token::Ident(name) => match name.as_ref() {
+            "normal" => LineHeight::Normal,
+
+            _ => {
+                return Err(
+                    CustomParseError::InvalidStringName(name.to_owned().to_string()).into(),
+                );
+            }
+        },
+
+        Token::Number { value: x, .. } if *x >= 0.0 => LineHeight::Number(*x),
+        Token::Percentage { unit_value: x, .. } if *x >= 0.0 => LineHeight::Number(*x),
+
+        Token::Dimension { value: x, .. } if *x >= 0.0 => LineHeight::Pixels(*x),
+
+        t => {
+            let basic_error = BasicParseError {
+                kind: BasicParseErrorKind::UnexpectedToken(t.to_owned()),
+                location,
+            };
+            return Err(basic_error.into());
+        }
+    })
+}
+
+fn parse_letter_spacing<'i, 't>(
+    input: &mut Parser<'i, 't>,
+) -> Result<f32, ParseError<'i, CustomParseError>> {
+    let location = input.current_source_location();
+
+    Ok(match input.next()? {
+        Token::Ident(name) => match name.as_ref() {
+            "normal" => 0.0,
+
+            _ => {
+                return Err(
+                    CustomParseError::InvalidStringName(name.to_owned().to_string()).into(),
+                );
+            }
+        },
+
+        Token::Number { value: x, .. } => *x,
+        Token::Dimension { value: x, .. } => *x,
+
+        t => {
+            let basic_error = BasicParseError {
+                kind: BasicParseErrorKind::UnexpectedToken(t.to_owned()),
+                location,
+            };
+            return Err(basic_error.into());
+        }
+    })
+}
+
+fn parse_text_overflow<'i, 't>(
+    input: &mut Parser<'i, 't>,
+) -> Result<TextOverflow, ParseError<'i, CustomParseError>> {
+    let location = input.current_source_location();
+
+    Ok(match input.next()? {
+        Token::Ident(name) => match name.as_ref() {
+            "clip" => TextOverflow::Clip,
+            "ellipsis" => TextOverflow::Ellipsis,
+
+            _ => {
+                return Err(
+                    CustomParseError::InvalidStringName(name.to_owned().to_string()).into(),
+                );
+            }
+        },
+
+        t => {
+            let basic_error = BasicParseError {
+                kind: BasicParseErrorKind::UnexpectedToken(t.to_owned()),
+                location,
+            };
+            return Err(basic_error.into());
+        }
+    })
+}
+
+// Parses inherit, initial or unset when it is the whole value
+fn parse_css_wide_keyword<'i, 't>(
+    input: &mut Parser<'i, 't>,
+) -> Result<String, ParseError<'i, CustomParseError>> {
+    let keyword = input.expect_ident()?.to_ascii_lowercase();
+    input.expect_exhausted()?;
+
+    match keyword.as_ref() {
+        "inherit" | "initial" | "unset" => Ok(keyword),
+        _ => Err(CustomParseError::InvalidValue(keyword).into()),
+    }
+}
+
+// Returns the property for a CSS-wide keyword, or None if the keyword isn't supported for the property.
+// Inherited properties support every keyword, where unset is the same as inherit.
+fn css_wide_keyword(name: &str, keyword: &str) -> Option<Property> {
+    let initial = match name {
+        "color" => Property::FontColor(Color::rgb(255, 255, 255)),
+        "font-size" => Property::FontSize(14.0),
+        "font-family" => Property::FontFamily(vec![String::from(DEFAULT_FONT_FAMILY)]),
+        "font-weight" => Property::FontWeight(FontWeight::default()),
+        "font-style" => Property::FontStyle(FontStyle::default()),
+        "white-space" => Property::WhiteSpace(WhiteSpace::default()),
+        "line-height" => Property::LineHeight(LineHeight::default()),
+        "letter-spacing" => Property::LetterSpacing(0.0),
+        "text-align" => Property::TextAlign(Align::default()),
+        "text-justify" => Property::TextJustify(Justify::default()),
+        _ => return None,
+    };
+
+    Some(match keyword {
+        "initial" => initial,
+        _ => Property::Inherit(name.to_string()),
+    })
+}
 
 fn parse_font_size<'i, 't>(
     input: &mut Parser<'i, 't>,
@@ -1011,5 +2480,11 @@ pub fn parse(s: &str) -> Vec<StyleRule> {
         rule_list_parser.collect::<Vec<_>>()
     };
 
-    rules.into_iter().filter_map(|rule| rule.ok()).collect()
+    rules
+        .into_iter()
+        .filter_map(|rule| match rule {
+            Ok(Rule::Style(style_rule)) => Some(style_rule),
+            _ => None,
+        })
+        .collect()
 }
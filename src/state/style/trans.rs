@@ -1,5 +1,6 @@
 // Rename to Transform when I've come up with a better name for the current transform mod
 use crate::state::animator::Interpolator;
+use crate::style::Length;
 
 #[derive(Copy, Clone, PartialEq, Debug)]
 pub struct Scale(pub f32);
@@ -15,3 +16,140 @@ impl Interpolator for Scale {
         return Scale(start.0 + (end.0 - start.0) * t);
     }
 }
+
+// A function in the transform property. Angles are in degrees.
+#[derive(Clone, Debug, PartialEq)]
+pub enum TransformFunction {
+    // Percentages are of the size of the entity
+    Translate(Length, Length),
+    Scale(f32, f32),
+    Rotate(f32),
+    Skew(f32, f32),
+    Matrix([f32; 6]),
+}
+
+// The point which the transform of an entity is applied around, relative to its top left corner
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct TransformOrigin {
+    pub x: Length,
+    pub y: Length,
+}
+
+impl Default for TransformOrigin {
+    fn default() -> Self {
+        TransformOrigin {
+            x: Length::Percentage(0.5),
+            y: Length::Percentage(0.5),
+        }
+    }
+}
+
+// An affine transform which maps (x, y) to (a*x + c*y + e, b*x + d*y + f), stored as [a, b, c, d, e, f]
+// in the same order as the femtovg canvas
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Transform2D(pub [f32; 6]);
+
+impl Default for Transform2D {
+    fn default() -> Self {
+        Transform2D::identity()
+    }
+}
+
+impl Transform2D {
+    pub fn identity() -> Self {
+        Transform2D([1.0, 0.0, 0.0, 1.0, 0.0, 0.0])
+    }
+
+    pub fn translation(x: f32, y: f32) -> Self {
+        Transform2D([1.0, 0.0, 0.0, 1.0, x, y])
+    }
+
+    pub fn scaling(x: f32, y: f32) -> Self {
+        Transform2D([x, 0.0, 0.0, y, 0.0, 0.0])
+    }
+
+    // Rotates clockwise on screen by an angle in radians
+    pub fn rotation(angle: f32) -> Self {
+        let (sin, cos) = angle.sin_cos();
+        Transform2D([cos, sin, -sin, cos, 0.0, 0.0])
+    }
+
+    // Skews along the x and y axes by angles in radians
+    pub fn skewing(x: f32, y: f32) -> Self {
+        Transform2D([1.0, y.tan(), x.tan(), 1.0, 0.0, 0.0])
+    }
+
+    pub fn is_identity(&self) -> bool {
+        *self == Transform2D::identity()
+    }
+
+    // Returns the transform which applies other and then self
+    pub fn multiply(&self, other: &Transform2D) -> Transform2D {
+        let [a, b, c, d, e, f] = self.0;
+        let [oa, ob, oc, od, oe, of] = other.0;
+
+        Transform2D([
+            a * oa + c * ob,
+            b * oa + d * ob,
+            a * oc + c * od,
+            b * oc + d * od,
+            a * oe + c * of + e,
+            b * oe + d * of + f,
+        ])
+    }
+
+    // Returns None if the transform collapses the plane, such as with a scale of zero
+    pub fn inverse(&self) -> Option<Transform2D> {
+        let [a, b, c, d, e, f] = self.0;
+
+        let det = a * d - b * c;
+        if det.abs() < std::f32::EPSILON {
+            return None;
+        }
+
+        Some(Transform2D([
+            d / det,
+            -b / det,
+            -c / det,
+            a / det,
+            (c * f - d * e) / det,
+            (b * e - a * f) / det,
+        ]))
+    }
+
+    pub fn transform_point(&self, x: f32, y: f32) -> (f32, f32) {
+        let [a, b, c, d, e, f] = self.0;
+        (a * x + c * y + e, b * x + d * y + f)
+    }
+}
+
+impl TransformFunction {
+    // Returns the matrix of the function for an entity of the given size
+    pub fn to_matrix(&self, width: f32, height: f32) -> Transform2D {
+        match self {
+            TransformFunction::Translate(x, y) => {
+                Transform2D::translation(resolve(*x, width), resolve(*y, height))
+            }
+            TransformFunction::Scale(x, y) => Transform2D::scaling(*x, *y),
+            TransformFunction::Rotate(angle) => Transform2D::rotation(angle.to_radians()),
+            TransformFunction::Skew(x, y) => Transform2D::skewing(x.to_radians(), y.to_radians()),
+            TransformFunction::Matrix(matrix) => Transform2D(*matrix),
+        }
+    }
+}
+
+impl TransformOrigin {
+    // Returns the origin relative to the top left corner of an entity of the given size
+    pub fn resolve(&self, width: f32, height: f32) -> (f32, f32) {
+        (resolve(self.x, width), resolve(self.y, height))
+    }
+}
+
+fn resolve(length: Length, size: f32) -> f32 {
+    match length {
+        Length::Pixels(val) => val,
+        Length::Percentage(val) => val * size,
+        Length::Calc(pixels, val) => pixels + val * size,
+        _ => 0.0,
+    }
+}
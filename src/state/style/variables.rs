@@ -0,0 +1,257 @@
+// CSS custom properties (--name: value), var() substitution and currentColor.
+//
+// Custom properties cascade like other properties and are inherited from the parent.
+// Declarations which use var() are substituted with the variables of each entity when the
+// entity is linked to the rule, and the result is stored as a rule of its own so that entities
+// with the same values share the data.
+
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use crate::entity::Entity;
+
+use super::{Color, Property};
+
+// A declaration which uses var() or currentColor, with its line and column in the stylesheet (starting at 1)
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Declaration {
+    pub name: String,
+    pub value: String,
+    pub line: u32,
+    pub column: u32,
+}
+
+pub struct Variables {
+    // Custom property declarations of each rule (indexed by rule)
+    declarations: Vec<Vec<(String, String)>>,
+    // Declarations which use var() of each rule (indexed by rule)
+    unresolved: Vec<Vec<Declaration>>,
+    // Transitions of rules with declarations which use var(), copied to the resolved rules (indexed by rule)
+    transitions: Vec<Vec<Property>>,
+    // Rules created for substituted declarations, keyed by the rule they came from and the declarations
+    resolved: HashMap<(usize, Vec<Declaration>), usize>,
+    // Custom properties of each entity, including those inherited from the parent (indexed by entity)
+    computed: Vec<Rc<HashMap<String, String>>>,
+}
+
+impl Variables {
+    pub fn new() -> Self {
+        Variables {
+            declarations: Vec::new(),
+            unresolved: Vec::new(),
+            transitions: Vec::new(),
+            resolved: HashMap::new(),
+            computed: Vec::new(),
+        }
+    }
+
+    pub fn insert_declaration(&mut self, rule: usize, name: String, value: String) {
+        if rule >= self.declarations.len() {
+            self.declarations.resize(rule + 1, Vec::new());
+        }
+
+        self.declarations[rule].push((name, value));
+    }
+
+    pub fn insert_unresolved(&mut self, rule: usize, declaration: Declaration) {
+        if rule >= self.unresolved.len() {
+            self.unresolved.resize(rule + 1, Vec::new());
+        }
+
+        self.unresolved[rule].push(declaration);
+    }
+
+    pub fn insert_transition(&mut self, rule: usize, transition: Property) {
+        if rule >= self.transitions.len() {
+            self.transitions.resize(rule + 1, Vec::new());
+        }
+
+        self.transitions[rule].push(transition);
+    }
+
+    // Computes the custom properties of an entity from the rules it matches, highest specificity first
+    pub fn compute(&mut self, entity: Entity, parent: Option<Entity>, rules: &[usize]) {
+        let inherited = parent
+            .and_then(|parent| self.computed.get(parent.index()).cloned())
+            .unwrap_or_default();
+
+        let mut declarations = rules
+            .iter()
+            .rev()
+            .filter_map(|rule| self.declarations.get(*rule))
+            .flatten()
+            .peekable();
+
+        // Entities without declarations share the variables of the parent
+        let computed = if declarations.peek().is_none() {
+            inherited
+        } else {
+            let mut variables = (*inherited).clone();
+            for (name, value) in declarations {
+                if let Some(value) = substitute(value, &variables) {
+                    variables.insert(name.clone(), value);
+                }
+            }
+
+            Rc::new(variables)
+        };
+
+        if entity.index() >= self.computed.len() {
+            self.computed.resize(entity.index() + 1, Default::default());
+        }
+
+        self.computed[entity.index()] = computed;
+    }
+
+    // Returns the declarations of a rule which use var() or currentColor with the variables and color of an
+    // entity substituted. Declarations using a variable which isn't set and has no fallback are left out.
+    pub fn resolve(
+        &self,
+        entity: Entity,
+        rule: usize,
+        current_color: Color,
+    ) -> Option<Vec<Declaration>> {
+        let declarations = self.unresolved.get(rule)?;
+
+        if declarations.is_empty() {
+            return None;
+        }
+
+        let empty = HashMap::new();
+        let variables = self
+            .computed
+            .get(entity.index())
+            .map(|variables| variables.as_ref())
+            .unwrap_or(&empty);
+
+        let resolved: Vec<Declaration> = declarations
+            .iter()
+            .filter_map(|declaration| {
+                let value = substitute(&declaration.value, variables)?;
+                Some(Declaration {
+                    value: substitute_current_color(&value, current_color),
+                    ..declaration.clone()
+                })
+            })
+            .collect();
+
+        if resolved.is_empty() {
+            None
+        } else {
+            Some(resolved)
+        }
+    }
+
+    pub fn get_transitions(&self, rule: usize) -> Vec<Property> {
+        self.transitions.get(rule).cloned().unwrap_or_default()
+    }
+
+    pub fn get_resolved_rule(
+        &self,
+        rule: usize,
+        declarations: &[Declaration],
+    ) -> Option<usize> {
+        self.resolved.get(&(rule, declarations.to_vec())).cloned()
+    }
+
+    pub fn insert_resolved_rule(
+        &mut self,
+        rule: usize,
+        declarations: Vec<Declaration>,
+        resolved_rule: usize,
+    ) {
+        self.resolved.insert((rule, declarations), resolved_rule);
+    }
+
+    // Returns the value of a custom property of an entity
+    pub fn get(&self, entity: Entity, name: &str) -> Option<&String> {
+        self.computed.get(entity.index())?.get(name)
+    }
+
+    pub fn remove(&mut self, entity: Entity) {
+        if let Some(computed) = self.computed.get_mut(entity.index()) {
+            *computed = Default::default();
+        }
+    }
+
+    // Removes the declarations of every rule
+    pub fn clear_rules(&mut self) {
+        self.declarations.clear();
+        self.unresolved.clear();
+        self.transitions.clear();
+        self.resolved.clear();
+    }
+}
+
+// Replaces each var(--name, fallback) in a value, returning None if a variable isn't set and has no fallback
+pub fn substitute(value: &str, variables: &HashMap<String, String>) -> Option<String> {
+    let mut result = String::new();
+    let mut rest = value;
+
+    while let Some(start) = rest.find("var(") {
+        result.push_str(&rest[..start]);
+
+        let arguments = start + 4;
+
+        // Find the closing parenthesis and the comma before the fallback
+        let mut depth = 1;
+        let mut comma = None;
+        let mut end = None;
+        for (index, c) in rest[arguments..].char_indices() {
+            match c {
+                '(' => depth += 1,
+                ')' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        end = Some(arguments + index);
+                        break;
+                    }
+                }
+                ',' if depth == 1 && comma.is_none() => comma = Some(arguments + index),
+                _ => {}
+            }
+        }
+
+        let end = end?;
+
+        let (name, fallback) = match comma {
+            Some(comma) => (&rest[arguments..comma], Some(&rest[comma + 1..end])),
+            None => (&rest[arguments..end], None),
+        };
+
+        match variables.get(name.trim()) {
+            Some(value) => result.push_str(value),
+            None => result.push_str(&substitute(fallback?.trim(), variables)?),
+        }
+
+        rest = &rest[end + 1..];
+    }
+
+    result.push_str(rest);
+
+    Some(result)
+}
+
+// Replaces each currentColor (in any case) in a value with the given color
+pub fn substitute_current_color(value: &str, color: Color) -> String {
+    let lowercase = value.to_ascii_lowercase();
+
+    let mut result = String::new();
+    let mut rest = 0;
+
+    for (start, keyword) in lowercase.match_indices("currentcolor") {
+        result.push_str(&value[rest..start]);
+        result.push_str(&format!(
+            "rgba({}, {}, {}, {})",
+            color.r(),
+            color.g(),
+            color.b(),
+            color.a() as f32 / 255.0
+        ));
+        rest = start + keyword.len();
+    }
+
+    result.push_str(&value[rest..]);
+
+    result
+}
@@ -0,0 +1,298 @@
+// Breaks text into lines which fit a width, following the white-space property, and cuts lines short
+// with an ellipsis for text-overflow. The same lines are used to size entities during layout and to draw them.
+
+use crate::entity::Entity;
+use crate::state::fonts::TextMeasure;
+use crate::style::{TextOverflow, WhiteSpace};
+use crate::State;
+
+const ELLIPSIS: &str = "\u{2026}";
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TextLine {
+    pub text: String,
+    pub width: f32,
+}
+
+// A line as a byte range of the text it was broken from, without the white space at the end
+#[derive(Clone, Debug, PartialEq)]
+pub struct LineRange {
+    pub start: usize,
+    pub end: usize,
+    pub width: f32,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TextLayout {
+    pub lines: Vec<TextLine>,
+    pub line_height: f32,
+}
+
+impl TextLayout {
+    // Returns the width of the widest line
+    pub fn width(&self) -> f32 {
+        self.lines
+            .iter()
+            .map(|line| line.width)
+            .fold(0.0, f32::max)
+    }
+
+    pub fn height(&self) -> f32 {
+        self.lines.len() as f32 * self.line_height
+    }
+}
+
+// Lays out the text of an entity with its text properties to fit a content box of the given size
+pub fn text_layout(
+    state: &mut State,
+    entity: Entity,
+    max_width: f32,
+    max_height: f32,
+) -> Option<TextLayout> {
+    let text = state.style.text.get(entity)?.text.clone();
+
+    let white_space = state.style.white_space.get(entity).cloned().unwrap_or_default();
+    let text_overflow = state.style.text_overflow.get(entity).cloned().unwrap_or_default();
+
+    let (mut measure, line_height) = text_measure(state, entity);
+
+    Some(layout_text(
+        &mut measure,
+        &text,
+        white_space,
+        text_overflow,
+        line_height,
+        max_width,
+        max_height,
+    ))
+}
+
+// Returns a measure for text with the font properties of an entity, along with its resolved line height
+pub fn text_measure(state: &mut State, entity: Entity) -> (TextMeasure<'_>, f32) {
+    let font_family = state.style.font_family.get(entity).cloned().unwrap_or_default();
+    let font_weight = state.style.font_weight.get(entity).cloned().unwrap_or_default();
+    let font_style = state.style.font_style.get(entity).cloned().unwrap_or_default();
+    let font_size = state.style.font_size.get(entity).cloned().unwrap_or(14.0);
+    let letter_spacing = state.style.letter_spacing.get(entity).cloned().unwrap_or_default();
+    let line_height = state.style.line_height.get(entity).cloned().unwrap_or_default();
+
+    let measure = state.fonts.measure(
+        &state.style.font_faces,
+        &font_family,
+        font_weight,
+        font_style,
+        font_size,
+        letter_spacing,
+    );
+
+    let line_height = line_height.resolve(font_size, measure.normal_line_height());
+
+    (measure, line_height)
+}
+
+// Breaks text into lines no wider than max_width where the white space allows it.
+// With an ellipsis, lines which are still too wide are cut short and, if there are more lines than
+// fit in max_height, the last line which fits ends with an ellipsis.
+pub fn layout_text(
+    measure: &mut TextMeasure,
+    text: &str,
+    white_space: WhiteSpace,
+    text_overflow: TextOverflow,
+    line_height: f32,
+    max_width: f32,
+    max_height: f32,
+) -> TextLayout {
+    let mut lines = Vec::new();
+
+    if !text.is_empty() {
+        for paragraph in paragraphs(text, white_space) {
+            if white_space.wraps() {
+                wrap_paragraph(measure, &paragraph, max_width, &mut lines);
+            } else {
+                let width = measure.width(paragraph.trim_end());
+                lines.push(TextLine {
+                    text: paragraph,
+                    width,
+                });
+            }
+        }
+    }
+
+    if text_overflow == TextOverflow::Ellipsis {
+        if max_height.is_finite() && line_height > 0.0 {
+            // At least one line is always shown
+            let max_lines = ((max_height / line_height).floor() as usize).max(1);
+
+            if lines.len() > max_lines {
+                lines.truncate(max_lines);
+
+                if let Some(line) = lines.last_mut() {
+                    *line = ellipsize(measure, &line.text, max_width);
+                }
+            }
+        }
+
+        for line in lines.iter_mut() {
+            if line.width > max_width {
+                *line = ellipsize(measure, &line.text, max_width);
+            }
+        }
+    }
+
+    TextLayout { lines, line_height }
+}
+
+// Splits text into the paragraphs which are broken into lines, collapsing white space where needed
+fn paragraphs(text: &str, white_space: WhiteSpace) -> Vec<String> {
+    match white_space {
+        WhiteSpace::Normal | WhiteSpace::NoWrap => {
+            let collapsed = collapse(text);
+
+            if collapsed.is_empty() {
+                Vec::new()
+            } else {
+                vec![collapsed]
+            }
+        }
+
+        WhiteSpace::PreLine => text.split('\n').map(collapse).collect(),
+
+        WhiteSpace::Pre | WhiteSpace::PreWrap => text
+            .split('\n')
+            .map(|line| line.trim_end_matches('\r').to_string())
+            .collect(),
+    }
+}
+
+// Collapses each sequence of white space to a single space and removes the white space at either end
+fn collapse(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+// Breaks a paragraph after white space into lines which fit max_width
+fn wrap_paragraph(
+    measure: &mut TextMeasure,
+    paragraph: &str,
+    max_width: f32,
+    lines: &mut Vec<TextLine>,
+) {
+    for range in break_paragraph(measure, paragraph, max_width) {
+        lines.push(TextLine {
+            text: paragraph[range.start..range.end].to_string(),
+            width: range.width,
+        });
+    }
+}
+
+// Breaks text which keeps its white space, as with pre-wrap, into lines which fit max_width.
+// Used by editors which need to map between the lines and the text they were taken from.
+pub fn break_lines(measure: &mut TextMeasure, text: &str, max_width: f32) -> Vec<LineRange> {
+    let mut lines = Vec::new();
+    let mut offset = 0;
+
+    for paragraph in text.split('\n') {
+        for range in break_paragraph(measure, paragraph, max_width) {
+            lines.push(LineRange {
+                start: offset + range.start,
+                end: offset + range.end,
+                width: range.width,
+            });
+        }
+
+        offset += paragraph.len() + 1;
+    }
+
+    lines
+}
+
+// Breaks a paragraph after white space into lines which fit max_width.
+// White space at the end of a line hangs past the edge and a word which is too wide gets a line of its own.
+fn break_paragraph(measure: &mut TextMeasure, paragraph: &str, max_width: f32) -> Vec<LineRange> {
+    let mut lines = Vec::new();
+
+    let mut line_start = 0;
+    // The width of the line including the white space at the end
+    let mut line_width = 0.0;
+    // The end and width of the line without the white space at the end
+    let mut content_end = 0;
+    let mut content_width = 0.0;
+
+    for (start, segment) in segments(paragraph) {
+        let word = segment.trim_end();
+        let word_width = measure.width(word);
+
+        if start > line_start && line_width + word_width > max_width {
+            lines.push(LineRange {
+                start: line_start,
+                end: content_end,
+                width: content_width,
+            });
+
+            line_start = start;
+            line_width = 0.0;
+        }
+
+        content_end = start + word.len();
+        content_width = line_width + word_width;
+        line_width = content_width + measure.width(&segment[word.len()..]);
+    }
+
+    lines.push(LineRange {
+        start: line_start,
+        end: content_end,
+        width: content_width,
+    });
+
+    lines
+}
+
+// Splits text into words, each with the white space which follows it
+fn segments(text: &str) -> Vec<(usize, &str)> {
+    let mut segments = Vec::new();
+    let mut start = 0;
+    let mut previous_space = false;
+
+    for (index, c) in text.char_indices() {
+        let space = c.is_whitespace();
+
+        if previous_space && !space {
+            segments.push((start, &text[start..index]));
+            start = index;
+        }
+
+        previous_space = space;
+    }
+
+    if start < text.len() {
+        segments.push((start, &text[start..]));
+    }
+
+    segments
+}
+
+// Cuts a line short so that it fits max_width with an ellipsis at the end
+fn ellipsize(measure: &mut TextMeasure, text: &str, max_width: f32) -> TextLine {
+    let ellipsis_width = measure.width(ELLIPSIS);
+
+    let mut width = 0.0;
+    let mut end = 0;
+    let mut buffer = [0; 4];
+
+    for (index, c) in text.char_indices() {
+        let advance = measure.width(c.encode_utf8(&mut buffer));
+
+        if width + advance + ellipsis_width > max_width {
+            break;
+        }
+
+        width += advance;
+        end = index + c.len_utf8();
+    }
+
+    let text = text[..end].trim_end();
+
+    TextLine {
+        width: measure.width(text) + ellipsis_width,
+        text: format!("{}{}", text, ELLIPSIS),
+    }
+}
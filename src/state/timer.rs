@@ -0,0 +1,75 @@
+// Events which are sent after a delay.
+// The application fires the timers which are due each time around the event loop, and the test driver moves
+// the time forward itself, so timers are started from the time they were last checked rather than the clock.
+
+use std::time::{Duration, Instant};
+
+use crate::Event;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct TimerId(usize);
+
+pub struct Timers {
+    // The time the timers were last checked
+    now: Instant,
+    next_id: usize,
+    pub(crate) pending: Vec<(TimerId, Instant, Event)>,
+}
+
+impl Timers {
+    pub fn new() -> Self {
+        Timers {
+            now: Instant::now(),
+            next_id: 0,
+            pending: Vec::new(),
+        }
+    }
+
+    pub fn now(&self) -> Instant {
+        self.now
+    }
+
+    pub fn is_pending(&self, id: TimerId) -> bool {
+        self.pending.iter().any(|(timer, _, _)| *timer == id)
+    }
+
+    pub(crate) fn start(&mut self, delay: Duration, event: Event) -> TimerId {
+        let id = TimerId(self.next_id);
+        self.next_id += 1;
+
+        self.pending.push((id, self.now + delay, event));
+
+        id
+    }
+
+    pub(crate) fn cancel(&mut self, id: TimerId) {
+        self.pending.retain(|(timer, _, _)| *timer != id);
+    }
+
+    // Returns the events of the timers which are due at the given time, in the order they are due
+    pub(crate) fn take_due(&mut self, now: Instant) -> Vec<Event> {
+        if now > self.now {
+            self.now = now;
+        }
+
+        let mut due = Vec::new();
+        let mut index = 0;
+        while index < self.pending.len() {
+            if self.pending[index].1 <= self.now {
+                due.push(self.pending.remove(index));
+            } else {
+                index += 1;
+            }
+        }
+
+        due.sort_by_key(|(_, deadline, _)| *deadline);
+
+        due.into_iter().map(|(_, _, event)| event).collect()
+    }
+}
+
+impl Default for Timers {
+    fn default() -> Self {
+        Timers::new()
+    }
+}
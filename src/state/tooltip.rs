@@ -0,0 +1,75 @@
+// Tooltips shown when the mouse rests over an entity.
+// Text tooltips share a single entity which is styled with the tooltip element selector, while a custom widget
+// is shown as its own tooltip. Either is opened as an overlay at the cursor.
+
+use std::time::Duration;
+
+use crate::{Entity, EventHandler, TimerId};
+
+// How long the mouse has to rest over an entity before its tooltip is shown
+pub const TOOLTIP_DELAY: Duration = Duration::from_millis(500);
+
+// The content of the tooltip of an entity
+#[derive(Clone, Debug, PartialEq)]
+pub enum Tooltip {
+    Text(String),
+    // An entity, usually built hidden, which is shown as the tooltip
+    Widget(Entity),
+}
+
+impl Default for Tooltip {
+    fn default() -> Self {
+        Tooltip::Text(String::new())
+    }
+}
+
+// Sent to the root by a timer once the mouse has rested over the entity long enough
+#[derive(Debug, Clone, PartialEq)]
+pub enum TooltipEvent {
+    Show(Entity),
+}
+
+pub struct Tooltips {
+    pub delay: Duration,
+    // The distance from the cursor to the top left corner of a tooltip
+    pub offset: (f32, f32),
+
+    // The hovered entity, or its nearest ancestor, which has a tooltip
+    pub(crate) owner: Entity,
+    pub(crate) timer: Option<TimerId>,
+    // The entity which is shown as a tooltip, if any
+    pub(crate) shown: Entity,
+    // The hoverability of the entities of a shown widget tooltip, which is restored when it is hidden
+    pub(crate) hoverability: Vec<(Entity, bool)>,
+    // Shows the text tooltips, created when the first one is shown
+    pub(crate) label: Entity,
+}
+
+impl Tooltips {
+    pub fn new() -> Self {
+        Tooltips {
+            delay: TOOLTIP_DELAY,
+            offset: (0.0, 20.0),
+            owner: Entity::null(),
+            timer: None,
+            shown: Entity::null(),
+            hoverability: Vec::new(),
+            label: Entity::null(),
+        }
+    }
+
+    // Returns the entity which is shown as a tooltip, or a null entity if there isn't one
+    pub fn get_shown(&self) -> Entity {
+        self.shown
+    }
+}
+
+impl Default for Tooltips {
+    fn default() -> Self {
+        Tooltips::new()
+    }
+}
+
+pub(crate) struct TooltipLabel;
+
+impl EventHandler for TooltipLabel {}
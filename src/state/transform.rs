@@ -1,6 +1,6 @@
 use crate::entity::Entity;
 
-use crate::state::style::Visibility;
+use crate::state::style::{Transform2D, Visibility};
 
 #[derive(Clone, Copy, Debug)]
 pub struct Rect {
@@ -10,6 +10,12 @@ pub struct Rect {
     pub h: f32,
 }
 
+impl Rect {
+    pub fn contains(&self, x: f32, y: f32) -> bool {
+        x >= self.x && x < self.x + self.w && y >= self.y && y < self.y + self.h
+    }
+}
+
 #[derive(Clone, Copy, Debug)]
 pub struct Pos {
     pub x: f32,
@@ -43,6 +49,15 @@ impl std::ops::Add for Pos {
     }
 }
 
+// The size of the text of an entity including its padding, measured during layout
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TextSize {
+    pub width: f32,
+    pub height: f32,
+    // The width available in the parent and the width the text was wrapped to when it was last laid out
+    pub wrapped: Option<(f32, f32)>,
+}
+
 pub struct Overflow {
     pub pos: f32,
     pub overflow: f32,
@@ -53,6 +68,7 @@ pub struct Transform {
     pub size: Vec<Pos>,
     pub visibility: Vec<Visibility>,
     pub hoverability: Vec<bool>,
+    // The z-index of each entity, or zero for an entity which doesn't form a stacking context
     pub z_order: Vec<i32>,
     pub clip_widget: Vec<Entity>,
     // Holds the child_width_sum and then the free_width_space
@@ -60,8 +76,25 @@ pub struct Transform {
     pub child_max: Vec<f32>, // Max child width
     pub child_pos: Vec<f32>,
     pub child_grow_sum: Vec<f32>,
+    pub text_size: Vec<TextSize>,
+    // The size of the contents of an entity which clips them, measured from its top left corner
+    pub content_size: Vec<Pos>,
 
     pub opacity: Vec<f32>,
+
+    // The transform of each entity composed with those of its ancestors, which maps its laid out bounds to the window
+    pub matrix: Vec<Transform2D>,
+
+    // Flags for entities which need to be laid out again
+    pub layout_dirty: Vec<bool>,
+    // The entities flagged since the last relayout. Their branches are the ones which need their visibility,
+    // clipping, scrolling and transforms updated.
+    dirty_branches: Vec<Entity>,
+    // Set when the hierarchy, a z-index or the open overlays change, so that the draw order is rebuilt
+    pub draw_order_dirty: bool,
+
+    // The entities in the order they are drawn, back to front, which is the reverse of the order they are hit-tested
+    pub draw_order: Vec<Entity>,
 }
 
 impl Transform {
@@ -75,9 +108,16 @@ impl Transform {
             child_max: Vec::new(),
             child_pos: Vec::new(),
             child_grow_sum: Vec::new(),
+            text_size: Vec::new(),
+            content_size: Vec::new(),
             opacity: Vec::new(),
+            matrix: Vec::new(),
             z_order: Vec::new(),
             clip_widget: Vec::new(),
+            layout_dirty: Vec::new(),
+            dirty_branches: Vec::new(),
+            draw_order_dirty: true,
+            draw_order: Vec::new(),
         }
     }
 
@@ -93,11 +133,18 @@ impl Transform {
             self.child_max.resize(key + 1, 0.0);
             self.child_pos.resize(key + 1, 0.0);
             self.child_grow_sum.resize(key + 1, 0.0);
+            self.text_size.resize(key + 1, Default::default());
+            self.content_size.resize(key + 1, Default::default());
             self.opacity.resize(key + 1, 0.0);
+            self.matrix.resize(key + 1, Default::default());
             self.z_order.resize(key + 1, 0);
             self.clip_widget.resize(key + 1, Entity::new(0, 0));
+            self.layout_dirty.resize(key + 1, false);
         }
 
+        self.set_layout_dirty(entity);
+        self.draw_order_dirty = true;
+
         // Are these needed?
         if let Some(stored) = self.size.get_mut(key) {
             *stored = Default::default();
@@ -112,7 +159,78 @@ impl Transform {
         }
     }
 
-    pub fn remove(&mut self, _entity: Entity) {}
+    // Resets the data for an entity so that it can be reused
+    pub fn remove(&mut self, entity: Entity) {
+        let key = entity.index();
+
+        if key >= self.position.len() {
+            return;
+        }
+
+        self.position[key] = Default::default();
+        self.size[key] = Default::default();
+        self.visibility[key] = Default::default();
+        self.hoverability[key] = true;
+        self.child_sum[key] = 0.0;
+        self.child_max[key] = 0.0;
+        self.child_pos[key] = 0.0;
+        self.child_grow_sum[key] = 0.0;
+        self.text_size[key] = Default::default();
+        self.content_size[key] = Default::default();
+        self.opacity[key] = 0.0;
+        self.matrix[key] = Default::default();
+        self.z_order[key] = 0;
+        self.clip_widget[key] = Entity::new(0, 0);
+        // An entity which reuses the index is flagged again when it's added
+        self.layout_dirty[key] = false;
+        self.draw_order_dirty = true;
+    }
+
+    // Flag an entity to be laid out again on the next relayout
+    pub fn set_layout_dirty(&mut self, entity: Entity) {
+        if let Some(dirty) = self.layout_dirty.get_mut(entity.index()) {
+            if !*dirty {
+                *dirty = true;
+                self.dirty_branches.push(entity);
+            }
+        }
+    }
+
+    // Flag an entity which was moved or resized while laying out its parent, so that its children are laid out
+    // and its branch is updated. It may already be flagged as an ancestor of a changed entity.
+    pub fn set_layout_moved(&mut self, entity: Entity) {
+        if let Some(dirty) = self.layout_dirty.get_mut(entity.index()) {
+            *dirty = true;
+            self.dirty_branches.push(entity);
+        }
+    }
+
+    // Flag every entity to be laid out again on the next relayout
+    pub fn set_all_layout_dirty(&mut self) {
+        for dirty in self.layout_dirty.iter_mut() {
+            *dirty = true;
+        }
+
+        // The branch of the window is every entity
+        self.dirty_branches.push(Entity::new(0, 0));
+    }
+
+    // Takes the entities flagged since the last relayout, which stay flagged until they are laid out
+    pub fn take_dirty_branches(&mut self) -> Vec<Entity> {
+        std::mem::take(&mut self.dirty_branches)
+    }
+
+    pub fn is_layout_dirty(&self, entity: Entity) -> bool {
+        self.layout_dirty.get(entity.index()).cloned().unwrap_or(true)
+    }
+
+    pub fn clear_layout_dirty(&mut self) {
+        for dirty in self.layout_dirty.iter_mut() {
+            *dirty = false;
+        }
+
+        self.dirty_branches.clear();
+    }
 
     // For getters and setters it's safe to use unwrap because every entity must have a position and size.
     // Event if the position and size are 0.0, or the entity is invisible.
@@ -121,6 +239,19 @@ impl Transform {
         self.clip_widget.get(entity.index()).cloned().unwrap()
     }
 
+    // Returns the clip widget of an entity followed by the clip widgets which clip each of them in turn
+    pub fn get_clip_widgets(&self, entity: Entity) -> Vec<Entity> {
+        let mut clip_widgets = Vec::new();
+        let mut clip_widget = self.get_clip_widget(entity);
+
+        while !clip_widgets.contains(&clip_widget) {
+            clip_widgets.push(clip_widget);
+            clip_widget = self.get_clip_widget(clip_widget);
+        }
+
+        clip_widgets
+    }
+
     pub fn get_z_order(&self, entity: Entity) -> i32 {
         self.z_order.get(entity.index()).cloned().unwrap()
     }
@@ -141,6 +272,10 @@ impl Transform {
         self.child_grow_sum.get(entity.index()).cloned().unwrap()
     }
 
+    pub fn get_text_size(&self, entity: Entity) -> TextSize {
+        self.text_size.get(entity.index()).cloned().unwrap()
+    }
+
     pub fn get_posx(&self, entity: Entity) -> f32 {
         self.position.get(entity.index()).cloned().unwrap().x
     }
@@ -157,10 +292,29 @@ impl Transform {
         self.size.get(entity.index()).cloned().unwrap().y
     }
 
+    pub fn get_content_width(&self, entity: Entity) -> f32 {
+        self.content_size.get(entity.index()).cloned().unwrap_or_default().x
+    }
+
+    pub fn get_content_height(&self, entity: Entity) -> f32 {
+        self.content_size.get(entity.index()).cloned().unwrap_or_default().y
+    }
+
     pub fn get_opacity(&self, entity: Entity) -> f32 {
         self.opacity.get(entity.index()).cloned().unwrap()
     }
 
+    pub fn get_matrix(&self, entity: Entity) -> Transform2D {
+        self.matrix.get(entity.index()).cloned().unwrap_or_default()
+    }
+
+    // Maps a point in the window, such as the cursor, to the untransformed coordinates of an entity.
+    // Returns None if the transform of the entity collapses it.
+    pub fn to_local(&self, entity: Entity, x: f32, y: f32) -> Option<(f32, f32)> {
+        let inverse = self.get_matrix(entity).inverse()?;
+        Some(inverse.transform_point(x, y))
+    }
+
     // SETTERS
 
     pub fn set_clip_widget(&mut self, entity: Entity, val: Entity) {
@@ -199,6 +353,12 @@ impl Transform {
         }
     }
 
+    pub fn set_text_size(&mut self, entity: Entity, val: TextSize) {
+        if let Some(text_size) = self.text_size.get_mut(entity.index()) {
+            *text_size = val;
+        }
+    }
+
     pub fn set_posx(&mut self, entity: Entity, val: f32) {
         if let Some(position) = self.position.get_mut(entity.index()) {
             position.x = val;
@@ -243,6 +403,18 @@ impl Transform {
         }
     }
 
+    pub fn set_content_size(&mut self, entity: Entity, width: f32, height: f32) {
+        if let Some(content_size) = self.content_size.get_mut(entity.index()) {
+            *content_size = Pos::with(width, height);
+        }
+    }
+
+    pub fn set_matrix(&mut self, entity: Entity, val: Transform2D) {
+        if let Some(matrix) = self.matrix.get_mut(entity.index()) {
+            *matrix = val;
+        }
+    }
+
     pub fn set_opacity(&mut self, entity: Entity, val: f32) {
         if let Some(opacity) = self.opacity.get_mut(entity.index()) {
             *opacity = val;
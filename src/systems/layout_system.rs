@@ -1,35 +1,266 @@
-use crate::{Entity, State};
+use crate::{scroll_offset, text_layout, Entity, Event, State, TextSize, WindowEvent};
 
 use crate::hierarchy::*;
 use crate::style::*;
 
 use crate::flexbox::AlignItems;
 
+// The most times the dirty entities are laid out in one relayout
+const MAX_LAYOUT_PASSES: usize = 8;
+
+// Orders the entities for drawing, back to front, in stacking contexts like those of CSS. The root and each
+// entity with a z-index form a stacking context, which is drawn after its parent context's entities with a
+// lower z-index and before those with a higher one. Within a context, the contexts with a negative z-index
+// are drawn first, then the other entities in hierarchy order, then the remaining contexts.
+// Open overlays, and the backdrops of modal ones, are drawn after the root context in the order they were opened.
 pub fn apply_z_ordering(state: &mut State, hierarchy: &Hierarchy) {
-    //println!("Z ORDERING");
+    // Nothing which affects the draw order has changed
+    if !state.transform.draw_order_dirty {
+        return;
+    }
+
+    state.transform.draw_order_dirty = false;
+
     for entity in hierarchy.into_iter() {
+        let z_index = state.style.z_order.get(entity).cloned().unwrap_or_default();
+        state.transform.set_z_order(entity, z_index.index().unwrap_or_default());
+    }
+
+    let mut draw_order = Vec::new();
+    stack(state, hierarchy, state.root, &mut draw_order);
+
+    for overlay in state.overlays.iter() {
+        if overlay.backdrop != Entity::null() {
+            stack(state, hierarchy, overlay.backdrop, &mut draw_order);
+        }
+
+        stack(state, hierarchy, overlay.entity, &mut draw_order);
+    }
+
+    state.transform.draw_order = draw_order;
+}
+
+// Adds a stacking context to the draw order
+fn stack(state: &State, hierarchy: &Hierarchy, context: Entity, draw_order: &mut Vec<Entity>) {
+    draw_order.push(context);
+
+    let mut entities = Vec::new();
+    let mut contexts = Vec::new();
+    gather(state, hierarchy, context, &mut entities, &mut contexts);
+
+    // Contexts with the same z-index stay in hierarchy order
+    contexts.sort_by_key(|(z_index, _)| *z_index);
+
+    for (_, child_context) in contexts.iter().filter(|(z_index, _)| *z_index < 0) {
+        stack(state, hierarchy, *child_context, draw_order);
+    }
+
+    draw_order.extend(entities);
+
+    for (_, child_context) in contexts.iter().filter(|(z_index, _)| *z_index >= 0) {
+        stack(state, hierarchy, *child_context, draw_order);
+    }
+}
+
+// Collects the descendants of an entity which belong to its stacking context, and the contexts nested in it
+fn gather(
+    state: &State,
+    hierarchy: &Hierarchy,
+    entity: Entity,
+    entities: &mut Vec<Entity>,
+    contexts: &mut Vec<(i32, Entity)>,
+) {
+    let mut child = hierarchy.get_first_child(entity);
+
+    while let Some(entity) = child {
+        if state.overlays.is_layer(entity) {
+            child = hierarchy.get_next_sibling(entity);
+            continue;
+        }
+
+        match state.style.z_order.get(entity).and_then(|z_index| z_index.index()) {
+            Some(z_index) => contexts.push((z_index, entity)),
+
+            None => {
+                entities.push(entity);
+                gather(state, hierarchy, entity, entities, contexts);
+            }
+        }
 
-        if entity == Entity::new(0,0) {
+        child = hierarchy.get_next_sibling(entity);
+    }
+}
+
+// Composes the transform of each entity in the branches with those of its ancestors and their scroll offsets.
+// Runs after layout because the transform origin and any percentages are relative to the laid out bounds.
+pub fn apply_transform(state: &mut State, hierarchy: &Hierarchy, branches: &[Entity]) {
+    for entity in branch_entities(state, hierarchy, branches) {
+        if entity == Entity::new(0, 0) {
             continue;
         }
 
         let parent = hierarchy.get_parent(entity).unwrap();
 
-        if let Some(z_order) = state.style.z_order.get(entity) {
-            state.transform.set_z_order(entity, *z_order);
-        } else {
-            let parent_z_order = state.transform.get_z_order(parent);
-            state.transform.set_z_order(entity, parent_z_order);
+        // Children move with the scroll offset of their parent, except for open overlays which are placed in the window
+        let parent_matrix = match state.overlays.get(entity) {
+            Some(overlay) => Transform2D::translation(overlay.offset.0, overlay.offset.1),
+
+            None => {
+                let (scrollx, scrolly) = scroll_offset(state, parent);
+                state
+                    .transform
+                    .get_matrix(parent)
+                    .multiply(&Transform2D::translation(-scrollx, -scrolly))
+            }
+        };
+
+        let posx = state.transform.get_posx(entity);
+        let posy = state.transform.get_posy(entity);
+        let width = state.transform.get_width(entity);
+        let height = state.transform.get_height(entity);
+
+        // The rotate and scaley properties are applied before the transform property
+        let mut functions = Vec::new();
+
+        if let Some(rotate) = state.style.rotate.get(entity) {
+            functions.push(TransformFunction::Rotate(*rotate));
+        }
+
+        if let Some(scaley) = state.style.scaley.get(entity) {
+            functions.push(TransformFunction::Scale(1.0, scaley.0));
         }
+
+        if let Some(transform) = state.style.transform.get(entity) {
+            functions.extend(transform.iter().cloned());
+        }
+
+        let mut matrix = parent_matrix;
+
+        if !functions.is_empty() {
+            let origin = state.style.transform_origin.get(entity).cloned().unwrap_or_default();
+            let (originx, originy) = origin.resolve(width, height);
+            let (originx, originy) = (posx + originx, posy + originy);
+
+            matrix = matrix.multiply(&Transform2D::translation(originx, originy));
+
+            for function in functions.iter() {
+                matrix = matrix.multiply(&function.to_matrix(width, height));
+            }
+
+            matrix = matrix.multiply(&Transform2D::translation(-originx, -originy));
+        }
+
+        state.transform.set_matrix(entity, matrix);
     }
 }
 
-pub fn layout_fun(state: &mut State, hierarchy: &Hierarchy) {
+// Takes the entities flagged to be laid out again since the last relayout. Along with their descendants these are
+// the entities whose visibility, clipping, scrolling and transforms need to be updated.
+pub fn take_dirty_branches(state: &mut State) -> Vec<Entity> {
+    // Inline values may have been inserted without a setter flagging the entity
+    for entity in state.style.take_layout_inserts() {
+        state.transform.set_layout_dirty(entity);
+    }
+
+    state.transform.take_dirty_branches()
+}
+
+// Returns the entities in the branches of the given entities, parents before their children.
+// Removed entities are left out and a branch inside another is only visited once.
+pub fn branch_entities(state: &State, hierarchy: &Hierarchy, branches: &[Entity]) -> Vec<Entity> {
+    let mut branches: Vec<Entity> = branches
+        .iter()
+        .cloned()
+        .filter(|entity| {
+            *entity == state.root || (state.is_alive(*entity) && hierarchy.get_parent(*entity).is_some())
+        })
+        .collect();
+
+    // Ancestors go first so that the branches inside of them are skipped
+    branches.sort_by_cached_key(|entity| entity.parent_iter(hierarchy).count());
+
+    let mut visited = vec![false; hierarchy.parent.len()];
+    let mut entities = Vec::new();
+
+    for branch in branches {
+        if visited[branch.index()] {
+            continue;
+        }
+
+        for entity in branch
+            .into_iter(hierarchy)
+            .take_while(|entity| *entity == branch || entity.is_descendant_of(hierarchy, branch))
+        {
+            visited[entity.index()] = true;
+            entities.push(entity);
+        }
+    }
+
+    entities
+}
+
+// Lays out the entities flagged as dirty, along with the children of their ancestors, and returns the entities
+// which were moved or resized as a result
+pub fn layout_fun(state: &mut State, hierarchy: &Hierarchy) -> Vec<Entity> {
+    let mut moved = Vec::new();
+
+    // Wrapped lines and text are measured with the sizes from the previous pass, so the containers sized by them
+    // are laid out again until they settle. The passes are capped in case sizes which depend on each other don't.
+    for _ in 0..MAX_LAYOUT_PASSES {
+        let relayout = layout_pass(state, hierarchy, &mut moved);
+
+        if relayout.is_empty() {
+            break;
+        }
+
+        for entity in relayout {
+            state.transform.set_layout_dirty(entity);
+        }
+    }
+
+    moved
+}
+
+// Lays out the dirty entities once, adding those which were moved or resized to the given list. Returns the
+// containers which need to be laid out again because their lines or text changed.
+fn layout_pass(state: &mut State, hierarchy: &Hierarchy, moved: &mut Vec<Entity>) -> Vec<Entity> {
+    // A change to the size of an entity can change the layout of its parent,
+    // so flag the ancestors of any dirty entities as dirty too
+    for entity in hierarchy.entities.iter().rev() {
+        if state.transform.is_layout_dirty(*entity) {
+            if let Some(parent) = hierarchy.get_parent(*entity) {
+                state.transform.set_layout_dirty(parent);
+            }
+        }
+    }
+
+    // Nothing has changed so the cached layout can be used
+    if !state.transform.is_layout_dirty(Entity::new(0, 0)) {
+        return Vec::new();
+    }
+
+    // Only the children of the ancestors flagged above are laid out again, so they aren't moved branches
+    state.transform.take_dirty_branches();
+
+    // Wrapping containers which need to be laid out again because their lines changed
+    let mut relayout = Vec::new();
+
     // Reset
     for entity in hierarchy.entities.iter() {
+        // Clean entities keep their cached child sums
+        if !state.transform.is_layout_dirty(*entity) {
+            continue;
+        }
+
         state.transform.set_child_sum(*entity, 0.0);
+        state.transform.set_child_max(*entity, 0.0);
         state.transform.set_child_pos(*entity, 0.0);
         state.transform.set_child_grow_sum(*entity, 0.0);
+
+        // Text is wrapped to the available width again
+        let mut text_size = state.transform.get_text_size(*entity);
+        text_size.wrapped = None;
+        state.transform.set_text_size(*entity, text_size);
     }
 
     //////////////////////
@@ -54,6 +285,11 @@ pub fn layout_fun(state: &mut State, hierarchy: &Hierarchy) {
 
         let parent = hierarchy.get_parent(*entity).unwrap();
 
+        // The child sums of a clean parent are cached
+        if !state.transform.is_layout_dirty(parent) {
+            continue;
+        }
+
         let parent_width = state.transform.get_width(parent);
         let parent_height = state.transform.get_height(parent);
 
@@ -66,6 +302,7 @@ pub fn layout_fun(state: &mut State, hierarchy: &Hierarchy) {
         {
             Length::Pixels(val) => val,
             Length::Percentage(val) => parent_width * val,
+            Length::Calc(pixels, val) => pixels + parent_width * val,
             _ => 0.0,
         };
 
@@ -78,6 +315,7 @@ pub fn layout_fun(state: &mut State, hierarchy: &Hierarchy) {
         {
             Length::Pixels(val) => val,
             Length::Percentage(val) => parent_width * val,
+            Length::Calc(pixels, val) => pixels + parent_width * val,
             _ => std::f32::INFINITY,
         };
 
@@ -90,6 +328,7 @@ pub fn layout_fun(state: &mut State, hierarchy: &Hierarchy) {
         {
             Length::Pixels(val) => val,
             Length::Percentage(val) => parent_height * val,
+            Length::Calc(pixels, val) => pixels + parent_height * val,
             _ => 0.0,
         };
 
@@ -102,30 +341,35 @@ pub fn layout_fun(state: &mut State, hierarchy: &Hierarchy) {
         {
             Length::Pixels(val) => val,
             Length::Percentage(val) => parent_height * val,
+            Length::Calc(pixels, val) => pixels + parent_height * val,
             _ => std::f32::INFINITY,
         };
 
         let margin_left = match state.style.margin_left.get(*entity).cloned().unwrap_or_default() {
             Length::Pixels(val) => val,
             Length::Percentage(val) => parent_width * val,
+            Length::Calc(pixels, val) => pixels + parent_width * val,
             _=> 0.0,
         };
 
         let margin_right = match state.style.margin_right.get(*entity).cloned().unwrap_or_default() {
             Length::Pixels(val) => val,
             Length::Percentage(val) => parent_width * val,
+            Length::Calc(pixels, val) => pixels + parent_width * val,
             _=> 0.0,
         };
 
         let margin_top = match state.style.margin_top.get(*entity).cloned().unwrap_or_default() {
             Length::Pixels(val) => val,
             Length::Percentage(val) => parent_height * val,
+            Length::Calc(pixels, val) => pixels + parent_height * val,
             _=> 0.0,
         };
 
         let margin_bottom = match state.style.margin_bottom.get(*entity).cloned().unwrap_or_default() {
             Length::Pixels(val) => val,
             Length::Percentage(val) => parent_height * val,
+            Length::Calc(pixels, val) => pixels + parent_height * val,
             _=> 0.0,
         };
 
@@ -155,6 +399,54 @@ pub fn layout_fun(state: &mut State, hierarchy: &Hierarchy) {
         // Get the desired height from the style
         let height = state.style.height.get(*entity).cloned().unwrap_or_default();
 
+        // The content of a grid is sized by its tracks rather than by its children
+        if display == Display::Grid {
+            let (grid_width, grid_height) = grid_content_size(state, hierarchy, *entity);
+            match flex_direction {
+                FlexDirection::Row => {
+                    state.transform.set_child_sum(*entity, grid_width);
+                    state.transform.set_child_max(*entity, grid_height);
+                }
+
+                FlexDirection::Column => {
+                    state.transform.set_child_sum(*entity, grid_height);
+                    state.transform.set_child_max(*entity, grid_width);
+                }
+            }
+        }
+
+        // A wrapping container needs room for all of its lines. Where the lines break depends on
+        // the size of the container, so the size from the previous layout is used if it isn't set.
+        let flex_wrap = state.style.flex_wrap.get(*entity).cloned().unwrap_or_default();
+        if display != Display::Grid && flex_wrap != FlexWrap::NoWrap {
+            let previous_width = state.transform.get_width(*entity);
+            let previous_height = state.transform.get_height(*entity);
+
+            let content_width = resolve_length(
+                Some(&width),
+                parent_width,
+                if previous_width > 0.0 { previous_width } else { f32::INFINITY },
+            ) - resolve_length(state.style.padding_left.get(*entity), parent_width, 0.0)
+                - resolve_length(state.style.padding_right.get(*entity), parent_width, 0.0);
+            let content_height = resolve_length(
+                Some(&height),
+                parent_height,
+                if previous_height > 0.0 { previous_height } else { f32::INFINITY },
+            ) - resolve_length(state.style.padding_top.get(*entity), parent_height, 0.0)
+                - resolve_length(state.style.padding_bottom.get(*entity), parent_height, 0.0);
+
+            let lines = flex_lines(state, hierarchy, *entity, content_width, content_height);
+
+            // The child sum is along the main axis and the child max is across it
+            let lines_main = lines.iter().map(|line| line.main).fold(0.0, f32::max);
+            let lines_cross = lines.iter().map(|line| line.cross).sum();
+            state.transform.set_child_sum(*entity, lines_main);
+            state.transform.set_child_max(*entity, lines_cross);
+        }
+
+        // Text sizes the entity to fit its lines
+        measure_text(state, *entity, parent, parent_width, parent_height);
+
         let mut new_width;
         let mut new_height;
 
@@ -170,6 +462,10 @@ pub fn layout_fun(state: &mut State, hierarchy: &Hierarchy) {
             }
         }
 
+        let text_size = state.transform.get_text_size(*entity);
+        new_width = new_width.max(text_size.width);
+        new_height = new_height.max(text_size.height);
+
         match parent_flex_direction {
             FlexDirection::Row => {
                 match width {
@@ -312,6 +608,11 @@ pub fn layout_fun(state: &mut State, hierarchy: &Hierarchy) {
     // Walk down the tree //
     ////////////////////////
     for parent in hierarchy.into_iter() {
+        // Skip clean branches
+        if !state.transform.is_layout_dirty(parent) {
+            continue;
+        }
+
         // Parent properties
 
 
@@ -336,6 +637,7 @@ pub fn layout_fun(state: &mut State, hierarchy: &Hierarchy) {
         {
             Length::Pixels(val) => val,
             Length::Percentage(val) => parent_width * val,
+            Length::Calc(pixels, val) => pixels + parent_width * val,
             _ => 0.0,
         };
 
@@ -348,6 +650,7 @@ pub fn layout_fun(state: &mut State, hierarchy: &Hierarchy) {
         {
             Length::Pixels(val) => val,
             Length::Percentage(val) => parent_width * val,
+            Length::Calc(pixels, val) => pixels + parent_width * val,
             _ => 0.0,
         };
 
@@ -360,6 +663,7 @@ pub fn layout_fun(state: &mut State, hierarchy: &Hierarchy) {
         {
             Length::Pixels(val) => val,
             Length::Percentage(val) => parent_height * val,
+            Length::Calc(pixels, val) => pixels + parent_height * val,
             _ => 0.0,
         };
 
@@ -372,6 +676,7 @@ pub fn layout_fun(state: &mut State, hierarchy: &Hierarchy) {
         {
             Length::Pixels(val) => val,
             Length::Percentage(val) => parent_height * val,
+            Length::Calc(pixels, val) => pixels + parent_height * val,
             _ => 0.0,
         };
 
@@ -379,6 +684,26 @@ pub fn layout_fun(state: &mut State, hierarchy: &Hierarchy) {
         let parent_posx = state.transform.get_posx(parent) + parent_padding_left + (parent_border_width / 2.0);
         let parent_posy = state.transform.get_posy(parent) + parent_padding_top + (parent_border_width / 2.0);
 
+        let parent_display = state
+            .style
+            .display
+            .get(parent)
+            .cloned()
+            .unwrap_or_default();
+
+        // Relative children of a grid are placed into cells rather than laid out along the flex direction
+        if parent_display == Display::Grid {
+            layout_grid(
+                state,
+                hierarchy,
+                parent,
+                parent_posx,
+                parent_posy,
+                parent_width - parent_padding_left - parent_padding_right,
+                parent_height - parent_padding_top - parent_padding_bottom,
+            );
+        }
+
         //TEMP
         let mut num_children = 0;
         for _ in parent.child_iter(&hierarchy) {
@@ -392,6 +717,41 @@ pub fn layout_fun(state: &mut State, hierarchy: &Hierarchy) {
             .cloned()
             .unwrap_or_default();
 
+        let parent_flex_wrap = state
+            .style
+            .flex_wrap
+            .get(parent)
+            .cloned()
+            .unwrap_or_default();
+
+        let parent_wraps = parent_display != Display::Grid && parent_flex_wrap != FlexWrap::NoWrap;
+
+        // Relative children of a wrapping container are laid out in lines
+        if parent_wraps {
+            let lines_cross = layout_flex_wrap(
+                state,
+                hierarchy,
+                parent,
+                parent_posx,
+                parent_posy,
+                parent_width - parent_padding_left - parent_padding_right,
+                parent_height - parent_padding_top - parent_padding_bottom,
+            );
+
+            let cross_size = match parent_flex_direction {
+                FlexDirection::Row => state.style.height.get(parent).cloned().unwrap_or_default(),
+                FlexDirection::Column => state.style.width.get(parent).cloned().unwrap_or_default(),
+            };
+
+            // The container was sized for different line breaks so it needs to be resized
+            if parent != Entity::new(0, 0)
+                && cross_size == Length::Auto
+                && (lines_cross - state.transform.get_child_max(parent)).abs() > 0.5
+            {
+                relayout.push(parent);
+            }
+        }
+
         let mut current_pos = 0.0;
         let mut space_per_widget = 0.0;
 
@@ -444,6 +804,19 @@ pub fn layout_fun(state: &mut State, hierarchy: &Hierarchy) {
                 continue;
             }
 
+            let position = state.style.position.get(child).cloned().unwrap_or_default();
+
+            if (parent_display == Display::Grid || parent_wraps) && position == Position::Relative {
+                continue;
+            }
+
+            // Used to check if the layout of the child has changed
+            let previous_bounds = (
+                state.transform.get_posx(child),
+                state.transform.get_posy(child),
+                state.transform.get_width(child),
+                state.transform.get_height(child),
+            );
 
             // Get the desired width and height
             let width = state.style.width.get(child).cloned().unwrap_or_default();
@@ -512,6 +885,7 @@ pub fn layout_fun(state: &mut State, hierarchy: &Hierarchy) {
             {
                 Length::Pixels(val) => val,
                 Length::Percentage(val) => parent_width * val,
+                Length::Calc(pixels, val) => pixels + parent_width * val,
                 _ => 0.0,
             };
 
@@ -524,6 +898,7 @@ pub fn layout_fun(state: &mut State, hierarchy: &Hierarchy) {
             {
                 Length::Pixels(val) => val,
                 Length::Percentage(val) => parent_width * val,
+                Length::Calc(pixels, val) => pixels + parent_width * val,
                 _ => 0.0,
             };
 
@@ -536,6 +911,7 @@ pub fn layout_fun(state: &mut State, hierarchy: &Hierarchy) {
             {
                 Length::Pixels(val) => val,
                 Length::Percentage(val) => parent_height * val,
+                Length::Calc(pixels, val) => pixels + parent_height * val,
                 _ => 0.0,
             };
 
@@ -548,6 +924,7 @@ pub fn layout_fun(state: &mut State, hierarchy: &Hierarchy) {
             {
                 Length::Pixels(val) => val,
                 Length::Percentage(val) => parent_height * val,
+                Length::Calc(pixels, val) => pixels + parent_height * val,
                 _ => 0.0,
             };
 
@@ -560,6 +937,7 @@ pub fn layout_fun(state: &mut State, hierarchy: &Hierarchy) {
             {
                 Length::Pixels(val) => val,
                 Length::Percentage(val) => parent_width * val,
+                Length::Calc(pixels, val) => pixels + parent_width * val,
                 _ => 0.0,
             };
 
@@ -572,6 +950,7 @@ pub fn layout_fun(state: &mut State, hierarchy: &Hierarchy) {
             {
                 Length::Pixels(val) => val,
                 Length::Percentage(val) => parent_width * val,
+                Length::Calc(pixels, val) => pixels + parent_width * val,
                 _ => 0.0,
             };
 
@@ -584,6 +963,7 @@ pub fn layout_fun(state: &mut State, hierarchy: &Hierarchy) {
             {
                 Length::Pixels(val) => val,
                 Length::Percentage(val) => parent_height * val,
+                Length::Calc(pixels, val) => pixels + parent_height * val,
                 _ => 0.0,
             };
 
@@ -596,6 +976,7 @@ pub fn layout_fun(state: &mut State, hierarchy: &Hierarchy) {
             {
                 Length::Pixels(val) => val,
                 Length::Percentage(val) => parent_height * val,
+                Length::Calc(pixels, val) => pixels + parent_height * val,
                 _ => 0.0,
             };
 
@@ -625,8 +1006,6 @@ pub fn layout_fun(state: &mut State, hierarchy: &Hierarchy) {
 
             let flex_fraction = flex_grow / child_grow_sum;
 
-            let position = state.style.position.get(child).cloned().unwrap_or_default();
-
             match flex_direction {
                 FlexDirection::Row => {
                     new_width = state.transform.get_child_sum(child) + child_padding_left + child_padding_right;
@@ -639,6 +1018,10 @@ pub fn layout_fun(state: &mut State, hierarchy: &Hierarchy) {
                 }
             }
 
+            let text_size = state.transform.get_text_size(child);
+            new_width = new_width.max(text_size.width);
+            new_height = new_height.max(text_size.height);
+
             match position {
                 Position::Relative => {
                     match parent_flex_direction {
@@ -653,6 +1036,10 @@ pub fn layout_fun(state: &mut State, hierarchy: &Hierarchy) {
                                 Length::Percentage(val) => {
                                     new_width = parent_width * val;
                                 }
+
+                                Length::Calc(pixels, val) => {
+                                    new_width = pixels + parent_width * val;
+                                }
                                 _ => {}
                             };
 
@@ -706,6 +1093,10 @@ pub fn layout_fun(state: &mut State, hierarchy: &Hierarchy) {
                                     new_height = parent_height * val;
                                 }
 
+                                Length::Calc(pixels, val) => {
+                                    new_height = pixels + parent_height * val;
+                                }
+
                                 _ => {}
                             };
 
@@ -751,6 +1142,10 @@ pub fn layout_fun(state: &mut State, hierarchy: &Hierarchy) {
                                     new_posx = current_pos + (val * (parent_width - parent_padding_left - parent_padding_right));
                                 }
 
+                                Length::Calc(pixels, val) => {
+                                    new_posx = current_pos + (pixels + val * (parent_width - parent_padding_left - parent_padding_right));
+                                }
+
                                 _ => {}
                             }
 
@@ -791,6 +1186,10 @@ pub fn layout_fun(state: &mut State, hierarchy: &Hierarchy) {
                                     new_posy += val * parent_height;
                                 }
 
+                                Length::Calc(pixels, val) => {
+                                    new_posy += pixels + val * parent_height;
+                                }
+
                                 _ => {}
                             }
 
@@ -814,6 +1213,10 @@ pub fn layout_fun(state: &mut State, hierarchy: &Hierarchy) {
                                 Length::Percentage(val) => {
                                     new_height = parent_height * val;
                                 }
+
+                                Length::Calc(pixels, val) => {
+                                    new_height = pixels + parent_height * val;
+                                }
                                 _ => {}
                             };
 
@@ -863,6 +1266,10 @@ pub fn layout_fun(state: &mut State, hierarchy: &Hierarchy) {
                                     new_width = parent_width * val;
                                 }
 
+                                Length::Calc(pixels, val) => {
+                                    new_width = pixels + parent_width * val;
+                                }
+
                                 _ => {}
                             };
 
@@ -898,6 +1305,10 @@ pub fn layout_fun(state: &mut State, hierarchy: &Hierarchy) {
                                     new_posy = current_pos + val * parent_height;
                                 }
 
+                                Length::Calc(pixels, val) => {
+                                    new_posy = current_pos + (pixels + val * parent_height);
+                                }
+
                                 _ => {}
                             }
 
@@ -938,6 +1349,10 @@ pub fn layout_fun(state: &mut State, hierarchy: &Hierarchy) {
                                     new_posx += val * parent_width;
                                 }
 
+                                Length::Calc(pixels, val) => {
+                                    new_posx += pixels + val * parent_width;
+                                }
+
                                 _ => {}
                             }
 
@@ -967,6 +1382,7 @@ pub fn layout_fun(state: &mut State, hierarchy: &Hierarchy) {
                     let r = match right {
                         Length::Pixels(val) => val,
                         Length::Percentage(val) => val * parent_width,
+                        Length::Calc(pixels, val) => pixels + val * parent_width,
                         Length::Initial(val) => val,
                         Length::Auto => 0.0,
                     };
@@ -974,6 +1390,7 @@ pub fn layout_fun(state: &mut State, hierarchy: &Hierarchy) {
                     let l = match left {
                         Length::Pixels(val) => val,
                         Length::Percentage(val) => val * parent_width,
+                        Length::Calc(pixels, val) => pixels + val * parent_width,
                         Length::Initial(val) => val,
                         Length::Auto => 0.0,
                     };
@@ -985,6 +1402,7 @@ pub fn layout_fun(state: &mut State, hierarchy: &Hierarchy) {
                     let b = match bottom {
                         Length::Pixels(val) => val,
                         Length::Percentage(val) => val * parent_height,
+                        Length::Calc(pixels, val) => pixels + val * parent_height,
                         Length::Initial(val) => val,
                         Length::Auto => 0.0,
                     };
@@ -992,6 +1410,7 @@ pub fn layout_fun(state: &mut State, hierarchy: &Hierarchy) {
                     let t = match top {
                         Length::Pixels(val) => val,
                         Length::Percentage(val) => val * parent_height,
+                        Length::Calc(pixels, val) => pixels + val * parent_height,
                         Length::Initial(val) => val,
                         Length::Auto => 0.0,
                     };
@@ -1005,6 +1424,7 @@ pub fn layout_fun(state: &mut State, hierarchy: &Hierarchy) {
                         Length::Pixels(val) => new_width = val,
                         Length::Initial(val) => new_width = val,
                         Length::Percentage(val) => new_width = val * parent_width,
+                        Length::Calc(pixels, val) => new_width = pixels + val * parent_width,
                     }
 
                     match height {
@@ -1012,6 +1432,7 @@ pub fn layout_fun(state: &mut State, hierarchy: &Hierarchy) {
                         Length::Pixels(val) => new_height = val,
                         Length::Initial(val) => new_height = val,
                         Length::Percentage(val) => new_height = val * parent_height,
+                        Length::Calc(pixels, val) => new_height = pixels + val * parent_height,
                     }
 
                     state.transform.set_width(child, new_width);
@@ -1027,6 +1448,11 @@ pub fn layout_fun(state: &mut State, hierarchy: &Hierarchy) {
                                 parent_posx + parent_width - new_width - (val * parent_width);
                         }
 
+                        Length::Calc(pixels, val) => {
+                            new_posx =
+                                parent_posx + parent_width - new_width - (pixels + val * parent_width);
+                        }
+
                         _ => {}
                     }
 
@@ -1039,6 +1465,10 @@ pub fn layout_fun(state: &mut State, hierarchy: &Hierarchy) {
                             new_posx = parent_posx + (val * parent_width);
                         }
 
+                        Length::Calc(pixels, val) => {
+                            new_posx = parent_posx + (pixels + val * parent_width);
+                        }
+
                         _ => {}
                     }
 
@@ -1052,6 +1482,11 @@ pub fn layout_fun(state: &mut State, hierarchy: &Hierarchy) {
                                 parent_posy + parent_height - new_height - (val * parent_height);
                         }
 
+                        Length::Calc(pixels, val) => {
+                            new_posy =
+                                parent_posy + parent_height - new_height - (pixels + val * parent_height);
+                        }
+
                         _ => {}
                     }
 
@@ -1064,6 +1499,10 @@ pub fn layout_fun(state: &mut State, hierarchy: &Hierarchy) {
                             new_posy = parent_posy + (val * parent_height);
                         }
 
+                        Length::Calc(pixels, val) => {
+                            new_posy = parent_posy + (pixels + val * parent_height);
+                        }
+
                         _ => {}
                     }
 
@@ -1072,6 +1511,875 @@ pub fn layout_fun(state: &mut State, hierarchy: &Hierarchy) {
 
                 }
             }
+
+            // If the child has moved or changed size then its children need to be laid out again
+            let bounds = (
+                state.transform.get_posx(child),
+                state.transform.get_posy(child),
+                state.transform.get_width(child),
+                state.transform.get_height(child),
+            );
+
+            if bounds != previous_bounds {
+                state.transform.set_layout_moved(child);
+            }
+        }
+    }
+
+    // Text which was laid out at a different width than it was measured at is wrapped to its new width,
+    // and if that changes its height the parent is laid out again
+    for entity in hierarchy.entities.iter() {
+        let parent = match hierarchy.get_parent(*entity) {
+            Some(parent) => parent,
+            None => continue,
+        };
+
+        // Only the children of dirty entities were laid out
+        if !state.transform.is_layout_dirty(parent) {
+            continue;
+        }
+
+        if state.style.display.get(*entity).cloned().unwrap_or_default() == Display::None {
+            continue;
+        }
+
+        if rewrap_text(state, *entity, parent) {
+            relayout.push(parent);
+        }
+    }
+
+    // Children which were moved or resized were flagged while walking down the tree
+    moved.extend(state.transform.take_dirty_branches());
+
+    state.transform.clear_layout_dirty();
+
+    relayout
+}
+
+// The cell occupied by an item in a grid
+struct GridCell {
+    entity: Entity,
+    row: usize,
+    row_span: usize,
+    column: usize,
+    column_span: usize,
+}
+
+fn is_cell_free(occupied: &[Vec<bool>], row: usize, column: usize, row_span: usize, column_span: usize) -> bool {
+    for r in row..row + row_span {
+        for c in column..column + column_span {
+            if let Some(true) = occupied.get(r).and_then(|cells| cells.get(c)) {
+                return false;
+            }
+        }
+    }
+
+    true
+}
+
+fn occupy_cell(occupied: &mut Vec<Vec<bool>>, row: usize, column: usize, row_span: usize, column_span: usize) {
+    if occupied.len() < row + row_span {
+        occupied.resize(row + row_span, Vec::new());
+    }
+
+    for cells in occupied[row..row + row_span].iter_mut() {
+        if cells.len() < column + column_span {
+            cells.resize(column + column_span, false);
+        }
+
+        for cell in cells[column..column + column_span].iter_mut() {
+            *cell = true;
+        }
+    }
+}
+
+// Places the children of a grid into cells, returning the cells and the number of rows and columns.
+// Items with an auto placement fill the grid row by row, skipping cells which are already taken.
+fn place_grid_items(state: &State, hierarchy: &Hierarchy, parent: Entity) -> (Vec<GridCell>, usize, usize) {
+    let template_columns = state
+        .style
+        .grid_template_columns
+        .get(parent)
+        .map(|tracks| tracks.len())
+        .unwrap_or_default()
+        .max(1);
+
+    let mut cells = Vec::new();
+    let mut occupied: Vec<Vec<bool>> = Vec::new();
+    let mut columns = template_columns;
+    let mut rows = state
+        .style
+        .grid_template_rows
+        .get(parent)
+        .map(|tracks| tracks.len())
+        .unwrap_or_default();
+
+    // Position of the auto placement cursor as (row, column)
+    let mut cursor = (0, 0);
+
+    for child in parent.child_iter(hierarchy) {
+        let display = state.style.display.get(child).cloned().unwrap_or_default();
+        let position = state.style.position.get(child).cloned().unwrap_or_default();
+
+        if display == Display::None || position == Position::Absolute {
+            continue;
+        }
+
+        let grid_column = state.style.grid_column.get(child).cloned().unwrap_or_default();
+        let grid_row = state.style.grid_row.get(child).cloned().unwrap_or_default();
+
+        let column_span = grid_column.span.max(1) as usize;
+        let row_span = grid_row.span.max(1) as usize;
+
+        // The last column an item of this span can start at without overflowing the grid
+        let last_column = template_columns.saturating_sub(column_span);
+
+        let (row, column) = match (grid_row.is_auto(), grid_column.is_auto()) {
+            (false, false) => (grid_row.start as usize - 1, grid_column.start as usize - 1),
+
+            // Fixed column so look for the first free row
+            (true, false) => {
+                let column = grid_column.start as usize - 1;
+                let mut row = cursor.0;
+                while !is_cell_free(&occupied, row, column, row_span, column_span) {
+                    row += 1;
+                }
+
+                (row, column)
+            }
+
+            // Fixed row so look for the first free column
+            (false, true) => {
+                let row = grid_row.start as usize - 1;
+                let mut column = 0;
+                while !is_cell_free(&occupied, row, column, row_span, column_span) {
+                    column += 1;
+                }
+
+                (row, column)
+            }
+
+            (true, true) => {
+                let (mut row, mut column) = cursor;
+                loop {
+                    if column > last_column {
+                        row += 1;
+                        column = 0;
+                    }
+
+                    if is_cell_free(&occupied, row, column, row_span, column_span) {
+                        break;
+                    }
+
+                    column += 1;
+                }
+
+                cursor = (row, column + column_span);
+
+                (row, column)
+            }
+        };
+
+        occupy_cell(&mut occupied, row, column, row_span, column_span);
+
+        columns = columns.max(column + column_span);
+        rows = rows.max(row + row_span);
+
+        cells.push(GridCell {
+            entity: child,
+            row,
+            row_span,
+            column,
+            column_span,
+        });
+    }
+
+    (cells, rows, columns)
+}
+
+// Returns the size of a grid item including its margins, used to size auto tracks
+fn grid_item_size(state: &State, child: Entity) -> (f32, f32) {
+    let flex_direction = state.style.flex_direction.get(child).cloned().unwrap_or_default();
+
+    let (mut width, mut height) = match flex_direction {
+        FlexDirection::Row => (
+            state.transform.get_child_sum(child),
+            state.transform.get_child_max(child),
+        ),
+
+        FlexDirection::Column => (
+            state.transform.get_child_max(child),
+            state.transform.get_child_sum(child),
+        ),
+    };
+
+    width += pixels(state.style.padding_left.get(child)) + pixels(state.style.padding_right.get(child));
+    height += pixels(state.style.padding_top.get(child)) + pixels(state.style.padding_bottom.get(child));
+
+    let text_size = state.transform.get_text_size(child);
+    width = width.max(text_size.width);
+    height = height.max(text_size.height);
+
+    if let Some(Length::Pixels(val)) = state.style.width.get(child) {
+        width = *val;
+    }
+
+    if let Some(Length::Pixels(val)) = state.style.height.get(child) {
+        height = *val;
+    }
+
+    width = width.max(pixels(state.style.min_width.get(child)));
+    height = height.max(pixels(state.style.min_height.get(child)));
+
+    if let Some(Length::Pixels(val)) = state.style.max_width.get(child) {
+        width = width.min(*val);
+    }
+
+    if let Some(Length::Pixels(val)) = state.style.max_height.get(child) {
+        height = height.min(*val);
+    }
+
+    width += pixels(state.style.margin_left.get(child)) + pixels(state.style.margin_right.get(child));
+    height += pixels(state.style.margin_top.get(child)) + pixels(state.style.margin_bottom.get(child));
+
+    (width, height)
+}
+
+// Resolves the size of each track. Auto tracks are sized to their content and fr tracks share
+// the free space. With no fr tracks, any free space is shared between the auto tracks instead.
+fn resolve_tracks(tracks: &[TrackSize], content: &[f32], available: f32, gap: f32) -> Vec<f32> {
+    let mut sizes = vec![0.0; content.len()];
+    let mut used = gap * content.len().saturating_sub(1) as f32;
+    let mut fraction_sum = 0.0;
+    let mut num_auto = 0;
+
+    for (index, size) in sizes.iter_mut().enumerate() {
+        *size = match tracks.get(index).cloned().unwrap_or_default() {
+            TrackSize::Pixels(val) => val,
+            TrackSize::Percentage(val) => available * val,
+            TrackSize::Fraction(val) => {
+                fraction_sum += val;
+                0.0
+            }
+            TrackSize::Auto => {
+                num_auto += 1;
+                content[index]
+            }
+        };
+
+        used += *size;
+    }
+
+    let free_space = (available - used).max(0.0);
+
+    for (index, size) in sizes.iter_mut().enumerate() {
+        match tracks.get(index).cloned().unwrap_or_default() {
+            // An fr track never shrinks below its content
+            TrackSize::Fraction(val) if fraction_sum > 0.0 => {
+                *size = (free_space * val / fraction_sum).max(content[index]);
+            }
+
+            TrackSize::Auto if fraction_sum == 0.0 => {
+                *size += free_space / num_auto as f32;
+            }
+
+            _ => {}
+        }
+    }
+
+    sizes
+}
+
+// Returns the content size of the items which sit in a single track, as (row sizes, column sizes)
+fn grid_track_content(state: &State, cells: &[GridCell], rows: usize, columns: usize) -> (Vec<f32>, Vec<f32>) {
+    let mut row_content = vec![0.0f32; rows];
+    let mut column_content = vec![0.0f32; columns];
+
+    for cell in cells.iter() {
+        let (width, height) = grid_item_size(state, cell.entity);
+
+        // Items spanning several tracks do not contribute to auto track sizes
+        if cell.column_span == 1 {
+            column_content[cell.column] = column_content[cell.column].max(width);
+        }
+
+        if cell.row_span == 1 {
+            row_content[cell.row] = row_content[cell.row].max(height);
+        }
+    }
+
+    (row_content, column_content)
+}
+
+// Measures the text of an entity including its padding. The text is wrapped to the width it was given in
+// the last layout, unless the width available in the parent has changed since then.
+fn measure_text(state: &mut State, entity: Entity, parent: Entity, parent_width: f32, parent_height: f32) {
+    if state.style.text.get(entity).is_none() {
+        state.transform.set_text_size(entity, TextSize::default());
+        return;
+    }
+
+    let padding_width = resolve_length(state.style.padding_left.get(entity), parent_width, 0.0)
+        + resolve_length(state.style.padding_right.get(entity), parent_width, 0.0);
+    let padding_height = resolve_length(state.style.padding_top.get(entity), parent_height, 0.0)
+        + resolve_length(state.style.padding_bottom.get(entity), parent_height, 0.0);
+
+    let available = available_width(state, entity, parent, parent_width);
+
+    let wrap_width = match state.transform.get_text_size(entity).wrapped {
+        Some((previous_available, wrap_width)) if previous_available == available => wrap_width,
+        _ => {
+            resolve_length(state.style.width.get(entity), parent_width, available)
+                .min(resolve_length(state.style.max_width.get(entity), parent_width, f32::INFINITY))
+                - padding_width
+        }
+    };
+
+    if let Some(layout) = text_layout(state, entity, wrap_width, f32::INFINITY) {
+        state.transform.set_text_size(
+            entity,
+            TextSize {
+                width: layout.width() + padding_width,
+                height: layout.height() + padding_height,
+                wrapped: Some((available, wrap_width)),
+            },
+        );
+    }
+}
+
+// Wraps the text of an entity again if it was measured before the size of its parent was known or
+// at a different width than its content box, returning true if the size of the text changed
+fn rewrap_text(state: &mut State, entity: Entity, parent: Entity) -> bool {
+    let mut text_size = state.transform.get_text_size(entity);
+
+    let (available, wrap_width) = match text_size.wrapped {
+        Some(wrapped) => wrapped,
+        None => return false,
+    };
+
+    let parent_width = state.transform.get_width(parent);
+    let parent_height = state.transform.get_height(parent);
+
+    // The parent changed size after the text was measured
+    if available_width(state, entity, parent, parent_width) != available {
+        measure_text(state, entity, parent, parent_width, parent_height);
+
+        let new_size = state.transform.get_text_size(entity);
+
+        return (new_size.width - text_size.width).abs() > 0.5
+            || (new_size.height - text_size.height).abs() > 0.5;
+    }
+
+    let content_width = state.transform.get_width(entity)
+        - resolve_length(state.style.padding_left.get(entity), parent_width, 0.0)
+        - resolve_length(state.style.padding_right.get(entity), parent_width, 0.0);
+
+    if (content_width - wrap_width).abs() <= 0.5 {
+        return false;
+    }
+
+    let height = match text_layout(state, entity, content_width, f32::INFINITY) {
+        Some(layout) => {
+            layout.height()
+                + resolve_length(state.style.padding_top.get(entity), parent_height, 0.0)
+                + resolve_length(state.style.padding_bottom.get(entity), parent_height, 0.0)
+        }
+        None => return false,
+    };
+
+    let changed = (height - text_size.height).abs() > 0.5
+        && state.style.height.get(entity).cloned().unwrap_or_default() == Length::Auto;
+
+    text_size.height = height;
+    text_size.wrapped = Some((available, content_width));
+    state.transform.set_text_size(entity, text_size);
+
+    changed
+}
+
+// Returns the width available to an entity in the content box of its parent, which is infinite
+// until the parent has been laid out
+fn available_width(state: &State, entity: Entity, parent: Entity, parent_width: f32) -> f32 {
+    if parent_width <= 0.0 {
+        return f32::INFINITY;
+    }
+
+    let parent_border_width = state.style.border_width.get(parent).cloned().unwrap_or_default();
+
+    parent_width
+        - parent_border_width
+        - resolve_length(state.style.padding_left.get(parent), parent_width, 0.0)
+        - resolve_length(state.style.padding_right.get(parent), parent_width, 0.0)
+        - resolve_length(state.style.margin_left.get(entity), parent_width, 0.0)
+        - resolve_length(state.style.margin_right.get(entity), parent_width, 0.0)
+}
+
+// Returns a length in pixels, or zero for other lengths
+pub(crate) fn pixels(length: Option<&Length>) -> f32 {
+    match length {
+        Some(Length::Pixels(val)) => *val,
+        _ => 0.0,
+    }
+}
+
+// Resolves a length against the available space, returning the default for auto lengths
+fn resolve_length(length: Option<&Length>, available: f32, default: f32) -> f32 {
+    match length.cloned().unwrap_or_default() {
+        Length::Pixels(val) => val,
+        Length::Percentage(val) => available * val,
+        Length::Calc(pixels, val) => pixels + available * val,
+        _ => default,
+    }
+}
+
+// Returns the size a grid needs to fit its tracks, ignoring percentages and free space
+fn grid_content_size(state: &State, hierarchy: &Hierarchy, grid: Entity) -> (f32, f32) {
+    let (cells, rows, columns) = place_grid_items(state, hierarchy, grid);
+    let (row_content, column_content) = grid_track_content(state, &cells, rows, columns);
+
+    let empty = Vec::new();
+    let template_columns = state.style.grid_template_columns.get(grid).unwrap_or(&empty);
+    let template_rows = state.style.grid_template_rows.get(grid).unwrap_or(&empty);
+
+    let column_gap = resolve_length(state.style.grid_column_gap.get(grid), 0.0, 0.0);
+    let row_gap = resolve_length(state.style.grid_row_gap.get(grid), 0.0, 0.0);
+
+    let width = resolve_tracks(template_columns, &column_content, 0.0, column_gap)
+        .iter()
+        .sum::<f32>()
+        + column_gap * columns.saturating_sub(1) as f32;
+    let height = resolve_tracks(template_rows, &row_content, 0.0, row_gap)
+        .iter()
+        .sum::<f32>()
+        + row_gap * rows.saturating_sub(1) as f32;
+
+    (width, height)
+}
+
+// Sizes the tracks of a grid to fit its content box and stretches each item to fill its cell
+fn layout_grid(
+    state: &mut State,
+    hierarchy: &Hierarchy,
+    grid: Entity,
+    posx: f32,
+    posy: f32,
+    width: f32,
+    height: f32,
+) {
+    let (cells, rows, columns) = place_grid_items(state, hierarchy, grid);
+    let (row_content, column_content) = grid_track_content(state, &cells, rows, columns);
+
+    let column_gap = resolve_length(state.style.grid_column_gap.get(grid), width, 0.0);
+    let row_gap = resolve_length(state.style.grid_row_gap.get(grid), height, 0.0);
+
+    let empty = Vec::new();
+    let column_sizes = resolve_tracks(
+        state.style.grid_template_columns.get(grid).unwrap_or(&empty),
+        &column_content,
+        width,
+        column_gap,
+    );
+    let row_sizes = resolve_tracks(
+        state.style.grid_template_rows.get(grid).unwrap_or(&empty),
+        &row_content,
+        height,
+        row_gap,
+    );
+
+    // Position of the start of each track
+    let mut column_offsets = Vec::with_capacity(columns);
+    let mut offset = 0.0;
+    for size in column_sizes.iter() {
+        column_offsets.push(offset);
+        offset += size + column_gap;
+    }
+
+    let mut row_offsets = Vec::with_capacity(rows);
+    let mut offset = 0.0;
+    for size in row_sizes.iter() {
+        row_offsets.push(offset);
+        offset += size + row_gap;
+    }
+
+    for cell in cells.iter() {
+        let child = cell.entity;
+
+        // Used to check if the layout of the child has changed
+        let previous_bounds = (
+            state.transform.get_posx(child),
+            state.transform.get_posy(child),
+            state.transform.get_width(child),
+            state.transform.get_height(child),
+        );
+
+        let cell_width = column_sizes[cell.column..cell.column + cell.column_span]
+            .iter()
+            .sum::<f32>()
+            + column_gap * (cell.column_span - 1) as f32;
+        let cell_height = row_sizes[cell.row..cell.row + cell.row_span]
+            .iter()
+            .sum::<f32>()
+            + row_gap * (cell.row_span - 1) as f32;
+
+        let margin_left = resolve_length(state.style.margin_left.get(child), cell_width, 0.0);
+        let margin_right = resolve_length(state.style.margin_right.get(child), cell_width, 0.0);
+        let margin_top = resolve_length(state.style.margin_top.get(child), cell_height, 0.0);
+        let margin_bottom = resolve_length(state.style.margin_bottom.get(child), cell_height, 0.0);
+
+        // Items without a width or height are stretched to fill the cell
+        let mut new_width = resolve_length(
+            state.style.width.get(child),
+            cell_width,
+            cell_width - margin_left - margin_right,
+        );
+        let mut new_height = resolve_length(
+            state.style.height.get(child),
+            cell_height,
+            cell_height - margin_top - margin_bottom,
+        );
+
+        // Apply size contraints
+        new_width = new_width
+            .max(resolve_length(state.style.min_width.get(child), cell_width, 0.0))
+            .min(resolve_length(state.style.max_width.get(child), cell_width, f32::INFINITY));
+        new_height = new_height
+            .max(resolve_length(state.style.min_height.get(child), cell_height, 0.0))
+            .min(resolve_length(state.style.max_height.get(child), cell_height, f32::INFINITY));
+
+        let child_border_width = state
+            .style
+            .border_width
+            .get(child)
+            .cloned()
+            .unwrap_or_default();
+
+        state.transform.set_width(child, new_width - child_border_width);
+        state.transform.set_height(child, new_height - child_border_width);
+
+        state.transform.set_posx(
+            child,
+            posx + column_offsets[cell.column] + margin_left + (child_border_width / 2.0),
+        );
+        state.transform.set_posy(
+            child,
+            posy + row_offsets[cell.row] + margin_top + (child_border_width / 2.0),
+        );
+
+        // If the child has moved or changed size then its children need to be laid out again
+        let bounds = (
+            state.transform.get_posx(child),
+            state.transform.get_posy(child),
+            state.transform.get_width(child),
+            state.transform.get_height(child),
+        );
+
+        if bounds != previous_bounds {
+            state.transform.set_layout_moved(child);
+        }
+    }
+}
+
+// An item in a wrapping flex container
+struct FlexItem {
+    entity: Entity,
+    width: f32,
+    height: f32,
+    margin_left: f32,
+    margin_right: f32,
+    margin_top: f32,
+    margin_bottom: f32,
+    flex_grow: f32,
+    // Items with a set cross size are not stretched
+    fixed_cross: bool,
+}
+
+impl FlexItem {
+    // Returns the main and cross size of the item including its margins
+    fn outer_size(&self, direction: FlexDirection) -> (f32, f32) {
+        let width = self.width + self.margin_left + self.margin_right;
+        let height = self.height + self.margin_top + self.margin_bottom;
+
+        match direction {
+            FlexDirection::Row => (width, height),
+            FlexDirection::Column => (height, width),
+        }
+    }
+}
+
+#[derive(Default)]
+struct FlexLine {
+    items: Vec<FlexItem>,
+    main: f32,
+    cross: f32,
+}
+
+// Measures a child of a wrapping flex container before any growing or stretching
+fn flex_item(state: &State, child: Entity, direction: FlexDirection, parent_width: f32, parent_height: f32) -> FlexItem {
+    let flex_direction = state.style.flex_direction.get(child).cloned().unwrap_or_default();
+
+    let (mut width, mut height) = match flex_direction {
+        FlexDirection::Row => (
+            state.transform.get_child_sum(child),
+            state.transform.get_child_max(child),
+        ),
+
+        FlexDirection::Column => (
+            state.transform.get_child_max(child),
+            state.transform.get_child_sum(child),
+        ),
+    };
+
+    width += resolve_length(state.style.padding_left.get(child), parent_width, 0.0)
+        + resolve_length(state.style.padding_right.get(child), parent_width, 0.0);
+    height += resolve_length(state.style.padding_top.get(child), parent_height, 0.0)
+        + resolve_length(state.style.padding_bottom.get(child), parent_height, 0.0);
+
+    let text_size = state.transform.get_text_size(child);
+    width = width.max(text_size.width);
+    height = height.max(text_size.height);
+
+    width = resolve_length(state.style.width.get(child), parent_width, width);
+    height = resolve_length(state.style.height.get(child), parent_height, height);
+
+    if let Some(flex_basis) = state.style.flex_basis.get(child) {
+        match direction {
+            FlexDirection::Row => width = *flex_basis,
+            FlexDirection::Column => height = *flex_basis,
+        }
+    }
+
+    // Apply size contraints
+    width = width
+        .max(resolve_length(state.style.min_width.get(child), parent_width, 0.0))
+        .min(resolve_length(state.style.max_width.get(child), parent_width, f32::INFINITY));
+    height = height
+        .max(resolve_length(state.style.min_height.get(child), parent_height, 0.0))
+        .min(resolve_length(state.style.max_height.get(child), parent_height, f32::INFINITY));
+
+    let cross_size = match direction {
+        FlexDirection::Row => state.style.height.get(child),
+        FlexDirection::Column => state.style.width.get(child),
+    };
+
+    FlexItem {
+        entity: child,
+        width,
+        height,
+        margin_left: resolve_length(state.style.margin_left.get(child), parent_width, 0.0),
+        margin_right: resolve_length(state.style.margin_right.get(child), parent_width, 0.0),
+        margin_top: resolve_length(state.style.margin_top.get(child), parent_height, 0.0),
+        margin_bottom: resolve_length(state.style.margin_bottom.get(child), parent_height, 0.0),
+        flex_grow: state.style.flex_grow.get(child).cloned().unwrap_or_default(),
+        fixed_cross: matches!(
+            cross_size,
+            Some(Length::Pixels(_)) | Some(Length::Percentage(_)) | Some(Length::Calc(_, _))
+        ),
+    }
+}
+
+// Breaks the children of a wrapping flex container into lines which fit the given content size
+fn flex_lines(state: &State, hierarchy: &Hierarchy, parent: Entity, width: f32, height: f32) -> Vec<FlexLine> {
+    let direction = state.style.flex_direction.get(parent).cloned().unwrap_or_default();
+
+    let available_main = match direction {
+        FlexDirection::Row => width,
+        FlexDirection::Column => height,
+    };
+
+    let mut lines = Vec::new();
+    let mut line = FlexLine::default();
+
+    for child in parent.child_iter(hierarchy) {
+        let display = state.style.display.get(child).cloned().unwrap_or_default();
+        let position = state.style.position.get(child).cloned().unwrap_or_default();
+
+        if display == Display::None || position == Position::Absolute {
+            continue;
+        }
+
+        let item = flex_item(state, child, direction, width, height);
+        let (main, cross) = item.outer_size(direction);
+
+        if !line.items.is_empty() && line.main + main > available_main {
+            lines.push(std::mem::take(&mut line));
+        }
+
+        line.main += main;
+        line.cross = line.cross.max(cross);
+        line.items.push(item);
+    }
+
+    if !line.items.is_empty() {
+        lines.push(line);
+    }
+
+    lines
+}
+
+// Lays out the children of a wrapping flex container line by line, with align-content distributing
+// the lines along the cross axis. Returns the cross size needed by the lines.
+fn layout_flex_wrap(
+    state: &mut State,
+    hierarchy: &Hierarchy,
+    parent: Entity,
+    posx: f32,
+    posy: f32,
+    width: f32,
+    height: f32,
+) -> f32 {
+    let direction = state.style.flex_direction.get(parent).cloned().unwrap_or_default();
+    let flex_wrap = state.style.flex_wrap.get(parent).cloned().unwrap_or_default();
+    let justify_content = state.style.justify_content.get(parent).cloned().unwrap_or_default();
+    let align_items = state.style.align_items.get(parent).cloned().unwrap_or_default();
+    let align_content = state.style.align_content.get(parent).cloned().unwrap_or_default();
+
+    let lines = flex_lines(state, hierarchy, parent, width, height);
+
+    let (available_main, available_cross) = match direction {
+        FlexDirection::Row => (width, height),
+        FlexDirection::Column => (height, width),
+    };
+
+    let lines_cross: f32 = lines.iter().map(|line| line.cross).sum();
+    let free_cross = available_cross - lines_cross;
+    let num_lines = lines.len() as f32;
+
+    // Start position, space between lines and extra size per line
+    let (mut line_pos, line_spacing, line_extra) = match align_content {
+        AlignContent::FlexStart => (0.0, 0.0, 0.0),
+        AlignContent::FlexEnd => (free_cross, 0.0, 0.0),
+        AlignContent::Center => (free_cross / 2.0, 0.0, 0.0),
+        AlignContent::SpaceBetween if lines.len() > 1 => {
+            (0.0, free_cross.max(0.0) / (num_lines - 1.0), 0.0)
+        }
+        AlignContent::SpaceBetween => (0.0, 0.0, 0.0),
+        AlignContent::SpaceAround => {
+            let spacing = free_cross.max(0.0) / num_lines;
+            (spacing / 2.0, spacing, 0.0)
+        }
+        AlignContent::Stretch => (0.0, 0.0, free_cross.max(0.0) / num_lines),
+    };
+
+    for line in lines.iter() {
+        let line_cross = line.cross + line_extra;
+
+        // Reversed wrapping stacks the lines from the end of the cross axis
+        let line_start = match flex_wrap {
+            FlexWrap::WrapReverse => available_cross - line_pos - line_cross,
+            _ => line_pos,
+        };
+
+        let free_main = available_main - line.main;
+        let line_grow: f32 = line.items.iter().map(|item| item.flex_grow).sum();
+        let grow_sum = line_grow.max(1.0);
+        let grow_space = if free_main > 0.0 { free_main } else { 0.0 };
+
+        // Free space left after growing is used to justify the line
+        let remaining = free_main - grow_space * line_grow / grow_sum;
+        let num_items = line.items.len() as f32;
+
+        let (mut main_pos, spacing) = match justify_content {
+            JustifyContent::FlexStart | JustifyContent::Stretch => (0.0, 0.0),
+            JustifyContent::FlexEnd => (remaining, 0.0),
+            JustifyContent::Center => (remaining / 2.0, 0.0),
+            JustifyContent::SpaceBetween if line.items.len() > 1 => {
+                (0.0, remaining / (num_items - 1.0))
+            }
+            JustifyContent::SpaceBetween => (0.0, 0.0),
+            JustifyContent::SpaceAround => (remaining / num_items / 2.0, remaining / num_items),
+            JustifyContent::SpaceEvenly => {
+                (remaining / (num_items + 1.0), remaining / (num_items + 1.0))
+            }
+        };
+
+        for item in line.items.iter() {
+            let child = item.entity;
+
+            // Used to check if the layout of the child has changed
+            let previous_bounds = (
+                state.transform.get_posx(child),
+                state.transform.get_posy(child),
+                state.transform.get_width(child),
+                state.transform.get_height(child),
+            );
+
+            let (mut main, mut cross, margin_main, margin_cross_start, margin_cross_end) =
+                match direction {
+                    FlexDirection::Row => (
+                        item.width,
+                        item.height,
+                        (item.margin_left, item.margin_right),
+                        item.margin_top,
+                        item.margin_bottom,
+                    ),
+                    FlexDirection::Column => (
+                        item.height,
+                        item.width,
+                        (item.margin_top, item.margin_bottom),
+                        item.margin_left,
+                        item.margin_right,
+                    ),
+                };
+
+            main += grow_space * item.flex_grow / grow_sum;
+
+            // align-self overrides align-items
+            let align = state.style.align_self.get(child).cloned().unwrap_or(match align_items {
+                AlignItems::FlexStart => AlignSelf::FlexStart,
+                AlignItems::FlexEnd => AlignSelf::FlexEnd,
+                AlignItems::Center => AlignSelf::Center,
+                AlignItems::Stretch => AlignSelf::Stretch,
+            });
+
+            if align == AlignSelf::Stretch && !item.fixed_cross {
+                cross = line_cross - margin_cross_start - margin_cross_end;
+            }
+
+            let cross_free = line_cross - cross - margin_cross_start - margin_cross_end;
+            let cross_pos = line_start
+                + margin_cross_start
+                + match align {
+                    AlignSelf::FlexStart | AlignSelf::Stretch => 0.0,
+                    AlignSelf::FlexEnd => cross_free,
+                    AlignSelf::Center => cross_free / 2.0,
+                };
+
+            main_pos += margin_main.0;
+
+            let (new_posx, new_posy, new_width, new_height) = match direction {
+                FlexDirection::Row => (main_pos, cross_pos, main, cross),
+                FlexDirection::Column => (cross_pos, main_pos, cross, main),
+            };
+
+            main_pos += main + margin_main.1 + spacing;
+
+            let child_border_width = state
+                .style
+                .border_width
+                .get(child)
+                .cloned()
+                .unwrap_or_default();
+
+            state.transform.set_width(child, new_width - child_border_width);
+            state.transform.set_height(child, new_height - child_border_width);
+            state.transform.set_posx(child, posx + new_posx + (child_border_width / 2.0));
+            state.transform.set_posy(child, posy + new_posy + (child_border_width / 2.0));
+
+            // If the child has moved or changed size then its children need to be laid out again
+            let bounds = (
+                state.transform.get_posx(child),
+                state.transform.get_posy(child),
+                state.transform.get_width(child),
+                state.transform.get_height(child),
+            );
+
+            if bounds != previous_bounds {
+                state.transform.set_layout_moved(child);
+            }
         }
+
+        line_pos += line_cross + line_spacing;
     }
+
+    lines_cross
 }
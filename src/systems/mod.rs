@@ -5,3 +5,12 @@ pub use style_system::*;
 
 pub mod layout_system;
 pub use layout_system::*;
+
+pub mod scroll_system;
+pub use scroll_system::*;
+
+pub mod overlay_system;
+pub use overlay_system::*;
+
+pub mod tooltip_system;
+pub use tooltip_system::*;
@@ -0,0 +1,168 @@
+// Places open overlays in the window and dismisses them when the mouse is pressed outside of them or escape is pressed.
+
+use crate::hierarchy::*;
+use crate::{apply_transform, Entity, HierarchyTree, Placement, State};
+
+// Moves each open overlay next to its anchor, keeping it inside the window. Runs after the transforms are composed,
+// as an anchor may be transformed or inside another overlay, and composes an overlay's transforms again when it moves.
+pub fn apply_overlays(state: &mut State, hierarchy: &Hierarchy) {
+    // Overlays are placed from the bottom up so that an overlay anchored inside another is placed after it
+    for index in 0..state.overlays.stack.len() {
+        let entity = state.overlays.stack[index].entity;
+
+        let (x, y) = place(state, index);
+        let offset = (
+            x - state.transform.get_posx(entity),
+            y - state.transform.get_posy(entity),
+        );
+
+        if state.overlays.stack[index].offset != offset {
+            state.overlays.stack[index].offset = offset;
+            apply_transform(state, hierarchy, &[entity]);
+        }
+    }
+}
+
+// Closes the light dismiss overlays above the one the hovered entity is in. Returns true if any were closed
+// and the hovered entity isn't in an overlay, in which case the press shouldn't reach it.
+pub fn dismiss_overlays(state: &mut State) -> bool {
+    let mut dismissed = false;
+
+    while let Some(overlay) = state.overlays.top().cloned() {
+        let inside = state.hovered == overlay.entity
+            || state.hovered.is_descendant_of(&state.hierarchy, overlay.entity);
+
+        if inside {
+            return false;
+        }
+
+        if !overlay.light_dismiss {
+            break;
+        }
+
+        state.close_overlay(overlay.entity);
+        dismissed = true;
+    }
+
+    dismissed
+}
+
+// Returns true if a modal overlay is open and the entity is outside of it
+pub fn outside_modal(state: &State, entity: Entity) -> bool {
+    match state.overlays.modal() {
+        Some(modal) => entity != modal.entity && !entity.is_descendant_of(&state.hierarchy, modal.entity),
+        None => false,
+    }
+}
+
+// Returns the position of the top left corner of an overlay in the window
+fn place(state: &State, index: usize) -> (f32, f32) {
+    let overlay = &state.overlays.stack[index];
+
+    let window_width = state.transform.get_width(state.root);
+    let window_height = state.transform.get_height(state.root);
+
+    let width = state.transform.get_width(overlay.entity);
+    let height = state.transform.get_height(overlay.entity);
+
+    let (anchorx, anchory, anchor_width, anchor_height) = anchor_bounds(state, overlay.anchor);
+
+    let below = (anchorx, anchory + anchor_height);
+    let above = (anchorx, anchory - height);
+    let right = (anchorx + anchor_width, anchory);
+    let left = (anchorx - width, anchory);
+
+    let fits_below = below.1 + height <= window_height;
+    let fits_above = above.1 >= 0.0;
+    let fits_right = right.0 + width <= window_width;
+    let fits_left = left.0 >= 0.0;
+
+    let (x, y) = match overlay.placement {
+        Placement::Below => {
+            if !fits_below && fits_above {
+                above
+            } else {
+                below
+            }
+        }
+
+        Placement::Right => {
+            if !fits_right && fits_left {
+                left
+            } else {
+                right
+            }
+        }
+
+        Placement::Auto => {
+            if fits_below {
+                below
+            } else if fits_right {
+                right
+            } else if fits_above {
+                above
+            } else if fits_left {
+                left
+            } else {
+                below
+            }
+        }
+
+        Placement::Center => ((window_width - width) / 2.0, (window_height - height) / 2.0),
+
+        Placement::At(x, y) => {
+            let x = if x + width > window_width && x - width >= 0.0 {
+                x - width
+            } else {
+                x
+            };
+
+            let y = if y + height > window_height && y - height >= 0.0 {
+                y - height
+            } else {
+                y
+            };
+
+            (x, y)
+        }
+    };
+
+    // Kept inside the window, showing the top left if it's too big
+    (
+        x.min(window_width - width).max(0.0),
+        y.min(window_height - height).max(0.0),
+    )
+}
+
+// Returns the bounds of an anchor in the window, or the window itself if there is no anchor
+fn anchor_bounds(state: &State, anchor: Entity) -> (f32, f32, f32, f32) {
+    if !state.is_alive(anchor) {
+        return (
+            0.0,
+            0.0,
+            state.transform.get_width(state.root),
+            state.transform.get_height(state.root),
+        );
+    }
+
+    let posx = state.transform.get_posx(anchor);
+    let posy = state.transform.get_posy(anchor);
+    let width = state.transform.get_width(anchor);
+    let height = state.transform.get_height(anchor);
+
+    let matrix = state.transform.get_matrix(anchor);
+
+    let corners = [
+        matrix.transform_point(posx, posy),
+        matrix.transform_point(posx + width, posy),
+        matrix.transform_point(posx, posy + height),
+        matrix.transform_point(posx + width, posy + height),
+    ];
+
+    let left = corners.iter().map(|corner| corner.0).fold(f32::INFINITY, f32::min);
+    let top = corners.iter().map(|corner| corner.1).fold(f32::INFINITY, f32::min);
+    let right = corners.iter().map(|corner| corner.0).fold(f32::NEG_INFINITY, f32::max);
+    let bottom = corners.iter().map(|corner| corner.1).fold(f32::NEG_INFINITY, f32::max);
+
+    (left, top, right - left, bottom - top)
+}
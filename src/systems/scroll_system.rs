@@ -0,0 +1,466 @@
+// Scrolls the contents of entities with overflow: hidden, scroll or auto.
+// Layout measures the contents which each of these entities clips and the scroll offset is applied to
+// the transform of its children, so drawing and hit-testing follow it. Scrollbars are drawn over the
+// edges of the contents and, along with the mouse wheel, are used when no handler uses the mouse event.
+
+use femtovg::{renderer::OpenGl, Canvas, Paint, Path};
+
+use crate::hierarchy::*;
+use crate::style::{Overflow, Visibility};
+use crate::systems::layout_system::{branch_entities, pixels};
+use crate::widgets::ScrollContainerEvent;
+use crate::{Entity, Event, IntoParentIterator, MouseButton, Rect, State, WindowEvent};
+
+// The width of the scrollbars drawn along the right and bottom edges of scrolling entities
+pub const SCROLLBAR_WIDTH: f32 = 8.0;
+
+// The shortest a scrollbar thumb can be
+const MIN_THUMB_LENGTH: f32 = 20.0;
+
+// The distance scrolled for each line of the mouse wheel
+pub const SCROLL_LINE: f32 = 40.0;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ScrollAxis {
+    Horizontal,
+    Vertical,
+}
+
+// The track along the edge of a scrolling entity and the thumb which shows the visible part of its contents
+#[derive(Copy, Clone, Debug)]
+pub struct ScrollbarBounds {
+    pub track: Rect,
+    pub thumb: Rect,
+}
+
+// A scrollbar thumb being dragged with the mouse
+#[derive(Copy, Clone, Debug)]
+pub struct ScrollDrag {
+    entity: Entity,
+    axis: ScrollAxis,
+    // The distance from the start of the thumb to the cursor when it was pressed
+    grab: f32,
+}
+
+// Measures the contents of entities which clip them and keeps their scroll positions in range.
+// Runs after layout and before the transforms, which include the scroll offsets, are composed.
+// A changed branch changes the contents of the entity which clips it, so the branches are returned
+// widened to their clipping entities for the transforms to be composed again.
+pub fn apply_scrolling(state: &mut State, hierarchy: &Hierarchy, branches: &[Entity]) -> Vec<Entity> {
+    let mut branches = branches.to_vec();
+    for index in 0..branches.len() {
+        let clip_widget = state.transform.get_clip_widget(branches[index]);
+        if overflow(state, clip_widget).clips() {
+            branches.push(clip_widget);
+        }
+    }
+
+    let entities = branch_entities(state, hierarchy, &branches);
+
+    for entity in entities.iter().cloned() {
+        let width = state.transform.get_width(entity);
+        let height = state.transform.get_height(entity);
+        state.transform.set_content_size(entity, width, height);
+    }
+
+    // The contents are the descendants which an entity clips, including the margins after them
+    for entity in entities.iter().cloned() {
+        if entity == Entity::new(0, 0) {
+            continue;
+        }
+
+        if state.transform.get_visibility(entity) == Visibility::Invisible {
+            continue;
+        }
+
+        let clip_widget = state.transform.get_clip_widget(entity);
+
+        if !overflow(state, clip_widget).clips() {
+            continue;
+        }
+
+        let right = state.transform.get_posx(entity) + state.transform.get_width(entity)
+            + pixels(state.style.margin_right.get(entity))
+            - state.transform.get_posx(clip_widget)
+            + pixels(state.style.padding_right.get(clip_widget));
+        let bottom = state.transform.get_posy(entity) + state.transform.get_height(entity)
+            + pixels(state.style.margin_bottom.get(entity))
+            - state.transform.get_posy(clip_widget)
+            + pixels(state.style.padding_bottom.get(clip_widget));
+
+        let content_width = state.transform.get_content_width(clip_widget).max(right);
+        let content_height = state.transform.get_content_height(clip_widget).max(bottom);
+        state
+            .transform
+            .set_content_size(clip_widget, content_width, content_height);
+    }
+
+    for entity in entities.iter().cloned() {
+        if !overflow(state, entity).clips() {
+            continue;
+        }
+
+        let width = state.transform.get_width(entity);
+        let height = state.transform.get_height(entity);
+        let content_width = state.transform.get_content_width(entity);
+        let content_height = state.transform.get_content_height(entity);
+
+        if let Some(scroll) = state.style.scroll.get_mut(entity) {
+            scroll.w = visible_proportion(width, content_width);
+            scroll.h = visible_proportion(height, content_height);
+            scroll.x = scroll.x.clamp(0.0, 1.0);
+            scroll.y = scroll.y.clamp(0.0, 1.0);
+        }
+    }
+
+    branches
+}
+
+// Returns the distance the contents of an entity are scrolled by
+pub fn scroll_offset(state: &State, entity: Entity) -> (f32, f32) {
+    if !overflow(state, entity).clips() {
+        return (0.0, 0.0);
+    }
+
+    let scroll = state.style.scroll.get(entity).cloned().unwrap_or_default();
+    let (rangex, rangey) = scroll_range(state, entity);
+
+    (scroll.x * rangex, scroll.y * rangey)
+}
+
+// Returns the furthest the contents of an entity can be scrolled
+pub fn scroll_range(state: &State, entity: Entity) -> (f32, f32) {
+    let rangex = state.transform.get_content_width(entity) - state.transform.get_width(entity);
+    let rangey = state.transform.get_content_height(entity) - state.transform.get_height(entity);
+
+    (rangex.max(0.0), rangey.max(0.0))
+}
+
+// Scrolls the contents of an entity so that they are offset by the given distance, returning true if they moved
+pub fn scroll_to(state: &mut State, entity: Entity, x: f32, y: f32) -> bool {
+    let (rangex, rangey) = scroll_range(state, entity);
+
+    let scroll = state.style.scroll.get(entity).cloned().unwrap_or_default();
+
+    let scrollx = if rangex > 0.0 { (x / rangex).clamp(0.0, 1.0) } else { scroll.x };
+    let scrolly = if rangey > 0.0 { (y / rangey).clamp(0.0, 1.0) } else { scroll.y };
+
+    set_scroll(state, entity, scrollx, scrolly)
+}
+
+// Scrolls the contents of an entity by a distance, returning true if they moved
+pub fn scroll_by(state: &mut State, entity: Entity, dx: f32, dy: f32) -> bool {
+    let (x, y) = scroll_offset(state, entity);
+    scroll_to(state, entity, x + dx, y + dy)
+}
+
+// Returns the scrollbar of an entity along an axis, or None if it doesn't have one
+pub fn scrollbar(state: &State, entity: Entity, axis: ScrollAxis) -> Option<ScrollbarBounds> {
+    let (horizontal, vertical) = scrollbars_shown(state, entity);
+
+    let posx = state.transform.get_posx(entity);
+    let posy = state.transform.get_posy(entity);
+    let width = state.transform.get_width(entity);
+    let height = state.transform.get_height(entity);
+
+    let scroll = state.style.scroll.get(entity).cloned().unwrap_or_default();
+
+    // When both scrollbars are shown they leave the corner between them empty
+    match axis {
+        ScrollAxis::Horizontal if horizontal => {
+            let corner = if vertical { SCROLLBAR_WIDTH } else { 0.0 };
+
+            let track = Rect {
+                x: posx,
+                y: posy + height - SCROLLBAR_WIDTH,
+                w: width - corner,
+                h: SCROLLBAR_WIDTH,
+            };
+
+            let length = thumb_length(track.w, scroll.w);
+
+            let thumb = Rect {
+                x: track.x + scroll.x * (track.w - length),
+                w: length,
+                ..track
+            };
+
+            Some(ScrollbarBounds { track, thumb })
+        }
+
+        ScrollAxis::Vertical if vertical => {
+            let corner = if horizontal { SCROLLBAR_WIDTH } else { 0.0 };
+
+            let track = Rect {
+                x: posx + width - SCROLLBAR_WIDTH,
+                y: posy,
+                w: SCROLLBAR_WIDTH,
+                h: height - corner,
+            };
+
+            let length = thumb_length(track.h, scroll.h);
+
+            let thumb = Rect {
+                y: track.y + scroll.y * (track.h - length),
+                h: length,
+                ..track
+            };
+
+            Some(ScrollbarBounds { track, thumb })
+        }
+
+        _ => None,
+    }
+}
+
+// Returns the scrollbar of an entity under a point in the window
+pub fn scrollbar_at(state: &State, entity: Entity, x: f32, y: f32) -> Option<ScrollAxis> {
+    let (x, y) = state.transform.to_local(entity, x, y)?;
+
+    [ScrollAxis::Horizontal, ScrollAxis::Vertical]
+        .iter()
+        .cloned()
+        .find(|axis| match scrollbar(state, entity, *axis) {
+            Some(bounds) => bounds.track.contains(x, y),
+            None => false,
+        })
+}
+
+// Draws the scrollbars of an entity, with the canvas already transformed and clipped for the entity
+pub fn draw_scrollbars(state: &State, entity: Entity, canvas: &mut Canvas<OpenGl>) {
+    if state.transform.get_visibility(entity) == Visibility::Invisible {
+        return;
+    }
+
+    let opacity = state.transform.get_opacity(entity);
+
+    for axis in [ScrollAxis::Horizontal, ScrollAxis::Vertical].iter() {
+        if let Some(bounds) = scrollbar(state, entity, *axis) {
+            let mut track_color = femtovg::Color::rgba(0, 0, 0, 48);
+            track_color.set_alphaf(track_color.a * opacity);
+
+            let mut path = Path::new();
+            path.rect(bounds.track.x, bounds.track.y, bounds.track.w, bounds.track.h);
+            canvas.fill_path(&mut path, Paint::color(track_color));
+
+            let mut thumb_color = femtovg::Color::rgba(160, 160, 160, 192);
+            thumb_color.set_alphaf(thumb_color.a * opacity);
+
+            let mut path = Path::new();
+            path.rounded_rect(
+                bounds.thumb.x,
+                bounds.thumb.y,
+                bounds.thumb.w,
+                bounds.thumb.h,
+                SCROLLBAR_WIDTH / 2.0,
+            );
+            canvas.fill_path(&mut path, Paint::color(thumb_color));
+        }
+    }
+}
+
+// Scrolls with the mouse wheel and the scrollbars, and scrolls descendants into view, for events which
+// no handler used. The drag holds the scrollbar thumb which is being dragged between events.
+pub fn scroll_input(state: &mut State, drag: &mut Option<ScrollDrag>, event: &mut Event) {
+    let ancestors: Vec<Entity> = event.target.parent_iter(&state.hierarchy).collect();
+
+    if let Some(ScrollContainerEvent::ScrollIntoView(top, bottom)) =
+        event.message.downcast::<ScrollContainerEvent>()
+    {
+        // The target draws itself without its own scroll offset, so only its ancestors scroll
+        for entity in ancestors.into_iter().skip(1) {
+            if overflow(state, entity).clips() {
+                scroll_into_view(state, entity, *top, *bottom);
+                return;
+            }
+        }
+
+        return;
+    }
+
+    if let Some(window_event) = event.message.downcast::<WindowEvent>() {
+        match window_event {
+            // A mouse wheel only scrolls vertically, so shift turns it sideways
+            WindowEvent::MouseScroll(x, y) => {
+                let (dx, dy) = if state.modifiers.shift && *x == 0.0 {
+                    (-*y * SCROLL_LINE, 0.0)
+                } else {
+                    (-*x * SCROLL_LINE, -*y * SCROLL_LINE)
+                };
+
+                // The nearest entity which can still scroll that way is scrolled
+                for entity in ancestors.into_iter() {
+                    if overflow(state, entity).scrolls() && scroll_by(state, entity, dx, dy) {
+                        break;
+                    }
+                }
+            }
+
+            WindowEvent::MouseDown(MouseButton::Left) if drag.is_none() => {
+                let (cursorx, cursory) = (state.mouse.cursorx, state.mouse.cursory);
+
+                for entity in ancestors.into_iter() {
+                    if let Some(axis) = scrollbar_at(state, entity, cursorx, cursory) {
+                        press_scrollbar(state, drag, entity, axis, cursorx, cursory);
+                        break;
+                    }
+                }
+            }
+
+            WindowEvent::MouseMove(x, y) => {
+                if let Some(ScrollDrag { entity, axis, grab }) = *drag {
+                    let bounds = match scrollbar(state, entity, axis) {
+                        Some(bounds) => bounds,
+                        None => return,
+                    };
+
+                    let (x, y) = state.transform.to_local(entity, *x, *y).unwrap_or((*x, *y));
+                    let scroll = state.style.scroll.get(entity).cloned().unwrap_or_default();
+
+                    // The thumb follows the cursor along its track
+                    match axis {
+                        ScrollAxis::Horizontal => {
+                            let travel = bounds.track.w - bounds.thumb.w;
+                            if travel > 0.0 {
+                                let scrollx = (x - grab - bounds.track.x) / travel;
+                                set_scroll(state, entity, scrollx.clamp(0.0, 1.0), scroll.y);
+                            }
+                        }
+
+                        ScrollAxis::Vertical => {
+                            let travel = bounds.track.h - bounds.thumb.h;
+                            if travel > 0.0 {
+                                let scrolly = (y - grab - bounds.track.y) / travel;
+                                set_scroll(state, entity, scroll.x, scrolly.clamp(0.0, 1.0));
+                            }
+                        }
+                    }
+                }
+            }
+
+            WindowEvent::MouseUp(MouseButton::Left) => {
+                if let Some(ScrollDrag { entity, .. }) = drag.take() {
+                    state.release(entity);
+                }
+            }
+
+            _ => {}
+        }
+    }
+}
+
+// Starts dragging the thumb of a scrollbar, or pages towards the cursor if the track is pressed outside the thumb
+fn press_scrollbar(
+    state: &mut State,
+    drag: &mut Option<ScrollDrag>,
+    entity: Entity,
+    axis: ScrollAxis,
+    cursorx: f32,
+    cursory: f32,
+) {
+    let bounds = match scrollbar(state, entity, axis) {
+        Some(bounds) => bounds,
+        None => return,
+    };
+
+    let (x, y) = state
+        .transform
+        .to_local(entity, cursorx, cursory)
+        .unwrap_or((cursorx, cursory));
+
+    if bounds.thumb.contains(x, y) {
+        let grab = match axis {
+            ScrollAxis::Horizontal => x - bounds.thumb.x,
+            ScrollAxis::Vertical => y - bounds.thumb.y,
+        };
+
+        *drag = Some(ScrollDrag { entity, axis, grab });
+        state.capture(entity);
+    } else {
+        match axis {
+            ScrollAxis::Horizontal => {
+                let page = state.transform.get_width(entity);
+                let direction = if x < bounds.thumb.x { -1.0 } else { 1.0 };
+                scroll_by(state, entity, direction * page, 0.0);
+            }
+
+            ScrollAxis::Vertical => {
+                let page = state.transform.get_height(entity);
+                let direction = if y < bounds.thumb.y { -1.0 } else { 1.0 };
+                scroll_by(state, entity, 0.0, direction * page);
+            }
+        }
+    }
+}
+
+// Scrolls the least distance needed to show the span between two y positions of the laid out contents
+fn scroll_into_view(state: &mut State, entity: Entity, top: f32, bottom: f32) {
+    let (offsetx, offsety) = scroll_offset(state, entity);
+
+    let posy = state.transform.get_posy(entity);
+    let height = state.transform.get_height(entity);
+
+    let visible_top = posy + offsety;
+    let visible_bottom = visible_top + height;
+
+    // The top is shown if the span doesn't fit
+    let distance = if top < visible_top || bottom - top > height {
+        top - visible_top
+    } else if bottom > visible_bottom {
+        bottom - visible_bottom
+    } else {
+        return;
+    };
+
+    scroll_to(state, entity, offsetx, offsety + distance);
+}
+
+fn set_scroll(state: &mut State, entity: Entity, x: f32, y: f32) -> bool {
+    let changed = match state.style.scroll.get_mut(entity) {
+        Some(scroll) if scroll.x != x || scroll.y != y => {
+            scroll.x = x;
+            scroll.y = y;
+            true
+        }
+
+        _ => false,
+    };
+
+    if changed {
+        state.transform.set_layout_dirty(entity);
+        state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
+        state.insert_event(Event::new(WindowEvent::Redraw));
+    }
+
+    changed
+}
+
+// Returns whether the horizontal and vertical scrollbars of an entity are shown
+fn scrollbars_shown(state: &State, entity: Entity) -> (bool, bool) {
+    let width = state.transform.get_width(entity);
+    let height = state.transform.get_height(entity);
+    let content_width = state.transform.get_content_width(entity);
+    let content_height = state.transform.get_content_height(entity);
+
+    match overflow(state, entity) {
+        Overflow::Scroll => (true, true),
+        Overflow::Auto => (content_width > width, content_height > height),
+        _ => (false, false),
+    }
+}
+
+fn overflow(state: &State, entity: Entity) -> Overflow {
+    state.style.overflow.get(entity).cloned().unwrap_or_default()
+}
+
+fn visible_proportion(size: f32, content_size: f32) -> f32 {
+    if content_size > 0.0 {
+        (size / content_size).min(1.0)
+    } else {
+        1.0
+    }
+}
+
+fn thumb_length(track_length: f32, visible: f32) -> f32 {
+    (track_length * visible).max(MIN_THUMB_LENGTH).min(track_length)
+}
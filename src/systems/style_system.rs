@@ -1,12 +1,12 @@
-use crate::{Entity, Event, HierarchyTree, IntoParentIterator, State, WindowEvent};
+use crate::{branch_entities, Entity, Event, HierarchyTree, IntoParentIterator, State, WindowEvent};
 
 use crate::hierarchy::*;
 use crate::state::animator::*;
 
 
-pub fn apply_clipping(state: &mut State, hierarchy: &Hierarchy) {
+pub fn apply_clipping(state: &mut State, hierarchy: &Hierarchy, branches: &[Entity]) {
 
-    for entity in hierarchy.into_iter() {
+    for entity in branch_entities(state, hierarchy, branches) {
 
         if entity == Entity::new(0,0) {
             continue;
@@ -15,8 +15,15 @@ pub fn apply_clipping(state: &mut State, hierarchy: &Hierarchy) {
         let parent = hierarchy.get_parent(entity).unwrap();
 
 
+        let parent_overflow = state.style.overflow.get(parent).cloned().unwrap_or_default();
+
+        // A parent which doesn't let its contents overflow clips them, but an open overlay escapes its ancestors
         if let Some(clip_widget) = state.style.clip_widget.get(entity) {
             state.transform.set_clip_widget(entity, *clip_widget);
+        } else if state.overlays.is_open(entity) {
+            state.transform.set_clip_widget(entity, state.root);
+        } else if parent_overflow.clips() {
+            state.transform.set_clip_widget(entity, parent);
         } else {
             let parent_clip_widget = state.transform.get_clip_widget(parent);
             state.transform.set_clip_widget(entity, parent_clip_widget);
@@ -26,12 +33,10 @@ pub fn apply_clipping(state: &mut State, hierarchy: &Hierarchy) {
     }
 }
 
-pub fn apply_visibility(state: &mut State, hierarchy: &Hierarchy) {
-
-    let mut draw_hierarchy: Vec<Entity> = hierarchy.into_iter().collect();
-    draw_hierarchy.sort_by_cached_key(|entity| state.transform.get_z_order(*entity));
+pub fn apply_visibility(state: &mut State, hierarchy: &Hierarchy, branches: &[Entity]) {
 
-    for widget in draw_hierarchy.into_iter() {
+    // Parents are visited before their children, which inherit their visibility
+    for widget in branch_entities(state, hierarchy, branches) {
         let visibility = state
             .style
             .visibility
@@ -75,7 +80,55 @@ pub fn apply_visibility(state: &mut State, hierarchy: &Hierarchy) {
 }
 
 // Returns true if the widget matches the selector
-fn check_match(state: &State, widget: Entity, selector: &Selector) -> bool {
+fn check_match(state: &State, hierarchy: &Hierarchy, widget: Entity, selector: &Selector) -> bool {
+    if !selector.matches(&entity_selector(state, widget)) {
+        return false;
+    }
+
+    if !check_structure(hierarchy, widget, selector) {
+        return false;
+    }
+
+    // The widget must not match any of the selectors in :not(...)
+    !selector
+        .not
+        .iter()
+        .any(|not_selector| check_match(state, hierarchy, widget, not_selector))
+}
+
+// Returns true if the position of the widget in the hierarchy matches the structural pseudo-classes of the selector
+fn check_structure(hierarchy: &Hierarchy, widget: Entity, selector: &Selector) -> bool {
+    if selector.first_child && hierarchy.get_prev_sibling(widget).is_some() {
+        return false;
+    }
+
+    if selector.last_child && hierarchy.get_next_sibling(widget).is_some() {
+        return false;
+    }
+
+    if selector.empty && hierarchy.get_first_child(widget).is_some() {
+        return false;
+    }
+
+    if selector.nth_child.is_some() {
+        // Count the previous siblings to find the position of the widget
+        let mut position = 1;
+        let mut sibling = widget;
+        while let Some(prev_sibling) = hierarchy.get_prev_sibling(sibling) {
+            position += 1;
+            sibling = prev_sibling;
+        }
+
+        if !selector.matches_nth_child(position) {
+            return false;
+        }
+    }
+
+    true
+}
+
+// Constructs a selector from the id, element, classes and pseudo-classes of a widget
+fn entity_selector(state: &State, widget: Entity) -> Selector {
     // Construct the widget selector
     let mut widget_selector = Selector::new();
 
@@ -98,320 +151,578 @@ fn check_match(state: &State, widget: Entity, selector: &Selector) -> bool {
         .cloned()
         .unwrap_or_default();
 
-    return selector.matches(&widget_selector);
+    widget_selector
 }
 
 
 pub fn apply_styles(state: &mut State, hierarchy: &Hierarchy) {
 
-    // Loop through all entities
-    for entity in hierarchy.into_iter() {
+    // Rules with sibling combinators depend on the previous siblings of an entity
+    let sibling_rules = state.style.rule_selectors.iter().flatten().any(|selector| {
+        matches!(selector.relation, Relation::Sibling | Relation::GeneralSibling)
+    });
 
-        if entity == Entity::new(0, 0) {
-            continue;
+    let mut dirty = match state.style.take_dirty() {
+        Some(dirty) => dirty,
+        None => state.root.child_iter(hierarchy).collect(),
+    };
+
+    // Removed entities are skipped, as is the root which isn't styled
+    dirty.retain(|entity| state.is_alive(*entity) && hierarchy.get_parent(*entity).is_some());
+
+    // Ancestors go first so that their descendants are restyled once, along with them
+    dirty.sort_by_cached_key(|entity| entity.parent_iter(hierarchy).count());
+
+    // Flags for entities restyled during this pass (indexed by entity)
+    let mut restyled: Vec<bool> = Vec::new();
+
+    for dirty_entity in dirty {
+        // Rules with parent and ancestor selectors depend on the ancestors of an entity, so the descendants
+        // of a dirty entity are restyled with it, along with its next siblings and their descendants if
+        // there are rules with sibling combinators
+        let scope = if sibling_rules {
+            hierarchy.get_parent(dirty_entity).unwrap()
+        } else {
+            dirty_entity
+        };
+
+        let affected: Vec<Entity> = dirty_entity
+            .into_iter(hierarchy)
+            .take_while(|entity| *entity == dirty_entity || entity.is_descendant_of(hierarchy, scope))
+            .collect();
+
+        for entity in affected {
+            if restyled.get(entity.index()).cloned().unwrap_or(false) {
+                continue;
+            }
+
+            if entity.index() >= restyled.len() {
+                restyled.resize(entity.index() + 1, false);
+            }
+
+            restyled[entity.index()] = true;
+
+            restyle(state, hierarchy, entity);
         }
+    }
+}
 
-        // Possible point to ad Cascading
+// Matches the style rules to an entity and links its properties to them
+fn restyle(state: &mut State, hierarchy: &Hierarchy, entity: Entity) {
+    let parent = hierarchy.get_parent(entity);
 
-        // Create a list of style rules that match this widget
-        let mut matched_rules: Vec<usize> = Vec::new();
+    // Possible point to ad Cascading
 
-        // Loop through all of the style rules
-        'rule_loop: for (index, selectors) in state.style.rule_selectors.iter().enumerate() {
+    // Create a list of style rules that match this widget
+    let mut matched_rules: Vec<usize> = Vec::new();
 
+    // Loop through all of the style rules
+    'rule_loop: for (index, selectors) in state.style.rule_selectors.iter().enumerate() {
+        // Rules created for declarations using var() are linked through the rule they came from
+        if selectors.is_empty() {
+            continue;
+        }
 
-            
 
-            let mut relation_entity = entity;
-            // Loop through selectors (Should be from right to left)
-            // All the selectors need to match for the rule to apply
-            'selector_loop: for rule_selector in selectors.iter().rev() {
+        
 
-                // Get the relation of the selector
-                match rule_selector.relation {
-                    Relation::None => {
-                        if !check_match(state, entity, rule_selector) {
-                            continue 'rule_loop;
-                        }
+        let mut relation_entity = entity;
+        // Loop through selectors (Should be from right to left)
+        // All the selectors need to match for the rule to apply
+        'selector_loop: for rule_selector in selectors.iter().rev() {
+
+            // Get the relation of the selector
+            match rule_selector.relation {
+                Relation::None => {
+                    if !check_match(state, hierarchy, entity, rule_selector) {
+                        continue 'rule_loop;
                     }
+                }
 
-                    Relation::Parent => {
-                        // Get the parent
-                        // Contrust the selector for the parent
-                        // Check if the parent selector matches the rule_seletor
-                        if let Some(parent) = relation_entity.parent(hierarchy) {
-                            if !check_match(state, parent, rule_selector) {
-                                continue 'rule_loop;
-                            }
-
-                            relation_entity = parent;
-                        } else {
+                Relation::Parent => {
+                    // Get the parent
+                    // Contrust the selector for the parent
+                    // Check if the parent selector matches the rule_seletor
+                    if let Some(parent) = relation_entity.parent(hierarchy) {
+                        if !check_match(state, hierarchy, parent, rule_selector) {
                             continue 'rule_loop;
                         }
+
+                        relation_entity = parent;
+                    } else {
+                        continue 'rule_loop;
                     }
+                }
+
+                Relation::Ancestor => {
+                    
+                    // Walk up the hierarchy
+                    // Check if each entity matches the selector
+                    // If any of them match, move on to the next selector
+                    // If none of them do, move on to the next rule
+                    for ancestor in relation_entity.parent_iter(hierarchy) {
 
-                    Relation::Ancestor => {
-                        
-                        // Walk up the hierarchy
-                        // Check if each entity matches the selector
-                        // If any of them match, move on to the next selector
-                        // If none of them do, move on to the next rule
-                        for ancestor in relation_entity.parent_iter(hierarchy) {
+                        if ancestor == relation_entity {
+                            continue;
+                        }
+
+                        if check_match(state, hierarchy, ancestor, rule_selector) {
 
-                            if ancestor == relation_entity {
-                                continue;
-                            }
+                            relation_entity = ancestor;
 
-                            if check_match(state, ancestor, rule_selector) {
+                            continue 'selector_loop;
+                        }
+                    }
 
-                                relation_entity = ancestor;
+                    continue 'rule_loop;
+                }
 
-                                continue 'selector_loop;
-                            }
+                Relation::Sibling => {
+                    // The previous sibling must match the selector
+                    if let Some(sibling) = hierarchy.get_prev_sibling(relation_entity) {
+                        if !check_match(state, hierarchy, sibling, rule_selector) {
+                            continue 'rule_loop;
                         }
 
+                        relation_entity = sibling;
+                    } else {
                         continue 'rule_loop;
                     }
                 }
-            }
 
-            // If all the selectors match then add the rule to the matched rules list
-            matched_rules.push(index);
-        }
+                Relation::GeneralSibling => {
+                    // Walk back through the siblings until one matches the selector
+                    let mut sibling = relation_entity;
+                    while let Some(prev_sibling) = hierarchy.get_prev_sibling(sibling) {
+                        if check_match(state, hierarchy, prev_sibling, rule_selector) {
+                            relation_entity = prev_sibling;
 
-        //println!("Entity: {}, Matched Rules: {:?}", entity, &matched_rules);
+                            continue 'selector_loop;
+                        }
 
-        if matched_rules.len() == 0 {
-            continue;
-        }
+                        sibling = prev_sibling;
+                    }
 
-        // Display
-        if state.style.display.link_rule(entity, &matched_rules) {
-            state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
-            state.insert_event(Event::new(WindowEvent::Redraw));
-        }
-        if state.style.visibility.link_rule(entity, &matched_rules) {
-            state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
-            state.insert_event(Event::new(WindowEvent::Redraw));
+                    continue 'rule_loop;
+                }
+            }
         }
 
-        // Currently doesn't do anything - TODO
-        state.style.overflow.link_rule(entity, &matched_rules);
+        // If all the selectors match then add the rule to the matched rules list
+        matched_rules.push(index);
+    }
 
+    //println!("Entity: {}, Matched Rules: {:?}", entity, &matched_rules);
 
-        // Opacity
-        if state.style.opacity.link_rule(entity, &matched_rules) {
-            state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
-            state.insert_event(Event::new(WindowEvent::Redraw));
-        }
+    let matched_rules = state.style.link_variables(entity, parent, matched_rules);
 
-        // Positioning
-        if state.style.position.link_rule(entity, &matched_rules) {
-            state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
-            state.insert_event(Event::new(WindowEvent::Redraw));
-        }
+    // Properties are linked even if no rules match so that rules which no longer match are unlinked
 
-        if state.style.left.link_rule(entity, &matched_rules) {
-            state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
-            state.insert_event(Event::new(WindowEvent::Redraw));
-        }
+    // Display
+    if state.style.display.link_rule(entity, &matched_rules) {
+        state.transform.set_layout_dirty(entity);
+        state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
+        state.insert_event(Event::new(WindowEvent::Redraw));
+    }
+    if state.style.visibility.link_rule(entity, &matched_rules) {
+        state.transform.set_layout_dirty(entity);
+        state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
+        state.insert_event(Event::new(WindowEvent::Redraw));
+    }
 
-        if state.style.right.link_rule(entity, &matched_rules) {
-            state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
-            state.insert_event(Event::new(WindowEvent::Redraw));
-        }
+    // Overflow
+    if state.style.overflow.link_rule(entity, &matched_rules) {
+        state.transform.set_layout_dirty(entity);
+        state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
+        state.insert_event(Event::new(WindowEvent::Redraw));
+    }
 
-        if state.style.top.link_rule(entity, &matched_rules) {
-            state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
-            state.insert_event(Event::new(WindowEvent::Redraw));
-        }
+    // Z Index
+    if state.style.z_order.link_rule(entity, &matched_rules) {
+        state.transform.draw_order_dirty = true;
+        state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
+        state.insert_event(Event::new(WindowEvent::Redraw));
+    }
 
-        if state.style.bottom.link_rule(entity, &matched_rules) {
-            state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
-            state.insert_event(Event::new(WindowEvent::Redraw));
-        }
 
-        // Size
-        if state.style.width.link_rule(entity, &matched_rules) {
-            state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
-            state.insert_event(Event::new(WindowEvent::Redraw));
-        }
+    // Opacity
+    if state.style.opacity.link_rule(entity, &matched_rules) {
+        state.transform.set_layout_dirty(entity);
+        state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
+        state.insert_event(Event::new(WindowEvent::Redraw));
+    }
 
-        if state.style.height.link_rule(entity, &matched_rules) {
-            state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
-            state.insert_event(Event::new(WindowEvent::Redraw));
-        }
+    // Transform
+    // The transform doesn't change the layout but is composed after it
+    if state.style.transform.link_rule(entity, &matched_rules) {
+        state.transform.set_layout_dirty(entity);
+        state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
+        state.insert_event(Event::new(WindowEvent::Redraw));
+    }
 
-        // Size Constraints
-        if state.style.max_width.link_rule(entity, &matched_rules) {
-            state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
-            state.insert_event(Event::new(WindowEvent::Redraw));
-        }
+    if state.style.transform_origin.link_rule(entity, &matched_rules) {
+        state.transform.set_layout_dirty(entity);
+        state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
+        state.insert_event(Event::new(WindowEvent::Redraw));
+    }
 
-        if state.style.min_width.link_rule(entity, &matched_rules) {
-            state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
-            state.insert_event(Event::new(WindowEvent::Redraw));
-        }
+    // Positioning
+    if state.style.position.link_rule(entity, &matched_rules) {
+        state.transform.set_layout_dirty(entity);
+        state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
+        state.insert_event(Event::new(WindowEvent::Redraw));
+    }
 
-        if state.style.max_height.link_rule(entity, &matched_rules) {
-            state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
-            state.insert_event(Event::new(WindowEvent::Redraw));
-        }
+    if state.style.left.link_rule(entity, &matched_rules) {
+        state.transform.set_layout_dirty(entity);
+        state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
+        state.insert_event(Event::new(WindowEvent::Redraw));
+    }
 
-        if state.style.min_height.link_rule(entity, &matched_rules) {
-            state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
-            state.insert_event(Event::new(WindowEvent::Redraw));
-        }
+    if state.style.right.link_rule(entity, &matched_rules) {
+        state.transform.set_layout_dirty(entity);
+        state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
+        state.insert_event(Event::new(WindowEvent::Redraw));
+    }
 
-        // Margin
-        if state.style.margin_left.link_rule(entity, &matched_rules) {
-            state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
-            state.insert_event(Event::new(WindowEvent::Redraw));
-        }
+    if state.style.top.link_rule(entity, &matched_rules) {
+        state.transform.set_layout_dirty(entity);
+        state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
+        state.insert_event(Event::new(WindowEvent::Redraw));
+    }
 
-        if state.style.margin_right.link_rule(entity, &matched_rules) {
-            state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
-            state.insert_event(Event::new(WindowEvent::Redraw));
-        }
+    if state.style.bottom.link_rule(entity, &matched_rules) {
+        state.transform.set_layout_dirty(entity);
+        state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
+        state.insert_event(Event::new(WindowEvent::Redraw));
+    }
 
-        if state.style.margin_top.link_rule(entity, &matched_rules) {
-            state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
-            state.insert_event(Event::new(WindowEvent::Redraw));
-        }
+    // Size
+    if state.style.width.link_rule(entity, &matched_rules) {
+        state.transform.set_layout_dirty(entity);
+        state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
+        state.insert_event(Event::new(WindowEvent::Redraw));
+    }
 
-        if state.style.margin_bottom.link_rule(entity, &matched_rules) {
-            state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
-            state.insert_event(Event::new(WindowEvent::Redraw));
-        }
+    if state.style.height.link_rule(entity, &matched_rules) {
+        state.transform.set_layout_dirty(entity);
+        state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
+        state.insert_event(Event::new(WindowEvent::Redraw));
+    }
 
-        // Padding
-        if state.style.padding_left.link_rule(entity, &matched_rules) {
-            state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
-            state.insert_event(Event::new(WindowEvent::Redraw));
-        }
+    // Size Constraints
+    if state.style.max_width.link_rule(entity, &matched_rules) {
+        state.transform.set_layout_dirty(entity);
+        state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
+        state.insert_event(Event::new(WindowEvent::Redraw));
+    }
 
-        if state.style.padding_right.link_rule(entity, &matched_rules) {
-            state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
-            state.insert_event(Event::new(WindowEvent::Redraw));
-        }
+    if state.style.min_width.link_rule(entity, &matched_rules) {
+        state.transform.set_layout_dirty(entity);
+        state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
+        state.insert_event(Event::new(WindowEvent::Redraw));
+    }
 
-        if state.style.padding_top.link_rule(entity, &matched_rules) {
-            state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
-            state.insert_event(Event::new(WindowEvent::Redraw));
-        }
+    if state.style.max_height.link_rule(entity, &matched_rules) {
+        state.transform.set_layout_dirty(entity);
+        state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
+        state.insert_event(Event::new(WindowEvent::Redraw));
+    }
 
-        if state
-            .style
-            .padding_bottom
-            .link_rule(entity, &matched_rules)
-        {
-            state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
-            state.insert_event(Event::new(WindowEvent::Redraw));
-        }
+    if state.style.min_height.link_rule(entity, &matched_rules) {
+        state.transform.set_layout_dirty(entity);
+        state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
+        state.insert_event(Event::new(WindowEvent::Redraw));
+    }
 
-        // Border
-        if state.style.border_width.link_rule(entity, &matched_rules) {
-            state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
-            state.insert_event(Event::new(WindowEvent::Redraw));
-        }
+    // Margin
+    if state.style.margin_left.link_rule(entity, &matched_rules) {
+        state.transform.set_layout_dirty(entity);
+        state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
+        state.insert_event(Event::new(WindowEvent::Redraw));
+    }
 
-        if state.style.border_color.link_rule(entity, &matched_rules) {
-            state.insert_event(Event::new(WindowEvent::Redraw));
-        }
+    if state.style.margin_right.link_rule(entity, &matched_rules) {
+        state.transform.set_layout_dirty(entity);
+        state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
+        state.insert_event(Event::new(WindowEvent::Redraw));
+    }
 
-        if state.style.border_radius.link_rule(entity, &matched_rules) {
-            state.insert_event(Event::new(WindowEvent::Redraw));
-        }
+    if state.style.margin_top.link_rule(entity, &matched_rules) {
+        state.transform.set_layout_dirty(entity);
+        state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
+        state.insert_event(Event::new(WindowEvent::Redraw));
+    }
 
-        // Flex Container
-        if state
-            .style
-            .flex_direction
-            .link_rule(entity, &matched_rules)
-        {
-            state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
-            state.insert_event(Event::new(WindowEvent::Redraw));
-        }
+    if state.style.margin_bottom.link_rule(entity, &matched_rules) {
+        state.transform.set_layout_dirty(entity);
+        state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
+        state.insert_event(Event::new(WindowEvent::Redraw));
+    }
 
-        if state
-            .style
-            .justify_content
-            .link_rule(entity, &matched_rules)
-        {
-            state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
-            state.insert_event(Event::new(WindowEvent::Redraw));
-        }
+    // Padding
+    if state.style.padding_left.link_rule(entity, &matched_rules) {
+        state.transform.set_layout_dirty(entity);
+        state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
+        state.insert_event(Event::new(WindowEvent::Redraw));
+    }
 
-        if state.style.align_content.link_rule(entity, &matched_rules) {
-            state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
-            state.insert_event(Event::new(WindowEvent::Redraw));
-        }
+    if state.style.padding_right.link_rule(entity, &matched_rules) {
+        state.transform.set_layout_dirty(entity);
+        state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
+        state.insert_event(Event::new(WindowEvent::Redraw));
+    }
 
-        if state.style.align_items.link_rule(entity, &matched_rules) {
-            state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
-            state.insert_event(Event::new(WindowEvent::Redraw));
-        }
+    if state.style.padding_top.link_rule(entity, &matched_rules) {
+        state.transform.set_layout_dirty(entity);
+        state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
+        state.insert_event(Event::new(WindowEvent::Redraw));
+    }
 
-        if state.style.align_self.link_rule(entity, &matched_rules) {
-            state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
-            state.insert_event(Event::new(WindowEvent::Redraw));
-        }
+    if state
+        .style
+        .padding_bottom
+        .link_rule(entity, &matched_rules)
+    {
+        state.transform.set_layout_dirty(entity);
+        state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
+        state.insert_event(Event::new(WindowEvent::Redraw));
+    }
 
-        // Flex Item
-        if state.style.flex_basis.link_rule(entity, &matched_rules) {
-            state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
-            state.insert_event(Event::new(WindowEvent::Redraw));
-        }
+    // Border
+    if state.style.border_width.link_rule(entity, &matched_rules) {
+        state.transform.set_layout_dirty(entity);
+        state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
+        state.insert_event(Event::new(WindowEvent::Redraw));
+    }
 
-        if state.style.flex_grow.link_rule(entity, &matched_rules) {
-            state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
-            state.insert_event(Event::new(WindowEvent::Redraw));
-        }
+    if state.style.border_color.link_rule(entity, &matched_rules) {
+        state.insert_event(Event::new(WindowEvent::Redraw));
+    }
 
-        if state.style.flex_shrink.link_rule(entity, &matched_rules) {
-            state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
-            state.insert_event(Event::new(WindowEvent::Redraw));
-        }
+    if state.style.border_radius.link_rule(entity, &matched_rules) {
+        state.insert_event(Event::new(WindowEvent::Redraw));
+    }
 
-        if state.style.align_self.link_rule(entity, &matched_rules) {
-            state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
-            state.insert_event(Event::new(WindowEvent::Redraw));
-        }
+    // Flex Container
+    if state
+        .style
+        .flex_direction
+        .link_rule(entity, &matched_rules)
+    {
+        state.transform.set_layout_dirty(entity);
+        state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
+        state.insert_event(Event::new(WindowEvent::Redraw));
+    }
 
-        // Text Alignment
-        if state.style.text_align.link_rule(entity, &matched_rules) {
-            state.insert_event(Event::new(WindowEvent::Redraw));
-        }
+    if state
+        .style
+        .justify_content
+        .link_rule(entity, &matched_rules)
+    {
+        state.transform.set_layout_dirty(entity);
+        state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
+        state.insert_event(Event::new(WindowEvent::Redraw));
+    }
 
-        if state.style.text_justify.link_rule(entity, &matched_rules) {
-            state.insert_event(Event::new(WindowEvent::Redraw));
-        }
+    if state.style.flex_wrap.link_rule(entity, &matched_rules) {
+        state.transform.set_layout_dirty(entity);
+        state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
+        state.insert_event(Event::new(WindowEvent::Redraw));
+    }
 
-        // Background
-        if state
-            .style
-            .background_color
-            .link_rule(entity, &matched_rules)
-        {
-            state.insert_event(Event::new(WindowEvent::Redraw));
-        }
+    if state.style.align_content.link_rule(entity, &matched_rules) {
+        state.transform.set_layout_dirty(entity);
+        state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
+        state.insert_event(Event::new(WindowEvent::Redraw));
+    }
 
+    if state.style.align_items.link_rule(entity, &matched_rules) {
+        state.transform.set_layout_dirty(entity);
+        state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
+        state.insert_event(Event::new(WindowEvent::Redraw));
+    }
 
+    if state.style.align_self.link_rule(entity, &matched_rules) {
+        state.transform.set_layout_dirty(entity);
+        state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
+        state.insert_event(Event::new(WindowEvent::Redraw));
+    }
 
-        if state
-            .style
-            .background_image
-            .link_rule(entity, &matched_rules)
+    // Grid Container
+    if state.style.grid_template_columns.link_rule(entity, &matched_rules) {
+        state.transform.set_layout_dirty(entity);
+        state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
+        state.insert_event(Event::new(WindowEvent::Redraw));
+    }
+
+    if state.style.grid_template_rows.link_rule(entity, &matched_rules) {
+        state.transform.set_layout_dirty(entity);
+        state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
+        state.insert_event(Event::new(WindowEvent::Redraw));
+    }
+
+    if state.style.grid_column_gap.link_rule(entity, &matched_rules) {
+        state.transform.set_layout_dirty(entity);
+        state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
+        state.insert_event(Event::new(WindowEvent::Redraw));
+    }
+
+    if state.style.grid_row_gap.link_rule(entity, &matched_rules) {
+        state.transform.set_layout_dirty(entity);
+        state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
+        state.insert_event(Event::new(WindowEvent::Redraw));
+    }
+
+    // Grid Item
+    if state.style.grid_column.link_rule(entity, &matched_rules) {
+        state.transform.set_layout_dirty(entity);
+        state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
+        state.insert_event(Event::new(WindowEvent::Redraw));
+    }
+
+    if state.style.grid_row.link_rule(entity, &matched_rules) {
+        state.transform.set_layout_dirty(entity);
+        state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
+        state.insert_event(Event::new(WindowEvent::Redraw));
+    }
+
+    // Flex Item
+    if state.style.flex_basis.link_rule(entity, &matched_rules) {
+        state.transform.set_layout_dirty(entity);
+        state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
+        state.insert_event(Event::new(WindowEvent::Redraw));
+    }
+
+    if state.style.flex_grow.link_rule(entity, &matched_rules) {
+        state.transform.set_layout_dirty(entity);
+        state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
+        state.insert_event(Event::new(WindowEvent::Redraw));
+    }
+
+    if state.style.flex_shrink.link_rule(entity, &matched_rules) {
+        state.transform.set_layout_dirty(entity);
+        state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
+        state.insert_event(Event::new(WindowEvent::Redraw));
+    }
+
+    if state.style.align_self.link_rule(entity, &matched_rules) {
+        state.transform.set_layout_dirty(entity);
+        state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
+        state.insert_event(Event::new(WindowEvent::Redraw));
+    }
+
+    // Text Alignment
+    if state.style.text_align.link_rule(entity, &matched_rules) {
+        state.insert_event(Event::new(WindowEvent::Redraw));
+    }
+
+    if state.style.text_justify.link_rule(entity, &matched_rules) {
+        state.insert_event(Event::new(WindowEvent::Redraw));
+    }
+
+    if state.style.text_overflow.link_rule(entity, &matched_rules) {
+        state.insert_event(Event::new(WindowEvent::Redraw));
+    }
+
+    // Background
+    if state
+        .style
+        .background_color
+        .link_rule(entity, &matched_rules)
+    {
+        state.insert_event(Event::new(WindowEvent::Redraw));
+    }
+
+
+
+    if state
+        .style
+        .background_image
+        .link_rule(entity, &matched_rules)
+    {
+        state.insert_event(Event::new(WindowEvent::Redraw));
+    }
+
+    if state
+    .style
+    .font_color
+    .link_rule(entity, &matched_rules)
+    {
+        state.insert_event(Event::new(WindowEvent::Redraw));
+    }
+
+    if state.style.font_family.link_rule(entity, &matched_rules) {
+        state.transform.set_layout_dirty(entity);
+        state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
+        state.insert_event(Event::new(WindowEvent::Redraw));
+    }
+
+    if state.style.font_weight.link_rule(entity, &matched_rules) {
+        state.transform.set_layout_dirty(entity);
+        state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
+        state.insert_event(Event::new(WindowEvent::Redraw));
+    }
+
+    if state.style.font_style.link_rule(entity, &matched_rules) {
+        state.transform.set_layout_dirty(entity);
+        state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
+        state.insert_event(Event::new(WindowEvent::Redraw));
+    }
+
+    if state.style.font_size.link_rule(entity, &matched_rules) {
+        state.transform.set_layout_dirty(entity);
+        state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
+        state.insert_event(Event::new(WindowEvent::Redraw));
+    }
+
+    if state.style.white_space.link_rule(entity, &matched_rules) {
+        state.transform.set_layout_dirty(entity);
+        state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
+        state.insert_event(Event::new(WindowEvent::Redraw));
+    }
+
+    if state.style.line_height.link_rule(entity, &matched_rules) {
+        state.transform.set_layout_dirty(entity);
+        state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
+        state.insert_event(Event::new(WindowEvent::Redraw));
+    }
+
+    if state.style.letter_spacing.link_rule(entity, &matched_rules) {
+        state.transform.set_layout_dirty(entity);
+        state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
+        state.insert_event(Event::new(WindowEvent::Redraw));
+    }
+
+    // Text properties which aren't set by a rule or inline are inherited from the parent
+    if let Some(parent) = parent {
+        // These change the size of the text
+        if state.style.font_family.inherit(entity, parent)
+            | state.style.font_weight.inherit(entity, parent)
+            | state.style.font_style.inherit(entity, parent)
+            | state.style.font_size.inherit(entity, parent)
+            | state.style.white_space.inherit(entity, parent)
+            | state.style.line_height.inherit(entity, parent)
+            | state.style.letter_spacing.inherit(entity, parent)
         {
+            state.transform.set_layout_dirty(entity);
+            state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
             state.insert_event(Event::new(WindowEvent::Redraw));
         }
 
-        if state
-        .style
-        .font_color
-        .link_rule(entity, &matched_rules)
+        if state.style.font_color.inherit(entity, parent)
+            | state.style.text_align.inherit(entity, parent)
+            | state.style.text_justify.inherit(entity, parent)
         {
             state.insert_event(Event::new(WindowEvent::Redraw));
         }
     }
+
+    // Animation (keyframe animations are started when the entity is newly linked to the rule)
+    if state.style.animation.link_rule(entity, &matched_rules) {
+        state.style.play_animations(entity);
+        state.transform.set_layout_dirty(entity);
+        state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
+        state.insert_event(Event::new(WindowEvent::Redraw));
+    }
 }
@@ -0,0 +1,120 @@
+// Shows the tooltip of the hovered entity once the mouse has rested over it, and hides it again
+// when the mouse leaves, is pressed or escape is pressed.
+
+use crate::hierarchy::*;
+use crate::{
+    Entity, Event, IntoParentIterator, Overlay, Placement, PropSet, Propagation, State, Tooltip,
+    TooltipEvent, TooltipLabel, Visibility,
+};
+
+// Called when the hovered entity changes. Starts the timer for the tooltip of the hovered entity,
+// or of its nearest ancestor with one, unless the mouse is still over the same tooltip owner.
+pub fn hover_tooltip(state: &mut State) {
+    let owner = state
+        .hovered
+        .parent_iter(&state.hierarchy)
+        .find(|entity| state.style.tooltip.get(*entity).is_some())
+        .unwrap_or_else(Entity::null);
+
+    if owner == state.tooltips.owner {
+        return;
+    }
+
+    hide_tooltip(state);
+
+    state.tooltips.owner = owner;
+
+    if owner != Entity::null() {
+        let timer = state.set_timer(
+            state.tooltips.delay,
+            Event::new(TooltipEvent::Show(owner))
+                .target(state.root)
+                .propagate(Propagation::Direct),
+        );
+
+        state.tooltips.timer = Some(timer);
+    }
+}
+
+// Shows the tooltip of an entity at the cursor if the mouse is still over it
+pub fn show_tooltip(state: &mut State, owner: Entity) {
+    state.tooltips.timer = None;
+
+    if owner != state.tooltips.owner || !state.is_alive(owner) {
+        return;
+    }
+
+    let widget = match state.style.tooltip.get(owner).cloned() {
+        Some(Tooltip::Text(text)) => {
+            if text.is_empty() {
+                return;
+            }
+
+            if !state.is_alive(state.tooltips.label) {
+                let label = state.add(state.root);
+                state.build(label, TooltipLabel);
+                state.style.insert_element(label, "tooltip");
+                state.style.visibility.insert(label, Visibility::Invisible);
+                state.transform.set_hoverability(label, false);
+
+                state.tooltips.label = label;
+            }
+
+            state.tooltips.label.set_text(state, &text)
+        }
+
+        Some(Tooltip::Widget(widget)) => {
+            if !state.is_alive(widget) {
+                return;
+            }
+
+            // The mouse passes through a tooltip so that it doesn't take the hover from its owner
+            let branch: Vec<Entity> = widget
+                .into_iter(&state.hierarchy)
+                .take_while(|entity| *entity == widget || entity.is_descendant_of(&state.hierarchy, widget))
+                .collect();
+            for entity in branch {
+                let hoverability = state.transform.get_hoverability(entity);
+                state.tooltips.hoverability.push((entity, hoverability));
+                state.transform.set_hoverability(entity, false);
+            }
+
+            widget
+        }
+
+        None => return,
+    };
+
+    state.tooltips.shown = widget;
+
+    let x = state.mouse.cursorx + state.tooltips.offset.0;
+    let y = state.mouse.cursory + state.tooltips.offset.1;
+
+    // Placed like a popup so that it's drawn above everything and kept inside the window
+    state.open_overlay(
+        widget,
+        Overlay::popup(owner, Placement::At(x, y)).with_light_dismiss(false),
+    );
+}
+
+// Hides the shown tooltip and stops one waiting to be shown. Returns true if a tooltip was shown.
+// The owner is kept, so the tooltip isn't shown again until the mouse moves to another entity.
+pub fn hide_tooltip(state: &mut State) -> bool {
+    if let Some(timer) = state.tooltips.timer.take() {
+        state.cancel_timer(timer);
+    }
+
+    let shown = state.tooltips.shown;
+    state.tooltips.shown = Entity::null();
+
+    for (entity, hoverability) in std::mem::take(&mut state.tooltips.hoverability) {
+        state.transform.set_hoverability(entity, hoverability);
+    }
+
+    if state.overlays.is_open(shown) {
+        state.close_overlay(shown);
+        return true;
+    }
+
+    false
+}
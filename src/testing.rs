@@ -0,0 +1,179 @@
+// A driver for testing widgets without a window.
+// Input is simulated through the same functions used by the application event loop
+// and every event which is sent to a handler is recorded so that tests can assert on it.
+
+use crate::application::init_root;
+use crate::events::input::*;
+use crate::events::{Event, EventManager, Message};
+use crate::state::mouse::{MouseButton, MouseButtonState};
+use crate::{Entity, State, VirtualKeyCode, WindowEvent};
+
+use std::time::Duration;
+
+pub struct TestDriver {
+    pub state: State,
+    pub event_manager: EventManager,
+    // Events which have been sent to handlers, in the order they were processed
+    pub events: Vec<Event>,
+}
+
+impl TestDriver {
+    pub fn new<F: FnMut(&mut State, Entity)>(width: f32, height: f32, mut app: F) -> Self {
+        let mut state = State::new();
+
+        let event_manager = EventManager::new();
+
+        let root = state.root;
+        state.hierarchy.add(state.root, None);
+
+        app(&mut state, root);
+
+        init_root(&mut state, width, height);
+
+        state.insert_event(Event::new(WindowEvent::Restyle));
+        state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
+
+        let mut driver = TestDriver {
+            state,
+            event_manager,
+            events: Vec::new(),
+        };
+
+        driver.flush();
+
+        driver
+    }
+
+    pub fn get_root(&self) -> Entity {
+        self.state.root
+    }
+
+    // Processes events until the queue is empty, returning true if a redraw was requested
+    pub fn flush(&mut self) -> bool {
+        let mut needs_redraw = false;
+
+        while !self.state.event_queue.is_empty() {
+            if self.event_manager.process_events(&mut self.state) {
+                needs_redraw = true;
+            }
+
+            self.events
+                .extend(self.event_manager.event_queue.iter().cloned());
+        }
+
+        needs_redraw
+    }
+
+    // Moves the time forward, sending the events of any timers which become due
+    pub fn advance(&mut self, duration: Duration) {
+        let now = self.state.timers.now() + duration;
+        self.state.fire_timers(now);
+        self.flush();
+    }
+
+    // Mouse
+
+    pub fn move_mouse(&mut self, x: f32, y: f32) {
+        cursor_moved(&mut self.state, x, y);
+        self.flush();
+    }
+
+    pub fn press(&mut self, button: MouseButton) {
+        mouse_input(&mut self.state, button, MouseButtonState::Pressed);
+        self.flush();
+    }
+
+    pub fn release(&mut self, button: MouseButton) {
+        mouse_input(&mut self.state, button, MouseButtonState::Released);
+        self.flush();
+    }
+
+    // Moves the mouse to the given point and clicks the left button
+    pub fn click_at(&mut self, x: f32, y: f32) {
+        self.move_mouse(x, y);
+        self.press(MouseButton::Left);
+        self.release(MouseButton::Left);
+    }
+
+    // Clicks the left button in the centre of an entity
+    pub fn click(&mut self, entity: Entity) {
+        let (x, y) = self.center(entity);
+        self.click_at(x, y);
+    }
+
+    // Presses the left button at one point, moves to another and releases it
+    pub fn drag(&mut self, from: (f32, f32), to: (f32, f32)) {
+        self.move_mouse(from.0, from.1);
+        self.press(MouseButton::Left);
+        self.move_mouse(to.0, to.1);
+        self.release(MouseButton::Left);
+    }
+
+    pub fn scroll(&mut self, x: f32, y: f32) {
+        mouse_scroll(&mut self.state, x, y);
+        self.flush();
+    }
+
+    // Returns the entity under the given point without changing the hovered entity
+    pub fn entity_at(&mut self, x: f32, y: f32) -> Entity {
+        hit_test(&mut self.state, x, y)
+    }
+
+    // Keyboard
+
+    pub fn key_down(&mut self, key: VirtualKeyCode) {
+        keyboard_input(&mut self.state, Some(key), MouseButtonState::Pressed);
+        self.flush();
+    }
+
+    pub fn key_up(&mut self, key: VirtualKeyCode) {
+        keyboard_input(&mut self.state, Some(key), MouseButtonState::Released);
+        self.flush();
+    }
+
+    pub fn press_key(&mut self, key: VirtualKeyCode) {
+        self.key_down(key);
+        self.key_up(key);
+    }
+
+    // Sends each character to the focused entity as if it were typed
+    pub fn type_text(&mut self, text: &str) {
+        for c in text.chars() {
+            char_input(&mut self.state, c);
+            self.flush();
+        }
+    }
+
+    pub fn resize(&mut self, width: f32, height: f32) {
+        window_resized(&mut self.state, width, height);
+        self.flush();
+    }
+
+    // Assertions
+
+    // Returns the messages of a given type which have been recorded, in order
+    pub fn events_of<M: Message + Clone>(&self) -> Vec<M> {
+        self.events
+            .iter()
+            .filter_map(|event| event.message.as_any().downcast_ref::<M>())
+            .cloned()
+            .collect()
+    }
+
+    pub fn clear_events(&mut self) {
+        self.events.clear();
+    }
+
+    // Returns the centre of an entity's bounds, where it appears in the window after it is transformed
+    pub fn center(&self, entity: Entity) -> (f32, f32) {
+        let posx = self.state.transform.get_posx(entity);
+        let posy = self.state.transform.get_posy(entity);
+        let width = self.state.transform.get_width(entity);
+        let height = self.state.transform.get_height(entity);
+
+        self.state
+            .transform
+            .get_matrix(entity)
+            .transform_point(posx + width / 2.0, posy + height / 2.0)
+    }
+}
@@ -4,6 +4,7 @@ use crate::entity::Entity;
 use crate::mouse::*;
 use crate::{BuildHandler, Event, EventHandler, Propagation, WindowEvent};
 use crate::{PropSet, State};
+use crate::Bind;
 
 use crate::style::layout::{Align, Justify};
 
@@ -245,3 +246,31 @@ impl EventHandler for Checkbox {
         false
     }
 }
+
+impl Bind<bool> for Checkbox {
+    fn set_value(state: &mut State, entity: Entity, value: &bool) {
+        let message = if *value {
+            CheckboxEvent::Check
+        } else {
+            CheckboxEvent::Uncheck
+        };
+
+        state.insert_event(
+            Event::new(message)
+                .target(entity)
+                .propagate(Propagation::Direct),
+        );
+    }
+
+    fn value_changed(entity: Entity, event: &Event) -> Option<bool> {
+        if event.target != entity {
+            return None;
+        }
+
+        match event.message.as_any().downcast_ref::<CheckboxEvent>() {
+            Some(CheckboxEvent::Checked) => Some(true),
+            Some(CheckboxEvent::Unchecked) => Some(false),
+            _ => None,
+        }
+    }
+}
@@ -2,7 +2,10 @@
 
 use crate::entity::Entity;
 use crate::mouse::*;
-use crate::{AnimationState, BuildHandler, Event, EventHandler, Propagation, WindowEvent};
+use crate::{
+    AnimationState, BuildHandler, Event, EventHandler, Overlay, OverlayEvent, Placement, Propagation,
+    WindowEvent,
+};
 use crate::{PropSet, State};
 
 use crate::state::style::*;
@@ -163,15 +166,14 @@ impl BuildHandler for Dropdown {
 
         //self.other_container = Button::new().build(state, self.container, |builder| builder.set_flex_grow(1.0).set_opacity(0.0).class("other"));
 
+        // The container is shown as an overlay below the dropdown while it is open
         self.container = RadioList::new("").build(state, entity, |builder| {
             builder
                 .set_position(Position::Absolute)
-                .set_top(Length::Percentage(1.0))
                 //.set_width(Length::Percentage(1.0))
                 //.set_height(Length::Pixels(0.0))
                 .set_opacity(0.0)
-                .set_z_order(1)
-                .set_clip_widget(Entity::new(0, 0))
+                .set_visibility(Visibility::Invisible)
                 //.set_background_color(Color::rgb(100, 50, 50))
                 .class("container")
         });
@@ -222,6 +224,32 @@ impl BuildHandler for Dropdown {
     }
 }
 
+impl Dropdown {
+    fn open(&mut self, state: &mut State, entity: Entity) {
+        state.open_overlay(self.container, Overlay::popup(entity, Placement::Below));
+
+        self.open = true;
+
+        self.header.set_enabled(state, true);
+
+        state
+            .style
+            .opacity
+            .play_animation(self.container, self.fade_in_animation);
+
+        self.container.set_opacity(state, 1.0);
+    }
+
+    // Called once the container has been closed
+    fn closed(&mut self, state: &mut State) {
+        self.open = false;
+
+        self.header.set_disabled(state, true);
+
+        self.container.set_opacity(state, 0.0);
+    }
+}
+
 impl EventHandler for Dropdown {
     fn on_event(&mut self, state: &mut State, entity: Entity, event: &mut Event) -> bool {
         if let Some(dropdown_event) = event.message.downcast::<DropdownEvent>() {
@@ -232,7 +260,7 @@ impl EventHandler for Dropdown {
                     //Check here if it's an event from a child (TODO)
                     self.header.set_text(state, proxy);
                     //self.container.set_visibility(state, Visibility::Invisible);
-                    self.open = false;
+                    state.close_overlay(self.container);
                     //state.style.height.play_animation(self.container, self.collapse_animation);
                     //state.style.opacity.play_animation(self.other_container, self.fade_out_animation);
                     // Temp until persistent animations work
@@ -252,6 +280,12 @@ impl EventHandler for Dropdown {
             //}
         }
 
+        if let Some(OverlayEvent::Closed(container)) = event.message.downcast::<OverlayEvent>() {
+            if *container == self.container {
+                self.closed(state);
+            }
+        }
+
         if let Some(window_event) = event.message.downcast::<WindowEvent>() {
             match window_event {
                 // WindowEvent::Relayout => {
@@ -275,80 +309,23 @@ impl EventHandler for Dropdown {
                 // }
                 WindowEvent::MouseDown(button) => match button {
                     MouseButton::Left => {
-                        
                         if event.target == entity || event.target == self.header {
-
-                            
-
-                            if state.hovered.is_child_of(&state.hierarchy, self.container) {
-                                state.insert_event(
-                                Event::new(WindowEvent::MouseDown(*button))
-                                    .target(state.hovered)
-                                    .propagate(Propagation::Direct),
-                                );
-                            }
-                            
-                            
-                            
                             return true;
-                            
                         }
-                        
                     }
                     _ => {}
                 },
 
-                WindowEvent::MouseCaptureOutEvent => {
-                    
-                    self.open = false;
-
-                    self.header.set_disabled(state, true);
-
-                    state
-                        .style
-                        .opacity
-                        .play_animation(self.container, self.fade_out_animation);
-                    //state.style.opacity.play_animation(self.other_container, self.fade_out_animation);
-
-                    //self.container.set_height(state, Length::Pixels(0.0));
-                    self.container.set_opacity(state, 0.0);
-                }
-
-                WindowEvent::MouseCaptureEvent => {
-
-                    self.open = true;
-                    
-                    self.header.set_enabled(state, true);
-
-                    state
-                        .style
-                        .opacity
-                        .play_animation(self.container, self.fade_in_animation);
-                    //state.style.opacity.play_animation(self.other_container, self.fade_in_animation);
-
-                    //self.container.set_height(state, Length::Pixels(self.container_height));
-                    self.container.set_opacity(state, 1.0);
-                    // Shouldn't need to do this but it's required for some reason. TODO: Investigate
-                    self.container.set_z_order(state, 1);
-                }
-
-                
                 WindowEvent::MouseUp(button) => match button {
                     MouseButton::Left => {
-
                         if event.target == entity || event.target == self.header {
                             if state.mouse.left.pressed == state.hovered {
                                 if !self.open {
-                                    state.capture(entity);
+                                    self.open(state, entity);
                                 } else {
-                                    state.release(entity);
+                                    state.close_overlay(self.container);
                                 }
 
-                                state.insert_event(
-                                    Event::new(WindowEvent::MouseUp(*button))
-                                        .target(state.hovered)
-                                        .propagate(Propagation::Direct));
-
                                 return true;
                             }
                         }
@@ -3,7 +3,8 @@
 use crate::entity::Entity;
 use crate::mouse::*;
 use crate::{
-    BuildHandler, Event, EventHandler, HierarchyTree, Propagation, WidgetEvent, WindowEvent,
+    BuildHandler, Event, EventHandler, HierarchyTree, Overlay, OverlayEvent, Placement, Propagation,
+    WidgetEvent, WindowEvent,
 };
 use crate::{PropSet, State};
 
@@ -29,7 +30,8 @@ pub enum MenuEvent {
 
 #[derive(Debug, Copy, Clone)]
 pub enum MenuPosition {
-    Auto, // TODO
+    // Down for a menu in the window and right for a menu inside another menu, flipped if there isn't room
+    Auto,
     Down,
     Right,
 }
@@ -73,38 +75,48 @@ impl BuildHandler for Menu {
             .set_text(state, &self.text)
             .set_flex_direction(state, FlexDirection::Column);
 
+        // The container is shown as an overlay while the menu is open
         self.container = Button::new().build(state, entity, |builder| {
             builder
                 .set_flex_direction(FlexDirection::Column)
                 .set_position(Position::Absolute)
+                .set_visibility(Visibility::Invisible)
                 .class("container")
         });
 
-        match self.menu_position {
-            MenuPosition::Down => {
-                self.container.set_top(state, Length::Percentage(1.0));
-            }
-
-            MenuPosition::Right => {
-                self.container.set_left(state, Length::Percentage(1.0));
-            }
-
-            _ => {}
-        }
-
         //state.style.checked.set(entity, false);
         entity.set_checked(state, false);
 
-        state.style.z_order.set(self.container, 1);
-
-        //self.container.set_visibility(state, Visibility::Invisible);
-
         state.style.insert_element(entity, "menu");
 
         self.container
     }
 }
 
+// Returns true if the menu is inside another open menu
+fn is_nested(state: &State, entity: Entity) -> bool {
+    state
+        .overlays
+        .iter()
+        .any(|overlay| entity.is_descendant_of(&state.hierarchy, overlay.entity))
+}
+
+impl Menu {
+    fn placement(&self, state: &State, entity: Entity) -> Placement {
+        match self.menu_position {
+            MenuPosition::Down => Placement::Below,
+            MenuPosition::Right => Placement::Right,
+            MenuPosition::Auto => {
+                if is_nested(state, entity) {
+                    Placement::Right
+                } else {
+                    Placement::Below
+                }
+            }
+        }
+    }
+}
+
 impl EventHandler for Menu {
     // fn add_child(&mut self, child: Entity, state: &mut State) {
     //     if child == self.container {
@@ -128,39 +140,24 @@ impl EventHandler for Menu {
     fn on_event(&mut self, state: &mut State, entity: Entity, event: &mut Event) -> bool {
         if let Some(menu_event) = event.message.downcast::<MenuEvent>() {
             match menu_event {
-                MenuEvent::Open(_id) => {
-                    println!("Open Menu");
-                    state.capture(entity);
-                    //state.style.checked.set(entity, true);
-                    entity.set_checked(state, true);
-                    self.open = true;
+                MenuEvent::Open(id) => {
+                    if *id == entity {
+                        let placement = self.placement(state, entity);
+                        state.open_overlay(self.container, Overlay::popup(entity, placement));
+                        entity.set_checked(state, true);
+                        self.open = true;
+                    }
                 }
 
+                // Closing the container also closes any menus opened from it
                 MenuEvent::Close(id) => {
-                    println!("Close Menu");
                     if *id == entity {
-                        if entity.parent(&state.hierarchy).unwrap() == state.root {
-                            state.release(entity);
-                        }
-                        //state.style.checked.set(entity, false);
-                        entity.set_checked(state, false);
-                        self.open = false;
-                    } else {
-                        state.capture(entity);
+                        state.close_overlay(self.container);
                     }
                 }
 
-                MenuEvent::CloseAll(_entity) => {
-                    println!("close menu: {:?}", entity);
-                    if let Some(_visibility) = state.style.visibility.get(self.container) {
-                        //self.container.set_visibility(state, Visibility::Invisible);
-                    }
-
-                    //state.style.checked.set(entity, false);
-                    entity.set_checked(state, false);
-                    self.open = false;
-
-                    state.release(entity);
+                MenuEvent::CloseAll(_) => {
+                    state.close_overlay(self.container);
                 }
 
                 MenuEvent::OpenHover(val) => {
@@ -171,112 +168,50 @@ impl EventHandler for Menu {
             state.insert_event(Event::new(WindowEvent::Restyle));
         }
 
+        if let Some(OverlayEvent::Closed(container)) = event.message.downcast::<OverlayEvent>() {
+            if *container == self.container {
+                entity.set_checked(state, false);
+                self.open = false;
+            }
+        }
+
         if let Some(window_event) = event.message.downcast::<WindowEvent>() {
             match window_event {
-                WindowEvent::MouseDown(button) => match button {
-                    MouseButton::Left => {
-                        if state.hovered == entity {
-                            if self.open {
-                                state.insert_event(
-                                    Event::new(MenuEvent::Close(entity))
-                                        .target(entity)
-                                        .propagate(Propagation::Direct),
-                                );
-                            } else {
-                                state.insert_event(
-                                    Event::new(MenuEvent::Open(entity))
-                                        .target(entity)
-                                        .propagate(Propagation::Direct),
-                                );
-                            }
-
-                            // if let Some(visibility) = state.style.visibility.get(self.container) {
-                            //     match visibility {
-                            //         Visibility::Invisible => {
-                            //             state.insert_event(
-                            //                 Event::new(MenuEvent::Open(entity))
-                            //                     .target(entity)
-                            //                     .propagate(Propagation::None),
-                            //             );
-                            //         }
-
-                            //         Visibility::Visible => {
-                            //             state.insert_event(
-                            //                 Event::new(MenuEvent::Close(entity))
-                            //                     .target(entity)
-                            //                     .propagate(Propagation::None),
-                            //             );
-                            //         }
-                            //     }
-                            // }
-
-                            state.insert_event(Event::new(WindowEvent::Restyle));
-                        } else {
-                            // state.insert_event(
-                            //     Event::new(WindowEvent::MouseDown(*button, *mods))
-                            //         .target(state.hovered)
-                            //         .propagate(false),
-                            // );
-
-                            if event.target == entity {
-                                for entity in self.container.child_iter(&state.hierarchy) {
-                                    if entity == state.hovered {
-                                        // Forward the mouse down event to the option that's hovered
-                                        state.insert_event(
-                                            Event::new(WindowEvent::MouseDown(*button))
-                                                .target(state.hovered)
-                                                .propagate(Propagation::Direct),
-                                        );
-
-                                        return false;
-                                    }
-                                }
-
-                                state.insert_event(
-                                    Event::new(MenuEvent::CloseAll(entity)).target(entity),
-                                );
-                                state.insert_event(Event::new(WindowEvent::Restyle));
-                            }
-                        }
+                // A menu inside another menu stays open when it's pressed again
+                WindowEvent::MouseDown(MouseButton::Left) if event.target == entity => {
+                    if !self.open {
+                        state.insert_event(
+                            Event::new(MenuEvent::Open(entity))
+                                .target(entity)
+                                .propagate(Propagation::Direct),
+                        );
+                    } else if !is_nested(state, entity) {
+                        state.insert_event(
+                            Event::new(MenuEvent::Close(entity))
+                                .target(entity)
+                                .propagate(Propagation::Direct),
+                        );
                     }
-                    _ => {}
-                },
-
-                WindowEvent::MouseUp(button) => {
-                    match button {
-                        MouseButton::Left => {
-                            for entity in self.container.child_iter(&state.hierarchy) {
-                                if entity == state.hovered {
-                                    // Forward the mouse down event to the option that's hovered
-                                    state.insert_event(
-                                        Event::new(WindowEvent::MouseUp(*button))
-                                            .target(state.hovered)
-                                            .propagate(Propagation::Direct),
-                                    );
-                                    //state.insert_event(Event::new(StyleEvent::Restyle));
-                                    //state.insert_event(Event::new(MenuEvent::Close(entity)).target(entity));
-                                    //state.captured = Entity::null();
-
-                                    state.insert_event(
-                                        Event::new(MenuEvent::CloseAll(entity)).target(entity),
-                                    );
-
-                                    return false;
-                                }
-                            }
-
-                            // for entity in self.container.child_iter(&state.hierarchy) {
-                            //     if entity == state.hovered {
-                            //         state.captured = Entity::null();
-                            //         self.container.set_visibility(state, Visibility::Invisible);
-                            //         //state.insert_event(Event::new(WindowEvent::MouseUp(*button, *mods)).target(state.hovered).propagate(false));
-                            //         state.insert_event(Event::new(StyleEvent::Restyle));
-                            //         return false;
-                            //     }
-                            // }
-                        }
-
-                        _ => {}
+
+                    state.insert_event(Event::new(WindowEvent::Restyle));
+                }
+
+                // Releasing the mouse over an item closes the menu, unless the item opened another menu
+                WindowEvent::MouseUp(MouseButton::Left) => {
+                    let opened_menu = state
+                        .overlays
+                        .iter()
+                        .any(|overlay| overlay.anchor == event.target);
+
+                    if self.open
+                        && event.target.is_descendant_of(&state.hierarchy, self.container)
+                        && !opened_menu
+                    {
+                        state.insert_event(
+                            Event::new(MenuEvent::Close(entity))
+                                .target(entity)
+                                .propagate(Propagation::Direct),
+                        );
                     }
                 }
 
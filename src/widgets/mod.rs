@@ -23,6 +23,9 @@ pub use tab::*;
 pub mod textbox;
 pub use textbox::*;
 
+pub mod textarea;
+pub use textarea::*;
+
 pub mod dropdown;
 pub use dropdown::*;
 
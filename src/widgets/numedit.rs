@@ -10,6 +10,7 @@ use crate::entity::Entity;
 use crate::events::*;
 use crate::state::style::*;
 use crate::{PropSet, State, WindowEvent};
+use crate::Bind;
 
 use crate::layout::{Align, Justify};
 
@@ -159,6 +160,20 @@ impl EventHandler for NumEdit {
                     }
                 }
 
+                NumEditEvent::SetValue(val) => {
+                    if event.target == entity {
+                        self.value = *val;
+
+                        let val_str = format!("{:.*}", 5, &self.value.to_string());
+
+                        self.textbox.set_text(state, &val_str);
+
+                        state.insert_event(
+                            Event::new(WindowEvent::Restyle).target(Entity::new(0, 0)),
+                        );
+                    }
+                }
+
                 _ => {}
             }
         }
@@ -205,3 +220,20 @@ impl EventHandler for NumEdit {
         false
     }
 }
+
+impl Bind<f32> for NumEdit {
+    fn set_value(state: &mut State, entity: Entity, value: &f32) {
+        state.insert_event(
+            Event::new(NumEditEvent::SetValue(*value))
+                .target(entity)
+                .propagate(Propagation::Direct),
+        );
+    }
+
+    fn value_changed(entity: Entity, event: &Event) -> Option<f32> {
+        match event.message.as_any().downcast_ref::<NumEditEvent>() {
+            Some(NumEditEvent::ValueChanged(value)) if event.target == entity => Some(*value),
+            _ => None,
+        }
+    }
+}
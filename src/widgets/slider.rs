@@ -4,6 +4,7 @@ use crate::entity::Entity;
 use crate::mouse::*;
 use crate::{BuildHandler, Event, EventHandler, Length, WindowEvent};
 use crate::{PropSet, State};
+use crate::{Bind, Propagation};
 
 use crate::state::style::*;
 
@@ -210,6 +211,9 @@ impl EventHandler for Slider {
                         state.insert_event(
                             Event::new(WindowEvent::Restyle).target(Entity::new(0, 0)),
                         );
+
+                        // Stop the wheel from also scrolling a parent
+                        return true;
                     }
                 }
 
@@ -221,6 +225,23 @@ impl EventHandler for Slider {
     }
 }
 
+impl Bind<f32> for Slider {
+    fn set_value(state: &mut State, entity: Entity, value: &f32) {
+        state.insert_event(
+            Event::new(SliderEvent::SetValue(entity, *value))
+                .target(entity)
+                .propagate(Propagation::Direct),
+        );
+    }
+
+    fn value_changed(entity: Entity, event: &Event) -> Option<f32> {
+        match event.message.as_any().downcast_ref::<SliderEvent>() {
+            Some(SliderEvent::ValueChanged(id, value)) if *id == entity => Some(*value),
+            _ => None,
+        }
+    }
+}
+
 // #![allow(dead_code)]
 
 // use crate::component::storage::Storage;
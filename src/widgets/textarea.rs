@@ -0,0 +1,861 @@
+// A multi-line text editor. The text is wrapped as with white-space: pre-wrap so the height of the widget
+// grows with its lines, which lets it scroll inside a ScrollContainer.
+// The caret and the other end of the selection are byte offsets into the text.
+
+use crate::entity::Entity;
+use crate::events::*;
+use crate::mouse::*;
+use crate::systems::layout_system::pixels;
+use crate::widgets::ScrollContainerEvent;
+use crate::{break_lines, text_measure, LineRange};
+use crate::{Bind, Length, PropSet, State, Visibility, WhiteSpace, WindowEvent};
+
+use glutin::event::VirtualKeyCode;
+
+use femtovg::{renderer::OpenGl, Align, Baseline, Canvas, Color, Paint, Path};
+
+use std::time::{Duration, Instant};
+
+// Clicks in the same place within this time of each other count as double and triple clicks
+const MULTI_CLICK_TIME: Duration = Duration::from_millis(500);
+const MULTI_CLICK_DISTANCE: f32 = 4.0;
+
+// The oldest undo steps are dropped past this many
+const MAX_UNDO: usize = 100;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TextAreaEvent {
+    SetValue(String),
+    ValueChanged(String),
+    Undo,
+    Redo,
+}
+
+// Consecutive edits of the same kind are undone in one step, except for edits of kind None such as pastes
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum EditKind {
+    None,
+    Insert,
+    Delete,
+}
+
+// The text and selection from before an edit
+#[derive(Debug, Clone)]
+struct Snapshot {
+    text: String,
+    cursor: usize,
+    anchor: usize,
+}
+
+pub struct TextArea {
+    text: String,
+
+    cursor: usize,
+    anchor: usize,
+    // The x position kept when moving up and down through lines of different lengths
+    preferred_x: Option<f32>,
+
+    dragging: bool,
+    click_count: u32,
+    click_time: Option<Instant>,
+    click_pos: (f32, f32),
+
+    undo_stack: Vec<Snapshot>,
+    redo_stack: Vec<Snapshot>,
+    last_edit: EditKind,
+
+    // Set by an edit so that the caret is scrolled into view once the layout has caught up
+    follow_caret: bool,
+}
+
+impl TextArea {
+    pub fn new(text: &str) -> Self {
+        TextArea {
+            text: text.to_string(),
+
+            cursor: text.len(),
+            anchor: text.len(),
+            preferred_x: None,
+
+            dragging: false,
+            click_count: 0,
+            click_time: None,
+            click_pos: (0.0, 0.0),
+
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+            last_edit: EditKind::None,
+
+            follow_caret: false,
+        }
+    }
+
+    // Keeps the caret and anchor on character boundaries of the text, which can be replaced with
+    // set_text without going through SetValue
+    fn clamp_to(&mut self, text: &str) {
+        self.cursor = floor_boundary(text, self.cursor);
+        self.anchor = floor_boundary(text, self.anchor);
+    }
+
+    // Returns the start and end of the selection in order
+    fn selection(&self) -> (usize, usize) {
+        (self.cursor.min(self.anchor), self.cursor.max(self.anchor))
+    }
+
+    // Moves the caret, extending the selection from the anchor if select is true
+    fn move_to(&mut self, state: &mut State, entity: Entity, index: usize, select: bool) {
+        self.cursor = index;
+
+        if !select {
+            self.anchor = index;
+        }
+
+        self.preferred_x = None;
+        self.last_edit = EditKind::None;
+
+        self.scroll_to_caret(state, entity);
+
+        state.insert_event(Event::new(WindowEvent::Redraw));
+    }
+
+    // Moves the caret by a number of lines, keeping as close as possible to the x position it started from
+    fn move_lines(&mut self, state: &mut State, entity: Entity, lines_moved: isize, select: bool) {
+        let text = get_text(state, entity);
+        let (lines, _) = layout(state, entity, &text);
+
+        let line = line_of(&lines, self.cursor);
+
+        let x = match self.preferred_x {
+            Some(x) => x,
+            None => caret_x(state, entity, &text, &lines[line], self.cursor),
+        };
+
+        let target = line as isize + lines_moved;
+
+        let index = if target < 0 {
+            0
+        } else if target as usize >= lines.len() {
+            text.len()
+        } else {
+            index_at(state, entity, &text, &lines, target as usize, x)
+        };
+
+        self.move_to(state, entity, index, select);
+
+        self.preferred_x = Some(x);
+    }
+
+    // Replaces the text between start and end, keeping the previous text for undo
+    fn replace(
+        &mut self,
+        state: &mut State,
+        entity: Entity,
+        start: usize,
+        end: usize,
+        value: &str,
+        kind: EditKind,
+    ) {
+        let mut text = get_text(state, entity);
+
+        if kind == EditKind::None || kind != self.last_edit {
+            self.undo_stack.push(Snapshot {
+                text: text.clone(),
+                cursor: self.cursor,
+                anchor: self.anchor,
+            });
+
+            if self.undo_stack.len() > MAX_UNDO {
+                self.undo_stack.remove(0);
+            }
+        }
+
+        self.redo_stack.clear();
+        self.last_edit = kind;
+
+        text.replace_range(start..end, value);
+
+        self.cursor = start + value.len();
+        self.anchor = self.cursor;
+        self.preferred_x = None;
+
+        self.set_text(state, entity, text);
+    }
+
+    // Replaces the selection, or inserts at the caret if nothing is selected
+    fn insert(&mut self, state: &mut State, entity: Entity, value: &str) {
+        let (start, end) = self.selection();
+        self.replace(state, entity, start, end, value, EditKind::Insert);
+    }
+
+    // Deletes the selection, or the character before or after the caret if nothing is selected
+    fn delete(&mut self, state: &mut State, entity: Entity, forward: bool) {
+        let text = get_text(state, entity);
+
+        let (start, end) = match self.selection() {
+            (start, end) if start != end => (start, end),
+            (index, _) if forward => (index, next_boundary(&text, index)),
+            (index, _) => (previous_boundary(&text, index), index),
+        };
+
+        if start != end {
+            self.replace(state, entity, start, end, "", EditKind::Delete);
+        }
+    }
+
+    fn undo(&mut self, state: &mut State, entity: Entity) {
+        if let Some(snapshot) = self.undo_stack.pop() {
+            self.redo_stack.push(Snapshot {
+                text: get_text(state, entity),
+                cursor: self.cursor,
+                anchor: self.anchor,
+            });
+
+            self.restore(state, entity, snapshot);
+        }
+    }
+
+    fn redo(&mut self, state: &mut State, entity: Entity) {
+        if let Some(snapshot) = self.redo_stack.pop() {
+            self.undo_stack.push(Snapshot {
+                text: get_text(state, entity),
+                cursor: self.cursor,
+                anchor: self.anchor,
+            });
+
+            self.restore(state, entity, snapshot);
+        }
+    }
+
+    fn restore(&mut self, state: &mut State, entity: Entity, snapshot: Snapshot) {
+        self.cursor = snapshot.cursor;
+        self.anchor = snapshot.anchor;
+        self.preferred_x = None;
+        self.last_edit = EditKind::None;
+
+        self.set_text(state, entity, snapshot.text);
+    }
+
+    fn set_text(&mut self, state: &mut State, entity: Entity, text: String) {
+        entity.set_text(state, &text);
+
+        self.follow_caret = true;
+
+        state.insert_event(Event::new(TextAreaEvent::ValueChanged(text)).target(entity));
+    }
+
+    // Asks an enclosing ScrollContainer to show the line with the caret
+    fn scroll_to_caret(&self, state: &mut State, entity: Entity) {
+        let text = get_text(state, entity);
+        let (lines, line_height) = layout(state, entity, &text);
+        let (_, posy, _, _) = content_box(state, entity);
+
+        let top = posy + line_of(&lines, self.cursor) as f32 * line_height;
+
+        state.insert_event(
+            Event::new(ScrollContainerEvent::ScrollIntoView(top, top + line_height))
+                .target(entity)
+                .propagate(Propagation::Up),
+        );
+    }
+
+    // Returns the caret position under a point
+    fn index_at_point(&self, state: &mut State, entity: Entity, x: f32, y: f32) -> usize {
+        // The text is laid out before the entity is transformed
+        let (x, y) = state.transform.to_local(entity, x, y).unwrap_or((x, y));
+
+        let text = get_text(state, entity);
+        let (lines, line_height) = layout(state, entity, &text);
+        let (posx, posy, _, _) = content_box(state, entity);
+
+        let line = ((y - posy) / line_height).floor().max(0.0) as usize;
+
+        index_at(state, entity, &text, &lines, line.min(lines.len() - 1), x - posx)
+    }
+
+    // Counts clicks in the same place in quick succession, going back to one after a triple click
+    fn count_click(&mut self, now: Instant, x: f32, y: f32) -> u32 {
+        let repeated = match self.click_time {
+            Some(time) => {
+                now.duration_since(time) < MULTI_CLICK_TIME
+                    && (x - self.click_pos.0).abs() <= MULTI_CLICK_DISTANCE
+                    && (y - self.click_pos.1).abs() <= MULTI_CLICK_DISTANCE
+            }
+            None => false,
+        };
+
+        self.click_count = if repeated && self.click_count < 3 {
+            self.click_count + 1
+        } else {
+            1
+        };
+
+        self.click_time = Some(now);
+        self.click_pos = (x, y);
+
+        self.click_count
+    }
+
+    fn on_key_down(&mut self, state: &mut State, entity: Entity, key: VirtualKeyCode) {
+        let shift = state.modifiers.shift;
+        let ctrl = state.modifiers.ctrl;
+        let shortcut = state.modifiers.shortcut();
+
+        let text = get_text(state, entity);
+
+        match key {
+            VirtualKeyCode::Left => {
+                let (start, end) = self.selection();
+                if start != end && !shift {
+                    self.move_to(state, entity, start, false);
+                } else {
+                    self.move_to(state, entity, previous_boundary(&text, self.cursor), shift);
+                }
+            }
+
+            VirtualKeyCode::Right => {
+                let (start, end) = self.selection();
+                if start != end && !shift {
+                    self.move_to(state, entity, end, false);
+                } else {
+                    self.move_to(state, entity, next_boundary(&text, self.cursor), shift);
+                }
+            }
+
+            VirtualKeyCode::Up => self.move_lines(state, entity, -1, shift),
+
+            VirtualKeyCode::Down => self.move_lines(state, entity, 1, shift),
+
+            VirtualKeyCode::PageUp => {
+                let page = page_lines(state, entity);
+                self.move_lines(state, entity, -page, shift);
+            }
+
+            VirtualKeyCode::PageDown => {
+                let page = page_lines(state, entity);
+                self.move_lines(state, entity, page, shift);
+            }
+
+            VirtualKeyCode::Home => {
+                if ctrl {
+                    self.move_to(state, entity, 0, shift);
+                } else {
+                    let (lines, _) = layout(state, entity, &text);
+                    let start = lines[line_of(&lines, self.cursor)].start;
+                    self.move_to(state, entity, start, shift);
+                }
+            }
+
+            VirtualKeyCode::End => {
+                if ctrl {
+                    self.move_to(state, entity, text.len(), shift);
+                } else {
+                    let (lines, _) = layout(state, entity, &text);
+                    let end = line_end(&lines, &text, line_of(&lines, self.cursor));
+                    self.move_to(state, entity, end, shift);
+                }
+            }
+
+            VirtualKeyCode::Back => self.delete(state, entity, false),
+
+            VirtualKeyCode::Delete => self.delete(state, entity, true),
+
+            VirtualKeyCode::Return | VirtualKeyCode::NumpadEnter => self.insert(state, entity, "\n"),
+
+            VirtualKeyCode::A if shortcut => {
+                self.anchor = 0;
+                self.move_to(state, entity, text.len(), true);
+            }
+
+            VirtualKeyCode::C if shortcut => {
+                let (start, end) = self.selection();
+                if start != end {
+                    state.clipboard.set_text(&text[start..end]);
+                }
+            }
+
+            VirtualKeyCode::X if shortcut => {
+                let (start, end) = self.selection();
+                if start != end {
+                    state.clipboard.set_text(&text[start..end]);
+                    self.replace(state, entity, start, end, "", EditKind::None);
+                }
+            }
+
+            VirtualKeyCode::V if shortcut => {
+                if let Some(pasted) = state.clipboard.get_text() {
+                    // Line breaks are kept but other control characters can't be typed
+                    let pasted: String = pasted
+                        .replace("\r\n", "\n")
+                        .chars()
+                        .filter(|c| *c == '\n' || *c == '\t' || !c.is_control())
+                        .collect();
+
+                    let (start, end) = self.selection();
+                    self.replace(state, entity, start, end, &pasted, EditKind::None);
+                }
+            }
+
+            VirtualKeyCode::Z if shortcut && shift => self.redo(state, entity),
+
+            VirtualKeyCode::Z if shortcut => self.undo(state, entity),
+
+            VirtualKeyCode::Y if shortcut => self.redo(state, entity),
+
+            VirtualKeyCode::Escape => {
+                state.focused = state.root;
+                state.insert_event(Event::new(WindowEvent::Redraw));
+            }
+
+            _ => {}
+        }
+    }
+}
+
+impl BuildHandler for TextArea {
+    type Ret = Entity;
+    fn on_build(&mut self, state: &mut State, entity: Entity) -> Self::Ret {
+        entity
+            .set_text(state, &self.text)
+            .set_white_space(state, WhiteSpace::PreWrap);
+
+        state.style.insert_element(entity, "textarea");
+
+        entity
+    }
+}
+
+impl EventHandler for TextArea {
+    fn on_event(&mut self, state: &mut State, entity: Entity, event: &mut Event) -> bool {
+        if let Some(text) = state.style.text.get(entity) {
+            self.clamp_to(&text.text);
+        }
+
+        if let Some(textarea_event) = event.message.downcast::<TextAreaEvent>() {
+            if event.target == entity {
+                match textarea_event {
+                    TextAreaEvent::SetValue(value) => {
+                        // A new value starts a new history
+                        self.cursor = value.len();
+                        self.anchor = value.len();
+                        self.preferred_x = None;
+                        self.last_edit = EditKind::None;
+                        self.undo_stack.clear();
+                        self.redo_stack.clear();
+
+                        entity.set_text(state, value);
+                    }
+
+                    TextAreaEvent::Undo => self.undo(state, entity),
+
+                    TextAreaEvent::Redo => self.redo(state, entity),
+
+                    TextAreaEvent::ValueChanged(_) => {}
+                }
+            }
+        }
+
+        if let Some(window_event) = event.message.downcast::<WindowEvent>() {
+            match window_event {
+                WindowEvent::Relayout if self.follow_caret => {
+                    self.follow_caret = false;
+                    self.scroll_to_caret(state, entity);
+                }
+
+                WindowEvent::MouseDown(MouseButton::Left) if event.target == entity => {
+                    let (x, y) = (state.mouse.cursorx, state.mouse.cursory);
+                    let index = self.index_at_point(state, entity, x, y);
+
+                    state.focused = entity;
+
+                    match self.count_click(state.timers.now(), x, y) {
+                        1 => {
+                            let select = state.modifiers.shift;
+                            self.move_to(state, entity, index, select);
+                        }
+
+                        2 => {
+                            let (start, end) = word_at(&get_text(state, entity), index);
+                            self.anchor = start;
+                            self.move_to(state, entity, end, true);
+                        }
+
+                        _ => {
+                            let (start, end) = paragraph_at(&get_text(state, entity), index);
+                            self.anchor = start;
+                            self.move_to(state, entity, end, true);
+                        }
+                    }
+
+                    self.dragging = true;
+                    state.capture(entity);
+                }
+
+                WindowEvent::MouseMove(x, y) if self.dragging => {
+                    let index = self.index_at_point(state, entity, *x, *y);
+
+                    if index != self.cursor {
+                        self.move_to(state, entity, index, true);
+                    }
+                }
+
+                WindowEvent::MouseUp(MouseButton::Left) if self.dragging => {
+                    self.dragging = false;
+                    state.release(entity);
+                }
+
+                WindowEvent::KeyDown(Some(key)) if state.focused == entity => {
+                    self.on_key_down(state, entity, *key);
+                }
+
+                WindowEvent::CharInput(input) => {
+                    // Control characters are handled as key presses and shortcuts don't type
+                    let shortcut = state.modifiers.shortcut() && !state.modifiers.alt;
+
+                    if state.focused == entity && !input.is_control() && !shortcut {
+                        self.insert(state, entity, &input.to_string());
+                    }
+                }
+
+                _ => {}
+            }
+        }
+
+        false
+    }
+
+    fn on_draw(&mut self, state: &mut State, entity: Entity, canvas: &mut Canvas<OpenGl>) {
+        // Skip invisible widgets
+        if state.transform.get_visibility(entity) == Visibility::Invisible {
+            return;
+        }
+
+        let opacity = state.transform.get_opacity(entity);
+
+        if opacity == 0.0 {
+            return;
+        }
+
+        let posx = state.transform.get_posx(entity);
+        let posy = state.transform.get_posy(entity);
+        let width = state.transform.get_width(entity);
+        let height = state.transform.get_height(entity);
+
+        // Skip widgets with no width or no height
+        if width == 0.0 || height == 0.0 {
+            return;
+        }
+
+        let background_color = state
+            .style
+            .background_color
+            .get(entity)
+            .cloned()
+            .unwrap_or_default();
+
+        let border_color = state
+            .style
+            .border_color
+            .get(entity)
+            .cloned()
+            .unwrap_or_default();
+
+        let border_width = state
+            .style
+            .border_width
+            .get(entity)
+            .cloned()
+            .unwrap_or_default();
+
+        let font_color = state
+            .style
+            .font_color
+            .get(entity)
+            .cloned()
+            .unwrap_or(crate::Color::rgb(255, 255, 255));
+
+        let mut background_color: femtovg::Color = background_color.into();
+        background_color.set_alphaf(background_color.a * opacity);
+
+        let mut border_color: femtovg::Color = border_color.into();
+        border_color.set_alphaf(border_color.a * opacity);
+
+        let mut font_color: femtovg::Color = font_color.into();
+        font_color.set_alphaf(font_color.a * opacity);
+
+        let mut path = Path::new();
+        path.rect(posx, posy, width, height);
+        canvas.fill_path(&mut path, Paint::color(background_color));
+        let mut paint = Paint::color(border_color);
+        paint.set_line_width(border_width);
+        canvas.stroke_path(&mut path, paint);
+
+        let text = get_text(state, entity);
+        let (lines, line_height) = layout(state, entity, &text);
+        let (x, y, _, _) = content_box(state, entity);
+
+        self.clamp_to(&text);
+
+        let focused = state.focused == entity;
+        let (select_start, select_end) = self.selection();
+
+        // Selection and caret positions are measured from the start of each line
+        let mut selections = Vec::new();
+        let mut caret = None;
+
+        {
+            let (mut measure, _) = text_measure(state, entity);
+
+            for (index, line) in lines.iter().enumerate() {
+                let end = line_end(&lines, &text, index);
+                let top = y + index as f32 * line_height;
+
+                if select_start < select_end && select_start <= end && select_end >= line.start {
+                    let left = measure.width(&text[line.start..select_start.max(line.start)]);
+                    let right = measure.width(&text[line.start..select_end.min(end)]);
+                    selections.push((x + left, top, right - left));
+                }
+
+                if focused && line_of(&lines, self.cursor) == index {
+                    caret = Some((x + measure.width(&text[line.start..self.cursor]), top));
+                }
+            }
+        }
+
+        for (left, top, width) in selections {
+            let mut path = Path::new();
+            path.rect(left, top, width, line_height);
+            canvas.fill_path(&mut path, Paint::color(Color::rgba(0, 0, 0, 64)));
+        }
+
+        let font_family = state.style.font_family.get(entity).cloned().unwrap_or_default();
+        let font_weight = state.style.font_weight.get(entity).cloned().unwrap_or_default();
+        let font_style = state.style.font_style.get(entity).cloned().unwrap_or_default();
+        let font_size = state.style.font_size.get(entity).cloned().unwrap_or(14.0);
+        let letter_spacing = state.style.letter_spacing.get(entity).cloned().unwrap_or_default();
+
+        let font_ids = state.fonts.font_ids(
+            canvas,
+            &state.style.font_faces,
+            &font_family,
+            font_weight,
+            font_style,
+        );
+
+        let mut paint = Paint::color(font_color);
+        paint.set_font_size(font_size);
+        paint.set_font(&font_ids);
+        paint.set_letter_spacing(letter_spacing);
+        paint.set_text_align(Align::Left);
+        paint.set_text_baseline(Baseline::Middle);
+
+        for (index, line) in lines.iter().enumerate() {
+            let top = y + index as f32 * line_height;
+            let _ = canvas.fill_text(x, top + 0.5 * line_height, &text[line.start..line.end], paint.clone());
+        }
+
+        if let Some((left, top)) = caret {
+            let mut path = Path::new();
+            path.rect(left, top, 1.0, line_height);
+            canvas.fill_path(&mut path, Paint::color(Color::rgba(255, 192, 0, 255)));
+        }
+    }
+}
+
+impl Bind<String> for TextArea {
+    fn set_value(state: &mut State, entity: Entity, value: &String) {
+        state.insert_event(
+            Event::new(TextAreaEvent::SetValue(value.clone()))
+                .target(entity)
+                .propagate(Propagation::Direct),
+        );
+    }
+
+    fn value_changed(entity: Entity, event: &Event) -> Option<String> {
+        match event.message.as_any().downcast_ref::<TextAreaEvent>() {
+            Some(TextAreaEvent::ValueChanged(text)) if event.target == entity => Some(text.clone()),
+            _ => None,
+        }
+    }
+}
+
+// Returns the closest character boundary at or before an index
+fn floor_boundary(text: &str, index: usize) -> usize {
+    let mut index = index.min(text.len());
+
+    while !text.is_char_boundary(index) {
+        index -= 1;
+    }
+
+    index
+}
+
+fn get_text(state: &State, entity: Entity) -> String {
+    state
+        .style
+        .text
+        .get(entity)
+        .map(|text| text.text.clone())
+        .unwrap_or_default()
+}
+
+// Returns the position and size of the content box, inside the padding
+fn content_box(state: &State, entity: Entity) -> (f32, f32, f32, f32) {
+    let padding_left = pixels(state.style.padding_left.get(entity));
+    let padding_right = pixels(state.style.padding_right.get(entity));
+    let padding_top = pixels(state.style.padding_top.get(entity));
+    let padding_bottom = pixels(state.style.padding_bottom.get(entity));
+
+    (
+        state.transform.get_posx(entity) + padding_left,
+        state.transform.get_posy(entity) + padding_top,
+        state.transform.get_width(entity) - padding_left - padding_right,
+        state.transform.get_height(entity) - padding_top - padding_bottom,
+    )
+}
+
+// Breaks the text into lines which fit the content box, returning them with the line height
+fn layout(state: &mut State, entity: Entity, text: &str) -> (Vec<LineRange>, f32) {
+    let (_, _, width, _) = content_box(state, entity);
+    let (mut measure, line_height) = text_measure(state, entity);
+
+    (break_lines(&mut measure, text, width), line_height)
+}
+
+// Returns the line which shows the caret at an index
+fn line_of(lines: &[LineRange], index: usize) -> usize {
+    lines
+        .iter()
+        .rposition(|line| line.start <= index)
+        .unwrap_or(0)
+}
+
+// Returns the last caret position on a line, which is before the white space a wrapped line was broken at
+// or before the line break
+fn line_end(lines: &[LineRange], text: &str, line: usize) -> usize {
+    match lines.get(line + 1) {
+        Some(next) => previous_boundary(text, next.start),
+        None => text.len(),
+    }
+}
+
+// Returns the x position of the caret at an index, from the start of its line
+fn caret_x(state: &mut State, entity: Entity, text: &str, line: &LineRange, index: usize) -> f32 {
+    let (mut measure, _) = text_measure(state, entity);
+    measure.width(&text[line.start..index])
+}
+
+// Returns the caret position on a line closest to an x position from the start of the line
+fn index_at(
+    state: &mut State,
+    entity: Entity,
+    text: &str,
+    lines: &[LineRange],
+    line: usize,
+    x: f32,
+) -> usize {
+    let (mut measure, _) = text_measure(state, entity);
+
+    let start = lines[line].start;
+    let end = line_end(lines, text, line);
+
+    let mut left = 0.0;
+    let mut buffer = [0; 4];
+
+    for (offset, c) in text[start..end].char_indices() {
+        let advance = measure.width(c.encode_utf8(&mut buffer));
+
+        if x < left + 0.5 * advance {
+            return start + offset;
+        }
+
+        left += advance;
+    }
+
+    end
+}
+
+// Returns the number of lines which fit in the visible part of the widget
+fn page_lines(state: &mut State, entity: Entity) -> isize {
+    let (_, line_height) = text_measure(state, entity);
+
+    let clip_widget = state.transform.get_clip_widget(entity);
+
+    let top = state
+        .transform
+        .get_posy(entity)
+        .max(state.transform.get_posy(clip_widget));
+    let bottom = (state.transform.get_posy(entity) + state.transform.get_height(entity))
+        .min(state.transform.get_posy(clip_widget) + state.transform.get_height(clip_widget));
+
+    (((bottom - top) / line_height).floor() as isize).max(1)
+}
+
+fn previous_boundary(text: &str, index: usize) -> usize {
+    text[..index]
+        .char_indices()
+        .next_back()
+        .map(|(offset, _)| offset)
+        .unwrap_or(0)
+}
+
+fn next_boundary(text: &str, index: usize) -> usize {
+    text[index..]
+        .chars()
+        .next()
+        .map(|c| index + c.len_utf8())
+        .unwrap_or(index)
+}
+
+// Returns the word, run of spaces or single other character at an index
+fn word_at(text: &str, index: usize) -> (usize, usize) {
+    // Characters of the same class are selected together except for class 2
+    let class = |c: char| {
+        if c.is_alphanumeric() || c == '_' {
+            0
+        } else if c.is_whitespace() && c != '\n' {
+            1
+        } else {
+            2
+        }
+    };
+
+    // At the end of the text the word before is selected
+    let index = if index == text.len() {
+        previous_boundary(text, index)
+    } else {
+        index
+    };
+
+    let kind = match text[index..].chars().next() {
+        Some(c) if class(c) != 2 => class(c),
+        Some(c) => return (index, index + c.len_utf8()),
+        None => return (index, index),
+    };
+
+    let start = text[..index]
+        .char_indices()
+        .rev()
+        .take_while(|(_, c)| class(*c) == kind)
+        .last()
+        .map(|(offset, _)| offset)
+        .unwrap_or(index);
+
+    let end = text[index..]
+        .char_indices()
+        .find(|(_, c)| class(*c) != kind)
+        .map(|(offset, _)| index + offset)
+        .unwrap_or(text.len());
+
+    (start, end)
+}
+
+// Returns the paragraph at an index, without its line break
+fn paragraph_at(text: &str, index: usize) -> (usize, usize) {
+    let start = text[..index].rfind('\n').map(|offset| offset + 1).unwrap_or(0);
+    let end = text[index..]
+        .find('\n')
+        .map(|offset| index + offset)
+        .unwrap_or(text.len());
+
+    (start, end)
+}
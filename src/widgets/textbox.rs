@@ -4,6 +4,7 @@ use crate::entity::Entity;
 use crate::events::*;
 use crate::mouse::*;
 use crate::{BuildHandler, Justify, Length, PropSet, State, Visibility, WindowEvent, Window};
+use crate::Bind;
 
 use glutin::event::VirtualKeyCode;
 
@@ -127,9 +128,9 @@ impl EventHandler for Textbox {
 
         if let Some(window_event) = event.message.downcast::<WindowEvent>() {
             match window_event {
-                WindowEvent::MouseMove(x, _) => {
+                WindowEvent::MouseMove(x, y) => {
                     if self.hitx != -1.0 {
-                        self.dragx = *x;
+                        self.dragx = local_x(state, entity, *x, *y);
 
                         state.insert_event(
                             Event::new(WindowEvent::Restyle).target(Entity::new(0, 0)),
@@ -150,8 +151,9 @@ impl EventHandler for Textbox {
                             state.capture(entity);
                         }
                         if self.edit == true {
-                            self.hitx = state.mouse.cursorx;
-                            self.dragx = state.mouse.cursorx;
+                            let (x, y) = (state.mouse.cursorx, state.mouse.cursory);
+                            self.hitx = local_x(state, entity, x, y);
+                            self.dragx = self.hitx;
                         }
                         self.edit = true;
 
@@ -305,7 +307,59 @@ impl EventHandler for Textbox {
                             }
                         }
 
+                        // Select all and clipboard shortcuts
+                        if state.modifiers.shortcut() && self.edit {
+                            let start = std::cmp::min(self.select_pos, self.cursor_pos) as usize;
+                            let end = std::cmp::max(self.select_pos, self.cursor_pos) as usize;
+
+                            match *virtual_keycode {
+                                VirtualKeyCode::A => {
+                                    self.select_pos = 0;
+                                    self.cursor_pos = text_data.text.len() as u32;
+                                }
+
+                                VirtualKeyCode::C if start != end => {
+                                    state.clipboard.set_text(&text_data.text[start..end]);
+                                }
+
+                                VirtualKeyCode::X if start != end => {
+                                    state.clipboard.set_text(&text_data.text[start..end]);
+
+                                    if let Some(txt) = state.style.text.get_mut(entity) {
+                                        txt.text.replace_range(start..end, "");
+                                    }
+
+                                    self.cursor_pos = start as u32;
+                                    self.select_pos = start as u32;
+                                }
 
+                                VirtualKeyCode::V => {
+                                    if let Some(pasted) = state.clipboard.get_text() {
+                                        // Line breaks and other control characters can't be typed into a textbox
+                                        let pasted: String =
+                                            pasted.chars().filter(|c| !c.is_control()).collect();
+
+                                        if let Some(txt) = state.style.text.get_mut(entity) {
+                                            txt.text.replace_range(start..end, &pasted);
+                                        }
+
+                                        self.cursor_pos = (start + pasted.len()) as u32;
+                                        self.select_pos = self.cursor_pos;
+                                    }
+                                }
+
+                                _ => {}
+                            }
+
+                            self.hitx = -1.0;
+
+                            state.insert_event(
+                                Event::new(WindowEvent::Restyle)
+                                    .target(Entity::new(0, 0)),
+                            );
+
+                            state.insert_event(Event::new(WindowEvent::Redraw));
+                        }
                     }
                 }
 
@@ -448,36 +502,34 @@ impl EventHandler for Textbox {
                 }
                 */
 
-                WindowEvent::CharInput(input) => {
-                    if *input as u8 != 8 && *input as u8 != 13 {
-                        println!("{}", *input as u8);
-                        if self.edit {
-                            let start = std::cmp::min(self.select_pos, self.cursor_pos) as usize;
-                            let end = std::cmp::max(self.select_pos, self.cursor_pos) as usize;
-                            //let start = text_data.select_pos as usize;
-                            //let end = text_data.cursor_pos as usize;
-                            if start == end {
-                                if let Some(txt) = state.style.text.get_mut(entity) {
-                                    txt.text.insert(start, *input);
-                                }
+                // Backspace, return and shortcuts such as Ctrl+C send control characters
+                WindowEvent::CharInput(input) if !input.is_control() => {
+                    if self.edit {
+                        let start = std::cmp::min(self.select_pos, self.cursor_pos) as usize;
+                        let end = std::cmp::max(self.select_pos, self.cursor_pos) as usize;
+                        //let start = text_data.select_pos as usize;
+                        //let end = text_data.cursor_pos as usize;
+                        if start == end {
+                            if let Some(txt) = state.style.text.get_mut(entity) {
+                                txt.text.insert(start, *input);
+                            }
 
-                                //text_data.text.remove((text_data.cursor_pos - 1) as usize);
-                                self.cursor_pos += 1;
-                                self.select_pos += 1;
-                            } else {
-                                if let Some(txt) = state.style.text.get_mut(entity) {
-                                    txt.text.replace_range(start..end, &input.to_string());
-                                }
-                                self.cursor_pos = (start + 1) as u32;
-                                self.select_pos = (start + 1) as u32;
+                            //text_data.text.remove((text_data.cursor_pos - 1) as usize);
+                            self.cursor_pos += 1;
+                            self.select_pos += 1;
+                        } else {
+                            if let Some(txt) = state.style.text.get_mut(entity) {
+                                txt.text.replace_range(start..end, &input.to_string());
                             }
+                            self.cursor_pos = (start + 1) as u32;
+                            self.select_pos = (start + 1) as u32;
+                        }
 
-                            state.insert_event(
-                                Event::new(WindowEvent::Restyle).target(Entity::new(0, 0)),
-                            );
+                        state.insert_event(
+                            Event::new(WindowEvent::Restyle).target(Entity::new(0, 0)),
+                        );
 
-                            state.insert_event(Event::new(WindowEvent::Redraw));
-                        }
+                        state.insert_event(Event::new(WindowEvent::Redraw));
                     }
                 }
 
@@ -630,11 +682,18 @@ impl EventHandler for Textbox {
         canvas.fill_path(&mut path, paint);
 
         if let Some(text) = state.style.text.get_mut(entity) {
-            let font_id = match text.font.as_ref() {
-                "Sans" => state.fonts.regular.unwrap(),
-                "Icons" => state.fonts.icons.unwrap(),
-                _ => state.fonts.regular.unwrap(),
-            };
+            let font_family = state.style.font_family.get(entity).cloned().unwrap_or_default();
+            let font_weight = state.style.font_weight.get(entity).cloned().unwrap_or_default();
+            let font_style = state.style.font_style.get(entity).cloned().unwrap_or_default();
+            let font_size = state.style.font_size.get(entity).cloned().unwrap_or(14.0);
+
+            let font_ids = state.fonts.font_ids(
+                canvas,
+                &state.style.font_faces,
+                &font_family,
+                font_weight,
+                font_style,
+            );
 
             let mut x = posx;
             let mut y = posy;
@@ -684,12 +743,19 @@ impl EventHandler for Textbox {
                 }
             };
 
-            let mut font_color: femtovg::Color = text.font_color.into();
+            let font_color = state
+                .style
+                .font_color
+                .get(entity)
+                .cloned()
+                .unwrap_or(crate::Color::rgb(255, 255, 255));
+
+            let mut font_color: femtovg::Color = font_color.into();
             font_color.set_alphaf(font_color.a * opacity);
 
             let mut paint = Paint::color(font_color);
-            paint.set_font_size(text.font_size);
-            paint.set_font(&[font_id]);
+            paint.set_font_size(font_size);
+            paint.set_font(&font_ids);
             paint.set_text_align(align);
             paint.set_text_baseline(baseline);
                 
@@ -793,3 +859,25 @@ impl EventHandler for Textbox {
     }
     
 }
+
+impl Bind<String> for Textbox {
+    fn set_value(state: &mut State, entity: Entity, value: &String) {
+        state.insert_event(
+            Event::new(TextboxEvent::SetValue(value.clone()))
+                .target(entity)
+                .propagate(Propagation::Direct),
+        );
+    }
+
+    fn value_changed(entity: Entity, event: &Event) -> Option<String> {
+        match event.message.as_any().downcast_ref::<TextboxEvent>() {
+            Some(TextboxEvent::ValueChanged(text)) if event.target == entity => Some(text.clone()),
+            _ => None,
+        }
+    }
+}
+
+// Returns the x position of a point in the untransformed bounds of the textbox, which the text is drawn in
+fn local_x(state: &State, entity: Entity, x: f32, y: f32) -> f32 {
+    state.transform.to_local(entity, x, y).map_or(x, |point| point.0)
+}
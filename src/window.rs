@@ -13,7 +13,7 @@ use femtovg::{
 use crate::entity::Entity;
 
 use crate::state::mouse::*;
-use crate::{apply_clipping, apply_styles, apply_visibility, layout_fun, State, apply_z_ordering};
+use crate::{apply_clipping, apply_overlays, apply_scrolling, apply_styles, apply_transform, apply_visibility, layout_fun, show_tooltip, take_dirty_branches, State, TooltipEvent, apply_z_ordering};
 
 //use crate::state::style::*;
 
@@ -72,6 +72,8 @@ pub enum WindowEvent {
     Redraw,
     Restyle,
     Relayout,
+    // Sent to the parent of a removed entity
+    Removed(Entity),
 }
 
 pub struct WindowDescription {
@@ -202,20 +204,31 @@ impl EventHandler for WindowWidget {
 
                 WindowEvent::Restyle => {
                     apply_styles(state, &state.hierarchy.clone());
-                    apply_visibility(state, &state.hierarchy.clone());
                 }
 
+                // Only the branches of the entities flagged since the last relayout, and of those moved by laying
+                // them out, are updated
                 WindowEvent::Relayout => {
-                    apply_z_ordering(state, &state.hierarchy.clone());
-                    apply_visibility(state, &state.hierarchy.clone());
-                    apply_clipping(state, &state.hierarchy.clone());
-                    layout_fun(state, &state.hierarchy.clone());
+                    let hierarchy = state.hierarchy.clone();
+                    let mut branches = take_dirty_branches(state);
+
+                    apply_z_ordering(state, &hierarchy);
+                    apply_visibility(state, &hierarchy, &branches);
+                    apply_clipping(state, &hierarchy, &branches);
+                    branches.extend(layout_fun(state, &hierarchy));
+                    let branches = apply_scrolling(state, &hierarchy, &branches);
+                    apply_transform(state, &hierarchy, &branches);
+                    apply_overlays(state, &hierarchy);
                 }
 
                 _ => {}
             }
         }
 
+        if let Some(TooltipEvent::Show(owner)) = event.message.downcast::<TooltipEvent>() {
+            show_tooltip(state, *owner);
+        }
+
         false
     }
 }
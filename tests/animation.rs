@@ -0,0 +1,127 @@
+use std::time::Duration;
+
+use tuix::widgets::*;
+use tuix::*;
+
+const STYLE: &str = "
+    @keyframes grow {
+        from { width: 10px; }
+        50% { width: 50px; background-color: #ff0000; }
+        to { width: 100px; }
+    }
+
+    .grows {
+        animation: grow 2s 0.5s 3;
+    }
+";
+
+#[test]
+fn keyframes_are_parsed_in_order() {
+    let mut state = State::new();
+    let report = state.insert_style(
+        "
+        @keyframes fade {
+            to { opacity: 1.0; }
+            0%, 40% { opacity: 0.0; }
+        }
+        ",
+    );
+    assert!(report.is_empty(), "{}", report);
+
+    let keyframes = &state.style.keyframes["fade"].keyframes;
+    let opacities: Vec<(f32, f32)> = keyframes
+        .iter()
+        .map(|(time, properties)| match properties.as_slice() {
+            [Property::Opacity(opacity)] => (*time, *opacity),
+            other => panic!("unexpected properties {:?}", other),
+        })
+        .collect();
+
+    assert_eq!(opacities, vec![(0.0, 0.0), (0.4, 0.0), (1.0, 1.0)]);
+}
+
+#[test]
+fn animation_property_creates_an_animation_for_each_animated_property() {
+    let mut button = Entity::null();
+    let driver = TestDriver::new(300.0, 200.0, |state, root| {
+        let report = state.insert_style(STYLE);
+        assert!(report.is_empty(), "{}", report);
+        button = Button::new().build(state, root, |builder| builder.class("grows"));
+    });
+
+    let animations = driver.state.style.animation.get(button).unwrap();
+    let properties: Vec<KeyframeProperty> =
+        animations.iter().map(|(property, _)| *property).collect();
+    assert_eq!(
+        properties,
+        vec![KeyframeProperty::BackgroundColor, KeyframeProperty::Width]
+    );
+
+    let (_, width) = animations[1];
+    let animation = &driver.state.style.width.animations[width];
+    assert_eq!(animation.duration, Duration::from_secs(2));
+    // The delay is stored as a fraction of the duration
+    assert_eq!(animation.delay, 0.25);
+    assert_eq!(animation.iteration_count, 3.0);
+    assert_eq!(animation.keyframes.len(), 3);
+
+    // Only the keyframe which sets the background color is used for it
+    let (_, background) = animations[0];
+    assert_eq!(
+        driver.state.style.background_color.animations[background].keyframes,
+        vec![(0.5, Color::rgb(255, 0, 0))]
+    );
+}
+
+#[test]
+fn animation_with_an_unknown_name_or_no_duration_does_nothing() {
+    let (mut unknown, mut instant) = Default::default();
+    let driver = TestDriver::new(300.0, 200.0, |state, root| {
+        state.insert_style(STYLE);
+        state.insert_style(".unknown { animation: shrink 1s; } .instant { animation: grow; }");
+        unknown = Button::new().build(state, root, |builder| builder.class("unknown"));
+        instant = Button::new().build(state, root, |builder| builder.class("instant"));
+    });
+
+    assert_eq!(driver.state.style.animation.get(unknown), Some(&Vec::new()));
+    assert_eq!(driver.state.style.animation.get(instant), Some(&Vec::new()));
+    assert!(!driver.state.style.width.is_animating(unknown));
+    assert!(!driver.state.style.width.is_animating(instant));
+}
+
+#[test]
+fn playing_animation_interpolates_between_keyframes() {
+    let mut button = Entity::null();
+    let mut driver = TestDriver::new(300.0, 200.0, |state, root| {
+        state.insert_style(
+            "
+            @keyframes grow {
+                from { width: 10px; }
+                50% { width: 50px; }
+                to { width: 100px; }
+            }
+
+            .grows { animation: grow 1s; }
+            ",
+        );
+        button = Button::new().build(state, root, |builder| builder.class("grows"));
+    });
+
+    assert!(driver.state.style.width.is_animating(button));
+    let start = driver.state.style.width.active_animations[0].start_time;
+
+    let mut width_at = |millis: u64| {
+        driver
+            .state
+            .style
+            .width
+            .animate(start + Duration::from_millis(millis));
+        *driver.state.style.width.get(button).unwrap()
+    };
+
+    assert_eq!(width_at(0), Length::Pixels(10.0));
+    assert_eq!(width_at(250), Length::Pixels(30.0));
+    assert_eq!(width_at(500), Length::Pixels(50.0));
+    assert_eq!(width_at(750), Length::Pixels(75.0));
+    assert_eq!(width_at(1000), Length::Pixels(100.0));
+}
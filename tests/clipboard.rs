@@ -0,0 +1,182 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use copypasta::ClipboardProvider;
+use tuix::widgets::*;
+use tuix::*;
+
+fn text(driver: &TestDriver, entity: Entity) -> String {
+    driver.state.style.text.get(entity).unwrap().text.clone()
+}
+
+// Presses the key with the platform shortcut modifier held down
+fn shortcut(driver: &mut TestDriver, key: VirtualKeyCode) {
+    if cfg!(target_os = "macos") {
+        driver.state.modifiers.logo = true;
+    } else {
+        driver.state.modifiers.ctrl = true;
+    }
+
+    driver.press_key(key);
+    driver.state.modifiers = ModifiersState::default();
+}
+
+fn textarea(value: &str) -> (TestDriver, Entity) {
+    let mut textarea = Entity::null();
+    let mut driver = TestDriver::new(300.0, 200.0, |state, root| {
+        textarea = TextArea::new(value).build(state, root, |builder| {
+            builder
+                .set_width(Length::Pixels(200.0))
+                .set_height(Length::Pixels(100.0))
+        });
+    });
+
+    driver.click(textarea);
+    (driver, textarea)
+}
+
+fn textbox(value: &str) -> (TestDriver, Entity) {
+    let mut textbox = Entity::null();
+    let mut driver = TestDriver::new(300.0, 200.0, |state, root| {
+        textbox = Textbox::new(value).build(state, root, |builder| {
+            builder
+                .set_width(Length::Pixels(200.0))
+                .set_height(Length::Pixels(30.0))
+        });
+    });
+
+    // Clicking a textbox selects all of its text
+    driver.click(textbox);
+    (driver, textbox)
+}
+
+#[test]
+fn textarea_copies_and_pastes_the_selection() {
+    let (mut driver, textarea) = textarea("one two");
+
+    shortcut(&mut driver, VirtualKeyCode::A);
+    shortcut(&mut driver, VirtualKeyCode::C);
+    assert_eq!(
+        driver.state.clipboard.get_text().as_deref(),
+        Some("one two")
+    );
+    assert_eq!(text(&driver, textarea), "one two");
+
+    // The first paste replaces the selection and the second one is inserted at the caret
+    shortcut(&mut driver, VirtualKeyCode::V);
+    shortcut(&mut driver, VirtualKeyCode::V);
+    assert_eq!(text(&driver, textarea), "one twoone two");
+}
+
+#[test]
+fn textarea_cuts_the_selection() {
+    let (mut driver, textarea) = textarea("one two");
+
+    shortcut(&mut driver, VirtualKeyCode::A);
+    shortcut(&mut driver, VirtualKeyCode::X);
+    assert_eq!(
+        driver.state.clipboard.get_text().as_deref(),
+        Some("one two")
+    );
+    assert_eq!(text(&driver, textarea), "");
+
+    shortcut(&mut driver, VirtualKeyCode::V);
+    assert_eq!(text(&driver, textarea), "one two");
+}
+
+#[test]
+fn textarea_pastes_line_breaks() {
+    let (mut driver, textarea) = textarea("");
+
+    driver.state.clipboard.set_text("one\r\ntwo");
+    shortcut(&mut driver, VirtualKeyCode::V);
+    assert_eq!(text(&driver, textarea), "one\ntwo");
+}
+
+#[test]
+fn textbox_copies_cuts_and_pastes_the_selection() {
+    let (mut driver, textbox) = textbox("abc");
+
+    shortcut(&mut driver, VirtualKeyCode::C);
+    assert_eq!(driver.state.clipboard.get_text().as_deref(), Some("abc"));
+    assert_eq!(text(&driver, textbox), "abc");
+
+    shortcut(&mut driver, VirtualKeyCode::A);
+    shortcut(&mut driver, VirtualKeyCode::X);
+    assert_eq!(text(&driver, textbox), "");
+
+    shortcut(&mut driver, VirtualKeyCode::V);
+    shortcut(&mut driver, VirtualKeyCode::V);
+    assert_eq!(text(&driver, textbox), "abcabc");
+}
+
+#[test]
+fn textbox_pastes_without_line_breaks() {
+    let (mut driver, textbox) = textbox("");
+
+    driver.state.clipboard.set_text("one\ntwo");
+    shortcut(&mut driver, VirtualKeyCode::V);
+    assert_eq!(text(&driver, textbox), "onetwo");
+}
+
+// A system clipboard which fails whenever it is switched off
+#[derive(Default)]
+struct FlakyClipboard {
+    working: Arc<AtomicBool>,
+    text: Arc<Mutex<String>>,
+}
+
+impl ClipboardProvider for FlakyClipboard {
+    fn get_contents(&mut self) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        if self.working.load(Ordering::SeqCst) {
+            Ok(self.text.lock().unwrap().clone())
+        } else {
+            Err("clipboard unavailable".into())
+        }
+    }
+
+    fn set_contents(
+        &mut self,
+        text: String,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        if self.working.load(Ordering::SeqCst) {
+            *self.text.lock().unwrap() = text;
+            Ok(())
+        } else {
+            Err("clipboard unavailable".into())
+        }
+    }
+}
+
+#[test]
+fn failing_system_clipboard_falls_back_to_memory() {
+    let flaky = FlakyClipboard::default();
+    let working = flaky.working.clone();
+    let system_text = flaky.text.clone();
+
+    let (mut driver, textarea) = textarea("one two");
+    driver.state.clipboard = Clipboard::new(SystemClipboard::with_provider(flaky));
+
+    // Copying and pasting still works within the application
+    shortcut(&mut driver, VirtualKeyCode::A);
+    shortcut(&mut driver, VirtualKeyCode::C);
+    assert_eq!(
+        driver.state.clipboard.get_text().as_deref(),
+        Some("one two")
+    );
+    shortcut(&mut driver, VirtualKeyCode::V);
+    shortcut(&mut driver, VirtualKeyCode::V);
+    assert_eq!(text(&driver, textarea), "one twoone two");
+
+    // Once the system clipboard works again, copied text goes there
+    working.store(true, Ordering::SeqCst);
+    shortcut(&mut driver, VirtualKeyCode::A);
+    shortcut(&mut driver, VirtualKeyCode::X);
+    assert_eq!(*system_text.lock().unwrap(), "one twoone two");
+
+    // And the text left in memory isn't pasted when it fails again
+    working.store(false, Ordering::SeqCst);
+    assert_eq!(driver.state.clipboard.get_text(), None);
+    shortcut(&mut driver, VirtualKeyCode::V);
+    assert_eq!(text(&driver, textarea), "");
+}
@@ -0,0 +1,34 @@
+use tuix::widgets::*;
+use tuix::*;
+
+#[test]
+fn eight_digit_hex_strings_put_the_alpha_first() {
+    let color = Color::from("#80102030");
+    assert_eq!((color.r(), color.g(), color.b(), color.a()), (0x10, 0x20, 0x30, 0x80));
+
+    let color = Color::from("#102030");
+    assert_eq!((color.r(), color.g(), color.b(), color.a()), (0x10, 0x20, 0x30, 0xFF));
+}
+
+#[test]
+fn css_hex_colors_put_the_alpha_last() {
+    let color = Color::from_hex("#10203080").unwrap();
+    assert_eq!((color.r(), color.g(), color.b(), color.a()), (0x10, 0x20, 0x30, 0x80));
+
+    let color = Color::from_hex("#1238").unwrap();
+    assert_eq!((color.r(), color.g(), color.b(), color.a()), (0x11, 0x22, 0x33, 0x88));
+
+    assert!(Color::from_hex("#12345").is_none());
+    assert!(Color::from_hex("#+fff").is_none());
+
+    let mut button = Entity::null();
+    let driver = TestDriver::new(300.0, 200.0, |state, root| {
+        let report = state.insert_style("button { background-color: #10203080; }");
+        assert!(report.is_empty(), "{}", report);
+
+        button = Button::new().build(state, root, |builder| builder);
+    });
+
+    let color = *driver.state.style.background_color.get(button).unwrap();
+    assert_eq!((color.r(), color.g(), color.b(), color.a()), (0x10, 0x20, 0x30, 0x80));
+}
@@ -0,0 +1,92 @@
+use std::cell::Cell;
+use std::rc::Rc;
+
+use tuix::widgets::*;
+use tuix::*;
+
+#[derive(Debug, Clone, PartialEq)]
+enum SettingsEvent {
+    Toggle,
+}
+
+struct Settings {
+    enabled: bool,
+    // Shared with the test so that it can see the data held by the store
+    seen: Rc<Cell<bool>>,
+}
+
+impl Model for Settings {
+    fn on_event(&mut self, _state: &mut State, _entity: Entity, event: &mut Event) {
+        if let Some(SettingsEvent::Toggle) = event.message.downcast::<SettingsEvent>() {
+            self.enabled = !self.enabled;
+        }
+
+        self.seen.set(self.enabled);
+    }
+}
+
+fn checked(driver: &TestDriver, entity: Entity) -> bool {
+    driver
+        .state
+        .style
+        .pseudo_classes
+        .get(entity)
+        .map(|pseudo_classes| pseudo_classes.get("checked"))
+        .unwrap_or_default()
+}
+
+// Builds a checkbox bound to the enabled setting of a store, returned as (store, checkbox)
+fn bound_checkbox(seen: &Rc<Cell<bool>>) -> (TestDriver, Entity, Entity) {
+    let (mut store, mut checkbox) = Default::default();
+    let driver = TestDriver::new(300.0, 200.0, |state, root| {
+        store = Store::new(Settings {
+            enabled: false,
+            seen: seen.clone(),
+        })
+        .build(state, root, |builder| builder);
+        checkbox = Checkbox::new(false).build(state, store, |builder| {
+            builder
+                .set_width(Length::Pixels(20.0))
+                .set_height(Length::Pixels(20.0))
+        });
+        Checkbox::bind(state, store, checkbox, lens!(Settings, enabled));
+    });
+
+    (driver, store, checkbox)
+}
+
+#[test]
+fn changes_to_the_data_are_sent_to_the_bound_widget() {
+    let seen = Rc::new(Cell::new(false));
+    let (mut driver, store, checkbox) = bound_checkbox(&seen);
+    assert!(!checked(&driver, checkbox));
+
+    driver.clear_events();
+    driver.state.insert_event(
+        Event::new(SettingsEvent::Toggle)
+            .target(store)
+            .propagate(Propagation::Direct),
+    );
+    driver.flush();
+
+    assert!(seen.get());
+    assert!(checked(&driver, checkbox));
+
+    // The redraw is sent to the widget which changed
+    assert!(driver.events.iter().any(|event| {
+        event.target == checkbox
+            && event.message.as_any().downcast_ref::<WindowEvent>() == Some(&WindowEvent::Redraw)
+    }));
+}
+
+#[test]
+fn edits_made_with_the_bound_widget_are_written_to_the_data() {
+    let seen = Rc::new(Cell::new(false));
+    let (mut driver, _, checkbox) = bound_checkbox(&seen);
+
+    driver.click(checkbox);
+    assert!(seen.get());
+
+    driver.click(checkbox);
+    assert!(!seen.get());
+}
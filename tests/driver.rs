@@ -0,0 +1,123 @@
+use std::time::Duration;
+
+use tuix::widgets::*;
+use tuix::*;
+
+#[derive(Debug, Clone, PartialEq)]
+enum TestEvent {
+    Ping(u32),
+}
+
+// Returns true if a message was sent to the entity as its target
+fn received<M: Message + PartialEq>(driver: &TestDriver, entity: Entity, message: &M) -> bool {
+    driver.events.iter().any(|event| {
+        event.target == entity && event.message.as_any().downcast_ref::<M>() == Some(message)
+    })
+}
+
+#[test]
+fn typing_into_a_textbox_changes_its_value() {
+    let mut textbox = Entity::null();
+    let mut driver = TestDriver::new(300.0, 200.0, |state, root| {
+        textbox = Textbox::new("").build(state, root, |builder| {
+            builder
+                .set_width(Length::Pixels(200.0))
+                .set_height(Length::Pixels(30.0))
+        });
+    });
+
+    driver.click(textbox);
+    assert_eq!(driver.state.focused, textbox);
+
+    driver.type_text("abc");
+    driver.press_key(VirtualKeyCode::Back);
+    driver.type_text("d");
+    assert_eq!(driver.state.style.text.get(textbox).unwrap().text, "abd");
+
+    driver.clear_events();
+    driver.press_key(VirtualKeyCode::Return);
+
+    assert!(received(
+        &driver,
+        textbox,
+        &TextboxEvent::ValueChanged("abd".to_string())
+    ));
+}
+
+#[test]
+fn advance_fires_timers_when_they_are_due() {
+    let mut driver = TestDriver::new(300.0, 200.0, |_, _| {});
+    let root = driver.get_root();
+
+    driver
+        .state
+        .set_timer(Duration::from_millis(200), Event::new(TestEvent::Ping(2)).target(root));
+    driver
+        .state
+        .set_timer(Duration::from_millis(100), Event::new(TestEvent::Ping(1)).target(root));
+    let cancelled = driver
+        .state
+        .set_timer(Duration::from_millis(100), Event::new(TestEvent::Ping(3)).target(root));
+    driver.state.cancel_timer(cancelled);
+
+    driver.flush();
+    assert!(driver.events_of::<TestEvent>().is_empty());
+
+    driver.advance(Duration::from_millis(99));
+    assert!(driver.events_of::<TestEvent>().is_empty());
+
+    driver.advance(Duration::from_millis(1));
+    assert_eq!(driver.events_of::<TestEvent>(), vec![TestEvent::Ping(1)]);
+
+    // Timers started later are due relative to the driver's clock
+    driver
+        .state
+        .set_timer(Duration::from_millis(50), Event::new(TestEvent::Ping(4)).target(root));
+
+    driver.advance(Duration::from_millis(500));
+    assert_eq!(
+        driver.events_of::<TestEvent>(),
+        vec![TestEvent::Ping(1), TestEvent::Ping(4), TestEvent::Ping(2)]
+    );
+}
+
+#[test]
+fn click_hits_the_centre_of_a_transformed_entity() {
+    let mut button = Entity::null();
+    let mut driver = TestDriver::new(400.0, 300.0, |state, root| {
+        state.insert_style(
+            "
+            .moved {
+                position: absolute;
+                left: 100px;
+                top: 100px;
+                width: 40px;
+                height: 20px;
+                transform: translate(150px, 50px) rotate(90deg);
+            }
+            ",
+        );
+
+        button = Button::new().build(state, root, |builder| builder.class("moved"));
+    });
+
+    let (x, y) = driver.center(button);
+    assert!((x - 270.0).abs() < 0.01 && (y - 160.0).abs() < 0.01);
+
+    // Rotated, so it's taller than it is wide
+    assert_eq!(driver.entity_at(270.0, 175.0), button);
+    assert_eq!(driver.entity_at(285.0, 160.0), driver.get_root());
+
+    // Where it was laid out before being moved
+    assert_eq!(driver.entity_at(120.0, 110.0), driver.get_root());
+
+    driver.clear_events();
+    driver.click(button);
+
+    assert!(received(
+        &driver,
+        button,
+        &WindowEvent::MouseDown(MouseButton::Left)
+    ));
+    assert!(received(&driver, button, &WindowEvent::MouseUp(MouseButton::Left)));
+}
@@ -0,0 +1,57 @@
+use tuix::widgets::*;
+use tuix::*;
+
+fn button(state: &mut State, parent: Entity) -> Entity {
+    Button::new().build(state, parent, |builder| {
+        builder
+            .set_width(Length::Pixels(100.0))
+            .set_height(Length::Pixels(30.0))
+    })
+}
+
+#[test]
+fn tab_moves_focus_to_the_next_entity_in_the_tree() {
+    let (mut first, mut second) = Default::default();
+    let mut driver = TestDriver::new(300.0, 200.0, |state, root| {
+        first = button(state, root);
+        second = button(state, root);
+    });
+
+    driver.state.focused = first;
+    driver.press_key(VirtualKeyCode::Tab);
+    assert_eq!(driver.state.focused, second);
+
+    // Past the last entity focus goes back to the root
+    driver.press_key(VirtualKeyCode::Tab);
+    assert_eq!(driver.state.focused, driver.get_root());
+}
+
+#[test]
+fn tab_wraps_around_inside_a_modal() {
+    let (mut dialog, mut first, mut second) = Default::default();
+    let mut driver = TestDriver::new(300.0, 200.0, |state, root| {
+        button(state, root);
+        dialog = Button::new().build(state, root, |builder| {
+            builder
+                .set_width(Length::Pixels(120.0))
+                .set_height(Length::Pixels(80.0))
+                .set_visibility(Visibility::Invisible)
+        });
+        first = button(state, dialog);
+        second = button(state, dialog);
+        button(state, root);
+    });
+
+    driver.state.open_overlay(dialog, Overlay::modal());
+    driver.flush();
+    assert_eq!(driver.state.focused, dialog);
+
+    driver.press_key(VirtualKeyCode::Tab);
+    assert_eq!(driver.state.focused, first);
+    driver.press_key(VirtualKeyCode::Tab);
+    assert_eq!(driver.state.focused, second);
+
+    // The next entity is outside of the dialog
+    driver.press_key(VirtualKeyCode::Tab);
+    assert_eq!(driver.state.focused, dialog);
+}
@@ -0,0 +1,50 @@
+use tuix::*;
+
+const REGULAR: &[u8] = include_bytes!("../resources/Roboto-Regular.ttf");
+const BOLD: &[u8] = include_bytes!("../resources/Roboto-Bold.ttf");
+
+fn width(fonts: &mut Fonts, stylesheet_faces: &[FontFace], family: &str) -> f32 {
+    fonts
+        .measure(
+            stylesheet_faces,
+            &[family.to_string()],
+            FontWeight::NORMAL,
+            FontStyle::Normal,
+            14.0,
+            0.0,
+        )
+        .width("Wide text")
+}
+
+#[test]
+fn registering_a_face_replaces_the_fallback() {
+    let mut fonts = Fonts::new();
+
+    // Falls back to the default family until a face is registered
+    let fallback = width(&mut fonts, &[], "Custom");
+    assert_eq!(fallback, width(&mut fonts, &[], DEFAULT_FONT_FAMILY));
+
+    fonts.add_memory("Custom", BOLD);
+    let bold = width(&mut fonts, &[], "Custom");
+    assert!(bold > fallback);
+}
+
+#[test]
+fn changing_the_stylesheet_faces_resolves_the_fonts_again() {
+    let mut fonts = Fonts::new();
+
+    let regular = [FontFace::from_memory("Custom", REGULAR)];
+    let bold = [FontFace::from_memory("Custom", BOLD)];
+
+    let regular_width = width(&mut fonts, &regular, "Custom");
+    let bold_width = width(&mut fonts, &bold, "Custom");
+    assert!(bold_width > regular_width);
+
+    // Stylesheets reloaded with the same faces create them again
+    let reloaded = [FontFace::from_memory("Custom", REGULAR)];
+    assert_eq!(width(&mut fonts, &reloaded, "Custom"), regular_width);
+    assert_eq!(
+        width(&mut fonts, &[], "Custom"),
+        width(&mut fonts, &[], DEFAULT_FONT_FAMILY)
+    );
+}
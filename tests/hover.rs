@@ -0,0 +1,44 @@
+use tuix::widgets::*;
+use tuix::*;
+
+fn over(driver: &TestDriver, entity: Entity) -> bool {
+    driver
+        .state
+        .style
+        .pseudo_classes
+        .get(entity)
+        .map(|pseudo_classes| pseudo_classes.get("over"))
+        .unwrap_or_default()
+}
+
+#[test]
+fn only_the_hovered_widget_and_its_ancestors_are_over() {
+    let (mut behind, mut outer, mut front, mut inner) = Default::default();
+    let mut driver = TestDriver::new(300.0, 200.0, |state, root| {
+        state.insert_style(
+            "
+            .box { position: absolute; left: 0px; top: 0px; width: 100px; height: 100px; }
+            .inner { width: 50px; height: 50px; }
+            ",
+        );
+
+        behind = Button::new().build(state, root, |builder| builder.class("box"));
+        outer = Button::new().build(state, root, |builder| builder.class("box"));
+        front = Button::new().build(state, outer, |builder| builder.class("box"));
+        inner = Button::new().build(state, front, |builder| builder.class("inner"));
+    });
+
+    driver.move_mouse(25.0, 25.0);
+    assert_eq!(driver.state.hovered, inner);
+    assert!(over(&driver, inner) && over(&driver, front) && over(&driver, outer));
+    assert!(!over(&driver, behind));
+
+    // Outside of the inner widget it's no longer over
+    driver.move_mouse(75.0, 75.0);
+    assert_eq!(driver.state.hovered, front);
+    assert!(!over(&driver, inner) && over(&driver, front) && over(&driver, outer));
+    assert!(!over(&driver, behind));
+
+    driver.move_mouse(200.0, 150.0);
+    assert!(!over(&driver, front) && !over(&driver, outer));
+}
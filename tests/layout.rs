@@ -0,0 +1,431 @@
+use tuix::widgets::*;
+use tuix::*;
+
+fn relayout(driver: &mut TestDriver) {
+    driver
+        .state
+        .insert_event(Event::new(WindowEvent::Relayout).target(Entity::null()));
+    driver.flush();
+}
+
+#[test]
+fn only_changed_branches_are_laid_out_again() {
+    let (mut first, mut second, mut other) = Default::default();
+    let mut driver = TestDriver::new(300.0, 200.0, |state, root| {
+        let row = Button::new().build(state, root, |builder| {
+            builder
+                .set_width(Length::Pixels(300.0))
+                .set_height(Length::Pixels(80.0))
+        });
+        first = Button::new().build(state, row, |builder| {
+            builder
+                .set_width(Length::Pixels(50.0))
+                .set_height(Length::Pixels(20.0))
+        });
+        second = Button::new().build(state, row, |builder| {
+            builder
+                .set_width(Length::Pixels(50.0))
+                .set_height(Length::Pixels(20.0))
+        });
+
+        let other_row = Button::new().build(state, root, |builder| {
+            builder
+                .set_width(Length::Pixels(300.0))
+                .set_height(Length::Pixels(80.0))
+        });
+        other = Button::new().build(state, other_row, |builder| {
+            builder
+                .set_width(Length::Pixels(50.0))
+                .set_height(Length::Pixels(20.0))
+        });
+    });
+
+    let second_posy = driver.state.transform.get_posy(second);
+    let other_posy = driver.state.transform.get_posy(other);
+
+    // Values which only a relayout of these entities would overwrite
+    driver.state.transform.set_opacity(first, 0.5);
+    driver.state.transform.set_posy(other, other_posy + 5.0);
+    driver.state.transform.set_opacity(other, 0.5);
+
+    first.set_height(&mut driver.state, Length::Pixels(30.0));
+    driver.flush();
+
+    // The changed entity and the sibling it pushed along are updated
+    assert_eq!(driver.state.transform.get_height(first), 30.0);
+    assert_eq!(driver.state.transform.get_opacity(first), 1.0);
+    assert_eq!(driver.state.transform.get_posy(second), second_posy + 10.0);
+
+    // The other row isn't affected, so it isn't laid out again
+    assert_eq!(driver.state.transform.get_posy(other), other_posy + 5.0);
+    assert_eq!(driver.state.transform.get_opacity(other), 0.5);
+
+    // Until it's flagged itself
+    other.set_opacity(&mut driver.state, 1.0);
+    driver.flush();
+    assert_eq!(driver.state.transform.get_opacity(other), 1.0);
+
+    // A relayout with nothing flagged leaves everything as it is
+    driver.state.transform.set_opacity(second, 0.5);
+    relayout(&mut driver);
+    assert_eq!(driver.state.transform.get_opacity(second), 0.5);
+}
+
+#[test]
+fn ancestors_resized_by_a_change_are_updated() {
+    let (mut parent, mut child) = Default::default();
+    let mut driver = TestDriver::new(300.0, 200.0, |state, root| {
+        parent = Button::new().build(state, root, |builder| {
+            builder.set_width(Length::Pixels(100.0))
+        });
+        parent.set_transform(state, vec![TransformFunction::Scale(1.0, 2.0)]);
+        child = Button::new().build(state, parent, |builder| {
+            builder
+                .set_width(Length::Pixels(50.0))
+                .set_height(Length::Pixels(20.0))
+        });
+    });
+
+    // The parent grows with the child, which moves the origin of its transform
+    child.set_height(&mut driver.state, Length::Pixels(40.0));
+    driver.flush();
+    assert_eq!(driver.state.transform.get_height(parent), 40.0);
+
+    let matrix = driver.state.transform.get_matrix(parent);
+    driver.state.transform.set_all_layout_dirty();
+    relayout(&mut driver);
+    assert_eq!(driver.state.transform.get_matrix(parent), matrix);
+}
+
+#[test]
+fn inline_values_inserted_directly_are_laid_out() {
+    let (mut parent, mut child) = Default::default();
+    let mut driver = TestDriver::new(300.0, 200.0, |state, root| {
+        parent = Button::new().build(state, root, |builder| {
+            builder
+                .set_width(Length::Pixels(200.0))
+                .set_height(Length::Pixels(100.0))
+        });
+        child = Button::new().build(state, parent, |builder| {
+            builder
+                .set_width(Length::Pixels(50.0))
+                .set_height(Length::Pixels(20.0))
+        });
+    });
+
+    assert_eq!(driver.state.transform.get_posy(child), 0.0);
+
+    // Written straight into the storages without a setter
+    driver.state.style.width.insert(child, Length::Pixels(120.0));
+    driver.state.style.top.insert(child, Length::Pixels(30.0));
+    relayout(&mut driver);
+
+    assert_eq!(driver.state.transform.get_width(child), 120.0);
+    assert_eq!(driver.state.transform.get_posy(child), 30.0);
+
+    driver.state.style.display.insert(child, Display::None);
+    driver.state.style.height.insert(parent, Length::Pixels(60.0));
+    relayout(&mut driver);
+
+    assert_eq!(driver.state.transform.get_height(parent), 60.0);
+    assert_eq!(
+        driver.state.transform.get_visibility(child),
+        Visibility::Invisible
+    );
+}
+
+// Returns the bounds of an entity relative to an ancestor as (posx, posy, width, height)
+fn relative_bounds(driver: &TestDriver, ancestor: Entity, entity: Entity) -> (f32, f32, f32, f32) {
+    let transform = &driver.state.transform;
+    (
+        transform.get_posx(entity) - transform.get_posx(ancestor),
+        transform.get_posy(entity) - transform.get_posy(ancestor),
+        transform.get_width(entity),
+        transform.get_height(entity),
+    )
+}
+
+fn grid_item(state: &mut State, grid: Entity) -> Entity {
+    Button::new().build(state, grid, |builder| builder)
+}
+
+#[test]
+fn grid_tracks_and_gaps_are_sized() {
+    let mut grid = Entity::null();
+    let mut items = Vec::new();
+    let mut driver = TestDriver::new(400.0, 300.0, |state, root| {
+        grid = Button::new().build(state, root, |builder| {
+            builder
+                .set_width(Length::Pixels(300.0))
+                .set_height(Length::Pixels(200.0))
+        });
+        grid.set_display(state, Display::Grid)
+            .set_grid_columns(
+                state,
+                vec![
+                    TrackSize::Pixels(50.0),
+                    TrackSize::Percentage(0.2),
+                    TrackSize::Fraction(1.0),
+                    TrackSize::Auto,
+                ],
+            )
+            .set_grid_rows(
+                state,
+                vec![
+                    TrackSize::Pixels(40.0),
+                    TrackSize::Auto,
+                    TrackSize::Fraction(1.0),
+                ],
+            )
+            .set_grid_column_gap(state, Length::Pixels(10.0))
+            .set_grid_row_gap(state, Length::Pixels(5.0));
+
+        for _ in 0..3 {
+            items.push(grid_item(state, grid));
+        }
+
+        // Sizes the auto column
+        items.push(Button::new().build(state, grid, |builder| {
+            builder
+                .set_width(Length::Pixels(30.0))
+                .set_height(Length::Pixels(25.0))
+        }));
+
+        // Sizes the auto row
+        items.push(Button::new().build(state, grid, |builder| {
+            builder
+                .set_width(Length::Pixels(20.0))
+                .set_height(Length::Pixels(35.0))
+        }));
+
+        items.push(Button::new().build(state, grid, |builder| builder));
+    });
+
+    // Columns of 50px, 20% of 300px, the 130px left over and the 30px content, with 10px between them
+    assert_eq!(
+        relative_bounds(&driver, grid, items[0]),
+        (0.0, 0.0, 50.0, 40.0)
+    );
+    assert_eq!(
+        relative_bounds(&driver, grid, items[1]),
+        (60.0, 0.0, 60.0, 40.0)
+    );
+    assert_eq!(
+        relative_bounds(&driver, grid, items[2]),
+        (130.0, 0.0, 130.0, 40.0)
+    );
+    assert_eq!(
+        relative_bounds(&driver, grid, items[3]),
+        (270.0, 0.0, 30.0, 25.0)
+    );
+
+    // Rows of 40px, the 35px content and the 115px left over, with 5px between them
+    assert_eq!(
+        relative_bounds(&driver, grid, items[4]),
+        (0.0, 45.0, 20.0, 35.0)
+    );
+    assert_eq!(
+        relative_bounds(&driver, grid, items[5]),
+        (60.0, 45.0, 60.0, 35.0)
+    );
+}
+
+#[test]
+fn grid_items_span_tracks_and_fill_the_next_free_cell() {
+    let (mut grid, mut wide, mut tall) = Default::default();
+    let mut items = Vec::new();
+    let mut driver = TestDriver::new(400.0, 300.0, |state, root| {
+        grid = Button::new().build(state, root, |builder| {
+            builder
+                .set_width(Length::Pixels(300.0))
+                .set_height(Length::Pixels(150.0))
+        });
+        grid.set_display(state, Display::Grid)
+            .set_grid_columns(state, vec![TrackSize::Fraction(1.0); 3])
+            .set_grid_rows(state, vec![TrackSize::Pixels(50.0); 3]);
+
+        // grid-column: 2 / span 2; grid-row: 1
+        wide = grid_item(state, grid)
+            .set_grid_column_start(state, 2)
+            .set_grid_column_span(state, 2)
+            .set_grid_row_start(state, 1);
+
+        // grid-column: 1; grid-row: 2 / span 2
+        tall = grid_item(state, grid)
+            .set_grid_column_start(state, 1)
+            .set_grid_row_start(state, 2)
+            .set_grid_row_span(state, 2);
+
+        for _ in 0..4 {
+            items.push(grid_item(state, grid));
+        }
+    });
+
+    assert_eq!(
+        relative_bounds(&driver, grid, wide),
+        (100.0, 0.0, 200.0, 50.0)
+    );
+    assert_eq!(
+        relative_bounds(&driver, grid, tall),
+        (0.0, 50.0, 100.0, 100.0)
+    );
+
+    // The items placed automatically go row by row into the cells which are left
+    assert_eq!(
+        relative_bounds(&driver, grid, items[0]),
+        (0.0, 0.0, 100.0, 50.0)
+    );
+    assert_eq!(
+        relative_bounds(&driver, grid, items[1]),
+        (100.0, 50.0, 100.0, 50.0)
+    );
+    assert_eq!(
+        relative_bounds(&driver, grid, items[2]),
+        (200.0, 50.0, 100.0, 50.0)
+    );
+    assert_eq!(
+        relative_bounds(&driver, grid, items[3]),
+        (100.0, 100.0, 100.0, 50.0)
+    );
+}
+
+// Builds a wrapping row with three 80x30 items, returning the container and its items
+fn wrapping_row(
+    state: &mut State,
+    parent: Entity,
+    flex_wrap: FlexWrap,
+    align_content: AlignContent,
+) -> (Entity, Vec<Entity>) {
+    let container = Button::new().build(state, parent, |builder| {
+        builder
+            .set_width(Length::Pixels(200.0))
+            .set_height(Length::Pixels(200.0))
+            .set_flex_direction(FlexDirection::Row)
+            .set_flex_wrap(flex_wrap)
+            .set_align_content(align_content)
+    });
+
+    let items = (0..3)
+        .map(|_| {
+            Button::new().build(state, container, |builder| {
+                builder
+                    .set_width(Length::Pixels(80.0))
+                    .set_height(Length::Pixels(30.0))
+            })
+        })
+        .collect();
+
+    (container, items)
+}
+
+#[test]
+fn wrapped_items_break_into_lines() {
+    let (mut container, mut items) = Default::default();
+    let driver = TestDriver::new(400.0, 300.0, |state, root| {
+        let (row, row_items) = wrapping_row(state, root, FlexWrap::Wrap, AlignContent::FlexStart);
+        container = row;
+        items = row_items;
+    });
+
+    assert_eq!(
+        relative_bounds(&driver, container, items[0]),
+        (0.0, 0.0, 80.0, 30.0)
+    );
+    assert_eq!(
+        relative_bounds(&driver, container, items[1]),
+        (80.0, 0.0, 80.0, 30.0)
+    );
+    assert_eq!(
+        relative_bounds(&driver, container, items[2]),
+        (0.0, 30.0, 80.0, 30.0)
+    );
+}
+
+#[test]
+fn reverse_wrapped_lines_start_from_the_end() {
+    let (mut container, mut items) = Default::default();
+    let driver = TestDriver::new(400.0, 300.0, |state, root| {
+        let (row, row_items) =
+            wrapping_row(state, root, FlexWrap::WrapReverse, AlignContent::FlexStart);
+        container = row;
+        items = row_items;
+    });
+
+    assert_eq!(
+        relative_bounds(&driver, container, items[0]),
+        (0.0, 170.0, 80.0, 30.0)
+    );
+    assert_eq!(
+        relative_bounds(&driver, container, items[1]),
+        (80.0, 170.0, 80.0, 30.0)
+    );
+    assert_eq!(
+        relative_bounds(&driver, container, items[2]),
+        (0.0, 140.0, 80.0, 30.0)
+    );
+}
+
+#[test]
+fn align_content_distributes_the_lines() {
+    // The two lines are 30px high, leaving 140px free
+    let cases = [
+        (AlignContent::FlexStart, 0.0, 30.0),
+        (AlignContent::FlexEnd, 140.0, 170.0),
+        (AlignContent::Center, 70.0, 100.0),
+        (AlignContent::SpaceBetween, 0.0, 170.0),
+        (AlignContent::SpaceAround, 35.0, 135.0),
+        (AlignContent::Stretch, 0.0, 100.0),
+    ];
+
+    for (align_content, first_line, second_line) in cases.iter().cloned() {
+        let (mut container, mut items) = Default::default();
+        let driver = TestDriver::new(400.0, 300.0, |state, root| {
+            let (row, row_items) = wrapping_row(state, root, FlexWrap::Wrap, align_content);
+            container = row;
+            items = row_items;
+        });
+
+        let posy = |item: Entity| relative_bounds(&driver, container, item).1;
+        assert_eq!(posy(items[0]), first_line, "{:?}", align_content);
+        assert_eq!(posy(items[1]), first_line, "{:?}", align_content);
+        assert_eq!(posy(items[2]), second_line, "{:?}", align_content);
+    }
+}
+
+#[test]
+fn wrapped_lines_settle_in_one_relayout() {
+    let (mut outer, mut container) = Default::default();
+    let mut driver = TestDriver::new(400.0, 300.0, |state, root| {
+        outer = Button::new().build(state, root, |builder| {
+            builder.set_width(Length::Pixels(200.0))
+        });
+        container = Button::new().build(state, outer, |builder| {
+            builder
+                .set_width(Length::Percentage(1.0))
+                .set_flex_direction(FlexDirection::Row)
+                .set_flex_wrap(FlexWrap::Wrap)
+        });
+
+        for _ in 0..3 {
+            Button::new().build(state, container, |builder| {
+                builder
+                    .set_width(Length::Pixels(80.0))
+                    .set_height(Length::Pixels(30.0))
+            });
+        }
+    });
+
+    // The container's height is auto, so it fits its two lines
+    assert_eq!(driver.state.transform.get_height(container), 60.0);
+
+    // Widening the container fits the items on one line, which it only finds out once it has been laid out
+    outer.set_width(&mut driver.state, Length::Pixels(300.0));
+    driver.state.event_queue.clear();
+
+    let hierarchy = driver.state.hierarchy.clone();
+    layout_fun(&mut driver.state, &hierarchy);
+
+    assert_eq!(driver.state.transform.get_height(container), 30.0);
+    assert_eq!(driver.state.transform.get_height(outer), 30.0);
+    assert!(driver.state.event_queue.is_empty());
+}
@@ -0,0 +1,40 @@
+use tuix::widgets::*;
+use tuix::*;
+
+#[test]
+fn closing_a_popup_restores_focus_from_inside_of_it() {
+    let (mut textbox, mut popup, mut field) = Default::default();
+    let mut driver = TestDriver::new(300.0, 200.0, |state, root| {
+        textbox = Textbox::new("").build(state, root, |builder| {
+            builder
+                .set_width(Length::Pixels(100.0))
+                .set_height(Length::Pixels(30.0))
+        });
+        popup = Button::new().build(state, root, |builder| {
+            builder
+                .set_width(Length::Pixels(120.0))
+                .set_height(Length::Pixels(60.0))
+                .set_visibility(Visibility::Invisible)
+        });
+        field = Textbox::new("").build(state, popup, |builder| {
+            builder
+                .set_width(Length::Pixels(100.0))
+                .set_height(Length::Pixels(30.0))
+        });
+    });
+
+    driver.click(textbox);
+    assert_eq!(driver.state.focused, textbox);
+
+    driver
+        .state
+        .open_overlay(popup, Overlay::popup(textbox, Placement::Below));
+    driver.flush();
+
+    driver.click(field);
+    assert_eq!(driver.state.focused, field);
+
+    driver.state.close_overlay(popup);
+    driver.flush();
+    assert_eq!(driver.state.focused, textbox);
+}
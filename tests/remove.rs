@@ -0,0 +1,151 @@
+use tuix::widgets::*;
+use tuix::*;
+
+// Builds a branch of two entities and a sibling which is kept when the branch is removed,
+// returned as (branch, inner, kept)
+fn branch_and_sibling() -> (TestDriver, Entity, Entity, Entity) {
+    let (mut branch, mut inner, mut kept) = Default::default();
+    let driver = TestDriver::new(300.0, 200.0, |state, root| {
+        branch = Button::new().build(state, root, |builder| {
+            builder
+                .set_width(Length::Pixels(100.0))
+                .set_height(Length::Pixels(50.0))
+        });
+        inner = Button::new().build(state, branch, |builder| {
+            builder
+                .set_width(Length::Pixels(40.0))
+                .set_height(Length::Pixels(20.0))
+        });
+        kept = Button::new().build(state, root, |builder| {
+            builder
+                .set_width(Length::Pixels(30.0))
+                .set_height(Length::Pixels(30.0))
+        });
+    });
+
+    (driver, branch, inner, kept)
+}
+
+#[test]
+fn removing_a_branch_frees_its_slots() {
+    let (mut driver, branch, inner, kept) = branch_and_sibling();
+
+    driver.state.remove(branch);
+    driver.flush();
+
+    for entity in [branch, inner].iter() {
+        assert!(!driver.state.is_alive(*entity));
+        assert!(!driver.state.hierarchy.entities.contains(entity));
+        assert!(!driver.state.event_handlers.contains_key(entity));
+        assert!(driver.state.style.width.get(*entity).is_none());
+    }
+
+    assert!(driver.state.is_alive(kept));
+    assert!(driver.state.hierarchy.entities.contains(&kept));
+    assert_eq!(
+        driver.state.hierarchy.get_parent(kept),
+        Some(driver.get_root())
+    );
+}
+
+#[test]
+fn removing_a_branch_moves_the_data_left_in_the_storages() {
+    let (mut driver, branch, inner, kept) = branch_and_sibling();
+    let root = driver.get_root();
+
+    // The kept entity's data is stored last, so removing the branch's data moves it
+    let state = &mut driver.state;
+    for (entity, value) in [(branch, 1.0), (inner, 2.0), (kept, 3.0)].iter().cloned() {
+        state.style.left.insert(entity, Length::Pixels(value));
+        state.style.border_width.insert(entity, value);
+        state.style.focus_order.insert(
+            entity,
+            FocusOrder {
+                next: root,
+                prev: entity,
+            },
+        );
+    }
+
+    let animatable = state.style.left.data.len();
+    let style = state.style.border_width.data.len();
+    let dense = state.style.focus_order.data.len();
+
+    state.remove(branch);
+    driver.flush();
+
+    let style_data = &driver.state.style;
+    assert_eq!(style_data.left.data.len(), animatable - 2);
+    assert_eq!(style_data.border_width.data.len(), style - 2);
+    assert_eq!(style_data.focus_order.data.len(), dense - 2);
+
+    assert_eq!(style_data.left.get(kept), Some(&Length::Pixels(3.0)));
+    assert_eq!(style_data.border_width.get(kept), Some(&3.0));
+    assert_eq!(
+        style_data.focus_order.get(kept).map(|order| order.prev),
+        Some(kept)
+    );
+}
+
+#[test]
+fn removing_a_branch_releases_the_entities_inside_of_it() {
+    let (mut driver, branch, inner, _) = branch_and_sibling();
+    let root = driver.get_root();
+
+    driver.state.focused = inner;
+    driver.state.hovered = inner;
+    driver.state.capture(inner);
+
+    driver.state.remove(branch);
+    driver.flush();
+
+    assert_eq!(driver.state.focused, root);
+    assert_eq!(driver.state.hovered, root);
+    assert_eq!(driver.state.captured, Entity::null());
+}
+
+#[test]
+fn removing_a_branch_closes_the_overlays_it_owns() {
+    // An overlay inside of the branch, and one outside of it but anchored to it
+    for inside in [true, false].iter().cloned() {
+        let (mut driver, branch, inner, kept) = branch_and_sibling();
+        let root = driver.get_root();
+
+        let popup = |state: &mut State, parent: Entity| {
+            Button::new().build(state, parent, |builder| {
+                builder
+                    .set_width(Length::Pixels(60.0))
+                    .set_height(Length::Pixels(20.0))
+                    .set_visibility(Visibility::Invisible)
+            })
+        };
+
+        let unrelated = popup(&mut driver.state, root);
+        let (owned, anchor) = if inside {
+            (popup(&mut driver.state, inner), kept)
+        } else {
+            (popup(&mut driver.state, root), inner)
+        };
+
+        driver
+            .state
+            .open_overlay(unrelated, Overlay::popup(kept, Placement::Below));
+        driver
+            .state
+            .open_overlay(owned, Overlay::popup(anchor, Placement::Below));
+        driver.flush();
+
+        driver.state.remove(branch);
+        driver.flush();
+
+        assert!(!driver.state.overlays.is_open(owned));
+        assert!(driver.state.overlays.is_open(unrelated));
+
+        if !inside {
+            assert_eq!(
+                driver.state.style.visibility.get(owned),
+                Some(&Visibility::Invisible)
+            );
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use tuix::widgets::*;
+use tuix::*;
+
+fn size(driver: &TestDriver, entity: Entity) -> (f32, f32) {
+    (
+        driver.state.transform.get_width(entity),
+        driver.state.transform.get_height(entity),
+    )
+}
+
+// Adds a class without flagging the entity, so it only takes effect if the entity is restyled anyway
+fn insert_class_quietly(driver: &mut TestDriver, entity: Entity, class: &str) {
+    driver
+        .state
+        .style
+        .classes
+        .get_mut(entity)
+        .unwrap()
+        .insert(class.to_string());
+}
+
+#[test]
+fn hover_restyles_only_the_hovered_branch() {
+    let (mut row, mut hovered, mut child, mut other) = Default::default();
+    let mut driver = TestDriver::new(300.0, 200.0, |state, root| {
+        state.insert_style(
+            "
+            button { width: 60px; height: 60px; flex-grow: 0.0; }
+            button:hover { width: 80px; }
+            .child { width: 20px; height: 20px; }
+            button:hover > .child { height: 10px; }
+            .quiet { width: 10px; }
+            ",
+        );
+
+        row = Button::new().build(state, root, |builder| builder.class("row"));
+        hovered = Button::new().build(state, row, |builder| builder);
+        child = Button::new().build(state, hovered, |builder| builder.class("child"));
+        other = Button::new().build(state, root, |builder| builder.class("other"));
+    });
+
+    for entity in [row, other].iter() {
+        insert_class_quietly(&mut driver, *entity, "quiet");
+    }
+
+    let (x, y) = driver.center(hovered);
+    driver.move_mouse(x + 15.0, y + 15.0);
+    assert_eq!(driver.state.hovered, hovered);
+
+    assert_eq!(size(&driver, hovered).0, 80.0);
+    assert_eq!(size(&driver, child), (20.0, 10.0));
+
+    // The row is restyled because it is now :over, but the unrelated entity isn't visited
+    assert_eq!(size(&driver, row).0, 10.0);
+    assert_eq!(size(&driver, other).0, 60.0);
+
+    driver.state.style.set_dirty(other);
+    driver.state.insert_event(Event::new(WindowEvent::Restyle));
+    driver.flush();
+    assert_eq!(size(&driver, other).0, 10.0);
+}
+
+#[test]
+fn sibling_selectors_follow_a_hover_change() {
+    let (mut marker, mut next, mut last) = Default::default();
+    let mut driver = TestDriver::new(300.0, 200.0, |state, root| {
+        state.insert_style(
+            "
+            button { width: 30px; height: 30px; flex-grow: 0.0; }
+            .marker:hover + button { width: 40px; }
+            .marker:hover ~ .last { height: 50px; }
+            ",
+        );
+
+        marker = Button::new().build(state, root, |builder| builder.class("marker"));
+        next = Button::new().build(state, root, |builder| builder);
+        last = Button::new().build(state, root, |builder| builder.class("last"));
+    });
+
+    assert_eq!(size(&driver, next), (30.0, 30.0));
+    assert_eq!(size(&driver, last), (30.0, 30.0));
+
+    driver.move_mouse(15.0, 15.0);
+    assert_eq!(driver.state.hovered, marker);
+    assert_eq!(size(&driver, next), (40.0, 30.0));
+    assert_eq!(size(&driver, last), (30.0, 50.0));
+
+    driver.move_mouse(250.0, 150.0);
+    assert_eq!(size(&driver, next), (30.0, 30.0));
+    assert_eq!(size(&driver, last), (30.0, 30.0));
+}
@@ -0,0 +1,80 @@
+use tuix::widgets::*;
+use tuix::*;
+
+const STYLE: &str = "
+    scroll_container {
+        width: 100px;
+        height: 100px;
+        flex-direction: row;
+    }
+
+    scroll_container>.container {
+        align-self: flex-start;
+        flex-grow: 1.0;
+    }
+
+    .item {
+        height: 50px;
+    }
+";
+
+// Asserts that an entity appears centred at a y position in the window
+fn assert_center_y(driver: &TestDriver, entity: Entity, y: f32) {
+    let center = driver.center(entity).1;
+    assert!((center - y).abs() < 0.01, "centred at {} rather than {}", center, y);
+}
+
+fn scroll_container() -> (TestDriver, Entity, Vec<Entity>) {
+    let mut scroll = Entity::null();
+    let mut items = Vec::new();
+    let driver = TestDriver::new(300.0, 200.0, |state, root| {
+        state.insert_style(STYLE);
+
+        let container = ScrollContainer::new().build(state, root, |builder| builder);
+        scroll = state.hierarchy.get_parent(container).unwrap();
+
+        for _ in 0..5 {
+            items.push(Button::new().build(state, container, |builder| builder.class("item")));
+        }
+    });
+
+    (driver, scroll, items)
+}
+
+#[test]
+fn the_mouse_wheel_scrolls_the_contents() {
+    let (mut driver, scroll, items) = scroll_container();
+
+    assert_eq!(driver.state.transform.get_content_height(scroll), 250.0);
+    assert_eq!(driver.entity_at(50.0, 75.0), items[1]);
+
+    driver.move_mouse(50.0, 50.0);
+    driver.scroll(0.0, -1.0);
+
+    // The contents move up by a line of the wheel without being laid out again
+    assert_eq!(driver.state.transform.get_posy(items[1]), 50.0);
+    assert_center_y(&driver, items[1], 75.0 - SCROLL_LINE);
+    assert_eq!(driver.entity_at(50.0, 75.0), items[2]);
+
+    // Scrolling stops at the end of the contents
+    for _ in 0..10 {
+        driver.scroll(0.0, -1.0);
+    }
+
+    assert_center_y(&driver, items[4], 75.0);
+}
+
+#[test]
+fn descendants_are_scrolled_into_view() {
+    let (mut driver, _, items) = scroll_container();
+
+    driver.state.insert_event(
+        Event::new(ScrollContainerEvent::ScrollIntoView(200.0, 250.0))
+            .target(items[4])
+            .propagate(Propagation::Up),
+    );
+    driver.flush();
+
+    assert_center_y(&driver, items[4], 75.0);
+    assert_center_y(&driver, items[0], -125.0);
+}
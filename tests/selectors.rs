@@ -0,0 +1,122 @@
+use tuix::widgets::*;
+use tuix::*;
+
+fn width(driver: &TestDriver, entity: Entity) -> f32 {
+    driver.state.transform.get_width(entity)
+}
+
+#[test]
+fn not_excludes_a_simple_selector() {
+    let (mut plain, mut marked) = Default::default();
+    let driver = TestDriver::new(300.0, 200.0, |state, root| {
+        let report = state.insert_style(
+            "
+            button { width: 20px; height: 10px; }
+            button:not(.marked) { width: 40px; }
+            ",
+        );
+        assert!(report.is_empty(), "{}", report);
+
+        plain = Button::new().build(state, root, |builder| builder);
+        marked = Button::new().build(state, root, |builder| builder.class("marked"));
+    });
+
+    assert_eq!(width(&driver, plain), 40.0);
+    assert_eq!(width(&driver, marked), 20.0);
+}
+
+#[test]
+fn not_rejects_selectors_which_are_not_simple() {
+    let mut state = State::new();
+
+    for (case, negated) in [".a .b", ".a > .b", ".a.b", "button:hover", ".a, .b", ":not(.a)"]
+        .iter()
+        .enumerate()
+    {
+        let stylesheet = format!("button {{ width: 1px; }}\nbutton:not({}) {{ width: 2px; }}", negated);
+        let report = state.insert_style(&stylesheet);
+
+        assert_eq!(report.diagnostics.len(), 1, "{} gave {}", negated, report);
+
+        let diagnostic = &report.diagnostics[0];
+        assert_eq!(diagnostic.severity, Severity::Error);
+        assert_eq!(diagnostic.line, 2, "{}", negated);
+        assert_eq!(
+            diagnostic.kind,
+            DiagnosticKind::InvalidSelector(format!("button:not({})", negated)),
+            "case {}",
+            case
+        );
+    }
+}
+
+#[test]
+fn sibling_combinators_and_structural_pseudo_classes() {
+    let mut buttons = Vec::new();
+    let driver = TestDriver::new(300.0, 200.0, |state, root| {
+        state.insert_style(
+            "
+            button { width: 10px; height: 10px; }
+            button:first-child { width: 20px; }
+            .marker + button { width: 30px; }
+            .marker ~ button:last-child { width: 40px; }
+            ",
+        );
+
+        for index in 0..4 {
+            buttons.push(Button::new().build(state, root, |builder| {
+                if index == 1 {
+                    builder.class("marker")
+                } else {
+                    builder
+                }
+            }));
+        }
+    });
+
+    let widths: Vec<f32> = buttons.iter().map(|button| width(&driver, *button)).collect();
+    assert_eq!(widths, vec![20.0, 10.0, 30.0, 40.0]);
+}
+
+#[test]
+fn custom_pseudo_classes_all_have_to_match() {
+    let (mut plain, mut selected) = Default::default();
+    let mut driver = TestDriver::new(300.0, 200.0, |state, root| {
+        let report = state.insert_style(
+            "
+            button { width: 20px; height: 10px; }
+            button:selected { width: 40px; }
+            button:selected:expanded { width: 60px; }
+            button:selected:checked { width: 80px; }
+            ",
+        );
+        assert!(report.is_empty(), "{}", report);
+
+        plain = Button::new().build(state, root, |builder| builder);
+        selected = Button::new().build(state, root, |builder| {
+            builder.set_pseudo_class("selected", true)
+        });
+    });
+
+    assert_eq!(width(&driver, plain), 20.0);
+    assert_eq!(width(&driver, selected), 40.0);
+
+    plain.set_pseudo_class(&mut driver.state, "expanded", true);
+    selected.set_pseudo_class(&mut driver.state, "expanded", true);
+    driver.flush();
+
+    // :expanded alone doesn't match a rule which also needs :selected
+    assert_eq!(width(&driver, plain), 20.0);
+    assert_eq!(width(&driver, selected), 60.0);
+
+    // Custom pseudo-classes combine with the built in ones
+    selected
+        .set_pseudo_class(&mut driver.state, "expanded", false)
+        .set_checked(&mut driver.state, true);
+    driver.flush();
+    assert_eq!(width(&driver, selected), 80.0);
+
+    selected.set_pseudo_class(&mut driver.state, "selected", false);
+    driver.flush();
+    assert_eq!(width(&driver, selected), 20.0);
+}
@@ -0,0 +1,99 @@
+use image::{Rgba, RgbaImage};
+use std::path::PathBuf;
+
+use tuix::compare_snapshot;
+use tuix::headless::SnapshotError;
+
+// Returns a path in a fresh directory under the system temp directory
+fn snapshot_path(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("tuix-snapshot-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir.join(format!("{}.png", name))
+}
+
+fn image(width: u32, height: u32, color: [u8; 4]) -> RgbaImage {
+    RgbaImage::from_pixel(width, height, Rgba(color))
+}
+
+#[test]
+fn missing_golden_is_an_error() {
+    let path = snapshot_path("missing");
+    let actual = image(4, 4, [10, 20, 30, 255]);
+
+    match compare_snapshot(&actual, &path, 0) {
+        Err(SnapshotError::MissingGolden(missing)) => assert_eq!(missing, path),
+        result => panic!("expected a missing golden image, got {:?}", result),
+    }
+
+    // The golden image is only created when the snapshots are being updated
+    assert!(!path.exists());
+    let saved = image::open(path.with_extension("actual.png")).unwrap().to_rgba8();
+    assert_eq!(saved, actual);
+}
+
+#[test]
+fn matching_image_passes() {
+    let path = snapshot_path("matching");
+    image(4, 4, [10, 20, 30, 255]).save(&path).unwrap();
+
+    assert!(compare_snapshot(&image(4, 4, [10, 20, 30, 255]), &path, 0).is_ok());
+    assert!(!path.with_extension("actual.png").exists());
+}
+
+#[test]
+fn size_mismatch() {
+    let path = snapshot_path("size");
+    image(4, 4, [10, 20, 30, 255]).save(&path).unwrap();
+
+    let actual = image(5, 3, [10, 20, 30, 255]);
+
+    match compare_snapshot(&actual, &path, 0) {
+        Err(SnapshotError::SizeMismatch { expected, actual }) => {
+            assert_eq!(expected, (4, 4));
+            assert_eq!(actual, (5, 3));
+        }
+        result => panic!("expected a size mismatch, got {:?}", result),
+    }
+
+    // The golden image is left alone and the new image is saved next to it
+    assert_eq!(image::open(&path).unwrap().to_rgba8().dimensions(), (4, 4));
+    let saved = image::open(path.with_extension("actual.png")).unwrap().to_rgba8();
+    assert_eq!(saved, actual);
+}
+
+#[test]
+fn pixel_difference_within_tolerance() {
+    let path = snapshot_path("within");
+    image(4, 4, [10, 20, 30, 255]).save(&path).unwrap();
+
+    let mut actual = image(4, 4, [10, 20, 30, 255]);
+    actual.put_pixel(1, 1, Rgba([13, 17, 30, 255]));
+
+    assert!(compare_snapshot(&actual, &path, 3).is_ok());
+    assert!(!path.with_extension("actual.png").exists());
+}
+
+#[test]
+fn pixel_difference_beyond_tolerance() {
+    let path = snapshot_path("beyond");
+    image(4, 4, [10, 20, 30, 255]).save(&path).unwrap();
+
+    let mut actual = image(4, 4, [10, 20, 30, 255]);
+    actual.put_pixel(1, 1, Rgba([14, 20, 30, 255]));
+    actual.put_pixel(2, 3, Rgba([10, 20, 30, 200]));
+    // Within the tolerance, so not counted
+    actual.put_pixel(3, 3, Rgba([12, 20, 30, 255]));
+
+    match compare_snapshot(&actual, &path, 3) {
+        Err(SnapshotError::PixelMismatch { count }) => assert_eq!(count, 2),
+        result => panic!("expected a pixel mismatch, got {:?}", result),
+    }
+
+    let saved = image::open(path.with_extension("actual.png")).unwrap().to_rgba8();
+    assert_eq!(saved, actual);
+
+    // The golden image is unchanged
+    let golden = image::open(&path).unwrap().to_rgba8();
+    assert_eq!(golden, image(4, 4, [10, 20, 30, 255]));
+}
@@ -0,0 +1,25 @@
+// Setting TUIX_UPDATE_SNAPSHOTS affects every test in the process, so these run on their own
+use image::{Rgba, RgbaImage};
+
+use tuix::compare_snapshot;
+
+#[test]
+fn goldens_are_written_when_updating_snapshots() {
+    let dir = std::env::temp_dir().join(format!("tuix-snapshot-update-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+
+    std::env::set_var("TUIX_UPDATE_SNAPSHOTS", "1");
+
+    // A missing golden image is created
+    let path = dir.join("missing.png");
+    let actual = RgbaImage::from_pixel(4, 4, Rgba([10, 20, 30, 255]));
+    assert!(compare_snapshot(&actual, &path, 0).is_ok());
+    assert_eq!(image::open(&path).unwrap().to_rgba8(), actual);
+
+    // An existing one is replaced rather than compared
+    let changed = RgbaImage::from_pixel(5, 3, Rgba([40, 50, 60, 255]));
+    assert!(compare_snapshot(&changed, &path, 0).is_ok());
+    assert_eq!(image::open(&path).unwrap().to_rgba8(), changed);
+    assert!(!path.with_extension("actual.png").exists());
+}
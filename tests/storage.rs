@@ -0,0 +1,38 @@
+use tuix::animatable_storage::AnimatableStorage;
+use tuix::style_storage::StyleStorage;
+use tuix::*;
+
+// The first entity has index 0, which is also the index of the first data, so an entity inheriting
+// from it looks like it shares that data
+#[test]
+fn inline_data_is_freed_when_an_inheriting_entity_points_at_the_same_index() {
+    let parent = Entity::new(0, 0);
+    let child = Entity::new(1, 0);
+    let removed = Entity::new(2, 0);
+
+    let mut storage: StyleStorage<f32> = StyleStorage::new();
+    storage.insert(removed, 2.0);
+    storage.inherit(child, parent);
+    storage.remove(removed);
+    assert!(storage.data.is_empty());
+
+    let mut storage: AnimatableStorage<f32> = AnimatableStorage::new();
+    storage.insert(removed, 2.0);
+    storage.inherit(child, parent);
+    storage.remove(removed);
+    assert!(storage.data.is_empty());
+}
+
+#[test]
+fn inline_data_linked_to_another_entity_is_kept() {
+    let first = Entity::new(0, 0);
+    let second = Entity::new(1, 0);
+
+    let mut storage: StyleStorage<f32> = StyleStorage::new();
+    storage.insert(first, 1.0);
+    storage.insert(second, 2.0);
+    storage.remove(first);
+
+    assert_eq!(storage.data, vec![2.0]);
+    assert_eq!(storage.get(second), Some(&2.0));
+}
@@ -0,0 +1,44 @@
+use std::time::Duration;
+
+use tuix::widgets::*;
+use tuix::*;
+
+const STYLE: &str = "
+    @keyframes grow {
+        0% { width: 10px; }
+        100% { width: 100px; }
+    }
+
+    .grows {
+        width: 50px;
+        transition: width 2.0 0.0;
+        animation: grow 1s;
+    }
+";
+
+#[test]
+fn reloading_replaces_rule_animations() {
+    let mut driver = TestDriver::new(300.0, 200.0, |state, root| {
+        state.insert_style(STYLE);
+        Button::new().build(state, root, |builder| builder.class("grows"));
+    });
+
+    let count = driver.state.style.width.animations.len();
+    assert!(count >= 2);
+
+    // Animations inserted by widgets are kept, along with their ids
+    let mut animation = AnimationState::new();
+    animation.duration = Duration::from_secs(3);
+    let widget_animation = driver.state.style.width.insert_animation(animation);
+
+    for _ in 0..3 {
+        driver.state.reload_styles();
+        driver.flush();
+    }
+
+    assert_eq!(driver.state.style.width.animations.len(), count + 1);
+    assert_eq!(
+        driver.state.style.width.animations[widget_animation].duration,
+        Duration::from_secs(3)
+    );
+}
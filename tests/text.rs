@@ -0,0 +1,134 @@
+use tuix::widgets::*;
+use tuix::*;
+
+const TEXT: &str = "one  two\nthree four";
+
+// Returns the lines of an entity's text as they are drawn in its bounds
+fn lines(driver: &mut TestDriver, entity: Entity) -> Vec<String> {
+    let width = driver.state.transform.get_width(entity);
+    let height = driver.state.transform.get_height(entity);
+
+    text_layout(&mut driver.state, entity, width, height)
+        .unwrap()
+        .lines
+        .into_iter()
+        .map(|line| line.text)
+        .collect()
+}
+
+// Builds an entity with text and a fixed width whose height fits its lines
+fn text_box(width: f32, text: &str, style: impl Fn(Builder) -> Builder) -> (TestDriver, Entity) {
+    let mut entity = Entity::null();
+    let driver = TestDriver::new(400.0, 300.0, |state, root| {
+        entity = Button::new().build(state, root, |builder| {
+            style(builder.set_width(Length::Pixels(width)).set_text(text))
+        });
+    });
+
+    (driver, entity)
+}
+
+#[test]
+fn text_wraps_to_the_width_of_its_entity() {
+    let (mut driver, entity) = text_box(80.0, "one two three four five six", |builder| builder);
+
+    let layout = text_layout(&mut driver.state, entity, 80.0, f32::INFINITY).unwrap();
+    assert!(layout.lines.len() > 1);
+    assert!(layout.lines.iter().all(|line| line.width <= 80.0));
+    assert_eq!(
+        lines(&mut driver, entity).join(" "),
+        "one two three four five six"
+    );
+
+    // The entity is as tall as its lines
+    assert_eq!(driver.state.transform.get_height(entity), layout.height());
+}
+
+#[test]
+fn white_space_collapses_and_wraps_text() {
+    // A width of 1px breaks the text at every point the white space allows
+    let cases: [(WhiteSpace, f32, &[&str]); 6] = [
+        (WhiteSpace::Normal, 400.0, &["one two three four"]),
+        (WhiteSpace::Normal, 1.0, &["one", "two", "three", "four"]),
+        (WhiteSpace::NoWrap, 1.0, &["one two three four"]),
+        (WhiteSpace::Pre, 1.0, &["one  two", "three four"]),
+        (WhiteSpace::PreWrap, 1.0, &["one", "two", "three", "four"]),
+        (WhiteSpace::PreLine, 400.0, &["one two", "three four"]),
+    ];
+
+    for (white_space, width, expected) in cases.iter() {
+        let (mut driver, entity) = text_box(*width, TEXT, |builder| {
+            builder.set_white_space(*white_space)
+        });
+
+        assert_eq!(lines(&mut driver, entity), *expected, "{:?}", white_space);
+    }
+}
+
+#[test]
+fn line_height_sets_the_height_of_each_line() {
+    let (driver, pixels) = text_box(400.0, TEXT, |builder| {
+        builder
+            .set_white_space(WhiteSpace::Pre)
+            .set_line_height(LineHeight::Pixels(30.0))
+    });
+    assert_eq!(driver.state.transform.get_height(pixels), 60.0);
+
+    // A number is a multiple of the font size
+    let (driver, number) = text_box(400.0, TEXT, |builder| {
+        builder
+            .set_white_space(WhiteSpace::Pre)
+            .set_font_size(20.0)
+            .set_line_height(LineHeight::Number(1.5))
+    });
+    assert_eq!(driver.state.transform.get_height(number), 60.0);
+}
+
+#[test]
+fn letter_spacing_is_added_after_each_character() {
+    let (mut driver, plain) = text_box(400.0, "abcd", |builder| builder);
+    let (mut spaced_driver, spaced) =
+        text_box(400.0, "abcd", |builder| builder.set_letter_spacing(2.0));
+
+    let plain_width = text_layout(&mut driver.state, plain, f32::INFINITY, f32::INFINITY)
+        .unwrap()
+        .width();
+    let spaced_width = text_layout(
+        &mut spaced_driver.state,
+        spaced,
+        f32::INFINITY,
+        f32::INFINITY,
+    )
+    .unwrap()
+    .width();
+
+    assert_eq!(spaced_width - plain_width, 8.0);
+}
+
+#[test]
+fn text_overflow_ellipsis_cuts_lines_short() {
+    // A line which doesn't wrap is cut at the width of the entity
+    let (mut driver, entity) = text_box(50.0, "a line which is too long", |builder| {
+        builder
+            .set_white_space(WhiteSpace::NoWrap)
+            .set_text_overflow(TextOverflow::Ellipsis)
+    });
+
+    let layout = text_layout(&mut driver.state, entity, 50.0, f32::INFINITY).unwrap();
+    assert_eq!(layout.lines.len(), 1);
+    assert!(layout.lines[0].text.ends_with('\u{2026}'));
+    assert!(layout.lines[0].width <= 50.0);
+
+    // Lines which don't fit the height are dropped and the last line shown ends with an ellipsis
+    let (mut driver, entity) = text_box(80.0, "one two three four five six", |builder| {
+        builder
+            .set_height(Length::Pixels(20.0))
+            .set_line_height(LineHeight::Pixels(20.0))
+            .set_text_overflow(TextOverflow::Ellipsis)
+    });
+
+    let shown = lines(&mut driver, entity);
+    assert_eq!(shown.len(), 1);
+    assert!(shown[0].ends_with('\u{2026}'));
+    assert!(shown[0].starts_with("one"));
+}
@@ -0,0 +1,65 @@
+use std::time::Duration;
+
+use tuix::widgets::*;
+use tuix::*;
+
+fn text(driver: &TestDriver, entity: Entity) -> String {
+    driver.state.style.text.get(entity).unwrap().text.clone()
+}
+
+fn textarea(value: &str) -> (TestDriver, Entity) {
+    let mut textarea = Entity::null();
+    let driver = TestDriver::new(300.0, 200.0, |state, root| {
+        textarea = TextArea::new(value).build(state, root, |builder| {
+            builder
+                .set_width(Length::Pixels(200.0))
+                .set_height(Length::Pixels(100.0))
+        });
+    });
+
+    (driver, textarea)
+}
+
+// Clicks twice at the start of the first line, with a delay in between, and types over the selection
+fn click_twice_and_type(delay: Duration) -> String {
+    let (mut driver, textarea) = textarea("one two");
+    let x = driver.state.transform.get_posx(textarea) + 1.0;
+    let y = driver.state.transform.get_posy(textarea) + 8.0;
+
+    driver.click_at(x, y);
+    driver.advance(delay);
+    driver.click_at(x, y);
+    driver.type_text("1");
+
+    text(&driver, textarea)
+}
+
+#[test]
+fn double_clicks_are_timed_with_the_timer_clock() {
+    // A double click selects the word
+    assert_eq!(click_twice_and_type(Duration::from_millis(100)), "1 two");
+
+    // Too slow for a double click, so the second click only moves the caret
+    assert_eq!(click_twice_and_type(Duration::from_millis(600)), "1one two");
+}
+
+#[test]
+fn text_replaced_with_set_text_keeps_the_caret_inside_it() {
+    let (mut driver, textarea) = textarea("abcdef");
+
+    driver.click(textarea);
+    driver.press_key(VirtualKeyCode::Home);
+    for _ in 0..3 {
+        driver.press_key(VirtualKeyCode::Right);
+    }
+
+    // The caret is now in the middle of a character
+    textarea.set_text(&mut driver.state, "ééé");
+    driver.type_text("x");
+    assert_eq!(text(&driver, textarea), "éxéé");
+
+    driver.press_key(VirtualKeyCode::End);
+    textarea.set_text(&mut driver.state, "ab");
+    driver.press_key(VirtualKeyCode::Back);
+    assert_eq!(text(&driver, textarea), "a");
+}
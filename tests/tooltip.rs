@@ -0,0 +1,105 @@
+use std::time::Duration;
+
+use tuix::widgets::*;
+use tuix::*;
+
+#[test]
+fn widget_tooltips_can_be_hovered_again_once_hidden() {
+    let (mut owner, mut other, mut tooltip, mut child) = Default::default();
+    let mut driver = TestDriver::new(300.0, 200.0, |state, root| {
+        tooltip = Button::new().build(state, root, |builder| {
+            builder
+                .set_width(Length::Pixels(80.0))
+                .set_height(Length::Pixels(40.0))
+                .set_visibility(Visibility::Invisible)
+        });
+        child = Button::new().build(state, tooltip, |builder| {
+            builder
+                .set_width(Length::Pixels(20.0))
+                .set_height(Length::Pixels(20.0))
+        });
+        owner = Button::new().build(state, root, |builder| {
+            builder
+                .set_width(Length::Pixels(100.0))
+                .set_height(Length::Pixels(30.0))
+                .set_tooltip_widget(tooltip)
+        });
+        other = Button::new().build(state, root, |builder| {
+            builder
+                .set_width(Length::Pixels(100.0))
+                .set_height(Length::Pixels(30.0))
+        });
+    });
+
+    // Already not hoverable before it is shown, which is kept
+    driver.state.transform.set_hoverability(child, false);
+
+    let (x, y) = driver.center(owner);
+    driver.move_mouse(x, y);
+    driver.advance(Duration::from_secs(1));
+    assert_eq!(driver.state.tooltips.get_shown(), tooltip);
+    assert!(!driver.state.transform.get_hoverability(tooltip));
+
+    let (x, y) = driver.center(other);
+    driver.move_mouse(x, y);
+    assert_eq!(driver.state.tooltips.get_shown(), Entity::null());
+    assert!(driver.state.transform.get_hoverability(tooltip));
+    assert!(!driver.state.transform.get_hoverability(child));
+}
+
+#[test]
+fn removing_the_owner_restores_hoverability_of_its_tooltip() {
+    let (mut owner, mut tooltip) = Default::default();
+    let mut driver = TestDriver::new(300.0, 200.0, |state, root| {
+        tooltip = Button::new().build(state, root, |builder| {
+            builder
+                .set_width(Length::Pixels(80.0))
+                .set_height(Length::Pixels(40.0))
+                .set_visibility(Visibility::Invisible)
+        });
+        owner = Button::new().build(state, root, |builder| {
+            builder
+                .set_width(Length::Pixels(100.0))
+                .set_height(Length::Pixels(30.0))
+                .set_tooltip_widget(tooltip)
+        });
+    });
+
+    let (x, y) = driver.center(owner);
+    driver.move_mouse(x, y);
+    driver.advance(Duration::from_secs(1));
+    assert_eq!(driver.state.tooltips.get_shown(), tooltip);
+
+    driver.state.remove(owner);
+    driver.flush();
+    assert!(driver.state.transform.get_hoverability(tooltip));
+}
+
+#[test]
+fn removing_a_shown_tooltip_hides_it() {
+    let (mut owner, mut tooltip) = Default::default();
+    let mut driver = TestDriver::new(300.0, 200.0, |state, root| {
+        tooltip = Button::new().build(state, root, |builder| {
+            builder
+                .set_width(Length::Pixels(80.0))
+                .set_height(Length::Pixels(40.0))
+                .set_visibility(Visibility::Invisible)
+        });
+        owner = Button::new().build(state, root, |builder| {
+            builder
+                .set_width(Length::Pixels(100.0))
+                .set_height(Length::Pixels(30.0))
+                .set_tooltip_widget(tooltip)
+        });
+    });
+
+    let (x, y) = driver.center(owner);
+    driver.move_mouse(x, y);
+    driver.advance(Duration::from_secs(1));
+    assert_eq!(driver.state.tooltips.get_shown(), tooltip);
+
+    driver.state.remove(tooltip);
+    driver.flush();
+    assert_eq!(driver.state.tooltips.get_shown(), Entity::null());
+    assert!(driver.state.overlays.is_empty());
+}
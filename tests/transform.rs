@@ -0,0 +1,154 @@
+use tuix::widgets::*;
+use tuix::*;
+
+// Builds a 40 by 20 box at (100, 100) with the given style
+fn transformed(style: &str) -> (TestDriver, Entity) {
+    let mut entity = Entity::null();
+    let driver = TestDriver::new(400.0, 300.0, |state, root| {
+        state.insert_style(&format!(
+            "
+            .box {{
+                position: absolute;
+                left: 100px;
+                top: 100px;
+                width: 40px;
+                height: 20px;
+                {}
+            }}
+            ",
+            style
+        ));
+
+        entity = Button::new().build(state, root, |builder| builder.class("box"));
+    });
+
+    (driver, entity)
+}
+
+// Returns the smallest rectangle around the corners of the entity as the canvas draws it
+fn drawn(driver: &TestDriver, entity: Entity) -> (f32, f32, f32, f32) {
+    let posx = driver.state.transform.get_posx(entity);
+    let posy = driver.state.transform.get_posy(entity);
+    let width = driver.state.transform.get_width(entity);
+    let height = driver.state.transform.get_height(entity);
+    let matrix = driver.state.transform.get_matrix(entity);
+
+    let corners = [
+        matrix.transform_point(posx, posy),
+        matrix.transform_point(posx + width, posy),
+        matrix.transform_point(posx, posy + height),
+        matrix.transform_point(posx + width, posy + height),
+    ];
+
+    corners.iter().fold(
+        (f32::MAX, f32::MAX, f32::MIN, f32::MIN),
+        |(left, top, right, bottom), (x, y)| {
+            (left.min(*x), top.min(*y), right.max(*x), bottom.max(*y))
+        },
+    )
+}
+
+fn assert_drawn(driver: &TestDriver, entity: Entity, expected: (f32, f32, f32, f32)) {
+    let bounds = drawn(driver, entity);
+    let close = |a: f32, b: f32| (a - b).abs() < 0.01;
+
+    assert!(
+        close(bounds.0, expected.0)
+            && close(bounds.1, expected.1)
+            && close(bounds.2, expected.2)
+            && close(bounds.3, expected.3),
+        "drawn at {:?}, expected {:?}",
+        bounds,
+        expected
+    );
+}
+
+#[test]
+fn translate_moves_where_an_entity_is_drawn_and_hit() {
+    // Percentages are of the size of the entity
+    let (mut driver, entity) = transformed("transform: translate(50px, 25%);");
+    assert_drawn(&driver, entity, (150.0, 105.0, 190.0, 125.0));
+
+    assert_eq!(driver.entity_at(185.0, 122.0), entity);
+    assert_eq!(driver.entity_at(120.0, 110.0), driver.get_root());
+}
+
+#[test]
+fn scale_grows_around_the_transform_origin() {
+    // The origin defaults to the centre
+    let (mut driver, entity) = transformed("transform: scale(2);");
+    assert_drawn(&driver, entity, (80.0, 90.0, 160.0, 130.0));
+    assert_eq!(driver.entity_at(85.0, 95.0), entity);
+
+    let (mut driver, entity) = transformed("transform: scale(2); transform-origin: left top;");
+    assert_drawn(&driver, entity, (100.0, 100.0, 180.0, 140.0));
+    assert_eq!(driver.entity_at(175.0, 135.0), entity);
+    assert_eq!(driver.entity_at(85.0, 95.0), driver.get_root());
+}
+
+#[test]
+fn rotate_turns_around_the_transform_origin() {
+    let (mut driver, entity) = transformed("transform: rotate(90deg);");
+    assert_drawn(&driver, entity, (110.0, 90.0, 130.0, 130.0));
+
+    // Clockwise around the top left corner, so it ends up to the left of where it was laid out
+    let (mut driver, entity) = transformed("transform: rotate(90deg); transform-origin: 0px 0px;");
+    assert_drawn(&driver, entity, (80.0, 100.0, 100.0, 140.0));
+    assert_eq!(driver.entity_at(90.0, 135.0), entity);
+    assert_eq!(driver.entity_at(120.0, 110.0), driver.get_root());
+}
+
+#[test]
+fn transform_functions_apply_from_right_to_left() {
+    // Rotated in place and then moved down
+    let (mut driver, entity) = transformed("transform: translate(0px, 100px) rotate(90deg);");
+    assert_drawn(&driver, entity, (110.0, 190.0, 130.0, 230.0));
+    assert_eq!(driver.entity_at(120.0, 225.0), entity);
+}
+
+#[test]
+fn children_are_transformed_with_their_ancestors() {
+    let (mut parent, mut child) = Default::default();
+    let mut driver = TestDriver::new(400.0, 300.0, |state, root| {
+        state.insert_style(
+            "
+            .parent {
+                position: absolute;
+                left: 100px;
+                top: 100px;
+                width: 40px;
+                height: 20px;
+                transform: scale(2);
+                transform-origin: left top;
+            }
+
+            .child {
+                position: absolute;
+                left: 10px;
+                top: 5px;
+                width: 10px;
+                height: 5px;
+                transform: translate(5px, 0px);
+            }
+            ",
+        );
+
+        parent = Button::new().build(state, root, |builder| builder.class("parent"));
+        child = Button::new().build(state, parent, |builder| builder.class("child"));
+    });
+
+    // The translation of the child is scaled along with it
+    assert_drawn(&driver, child, (130.0, 110.0, 150.0, 120.0));
+
+    assert_eq!(driver.entity_at(145.0, 117.0), child);
+    assert_eq!(driver.entity_at(125.0, 115.0), parent);
+}
+
+#[test]
+fn entity_scaled_to_nothing_is_never_hit() {
+    let (mut driver, entity) = transformed("transform: scale(0);");
+    assert_eq!(driver.entity_at(120.0, 110.0), driver.get_root());
+
+    driver.click(entity);
+    assert_eq!(driver.state.hovered, driver.get_root());
+}
@@ -0,0 +1,58 @@
+use tuix::widgets::*;
+use tuix::*;
+
+#[test]
+fn unknown_properties_using_var_are_reported() {
+    let mut state = State::new();
+
+    let report = state.insert_style(
+        "
+        :root { --size: 10px; }
+        button { colr: var(--size); width: var(--size); }
+        ",
+    );
+
+    assert_eq!(report.diagnostics.len(), 1, "{}", report);
+    assert_eq!(
+        report.diagnostics[0].kind,
+        DiagnosticKind::UnknownProperty(String::from("colr"))
+    );
+    assert_eq!(report.diagnostics[0].line, 3);
+}
+
+#[test]
+fn substituted_values_which_fail_to_parse_are_reported() {
+    let (mut sized, mut broken) = Default::default();
+    let mut driver = TestDriver::new(300.0, 200.0, |state, root| {
+        let report = state.insert_style(
+            "
+            button { width: var(--size, 20px); height: 10px; }
+            .broken { --size: red; }
+            ",
+        );
+        assert!(report.is_empty(), "{}", report);
+
+        sized = Button::new().build(state, root, |builder| builder);
+        broken = Button::new().build(state, root, |builder| builder.class("broken"));
+    });
+
+    assert_eq!(driver.state.transform.get_width(sized), 20.0);
+
+    let report = driver.state.take_style_report();
+    assert_eq!(report.diagnostics.len(), 1, "{}", report);
+
+    let diagnostic = &report.diagnostics[0];
+    assert_eq!(diagnostic.severity, Severity::Error);
+    assert_eq!(
+        diagnostic.kind,
+        DiagnosticKind::InvalidValue {
+            property: String::from("width"),
+            value: String::from("red"),
+        }
+    );
+    assert_eq!((diagnostic.line, diagnostic.column), (2, 28));
+
+    // The declaration is left out, as it would be if the stylesheet had the value, and only reported once
+    assert_ne!(driver.state.transform.get_width(broken), 20.0);
+    assert!(driver.state.take_style_report().is_empty());
+}